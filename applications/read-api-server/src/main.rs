@@ -1,34 +1,118 @@
+mod metrics;
+mod openapi;
+mod order_summary_handler;
+
+use crate::metrics::ProjectionLag;
+use crate::openapi::ApiDoc;
 use anyhow::Result;
+use axum::http::Method;
 use axum::routing::get;
 use axum::Router;
-use config::Config;
+use query_read_model::order_event_log::FileOrderEventLog;
+use query_read_model::order_summary_projection::OrderSummaryProjection;
+use query_read_model::order_summary_store::{InMemoryOrderSummaryStore, OrderSummaryQuery};
 use serde::Deserialize;
-use std::net::{IpAddr, SocketAddr};
-use std::str::FromStr;
-use tracing::{info, Level};
+use shared_http_bootstrap::health::Readiness;
+use shared_http_bootstrap::metrics::Metrics;
+use shared_http_bootstrap::openapi::ApiRoutes;
+use shared_http_bootstrap::settings::{
+    load_settings, ApiSettings, ConfigOptions, EventLogSettings, LoggingSettings, TelemetrySettings, ValidateSettings,
+};
+use shared_http_bootstrap::shutdown::Shutdown;
+use shared_http_bootstrap::{logging, server, version_info};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info};
+use utoipa::OpenApi;
 
 /// 各設定の集約的な構造体です
 ///
 /// api: ApiSettings
+///
+/// logging: ログ出力の設定
+///
+/// telemetry: トレースの送信の設定
+///
+/// event_log: 注文サマリーに投影する注文のイベントの読み込み元(未指定の場合は投影しません)
 #[derive(Deserialize, Debug)]
 struct AppSettings {
     api: ApiSettings,
+    #[serde(default)]
+    logging: LoggingSettings,
+    #[serde(default)]
+    telemetry: TelemetrySettings,
+    event_log: Option<EventLogSettings>,
 }
 
-/// API起動時の設定用の構造体です
+impl ValidateSettings for AppSettings {
+    fn validate(&self) -> Vec<String> {
+        let mut errors = self.api.validate();
+        errors.extend(self.logging.validate());
+        errors.extend(self.telemetry.validate());
+        errors.extend(self.event_log.iter().flat_map(EventLogSettings::validate));
+        errors
+    }
+}
+
+/// ハンドラー間で共有する状態です
 ///
-/// host: ホスト
+/// order_summaries: 注文サマリーのクエリ
 ///
-/// port: ポート番号
-#[derive(Deserialize, Debug)]
-struct ApiSettings {
-    host: String,
-    port: u16,
+/// readiness: 注文サマリーのストアと投影処理の確認処理
+///
+/// metrics: `/metrics`で公開するメトリクス
+#[derive(Clone)]
+pub struct AppState {
+    order_summaries: Arc<dyn OrderSummaryQuery>,
+    readiness: Readiness,
+    metrics: Metrics,
+}
+
+impl AppState {
+    /// コンストラクタです
+    ///
+    /// # Arguments
+    /// * `order_summaries`: 注文サマリーのクエリ
+    /// * `metrics`: `/metrics`で公開するメトリクス
+    ///
+    /// readinessは注文サマリーのストアを利用できるかで判定します
+    ///
+    /// # Return
+    /// * `AppState`
+    fn new(order_summaries: Arc<dyn OrderSummaryQuery>, metrics: Metrics) -> Self {
+        let readiness = Readiness::new().with_check("read_model_store", {
+            let order_summaries = order_summaries.clone();
+            move || if order_summaries.is_available() { Ok(()) } else { Err("order summary store is unavailable".to_string()) }
+        });
+        Self { order_summaries, readiness, metrics }
+    }
+
+    /// 注文サマリーの投影の遅れを、メトリクスとreadinessの確認に追加します
+    ///
+    /// # Arguments
+    /// * `projection`: 注文サマリーの投影処理
+    /// * `max_lag`: 投影の遅れの上限(超えた場合はreadinessを失敗にします)
+    ///
+    /// # Return
+    /// * `AppState`
+    fn with_projection(mut self, projection: Arc<OrderSummaryProjection>, max_lag: Duration) -> Self {
+        ProjectionLag::register(&self.metrics, projection.clone());
+        self.readiness = self.readiness.with_check("order_summary_projection", move || {
+            let lag = projection.lag();
+            if lag <= max_lag {
+                Ok(())
+            } else {
+                Err(format!("order summary projection is {}s behind the event log (max {}s)", lag.as_secs(), max_lag.as_secs()))
+            }
+        });
+        self
+    }
 }
 
 /// 読み込み用サーバーの起動用関数です
 ///
-/// 0.0.0.0:18080で起動します
+/// config/read-api-server.tomlの設定(開発環境では0.0.0.0:18081、本番環境では0.0.0.0:8080)で起動します。
+/// `--config`で設定ファイルを、`--profile`でプロファイルを指定できます
 ///
 /// # return
 /// ```
@@ -36,47 +120,118 @@ struct ApiSettings {
 /// ```
 #[tokio::main]
 async fn main() -> Result<()> {
-    // ログ出力の設定
-    tracing_subscriber::fmt()
-        .with_max_level(Level::DEBUG)
-        .with_ansi(false)
-        .with_target(false)
-        .init();
-
-    // 仮でルーティング設定
-    // Module側を作成したら置き換え
-    let app = Router::new()
-        .route("/", get(|| async { "Hello World" }));
-
-    // configの読み込み
-    let app_settings = load_app_config()?;
-
-    // 起動用のアドレス
-    let socket_addr = SocketAddr::new(
-        IpAddr::from_str(&app_settings.api.host)?,
-        app_settings.api.port,
-    );
-    info!("Read server started!!");
-    info!("Running on: http://{}", &socket_addr);
-    let listener = tokio::net::TcpListener::bind(socket_addr).await?;
-    axum::serve(listener, app).await?;
-
-    Ok(())
+    // 設定ファイルの読み込み
+    let options = ConfigOptions::from_args(std::env::args().skip(1))?;
+    let app_settings = load_settings::<AppSettings>("read-api-server", &options)?;
+
+    // ログ出力とトレースの送信の設定
+    let telemetry = logging::init("read-api-server", &app_settings.logging, &app_settings.telemetry)?;
+
+    // 注文のイベントを注文サマリーに投影する設定
+    let store = Arc::new(InMemoryOrderSummaryStore::new());
+    let mut state = AppState::new(store.clone(), Metrics::new());
+    let mut projection = None;
+    if let Some(event_log) = &app_settings.event_log {
+        let order_summary_projection = Arc::new(OrderSummaryProjection::new(store, Arc::new(FileOrderEventLog::new(&event_log.path))));
+        spawn_projection(order_summary_projection.clone(), event_log.interval());
+        state = state.with_projection(order_summary_projection.clone(), event_log.max_lag());
+        projection = Some(order_summary_projection);
+    }
+
+    let app = app(state);
+
+    // 処理中のリクエストの完了を待ってから、追記済みの注文のイベントを投影し、送信していないスパンを書き出します
+    let mut shutdown = Shutdown::new(app_settings.api.shutdown_timeout());
+    if let Some(projection) = projection {
+        shutdown = shutdown.with_hook("order_summary_projection", move || async move {
+            tokio::task::spawn_blocking(move || projection.catch_up()).await??;
+            Ok(())
+        });
+    }
+    let shutdown = telemetry.flush_on(shutdown);
+    server::serve("Read server", &app_settings.api, app, shutdown).await
+}
+
+/// 一定の間隔でイベントログを読み込み、追記された注文のイベントを注文サマリーに投影します
+///
+/// # Arguments
+/// * `projection`: 注文サマリーの投影処理
+/// * `interval`: 読み込みの間隔
+fn spawn_projection(projection: Arc<OrderSummaryProjection>, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let catching_up = projection.clone();
+            match tokio::task::spawn_blocking(move || catching_up.catch_up()).await {
+                Ok(Ok(0)) => {}
+                Ok(Ok(applied)) => info!("projected {} order events from {}", applied, projection.location()),
+                Ok(Err(e)) => error!("failed to read order events from {}: {}", projection.location(), e),
+                Err(e) => error!("failed to read order events from {}: {}", projection.location(), e),
+            }
+        }
+    });
 }
 
-/// read-api-serverから設定ファイルを読み込みます
+/// OpenAPIのドキュメントに記載するルーティングの一覧です
+fn api_routes() -> ApiRoutes<AppState> {
+    ApiRoutes::new().route(Method::GET, "/customers/:customer_id/orders", order_summary_handler::find_orders_by_customer)
+}
+
+/// ルーティングを設定します
+///
+/// ヘルスチェック・バージョン・メトリクス・APIドキュメントと共通のミドルウェアは`server::app`で追加します。
+/// readinessとメトリクスは`AppState`に追加したものを使います
 ///
-/// AppSettingsのapiとawsに値をセットします
+/// # Arguments
+/// * `state`: AppState
 ///
-/// ## return
+/// # return
 /// ```
-/// AppSettings
+/// Router
 /// ```
-fn load_app_config() -> Result<AppSettings> {
-    let settings = Config::builder()
-        .add_source(config::File::with_name("../../config/read-api-server"))
-        .add_source(config::Environment::with_prefix("APP"))
-        .build()?;
-    let config = settings.try_deserialize::<AppSettings>()?;
-    Ok(config)
-}
\ No newline at end of file
+fn app(state: AppState) -> Router {
+    let router = api_routes()
+        .into_router()
+        .route("/", get(|| async { "Hello World" }))
+        .with_state(state.clone());
+    server::app(router, state.readiness, version_info!(), &state.metrics, ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use axum_test::TestServer;
+    use serde_json::Value;
+
+    #[tokio::test]
+    async fn test_readiness_checks_projection_lag() {
+        let store = Arc::new(InMemoryOrderSummaryStore::new());
+        let event_log = std::env::temp_dir()
+            .join(format!("read-api-server-readiness-{}", std::process::id()))
+            .join("order-events.jsonl");
+        let caught_up = Arc::new(OrderSummaryProjection::new(store.clone(), Arc::new(FileOrderEventLog::new(&event_log))));
+        caught_up.catch_up().unwrap();
+        let behind = Arc::new(OrderSummaryProjection::new(store.clone(), Arc::new(FileOrderEventLog::new(&event_log))));
+        let ready_server = TestServer::new(app(
+            AppState::new(store.clone(), Metrics::new()).with_projection(caught_up, Duration::from_secs(30)),
+        )).unwrap();
+        let behind_server = TestServer::new(app(
+            AppState::new(store, Metrics::new()).with_projection(behind, Duration::from_millis(10)),
+        )).unwrap();
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let ready = ready_server.get("/health/ready").await;
+        let not_ready = behind_server.get("/health/ready").await;
+        let metrics = behind_server.get("/metrics").await;
+
+        // assert
+        ready.assert_status_ok();
+        assert_eq!("ok", ready.json::<Value>()["checks"]["order_summary_projection"]);
+        not_ready.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+        assert!(not_ready.json::<Value>()["checks"]["order_summary_projection"].as_str().unwrap().contains("behind the event log"));
+        assert_eq!("ok", not_ready.json::<Value>()["checks"]["read_model_store"]);
+        assert!(metrics.text().contains("projection_lag_seconds"));
+    }
+}
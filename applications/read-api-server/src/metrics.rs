@@ -0,0 +1,72 @@
+use prometheus::core::{Collector, Desc};
+use prometheus::proto::MetricFamily;
+use prometheus::{Gauge, Opts};
+use query_read_model::order_summary_projection::OrderSummaryProjection;
+use shared_http_bootstrap::metrics::{register, Metrics};
+use std::sync::Arc;
+
+/// 注文サマリーの投影の遅れのPrometheusのメトリクスです
+///
+/// projection_lag_seconds: 最後にイベントログの末尾まで投影してからの秒数。
+/// 投影処理が止まっても値が増えるよう、`/metrics`の取得時に計算します
+#[derive(Clone)]
+pub struct ProjectionLag {
+    gauge: Gauge,
+    projection: Arc<OrderSummaryProjection>,
+}
+
+impl ProjectionLag {
+    /// 投影の遅れを`/metrics`で公開するレジストリに登録します
+    ///
+    /// # Arguments
+    /// * `metrics`: Metrics
+    /// * `projection`: 注文サマリーの投影処理
+    pub fn register(metrics: &Metrics, projection: Arc<OrderSummaryProjection>) {
+        register(
+            metrics.registry(),
+            Gauge::with_opts(Opts::new(
+                "projection_lag_seconds",
+                "Seconds since the order summary projection last caught up with the event log",
+            ))
+                .map(|gauge| Self { gauge, projection }),
+        );
+    }
+}
+
+impl Collector for ProjectionLag {
+    fn desc(&self) -> Vec<&Desc> {
+        self.gauge.desc()
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        self.gauge.set(self.projection.lag().as_secs_f64());
+        self.gauge.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use query_read_model::order_event_log::FileOrderEventLog;
+    use query_read_model::order_summary_store::InMemoryOrderSummaryStore;
+
+    #[test]
+    fn test_projection_lag_is_rendered() {
+        let metrics = Metrics::new();
+        let projection = Arc::new(OrderSummaryProjection::new(
+            Arc::new(InMemoryOrderSummaryStore::new()),
+            Arc::new(FileOrderEventLog::new("missing/order-events.jsonl")),
+        ));
+        ProjectionLag::register(&metrics, projection);
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let lag = metrics
+            .render()
+            .lines()
+            .find_map(|line| line.strip_prefix("projection_lag_seconds "))
+            .map(|value| value.parse::<f64>().unwrap());
+
+        // assert
+        assert!(lag.unwrap() >= 0.01);
+    }
+}
@@ -0,0 +1,59 @@
+use crate::order_summary_handler;
+use shared_http_bootstrap::openapi::OmitEmptyLicense;
+use utoipa::OpenApi;
+
+/// 読み込みAPIのOpenAPIのドキュメントです
+///
+/// ルーティングを追加・変更した場合は、`paths`とチェックインした`openapi.json`も更新します
+#[derive(OpenApi)]
+#[openapi(
+    info(description = "注文サマリーなどの読み込みモデルを参照するAPIです"),
+    paths(order_summary_handler::find_orders_by_customer),
+    tags((name = "orders", description = "注文")),
+    modifiers(&OmitEmptyLicense),
+)]
+pub struct ApiDoc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{api_routes, app, AppState};
+    use axum_test::TestServer;
+    use query_read_model::order_summary_store::InMemoryOrderSummaryStore;
+    use serde_json::Value;
+    use shared_http_bootstrap::metrics::Metrics;
+    use shared_http_bootstrap::openapi::{check_document, check_routes, UPDATE_OPENAPI};
+    use std::path::Path;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_openapi_document_is_up_to_date() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("openapi.json");
+
+        let result = check_document(&ApiDoc::openapi(), &path, std::env::var_os(UPDATE_OPENAPI).is_some());
+
+        // assert
+        assert_eq!(Ok(()), result);
+    }
+
+    #[test]
+    fn test_openapi_document_covers_routes() {
+        let result = check_routes(&ApiDoc::openapi(), &api_routes());
+
+        // assert
+        assert_eq!(Ok(()), result);
+    }
+
+    #[tokio::test]
+    async fn test_openapi_json_is_served() {
+        let server = TestServer::new(app(AppState::new(Arc::new(InMemoryOrderSummaryStore::new()), Metrics::new()))).unwrap();
+
+        let response = server.get("/openapi.json").await;
+        let docs = server.get("/docs").await;
+
+        // assert
+        response.assert_status_ok();
+        assert_eq!(serde_json::to_value(ApiDoc::openapi()).unwrap(), response.json::<Value>());
+        docs.assert_status_ok();
+    }
+}
@@ -0,0 +1,169 @@
+use crate::AppState;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use command_domain::customer::customer_id::CustomerId;
+use query_read_model::order_summary::OrderSummary;
+use serde::Serialize;
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+/// エラーのレスポンスです
+#[derive(Serialize, Debug, ToSchema)]
+pub struct ErrorResponse {
+    error: String,
+}
+
+/// 顧客の注文の一覧を返します
+///
+/// 顧客IDは`CUSTOMER-<uuid>`形式とUUIDのみの形式を受け付け、
+/// 注文日時の新しい順に注文サマリーを返します
+#[utoipa::path(
+    get,
+    path = "/customers/{customer_id}/orders",
+    tag = "orders",
+    params(("customer_id" = String, Path, description = "`CUSTOMER-<uuid>`形式またはUUIDのみの形式の顧客ID")),
+    responses(
+        (status = 200, description = "顧客の注文サマリー", body = Vec<OrderSummary>),
+        (status = 400, description = "顧客IDの形式の誤り", body = ErrorResponse),
+        (status = 503, description = "注文サマリーのストアを利用できない", body = ErrorResponse),
+    ),
+)]
+pub async fn find_orders_by_customer(
+    State(state): State<AppState>,
+    Path(customer_id): Path<String>,
+) -> Response {
+    match CustomerId::from_str(&customer_id) {
+        Ok(customer_id) => match state.order_summaries.find_by_customer_id(&customer_id.to_string()) {
+            Ok(summaries) => (StatusCode::OK, Json(summaries)).into_response(),
+            Err(e) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(ErrorResponse { error: e.to_string() }),
+            ).into_response(),
+        },
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse { error: e.to_string() }),
+        ).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{app, AppState};
+    use axum::http::StatusCode;
+    use axum_test::TestServer;
+    use chrono::{TimeZone, Utc};
+    use command_domain::clock::FixedClock;
+    use command_domain::customer::customer_id::CustomerId;
+    use command_domain::id_generator::UuidV4Generator;
+    use command_domain::order::order_id::OrderId;
+    use command_domain::order::order_item::{OrderItem, OrderItemParams};
+    use command_domain::order::order_item_id::OrderItemId;
+    use command_domain::order::order_pricing::OrderPricing;
+    use command_domain::order::Order;
+    use command_domain::shipping::delivery_method::DeliveryMethod;
+    use command_domain::shipping::shipping_address::ShippingAddress;
+    use command_domain::shipping::shipping_details::ShippingDetails;
+    use command_domain::tax::region::Region;
+    use command_domain::tax::tax_rule::TaxRules;
+    use command_domain::value_object::currency::Currency;
+    use command_domain::value_object::discount::Discount;
+    use query_read_model::order_summary::{OrderSummary, OrderSummaryError};
+    use query_read_model::order_summary_store::{InMemoryOrderSummaryStore, OrderSummaryQuery};
+    use rust_decimal::Decimal;
+    use serde_json::Value;
+    use shared_http_bootstrap::metrics::Metrics;
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    const CUSTOMER_UUID: &str = "00000000-0000-0000-0000-000000000001";
+
+    /// ストアを利用できない状態を再現するクエリです
+    struct UnavailableQuery;
+
+    impl OrderSummaryQuery for UnavailableQuery {
+        fn find_by_order_id(&self, _order_id: &str) -> Result<Option<OrderSummary>, OrderSummaryError> {
+            Err(OrderSummaryError::Unavailable("poisoned".to_string()))
+        }
+
+        fn find_by_customer_id(&self, _customer_id: &str) -> Result<Vec<OrderSummary>, OrderSummaryError> {
+            Err(OrderSummaryError::Unavailable("poisoned".to_string()))
+        }
+
+        fn is_available(&self) -> bool {
+            false
+        }
+    }
+
+    fn test_server() -> TestServer {
+        let item = OrderItem::place_order_item(OrderItemParams {
+            order_item_id: OrderItemId::generate(&UuidV4Generator),
+            product_id: 1,
+            product_name: "hogehoge",
+            product_category: "general",
+            unit_price: Decimal::from(500),
+            currency: "JPY",
+            discount: Discount::none(),
+            quantity: 2,
+        }).unwrap();
+        let (_, events) = Order::place_order(
+            OrderId::generate(&UuidV4Generator),
+            CustomerId::from_str(CUSTOMER_UUID).unwrap(),
+            &FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()),
+            OrderPricing {
+                currency: Currency::JPY,
+                rounding_policy: Currency::JPY.default_rounding_policy(),
+                region: Region::from_str("JP").unwrap(),
+                tax_rule: &TaxRules::default(),
+            },
+            vec![item],
+            vec![],
+            ShippingDetails {
+                address: ShippingAddress::new(
+                    "山田 太郎", "JP", "100-0001", Some("東京都"), "千代田区", "千代田1-1", None,
+                ).unwrap(),
+                delivery_method: DeliveryMethod::Standard,
+            },
+        ).unwrap();
+        let store = InMemoryOrderSummaryStore::new();
+        events.iter().for_each(|event| store.apply(event).unwrap());
+        TestServer::new(app(AppState::new(Arc::new(store), Metrics::new()))).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_find_orders_by_customer_success() {
+        let server = test_server();
+        let response = server
+            .get(&format!("/customers/CUSTOMER-{}/orders", CUSTOMER_UUID))
+            .await;
+        let raw_uuid = server.get(&format!("/customers/{}/orders", CUSTOMER_UUID)).await;
+
+        // assert
+        response.assert_status(StatusCode::OK);
+        let body = response.json::<Value>();
+        assert_eq!(1, body.as_array().unwrap().len());
+        assert_eq!(body[0]["customer_id"], format!("CUSTOMER-{}", CUSTOMER_UUID));
+        assert_eq!(body[0]["grand_total"], "1000");
+        assert_eq!(body, raw_uuid.json::<Value>());
+    }
+
+    #[tokio::test]
+    async fn test_find_orders_by_customer_failed() {
+        let server = test_server();
+        let response = server.get("/customers/ORDER-00000000-0000-0000-0000-000000000001/orders").await;
+
+        // assert
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_find_orders_by_customer_unavailable() {
+        let server = TestServer::new(app(AppState::new(Arc::new(UnavailableQuery), Metrics::new()))).unwrap();
+        let response = server.get(&format!("/customers/{}/orders", CUSTOMER_UUID)).await;
+
+        // assert
+        response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::problem::Problem;
+use crate::AppState;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use command_processor::command::{CreditLimit, RegisterCustomer};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// 顧客登録のリクエストです
+///
+/// max_open_orders、credit_limitを省略した場合は上限なしになります
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct RegisterCustomerRequest {
+  name: String,
+  #[serde(default)]
+  max_open_orders: Option<u32>,
+  #[serde(default)]
+  credit_limit: Option<CreditLimitRequest>,
+}
+
+/// 与信枠のリクエストです
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct CreditLimitRequest {
+  amount: Decimal,
+  currency: String,
+}
+
+impl From<RegisterCustomerRequest> for RegisterCustomer {
+  fn from(value: RegisterCustomerRequest) -> Self {
+    RegisterCustomer {
+      name: value.name,
+      max_open_orders: value.max_open_orders,
+      credit_limit: value.credit_limit.map(|credit_limit| CreditLimit {
+        amount: credit_limit.amount,
+        currency: credit_limit.currency,
+      }),
+    }
+  }
+}
+
+/// 顧客登録のレスポンスです
+#[derive(Serialize, Debug, ToSchema)]
+pub struct RegisterCustomerResponse {
+  customer_id: String,
+  name: String,
+}
+
+/// 顧客を登録します
+#[utoipa::path(
+  post,
+  path = "/customers",
+  tag = "customers",
+  request_body = RegisterCustomerRequest,
+  responses(
+    (status = 201, description = "登録した顧客", body = RegisterCustomerResponse),
+    (status = 400, description = "入力の誤り", body = Problem, content_type = "application/problem+json"),
+  ),
+)]
+pub async fn register_customer(
+  State(state): State<AppState>,
+  Json(request): Json<RegisterCustomerRequest>,
+) -> Response {
+  match state.customer_processor.register_customer(request.into()) {
+    Ok((customer, _)) => (
+      StatusCode::CREATED,
+      Json(RegisterCustomerResponse {
+        customer_id: customer.get_id().to_string(),
+        name: customer.get_name().to_string(),
+      }),
+    ).into_response(),
+    Err(e) => Problem::from(e).into_response(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{app, AppState};
+  use axum::http::StatusCode;
+  use axum_test::TestServer;
+  use chrono::{TimeZone, Utc};
+  use command_domain::clock::FixedClock;
+  use command_domain::id_generator::SequentialIdGenerator;
+  use command_domain::order::order_event_publisher::NoopOrderEventPublisher;
+  use command_domain::product::product_quantity_limits::ProductQuantityLimits;
+  use command_domain::tax::tax_rule::TaxRules;
+  use serde_json::{json, Value};
+  use std::sync::Arc;
+
+  fn test_server() -> TestServer {
+    let clock = FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap());
+    let state = AppState::new(Arc::new(clock), Arc::new(SequentialIdGenerator::new(1)), Arc::new(TaxRules::default()), ProductQuantityLimits::default(), Arc::new(NoopOrderEventPublisher));
+    TestServer::new(app(state)).unwrap()
+  }
+
+  fn place_order_request(customer_id: &Value) -> Value {
+    json!({
+      "customer_id": customer_id,
+      "currency": "JPY",
+      "region": "JP",
+      "items": [
+        { "product_id": 1, "product_name": "hogehoge", "product_category": "general", "unit_price": 500, "quantity": 2 }
+      ],
+      "shipping_address": {
+        "recipient": "山田 太郎",
+        "country": "JP",
+        "postal_code": "100-0001",
+        "subdivision": "東京都",
+        "city": "千代田区",
+        "line1": "千代田1-1"
+      }
+    })
+  }
+
+  #[tokio::test]
+  async fn test_register_customer_and_place_order_success() {
+    let server = test_server();
+    let registered = server
+      .post("/customers")
+      .json(&json!({ "name": "山田 太郎", "max_open_orders": 1, "credit_limit": { "amount": "5000", "currency": "JPY" } }))
+      .await;
+    let customer_id = registered.json::<Value>()["customer_id"].clone();
+    let placed = server.post("/orders").json(&place_order_request(&customer_id)).await;
+    let limited = server.post("/orders").json(&place_order_request(&customer_id)).await;
+
+    // assert
+    registered.assert_status(StatusCode::CREATED);
+    assert_eq!(customer_id, "CUSTOMER-00000000-0000-0001-0000-000000000001");
+    placed.assert_status(StatusCode::CREATED);
+    assert_eq!(placed.json::<Value>()["customer_id"], customer_id);
+    limited.assert_status(StatusCode::BAD_REQUEST);
+  }
+
+  #[tokio::test]
+  async fn test_register_customer_failed() {
+    let server = test_server();
+    let response = server.post("/customers").json(&json!({ "name": " " })).await;
+    let unknown = server
+      .post("/orders")
+      .json(&place_order_request(&json!("CUSTOMER-00000000-0000-0001-0000-000000000001")))
+      .await;
+
+    // assert
+    response.assert_status(StatusCode::BAD_REQUEST);
+    unknown.assert_status(StatusCode::NOT_FOUND);
+  }
+}
@@ -1,42 +1,311 @@
+mod customer_handler;
+mod metrics;
+mod openapi;
+mod order_handler;
+mod payment_handler;
+mod problem;
+mod promotion_handler;
+
+use crate::metrics::PrometheusMetrics;
+use crate::openapi::ApiDoc;
+use axum::extract::State;
+use axum::http::Method;
 use axum::routing::get;
 use axum::{Json, Router};
+use command_domain::clock::{Clock, SystemClock};
+use command_domain::id_generator::{IdGenerator, UuidV4Generator, UuidV7Generator};
+use command_domain::order::order_event_publisher::{NoopOrderEventPublisher, OrderEventPublisher};
 use command_domain::order::order_id::OrderId;
-use config::Config;
+use command_domain::product::product_category::ProductCategory;
+use command_domain::product::product_quantity_limits::ProductQuantityLimits;
+use command_domain::tax::region::Region;
+use command_domain::tax::tax_rule::{RegionalTaxRule, TaxInclusion, TaxRate, TaxRule, TaxRules, TaxTreatment};
+use command_domain::value_object::quantity::Quantity;
+use command_infrastructure::fake_payment_provider::FakePaymentProvider;
+use command_infrastructure::file_order_event_outbox::FileOrderEventOutbox;
+use command_infrastructure::in_memory_customer_repository::InMemoryCustomerRepository;
+use command_infrastructure::in_memory_order_repository::InMemoryOrderRepository;
+use command_infrastructure::in_memory_payment_repository::InMemoryPaymentRepository;
+use command_infrastructure::in_memory_promotion_repository::InMemoryPromotionRepository;
+use command_infrastructure::instrumented_repository::InstrumentedRepository;
+use command_processor::customer_command_processor::CustomerCommandProcessor;
+use command_processor::order_command_processor::OrderCommandProcessor;
+use command_processor::payment_command_processor::PaymentCommandProcessor;
+use command_processor::promotion_command_processor::PromotionCommandProcessor;
+use command_processor::validation::ValidationMode;
+use rust_decimal::Decimal;
 use serde::Deserialize;
 use serde_json::{json, Value};
+use shared_http_bootstrap::health::Readiness;
+use shared_http_bootstrap::metrics::Metrics;
+use shared_http_bootstrap::openapi::ApiRoutes;
+use shared_http_bootstrap::settings::{
+  load_settings, ApiSettings, ConfigOptions, EventLogSettings, LoggingSettings, TelemetrySettings, ValidateSettings,
+};
+use shared_http_bootstrap::shutdown::Shutdown;
+use shared_http_bootstrap::{logging, server, version_info};
 use std::fmt::Debug;
-use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
-use tower_http::trace::TraceLayer;
-use tracing::log::info;
-use tracing::Level;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::error;
+use utoipa::OpenApi;
 
 /// 各設定の集約的な構造体です
 ///
 /// api: ApiSettings
+///
+/// id_generator: IDの生成方式(未指定の場合はv4)
+///
+/// tax_rules: 税ルールの一覧(先に定義したものが優先されます)
+///
+/// quantity_limits: 商品ごとの注文数量の上限
+///
+/// logging: ログ出力の設定
+///
+/// telemetry: トレースの送信の設定
+///
+/// event_log: 注文のイベントの書き出し先(未指定の場合は書き出しません)
 #[derive(Deserialize, Debug)]
 struct AppSettings {
   api: ApiSettings,
+  #[serde(default)]
+  id_generator: IdGeneratorKind,
+  #[serde(default)]
+  tax_rules: Vec<TaxRuleSettings>,
+  #[serde(default)]
+  quantity_limits: QuantityLimitSettings,
+  #[serde(default)]
+  logging: LoggingSettings,
+  #[serde(default)]
+  telemetry: TelemetrySettings,
+  event_log: Option<EventLogSettings>,
 }
 
-/// API起動時の設定用の構造体です
+/// 税ルールの設定用の構造体です
+///
+/// region: 地域(例: `JP`、`US-CA`)
 ///
-/// host: ホスト
+/// category: 商品カテゴリ(未指定の場合はすべてのカテゴリ)
 ///
-/// port: ポート番号
+/// rate: 税率(%)
+///
+/// inclusive: 内税の場合true
 #[derive(Deserialize, Debug)]
-struct ApiSettings {
-  host: String,
-  port: u16,
+struct TaxRuleSettings {
+  region: String,
+  category: Option<String>,
+  rate: Decimal,
+  #[serde(default)]
+  inclusive: bool,
 }
 
-/// 書き込み用サーバーの起動用関数です
+impl ValidateSettings for AppSettings {
+  fn validate(&self) -> Vec<String> {
+    let mut errors = self.api.validate();
+    errors.extend(self.tax_rules
+      .iter()
+      .enumerate()
+      .filter_map(|(i, tax_rule)| tax_rule.tax_rule().err().map(|e| format!("tax_rules[{}]: {}", i, e))));
+    if let Err(e) = self.quantity_limits.quantity_limits() {
+      errors.push(format!("quantity_limits: {}", e));
+    }
+    errors.extend(self.logging.validate());
+    errors.extend(self.telemetry.validate());
+    errors.extend(self.event_log.iter().flat_map(EventLogSettings::validate));
+    errors
+  }
+}
+
+impl TaxRuleSettings {
+  /// 設定に対応するTaxRuleを返します
+  fn tax_rule(&self) -> anyhow::Result<Arc<dyn TaxRule>> {
+    let inclusion = if self.inclusive { TaxInclusion::Inclusive } else { TaxInclusion::Exclusive };
+    Ok(Arc::new(RegionalTaxRule::new(
+      Region::from_str(&self.region)?,
+      self.category.as_deref().map(ProductCategory::new).transpose()?,
+      TaxTreatment::new(TaxRate::try_from(self.rate)?, inclusion),
+    )))
+  }
+}
+
+/// 注文数量の上限の設定用の構造体です
+///
+/// default_max: 商品ごとの上限がない商品の上限(未指定の場合は上限なし)
+///
+/// products: 商品ごとの上限
+#[derive(Deserialize, Debug, Default)]
+struct QuantityLimitSettings {
+  default_max: Option<i32>,
+  #[serde(default)]
+  products: Vec<ProductQuantityLimitSettings>,
+}
+
+/// 商品ごとの注文数量の上限の設定用の構造体です
+///
+/// product_id: 商品ID
+///
+/// max: 1回の注文で購入できる数量の上限
+#[derive(Deserialize, Debug)]
+struct ProductQuantityLimitSettings {
+  product_id: i32,
+  max: i32,
+}
+
+impl QuantityLimitSettings {
+  /// 設定に対応するProductQuantityLimitsを返します
+  fn quantity_limits(&self) -> anyhow::Result<ProductQuantityLimits> {
+    let default_max = self.default_max.map(Quantity::try_from).transpose()?;
+    self.products
+      .iter()
+      .try_fold(ProductQuantityLimits::new(default_max), |limits, product| {
+        Ok(limits.with_limit(product.product_id, Quantity::try_from(product.max)?))
+      })
+  }
+}
+
+/// IDの生成方式です
+///
+/// v4: ランダム
+///
+/// v7: 時刻順
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum IdGeneratorKind {
+  #[default]
+  V4,
+  V7,
+}
+
+impl IdGeneratorKind {
+  /// 設定に対応するIdGeneratorを返します
+  fn generator(self) -> Arc<dyn IdGenerator> {
+    match self {
+      IdGeneratorKind::V4 => Arc::new(UuidV4Generator),
+      IdGeneratorKind::V7 => Arc::new(UuidV7Generator),
+    }
+  }
+}
+
+/// ハンドラー間で共有する状態です
+///
+/// processor: 注文のコマンドプロセッサー
+///
+/// promotion_processor: プロモーションのコマンドプロセッサー
 ///
-/// 開発環境
-/// 0.0.0.0:18080で起動します
+/// customer_processor: 顧客のコマンドプロセッサー
 ///
-/// 本番環境
-/// 0.0.0.0:8080で起動します
+/// payment_processor: 支払いのコマンドプロセッサー
+///
+/// readiness: リポジトリを利用できるかの確認処理
+///
+/// metrics: `/metrics`で公開するメトリクス
+///
+/// id_generator: IDの生成方式
+#[derive(Clone)]
+pub struct AppState {
+  processor: Arc<OrderCommandProcessor>,
+  promotion_processor: Arc<PromotionCommandProcessor>,
+  customer_processor: Arc<CustomerCommandProcessor>,
+  payment_processor: Arc<PaymentCommandProcessor>,
+  readiness: Readiness,
+  metrics: Metrics,
+  id_generator: Arc<dyn IdGenerator>,
+}
+
+impl AppState {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `clock`: 日時の取得元。本番では`SystemClock`を渡します
+  /// * `id_generator`: IDの生成方式
+  /// * `tax_rule`: 税ルール
+  /// * `quantity_limits`: 商品ごとの注文数量の上限
+  /// * `event_publisher`: 保存した注文のイベントの配信先
+  ///
+  /// 決済事業者は外部との接続がないため、`FakePaymentProvider`を使用します
+  ///
+  /// 注文の入力のエラーは、クライアントが一度に修正できるようにすべてまとめて返します
+  ///
+  /// コマンドとリポジトリの操作はメトリクスに記録します。readinessの確認には計測しないリポジトリを使います
+  ///
+  /// # Return
+  /// * `AppState`
+  fn new(
+    clock: Arc<dyn Clock>,
+    id_generator: Arc<dyn IdGenerator>,
+    tax_rule: Arc<dyn TaxRule>,
+    quantity_limits: ProductQuantityLimits,
+    event_publisher: Arc<dyn OrderEventPublisher>,
+  ) -> Self {
+    let promotion_repository = Arc::new(InMemoryPromotionRepository::new());
+    let customer_repository = Arc::new(InMemoryCustomerRepository::new());
+    let order_repository = Arc::new(InMemoryOrderRepository::new());
+    let payment_repository = Arc::new(InMemoryPaymentRepository::new());
+    let readiness = Readiness::new()
+      .with_check("order_repository", repository_check(order_repository.clone(), InMemoryOrderRepository::is_available))
+      .with_check("customer_repository", repository_check(customer_repository.clone(), InMemoryCustomerRepository::is_available))
+      .with_check("payment_repository", repository_check(payment_repository.clone(), InMemoryPaymentRepository::is_available))
+      .with_check("promotion_repository", repository_check(promotion_repository.clone(), InMemoryPromotionRepository::is_available));
+    let metrics = Metrics::new();
+    let command_metrics = Arc::new(PrometheusMetrics::new(&metrics));
+    let promotion_repository = Arc::new(InstrumentedRepository::new(promotion_repository, "promotion", command_metrics.clone()));
+    let customer_repository = Arc::new(InstrumentedRepository::new(customer_repository, "customer", command_metrics.clone()));
+    let order_repository = Arc::new(InstrumentedRepository::new(order_repository, "order", command_metrics.clone()));
+    let payment_repository = Arc::new(InstrumentedRepository::new(payment_repository, "payment", command_metrics.clone()));
+    let processor = OrderCommandProcessor::new(
+      clock.clone(),
+      id_generator.clone(),
+      customer_repository.clone(),
+      order_repository.clone(),
+    )
+      .with_tax_rule(tax_rule)
+      .with_quantity_limits(quantity_limits)
+      .with_validation_mode(ValidationMode::Accumulate)
+      .with_promotion_repository(promotion_repository.clone())
+      .with_metrics(command_metrics.clone())
+      .with_event_publisher(event_publisher.clone());
+    let promotion_processor = PromotionCommandProcessor::new(clock.clone(), id_generator.clone(), promotion_repository)
+      .with_metrics(command_metrics.clone());
+    let customer_processor = CustomerCommandProcessor::new(clock.clone(), id_generator.clone(), customer_repository)
+      .with_metrics(command_metrics.clone());
+    let payment_processor = PaymentCommandProcessor::new(
+      clock,
+      id_generator.clone(),
+      payment_repository,
+      order_repository,
+      Arc::new(FakePaymentProvider::new()),
+    )
+      .with_metrics(command_metrics)
+      .with_event_publisher(event_publisher);
+    Self {
+      processor: Arc::new(processor),
+      promotion_processor: Arc::new(promotion_processor),
+      customer_processor: Arc::new(customer_processor),
+      payment_processor: Arc::new(payment_processor),
+      readiness,
+      metrics,
+      id_generator,
+    }
+  }
+}
+
+/// リポジトリを利用できるかの確認処理を返します
+///
+/// # Arguments
+/// * `repository`: リポジトリ
+/// * `is_available`: リポジトリを利用できる場合trueを返す関数
+fn repository_check<R: Send + Sync + 'static>(
+  repository: Arc<R>,
+  is_available: fn(&R) -> bool,
+) -> impl Fn() -> Result<(), String> + Send + Sync + 'static {
+  move || if is_available(&repository) { Ok(()) } else { Err("repository is unavailable".to_string()) }
+}
+
+/// 書き込み用サーバーの起動用関数です
+///
+/// config/write-api-server.tomlの設定(開発環境では0.0.0.0:18080、本番環境では0.0.0.0:8080)で起動します。
+/// `--config`で設定ファイルを、`--profile`でプロファイルを指定できます
 ///
 /// # return
 /// ```
@@ -44,78 +313,107 @@ struct ApiSettings {
 /// ```
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-  // ログ出力の設定
-  tracing_subscriber::fmt()
-    .with_max_level(Level::TRACE)
-    .with_ansi(false)
-    .with_target(false)
-    .init();
-
   // 設定ファイルの読み込み
-  let app_settings = load_app_config()?;
-
-  // 仮でルーティング設定
-  // Module側を作成したら置き換え
-  let app = Router::new()
-    .route("/", get(root))
-    .layer(TraceLayer::new_for_http());
+  let options = ConfigOptions::from_args(std::env::args().skip(1))?;
+  let app_settings = load_settings::<AppSettings>("write-api-server", &options)?;
 
-  // 起動用のアドレス
-  let socket_addr = SocketAddr::new(
-    IpAddr::from_str(&app_settings.api.host)?,
-    app_settings.api.port,
-  );
-  info!("Binding to address: {}", socket_addr);
+  // ログ出力とトレースの送信の設定
+  let telemetry = logging::init("write-api-server", &app_settings.logging, &app_settings.telemetry)?;
+  let tax_rules = app_settings.tax_rules
+    .iter()
+    .map(TaxRuleSettings::tax_rule)
+    .collect::<anyhow::Result<Vec<_>>>()?;
 
-  let listener = tokio::net::TcpListener::bind(socket_addr)
-    .await
-    .expect("Failed to bind to address");
+  // 注文のイベントの書き出しの設定
+  let outbox = app_settings.event_log
+    .as_ref()
+    .map(|event_log| Arc::new(FileOrderEventOutbox::new(&event_log.path)));
+  let event_publisher: Arc<dyn OrderEventPublisher> = match (&outbox, &app_settings.event_log) {
+    (Some(outbox), Some(event_log)) => {
+      spawn_outbox_flush(outbox.clone(), event_log.interval());
+      outbox.clone()
+    }
+    _ => Arc::new(NoopOrderEventPublisher),
+  };
 
-  info!("Write server started!!");
-  axum::serve(listener, app)
-    .await?;
+  let app = app(AppState::new(
+    Arc::new(SystemClock),
+    app_settings.id_generator.generator(),
+    Arc::new(TaxRules::new(tax_rules)),
+    app_settings.quantity_limits.quantity_limits()?,
+    event_publisher,
+  ));
 
-  Ok(())
+  // 処理中のリクエストの完了を待ってから、溜めた注文のイベントと送信していないスパンを書き出します
+  let mut shutdown = Shutdown::new(app_settings.api.shutdown_timeout());
+  if let Some(outbox) = outbox {
+    shutdown = shutdown.with_hook("order_event_outbox", move || async move {
+      tokio::task::spawn_blocking(move || outbox.flush()).await??;
+      Ok(())
+    });
+  }
+  let shutdown = telemetry.flush_on(shutdown);
+  server::serve("Write server", &app_settings.api, app, shutdown).await
 }
 
-
-/// write-api-server.tomlから設定ファイルを読み込みます
+/// 溜めた注文のイベントを一定の間隔でイベントログに書き出します
 ///
-/// AppSettingsのapiとawsに値をセットします
+/// 書き出しに失敗したイベントはアウトボックスに残り、次の書き出しで再度書き出します
 ///
-/// ## return
-/// ```
-/// AppSettings
-/// ```
-#[cfg(debug_assertions)]
-fn load_app_config() -> anyhow::Result<AppSettings> {
-  let settings = Config::builder()
-    .add_source(config::File::with_name("../../config/write-api-server"))
-    .add_source(config::Environment::with_prefix("APP"))
-    .build()?;
-  let config = settings.try_deserialize::<AppSettings>()?;
-  Ok(config)
+/// # Arguments
+/// * `outbox`: 注文のイベントのアウトボックス
+/// * `interval`: 書き出しの間隔
+fn spawn_outbox_flush(outbox: Arc<FileOrderEventOutbox>, interval: Duration) {
+  tokio::spawn(async move {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+      ticker.tick().await;
+      let flushing = outbox.clone();
+      match tokio::task::spawn_blocking(move || flushing.flush()).await {
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => error!("failed to write order events to {}: {}", outbox.path().display(), e),
+        Err(e) => error!("failed to write order events to {}: {}", outbox.path().display(), e),
+      }
+    }
+  });
 }
 
-/// 環境変数から設定を読み込みます
+/// OpenAPIのドキュメントに記載するルーティングの一覧です
+fn api_routes() -> ApiRoutes<AppState> {
+  ApiRoutes::new()
+    .route(Method::POST, "/orders", order_handler::place_order)
+    .route(Method::POST, "/orders/:order_id/items", order_handler::add_order_items)
+    .route(Method::PUT, "/orders/:order_id/shipping-address", order_handler::change_shipping_address)
+    .route(Method::POST, "/orders/:order_id/ship", order_handler::ship_order)
+    .route(Method::POST, "/promotions", promotion_handler::create_promotion)
+    .route(Method::POST, "/customers", customer_handler::register_customer)
+    .route(Method::POST, "/orders/:order_id/payments", payment_handler::authorize_payment)
+    .route(Method::POST, "/orders/:order_id/returns", payment_handler::return_items)
+    .route(Method::POST, "/payments/:payment_id/capture", payment_handler::capture_payment)
+    .route(Method::POST, "/payments/:payment_id/refunds", payment_handler::refund_payment)
+    .route(Method::POST, "/payments/:payment_id/refunds/retry", payment_handler::retry_refunds)
+}
+
+/// ルーティングを設定します
 ///
-/// AppSettingsのapiとawsに値をセットします
+/// ヘルスチェック・バージョン・メトリクス・APIドキュメントと共通のミドルウェアは`server::app`で追加します
 ///
-/// ## return
+/// # Arguments
+/// * `state`: AppState
+///
+/// # return
 /// ```
-/// AppSettings
+/// Router
 /// ```
-#[cfg(not(debug_assertions))]
-fn load_app_config() -> anyhow::Result<AppSettings> {
-  let host = std::env::var("HOST").expect("HOST must set");
-  let port = std::env::var("PORT").expect("PORT must set");
-
-  let api_settings = ApiSettings { host: host, port: port.parse::<u16>().expect("failed to parse port") };
-
-  Ok(AppSettings { api: api_settings })
+fn app(state: AppState) -> Router {
+  let router = api_routes()
+    .into_router()
+    .route("/", get(root))
+    .with_state(state.clone());
+  server::app(router, state.readiness, version_info!(), &state.metrics, ApiDoc::openapi())
 }
 
-async fn root() -> Json<Value> {
-  let order_id = OrderId::new();
+async fn root(State(state): State<AppState>) -> Json<Value> {
+  let order_id = OrderId::generate(state.id_generator.as_ref());
   Json(json!({ "msg": order_id }))
 }
\ No newline at end of file
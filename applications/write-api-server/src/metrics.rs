@@ -0,0 +1,84 @@
+use command_infrastructure::instrumented_repository::StoreMetrics;
+use command_processor::command_metrics::{CommandMetrics, CommandOutcome};
+use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, Opts};
+use shared_http_bootstrap::metrics::{register, Metrics};
+use std::time::Duration;
+
+/// コマンドとストアのPrometheusのメトリクスです
+///
+/// commands_total: 処理したコマンド数(command、outcome別)
+///
+/// command_duration: コマンドの処理時間(command別)
+///
+/// events_appended_total: 発生したイベント数(aggregate_type別)
+///
+/// concurrency_conflicts_total: 楽観的排他制御でバージョンが競合した回数(aggregate_type別)
+///
+/// store_operation_duration: ストアの操作の処理時間(aggregate_type、operation別)
+pub struct PrometheusMetrics {
+  commands_total: IntCounterVec,
+  command_duration: HistogramVec,
+  events_appended_total: IntCounterVec,
+  concurrency_conflicts_total: IntCounterVec,
+  store_operation_duration: HistogramVec,
+}
+
+impl PrometheusMetrics {
+  /// コンストラクタです
+  ///
+  /// メトリクスは`/metrics`で公開するレジストリに登録します
+  ///
+  /// # Arguments
+  /// * `metrics`: Metrics
+  ///
+  /// # Return
+  /// * `PrometheusMetrics`
+  pub fn new(metrics: &Metrics) -> Self {
+    let registry = metrics.registry();
+    Self {
+      commands_total: register(registry, IntCounterVec::new(
+        Opts::new("commands_total", "Commands handled by command type and outcome"),
+        &["command", "outcome"],
+      )),
+      command_duration: register(registry, HistogramVec::new(
+        HistogramOpts::new("command_duration_seconds", "Command handling latency in seconds"),
+        &["command"],
+      )),
+      events_appended_total: register(registry, IntCounterVec::new(
+        Opts::new("events_appended_total", "Events appended by aggregate type"),
+        &["aggregate_type"],
+      )),
+      concurrency_conflicts_total: register(registry, IntCounterVec::new(
+        Opts::new("concurrency_conflicts_total", "Optimistic concurrency conflicts by aggregate type"),
+        &["aggregate_type"],
+      )),
+      store_operation_duration: register(registry, HistogramVec::new(
+        HistogramOpts::new("store_operation_duration_seconds", "Store operation latency in seconds"),
+        &["aggregate_type", "operation"],
+      )),
+    }
+  }
+}
+
+impl CommandMetrics for PrometheusMetrics {
+  fn command_handled(&self, command: &'static str, outcome: CommandOutcome, elapsed: Duration) {
+    self.commands_total.with_label_values(&[command, outcome.as_str()]).inc();
+    self.command_duration.with_label_values(&[command]).observe(elapsed.as_secs_f64());
+  }
+
+  fn events_appended(&self, aggregate_type: &'static str, count: usize) {
+    self.events_appended_total.with_label_values(&[aggregate_type]).inc_by(count as u64);
+  }
+}
+
+impl StoreMetrics for PrometheusMetrics {
+  fn operation_completed(&self, aggregate_type: &'static str, operation: &'static str, elapsed: Duration) {
+    self.store_operation_duration
+      .with_label_values(&[aggregate_type, operation])
+      .observe(elapsed.as_secs_f64());
+  }
+
+  fn version_conflicted(&self, aggregate_type: &'static str) {
+    self.concurrency_conflicts_total.with_label_values(&[aggregate_type]).inc();
+  }
+}
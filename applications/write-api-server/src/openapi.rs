@@ -0,0 +1,82 @@
+use crate::{customer_handler, order_handler, payment_handler, promotion_handler};
+use shared_http_bootstrap::openapi::OmitEmptyLicense;
+use utoipa::OpenApi;
+
+/// 書き込みAPIのOpenAPIのドキュメントです
+///
+/// ルーティングを追加・変更した場合は、`paths`とチェックインした`openapi.json`も更新します
+#[derive(OpenApi)]
+#[openapi(
+  info(description = "注文・支払い・顧客・プロモーションのコマンドを受け付けるAPIです"),
+  paths(
+    order_handler::place_order,
+    order_handler::add_order_items,
+    order_handler::change_shipping_address,
+    order_handler::ship_order,
+    promotion_handler::create_promotion,
+    customer_handler::register_customer,
+    payment_handler::authorize_payment,
+    payment_handler::return_items,
+    payment_handler::capture_payment,
+    payment_handler::refund_payment,
+    payment_handler::retry_refunds,
+  ),
+  tags(
+    (name = "orders", description = "注文"),
+    (name = "payments", description = "支払いと返品"),
+    (name = "customers", description = "顧客"),
+    (name = "promotions", description = "プロモーション"),
+  ),
+  modifiers(&OmitEmptyLicense),
+)]
+pub struct ApiDoc;
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{api_routes, app, AppState};
+  use axum_test::TestServer;
+  use chrono::{TimeZone, Utc};
+  use command_domain::clock::FixedClock;
+  use command_domain::id_generator::SequentialIdGenerator;
+  use command_domain::order::order_event_publisher::NoopOrderEventPublisher;
+  use command_domain::product::product_quantity_limits::ProductQuantityLimits;
+  use command_domain::tax::tax_rule::TaxRules;
+  use serde_json::Value;
+  use shared_http_bootstrap::openapi::{check_document, check_routes, UPDATE_OPENAPI};
+  use std::path::Path;
+  use std::sync::Arc;
+
+  #[test]
+  fn test_openapi_document_is_up_to_date() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("openapi.json");
+
+    let result = check_document(&ApiDoc::openapi(), &path, std::env::var_os(UPDATE_OPENAPI).is_some());
+
+    // assert
+    assert_eq!(Ok(()), result);
+  }
+
+  #[test]
+  fn test_openapi_document_covers_routes() {
+    let result = check_routes(&ApiDoc::openapi(), &api_routes());
+
+    // assert
+    assert_eq!(Ok(()), result);
+  }
+
+  #[tokio::test]
+  async fn test_openapi_json_is_served() {
+    let clock = FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap());
+    let state = AppState::new(Arc::new(clock), Arc::new(SequentialIdGenerator::new(1)), Arc::new(TaxRules::default()), ProductQuantityLimits::default(), Arc::new(NoopOrderEventPublisher));
+    let server = TestServer::new(app(state)).unwrap();
+
+    let response = server.get("/openapi.json").await;
+    let docs = server.get("/docs").await;
+
+    // assert
+    response.assert_status_ok();
+    assert_eq!(serde_json::to_value(ApiDoc::openapi()).unwrap(), response.json::<Value>());
+    docs.assert_status_ok();
+  }
+}
@@ -0,0 +1,696 @@
+use crate::problem::Problem;
+use crate::AppState;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use chrono::{DateTime, Utc};
+use command_domain::order::Order;
+use command_domain::shipping::shipping_address::ShippingAddress;
+use command_processor::command::{
+  AddOrderItems, ChangeShippingAddress, DiscountValue, PlaceOrder, PlaceOrderDiscount, PlaceOrderItem, ShipOrder,
+  ShippingAddressValue,
+};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// 注文確定のリクエストです
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct PlaceOrderRequest {
+  customer_id: String,
+  currency: String,
+  region: String,
+  items: Vec<PlaceOrderItemRequest>,
+  #[serde(default)]
+  discounts: Vec<OrderDiscountRequest>,
+  #[serde(default)]
+  coupon_code: Option<String>,
+  shipping_address: ShippingAddressRequest,
+  #[serde(default = "default_delivery_method")]
+  delivery_method: String,
+}
+
+/// 配送方法の既定値です
+fn default_delivery_method() -> String {
+  "standard".to_string()
+}
+
+/// 配送先の住所のリクエストです
+///
+/// subdivisionはJP・US・CAでは必須です
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct ShippingAddressRequest {
+  recipient: String,
+  country: String,
+  postal_code: String,
+  #[serde(default)]
+  subdivision: Option<String>,
+  city: String,
+  line1: String,
+  #[serde(default)]
+  line2: Option<String>,
+}
+
+impl From<ShippingAddressRequest> for ShippingAddressValue {
+  fn from(value: ShippingAddressRequest) -> Self {
+    ShippingAddressValue {
+      recipient: value.recipient,
+      country: value.country,
+      postal_code: value.postal_code,
+      subdivision: value.subdivision,
+      city: value.city,
+      line1: value.line1,
+      line2: value.line2,
+    }
+  }
+}
+
+/// 配送先の住所のレスポンスです
+#[derive(Serialize, Debug, ToSchema)]
+pub struct ShippingAddressResponse {
+  recipient: String,
+  country: String,
+  postal_code: String,
+  subdivision: Option<String>,
+  city: String,
+  line1: String,
+  line2: Option<String>,
+}
+
+impl From<&ShippingAddress> for ShippingAddressResponse {
+  fn from(value: &ShippingAddress) -> Self {
+    ShippingAddressResponse {
+      recipient: value.get_recipient().to_string(),
+      country: value.get_country().to_string(),
+      postal_code: value.get_postal_code().to_string(),
+      subdivision: value.get_subdivision().map(str::to_string),
+      city: value.get_city().to_string(),
+      line1: value.get_line1().to_string(),
+      line2: value.get_line2().map(str::to_string),
+    }
+  }
+}
+
+/// 注文確定リクエストの明細です
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct PlaceOrderItemRequest {
+  product_id: i32,
+  product_name: String,
+  product_category: String,
+  unit_price: Decimal,
+  #[serde(default)]
+  discount: Option<DiscountRequest>,
+  quantity: i32,
+}
+
+impl From<PlaceOrderItemRequest> for PlaceOrderItem {
+  fn from(value: PlaceOrderItemRequest) -> Self {
+    PlaceOrderItem {
+      product_id: value.product_id,
+      product_name: value.product_name,
+      product_category: value.product_category,
+      unit_price: value.unit_price,
+      discount: value.discount.map(DiscountValue::from),
+      quantity: value.quantity,
+    }
+  }
+}
+
+/// 明細追加のリクエストです
+///
+/// 単価は注文の通貨で指定します
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct AddOrderItemsRequest {
+  items: Vec<PlaceOrderItemRequest>,
+}
+
+/// 割引のリクエストです
+///
+/// `{"type": "percentage", "value": 10}`
+///
+/// `{"type": "fixed_amount", "value": "300"}`
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum DiscountRequest {
+  Percentage(Decimal),
+  FixedAmount(Decimal),
+}
+
+/// 注文全体に対する割引のリクエストです
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct OrderDiscountRequest {
+  discount: DiscountRequest,
+  coupon_code: Option<String>,
+  #[serde(default)]
+  stackable: bool,
+}
+
+impl From<DiscountRequest> for DiscountValue {
+  fn from(value: DiscountRequest) -> Self {
+    match value {
+      DiscountRequest::Percentage(rate) => DiscountValue::Percentage(rate),
+      DiscountRequest::FixedAmount(amount) => DiscountValue::FixedAmount(amount),
+    }
+  }
+}
+
+/// 注文確定のレスポンスです
+///
+/// 明細追加のレスポンスにも、追加後の注文を返すために使用します
+#[derive(Serialize, Debug, ToSchema)]
+pub struct PlaceOrderResponse {
+  order_id: String,
+  customer_id: String,
+  ordered_at: DateTime<Utc>,
+  currency: String,
+  total_price: Decimal,
+  tax_total: Decimal,
+  grand_total: Decimal,
+  items: Vec<PlaceOrderItemResponse>,
+  shipping_address: ShippingAddressResponse,
+  delivery_method: String,
+}
+
+impl From<&Order> for PlaceOrderResponse {
+  fn from(order: &Order) -> Self {
+    PlaceOrderResponse {
+      order_id: order.get_id().to_string(),
+      customer_id: order.get_customer_id().to_string(),
+      ordered_at: *order.get_ordered_at(),
+      currency: order.get_currency().to_string(),
+      total_price: *order.get_total_price().amount(),
+      tax_total: *order.get_tax_breakdown().total_tax().amount(),
+      grand_total: *order.get_grand_total().amount(),
+      items: order.get_order_items()
+        .iter()
+        .map(|item| PlaceOrderItemResponse {
+          order_item_id: item.get_order_item_id().to_string(),
+          product_id: item.get_product_id(),
+          quantity: item.get_quantity(),
+        })
+        .collect(),
+      shipping_address: (&order.get_shipping().address).into(),
+      delivery_method: order.get_shipping().delivery_method.to_string(),
+    }
+  }
+}
+
+/// 注文確定のレスポンスの明細です
+///
+/// order_item_idは返品時に明細を指定するために使用します
+#[derive(Serialize, Debug, ToSchema)]
+pub struct PlaceOrderItemResponse {
+  order_item_id: String,
+  product_id: i32,
+  quantity: i32,
+}
+
+/// 出荷のレスポンスです
+#[derive(Serialize, Debug, ToSchema)]
+pub struct ShipOrderResponse {
+  order_id: String,
+  status: String,
+}
+
+/// 配送先変更のレスポンスです
+#[derive(Serialize, Debug, ToSchema)]
+pub struct ChangeShippingAddressResponse {
+  order_id: String,
+  shipping_address: ShippingAddressResponse,
+}
+
+impl From<PlaceOrderRequest> for PlaceOrder {
+  fn from(value: PlaceOrderRequest) -> Self {
+    PlaceOrder {
+      customer_id: value.customer_id,
+      currency: value.currency,
+      region: value.region,
+      items: value.items.into_iter().map(PlaceOrderItem::from).collect(),
+      discounts: value.discounts
+        .into_iter()
+        .map(|discount| PlaceOrderDiscount {
+          discount: discount.discount.into(),
+          coupon_code: discount.coupon_code,
+          stackable: discount.stackable,
+        })
+        .collect(),
+      coupon_code: value.coupon_code,
+      shipping_address: value.shipping_address.into(),
+      delivery_method: value.delivery_method,
+    }
+  }
+}
+
+/// 注文を確定します
+///
+/// 注文日時はAppStateのClockから取得されます
+///
+/// 顧客やクーポンが見つからない場合は404、同時実行による競合が解消しなかった場合は409を返します
+#[utoipa::path(
+  post,
+  path = "/orders",
+  tag = "orders",
+  request_body = PlaceOrderRequest,
+  responses(
+    (status = 201, description = "確定した注文", body = PlaceOrderResponse),
+    (status = 400, description = "入力の誤り", body = Problem, content_type = "application/problem+json"),
+    (status = 404, description = "顧客またはクーポンがない", body = Problem, content_type = "application/problem+json"),
+    (status = 409, description = "同時実行による競合が解消しなかった", body = Problem, content_type = "application/problem+json"),
+  ),
+)]
+pub async fn place_order(
+  State(state): State<AppState>,
+  Json(request): Json<PlaceOrderRequest>,
+) -> Response {
+  match state.processor.place_order(request.into()) {
+    Ok((order, _)) => (StatusCode::CREATED, Json(PlaceOrderResponse::from(&order))).into_response(),
+    Err(e) => Problem::from(e).into_response(),
+  }
+}
+
+/// 確定後、支払い前の注文に明細を追加します
+///
+/// 入力の誤りはすべての明細についてまとめて返します。
+/// 注文がない場合は404、支払い後の場合は409を返します
+#[utoipa::path(
+  post,
+  path = "/orders/{order_id}/items",
+  tag = "orders",
+  params(("order_id" = String, Path, description = "`ORDER-<uuid>`形式の注文ID")),
+  request_body = AddOrderItemsRequest,
+  responses(
+    (status = 200, description = "明細を追加した注文", body = PlaceOrderResponse),
+    (status = 400, description = "入力の誤り、または与信枠の超過", body = Problem, content_type = "application/problem+json"),
+    (status = 404, description = "注文がない", body = Problem, content_type = "application/problem+json"),
+    (status = 409, description = "支払い後の注文", body = Problem, content_type = "application/problem+json"),
+  ),
+)]
+pub async fn add_order_items(
+  State(state): State<AppState>,
+  Path(order_id): Path<String>,
+  Json(request): Json<AddOrderItemsRequest>,
+) -> Response {
+  let command = AddOrderItems { order_id, items: request.items.into_iter().map(PlaceOrderItem::from).collect() };
+  match state.processor.add_order_items(command) {
+    Ok((order, _)) => (StatusCode::OK, Json(PlaceOrderResponse::from(&order))).into_response(),
+    Err(e) => Problem::from(e).into_response(),
+  }
+}
+
+/// 出荷前の注文の配送先を変更します
+///
+/// 注文がない場合は404、出荷済みの場合は409を返します
+#[utoipa::path(
+  put,
+  path = "/orders/{order_id}/shipping-address",
+  tag = "orders",
+  params(("order_id" = String, Path, description = "`ORDER-<uuid>`形式の注文ID")),
+  request_body = ShippingAddressRequest,
+  responses(
+    (status = 200, description = "変更後の配送先", body = ChangeShippingAddressResponse),
+    (status = 400, description = "入力の誤り", body = Problem, content_type = "application/problem+json"),
+    (status = 404, description = "注文がない", body = Problem, content_type = "application/problem+json"),
+    (status = 409, description = "出荷済み", body = Problem, content_type = "application/problem+json"),
+  ),
+)]
+pub async fn change_shipping_address(
+  State(state): State<AppState>,
+  Path(order_id): Path<String>,
+  Json(request): Json<ShippingAddressRequest>,
+) -> Response {
+  let command = ChangeShippingAddress { order_id, shipping_address: request.into() };
+  match state.processor.change_shipping_address(command) {
+    Ok((order, _)) => (
+      StatusCode::OK,
+      Json(ChangeShippingAddressResponse {
+        order_id: order.get_id().to_string(),
+        shipping_address: (&order.get_shipping().address).into(),
+      }),
+    ).into_response(),
+    Err(e) => Problem::from(e).into_response(),
+  }
+}
+
+/// 支払い済みの注文を出荷します
+///
+/// 注文がない場合は404、支払い済みでない場合や出荷済みの場合は409を返します
+#[utoipa::path(
+  post,
+  path = "/orders/{order_id}/ship",
+  tag = "orders",
+  params(("order_id" = String, Path, description = "`ORDER-<uuid>`形式の注文ID")),
+  responses(
+    (status = 200, description = "出荷した注文", body = ShipOrderResponse),
+    (status = 404, description = "注文がない", body = Problem, content_type = "application/problem+json"),
+    (status = 409, description = "支払い済みでない、または出荷済み", body = Problem, content_type = "application/problem+json"),
+  ),
+)]
+pub async fn ship_order(
+  State(state): State<AppState>,
+  Path(order_id): Path<String>,
+) -> Response {
+  match state.processor.ship_order(ShipOrder { order_id }) {
+    Ok((order, _)) => (
+      StatusCode::OK,
+      Json(ShipOrderResponse { order_id: order.get_id().to_string(), status: order.get_status().to_string() }),
+    ).into_response(),
+    Err(e) => Problem::from(e).into_response(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{app, AppState};
+  use axum::http::StatusCode;
+  use axum_test::TestServer;
+  use chrono::{TimeZone, Utc};
+  use command_domain::clock::FixedClock;
+  use command_domain::id_generator::SequentialIdGenerator;
+  use command_domain::order::order_event_publisher::NoopOrderEventPublisher;
+  use command_domain::product::product_quantity_limits::ProductQuantityLimits;
+  use command_domain::tax::region::Region;
+  use command_domain::tax::tax_rule::{RegionalTaxRule, TaxInclusion, TaxRate, TaxTreatment};
+  use command_domain::value_object::quantity::Quantity;
+  use rust_decimal::Decimal;
+  use serde_json::{json, Value};
+  use std::str::FromStr;
+  use std::sync::Arc;
+
+  fn test_server() -> TestServer {
+    let clock = FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap());
+    let id_generator = SequentialIdGenerator::new(1);
+    let tax_rule = RegionalTaxRule::new(
+      Region::from_str("JP").unwrap(),
+      None,
+      TaxTreatment::new(TaxRate::try_from(Decimal::from(10)).unwrap(), TaxInclusion::Exclusive),
+    );
+    let quantity_limits = ProductQuantityLimits::default().with_limit(1, Quantity::try_from(10).unwrap());
+    let state = AppState::new(Arc::new(clock), Arc::new(id_generator), Arc::new(tax_rule), quantity_limits, Arc::new(NoopOrderEventPublisher));
+    TestServer::new(app(state)).unwrap()
+  }
+
+  fn shipping_address(postal_code: &str) -> Value {
+    json!({
+      "recipient": "山田 太郎",
+      "country": "JP",
+      "postal_code": postal_code,
+      "subdivision": "東京都",
+      "city": "千代田区",
+      "line1": "千代田1-1"
+    })
+  }
+
+  async fn register_customer(server: &TestServer) -> Value {
+    let response = server.post("/customers").json(&json!({ "name": "山田 太郎" })).await;
+    response.json::<Value>()["customer_id"].clone()
+  }
+
+  #[tokio::test]
+  async fn test_place_order_success() {
+    let server = test_server();
+    let customer_id = register_customer(&server).await;
+    let response = server
+      .post("/orders")
+      .json(&json!({
+        "customer_id": customer_id,
+        "currency": "JPY",
+        "region": "JP",
+        "items": [
+          {
+            "product_id": 1,
+            "product_name": "hogehoge",
+            "product_category": "general",
+            "unit_price": 500,
+            "discount": { "type": "percentage", "value": 10 },
+            "quantity": 2
+          }
+        ],
+        "discounts": [
+          { "discount": { "type": "fixed_amount", "value": "100" }, "coupon_code": "WELCOME" }
+        ],
+        "shipping_address": shipping_address("1000001"),
+        "delivery_method": "express"
+      }))
+      .await;
+
+    // assert
+    response.assert_status(StatusCode::CREATED);
+    let body = response.json::<Value>();
+    assert_eq!(body["order_id"], "ORDER-00000000-0000-0001-0000-000000000003");
+    assert_eq!(body["customer_id"], customer_id);
+    assert_eq!(body["ordered_at"], "2024-10-01T09:00:00Z");
+    assert_eq!(body["currency"], "JPY");
+    assert_eq!(body["total_price"], "800");
+    assert_eq!(body["tax_total"], "80");
+    assert_eq!(body["grand_total"], "880");
+    assert_eq!(body["shipping_address"]["postal_code"], "100-0001");
+    assert_eq!(body["delivery_method"], "express");
+  }
+
+  #[tokio::test]
+  async fn test_place_order_failed() {
+    let server = test_server();
+    let customer_id = register_customer(&server).await;
+    let response = server
+      .post("/orders")
+      .json(&json!({
+        "customer_id": customer_id,
+        "currency": "JPY",
+        "region": "JP",
+        "items": [],
+        "shipping_address": shipping_address("100-0001")
+      }))
+      .await;
+    let exceeded = server
+      .post("/orders")
+      .json(&json!({
+        "customer_id": customer_id,
+        "currency": "JPY",
+        "region": "JP",
+        "items": [
+          { "product_id": 1, "product_name": "hogehoge", "product_category": "general", "unit_price": 500, "quantity": 11 }
+        ],
+        "shipping_address": shipping_address("100-0001")
+      }))
+      .await;
+    let invalid_item = server
+      .post("/orders")
+      .json(&json!({
+        "customer_id": customer_id,
+        "currency": "JPY",
+        "region": "JP",
+        "items": [
+          { "product_id": 1, "product_name": "hogehoge", "product_category": "general", "unit_price": 500, "quantity": 1 },
+          { "product_id": 2, "product_name": "fugafuga", "product_category": "general", "unit_price": 500, "quantity": 0 }
+        ],
+        "shipping_address": shipping_address("100-0001")
+      }))
+      .await;
+
+    // assert
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!("order.empty_items", response.json::<Value>()["code"]);
+    exceeded.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!("application/problem+json", exceeded.header("content-type"));
+    let exceeded = exceeded.json::<Value>();
+    assert_eq!("quantity.exceeds_maximum", exceeded["code"]);
+    assert_eq!(400, exceeded["status"]);
+    assert!(exceeded["detail"].as_str().unwrap().contains("exceeds the maximum 10"));
+    invalid_item.assert_status(StatusCode::BAD_REQUEST);
+    let invalid_item = invalid_item.json::<Value>();
+    assert_eq!("quantity.not_positive", invalid_item["code"]);
+    assert_eq!("items[1].quantity", invalid_item["errors"][0]["field"]);
+  }
+
+  #[tokio::test]
+  async fn test_place_order_metrics() {
+    let server = test_server();
+    let customer_id = register_customer(&server).await;
+    server
+      .post("/orders")
+      .json(&json!({
+        "customer_id": customer_id,
+        "currency": "JPY",
+        "region": "JP",
+        "items": [
+          { "product_id": 1, "product_name": "hogehoge", "product_category": "general", "unit_price": 500, "quantity": 1 }
+        ],
+        "shipping_address": shipping_address("100-0001")
+      }))
+      .await;
+    server
+      .post("/orders")
+      .json(&json!({
+        "customer_id": customer_id,
+        "currency": "JPY",
+        "region": "JP",
+        "items": [],
+        "shipping_address": shipping_address("100-0001")
+      }))
+      .await;
+    let response = server.get("/metrics").await;
+
+    // assert
+    response.assert_status_ok();
+    let body = response.text();
+    assert!(body.contains(r#"commands_total{command="place_order",outcome="success"} 1"#));
+    assert!(body.contains(r#"commands_total{command="place_order",outcome="rejected"} 1"#));
+    assert!(body.contains(r#"events_appended_total{aggregate_type="order"}"#));
+    assert!(body.contains(r#"store_operation_duration_seconds_count{aggregate_type="order",operation="insert"} 1"#));
+    assert!(body.contains(r#"http_request_duration_seconds_count{method="POST",route="/orders",status="201"} 1"#));
+  }
+
+  #[tokio::test]
+  async fn test_place_order_returns_all_validation_errors() {
+    let server = test_server();
+    let customer_id = register_customer(&server).await;
+    let response = server
+      .post("/orders")
+      .json(&json!({
+        "customer_id": customer_id,
+        "currency": "JPY",
+        "region": "JP",
+        "items": [
+          { "product_id": 1, "product_name": "", "product_category": "general", "unit_price": 500, "quantity": 0 },
+          { "product_id": 2, "product_name": "fugafuga", "product_category": "general", "unit_price": 500, "quantity": 1 },
+          { "product_id": 3, "product_name": "piyopiyo", "product_category": "", "unit_price": -1, "quantity": 1 }
+        ],
+        "shipping_address": shipping_address("100-0001")
+      }))
+      .await;
+
+    // assert
+    response.assert_status(StatusCode::BAD_REQUEST);
+    let body = response.json::<Value>();
+    assert_eq!("command.validation_failed", body["code"]);
+    let fields = body["errors"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|error| error["field"].as_str().unwrap())
+      .collect::<Vec<_>>();
+    assert_eq!(vec!["items[0].quantity", "items[0].product_name", "items[2].unit_price", "items[2].product_category"], fields);
+  }
+
+  #[tokio::test]
+  async fn test_change_shipping_address_success() {
+    let server = test_server();
+    let customer_id = register_customer(&server).await;
+    let placed = server
+      .post("/orders")
+      .json(&json!({
+        "customer_id": customer_id,
+        "currency": "JPY",
+        "region": "JP",
+        "items": [
+          { "product_id": 1, "product_name": "hogehoge", "product_category": "general", "unit_price": 500, "quantity": 1 }
+        ],
+        "shipping_address": shipping_address("100-0001")
+      }))
+      .await;
+    let order_id = placed.json::<Value>()["order_id"].as_str().unwrap().to_string();
+    let response = server
+      .put(&format!("/orders/{}/shipping-address", order_id))
+      .json(&shipping_address("150-0001"))
+      .await;
+
+    // assert
+    response.assert_status(StatusCode::OK);
+    assert_eq!(response.json::<Value>()["shipping_address"]["postal_code"], "150-0001");
+  }
+
+  #[tokio::test]
+  async fn test_change_shipping_address_failed() {
+    let server = test_server();
+    let not_found = server
+      .put("/orders/ORDER-00000000-0000-0001-0000-000000000009/shipping-address")
+      .json(&shipping_address("150-0001"))
+      .await;
+    let invalid = server
+      .put("/orders/ORDER-00000000-0000-0001-0000-000000000009/shipping-address")
+      .json(&shipping_address("150"))
+      .await;
+
+    // assert
+    not_found.assert_status(StatusCode::NOT_FOUND);
+    invalid.assert_status(StatusCode::BAD_REQUEST);
+  }
+
+  #[tokio::test]
+  async fn test_add_order_items_success() {
+    let server = test_server();
+    let customer_id = register_customer(&server).await;
+    let placed = server
+      .post("/orders")
+      .json(&json!({
+        "customer_id": customer_id,
+        "currency": "JPY",
+        "region": "JP",
+        "items": [
+          { "product_id": 1, "product_name": "hogehoge", "product_category": "general", "unit_price": 500, "quantity": 1 }
+        ],
+        "shipping_address": shipping_address("100-0001")
+      }))
+      .await;
+    let order_id = placed.json::<Value>()["order_id"].as_str().unwrap().to_string();
+    let response = server
+      .post(&format!("/orders/{}/items", order_id))
+      .json(&json!({
+        "items": [
+          { "product_id": 2, "product_name": "fugafuga", "product_category": "general", "unit_price": 300, "quantity": 2 }
+        ]
+      }))
+      .await;
+
+    // assert
+    response.assert_status(StatusCode::OK);
+    let body = response.json::<Value>();
+    assert_eq!(2, body["items"].as_array().unwrap().len());
+    assert_eq!(body["total_price"], "1100");
+    assert_eq!(body["grand_total"], "1210");
+  }
+
+  #[tokio::test]
+  async fn test_add_order_items_returns_all_validation_errors() {
+    let server = test_server();
+    let customer_id = register_customer(&server).await;
+    let placed = server
+      .post("/orders")
+      .json(&json!({
+        "customer_id": customer_id,
+        "currency": "JPY",
+        "region": "JP",
+        "items": [
+          { "product_id": 1, "product_name": "hogehoge", "product_category": "general", "unit_price": 500, "quantity": 1 }
+        ],
+        "shipping_address": shipping_address("100-0001")
+      }))
+      .await;
+    let order_id = placed.json::<Value>()["order_id"].as_str().unwrap().to_string();
+    let response = server
+      .post(&format!("/orders/{}/items", order_id))
+      .json(&json!({
+        "items": [
+          { "product_id": 2, "product_name": "", "product_category": "general", "unit_price": 500, "quantity": 0 },
+          { "product_id": 3, "product_name": "piyopiyo", "product_category": "", "unit_price": -1, "quantity": 1 }
+        ]
+      }))
+      .await;
+    let not_found = server
+      .post("/orders/ORDER-00000000-0000-0001-0000-000000000009/items")
+      .json(&json!({ "items": [] }))
+      .await;
+
+    // assert
+    response.assert_status(StatusCode::BAD_REQUEST);
+    let fields = response.json::<Value>()["errors"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|error| error["field"].as_str().unwrap().to_string())
+      .collect::<Vec<_>>();
+    assert_eq!(vec!["items[0].quantity", "items[0].product_name", "items[1].unit_price", "items[1].product_category"], fields);
+    not_found.assert_status(StatusCode::NOT_FOUND);
+  }
+}
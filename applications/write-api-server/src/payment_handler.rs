@@ -0,0 +1,379 @@
+use crate::problem::Problem;
+use crate::AppState;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use command_domain::order::order_event::OrderEvent;
+use command_domain::payment::Payment;
+use command_processor::command::{AuthorizePayment, CapturePayment, RefundPayment, RetryRefunds, ReturnItemValue, ReturnItems};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// 返金のリクエストです
+///
+/// amountを省略した場合は返金可能な残額をすべて返金します
+#[derive(Deserialize, Debug, Default, ToSchema)]
+pub struct RefundPaymentRequest {
+  #[serde(default)]
+  amount: Option<Decimal>,
+}
+
+/// 返品のリクエストです
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct ReturnItemsRequest {
+  items: Vec<ReturnItemRequest>,
+}
+
+/// 返品する明細のリクエストです
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct ReturnItemRequest {
+  order_item_id: String,
+  quantity: i32,
+}
+
+/// 返品のレスポンスです
+///
+/// refund_amountは今回の返品による返金額です
+#[derive(Serialize, Debug, ToSchema)]
+pub struct ReturnItemsResponse {
+  order_id: String,
+  status: String,
+  refund_amount: Decimal,
+  items: Vec<ReturnedItemResponse>,
+}
+
+/// 返品された明細のレスポンスです
+#[derive(Serialize, Debug, ToSchema)]
+pub struct ReturnedItemResponse {
+  order_item_id: String,
+  quantity: i32,
+  refund_amount: Decimal,
+}
+
+/// 支払いのレスポンスです
+#[derive(Serialize, Debug, ToSchema)]
+pub struct PaymentResponse {
+  payment_id: String,
+  order_id: String,
+  status: String,
+  amount: Decimal,
+  refunded_amount: Decimal,
+  pending_refund_amount: Decimal,
+  currency: String,
+}
+
+impl From<&Payment> for PaymentResponse {
+  fn from(value: &Payment) -> Self {
+    PaymentResponse {
+      payment_id: value.get_id().to_string(),
+      order_id: value.get_order_id().to_string(),
+      status: value.get_status().to_string(),
+      amount: *value.get_amount().amount(),
+      refunded_amount: *value.get_refunded_amount().amount(),
+      pending_refund_amount: *value.get_pending_refund_amount().amount(),
+      currency: value.get_amount().currency().to_string(),
+    }
+  }
+}
+
+/// 注文の支払いをオーソリします
+///
+/// 決済事業者に拒否された場合も支払いは作成され、statusがfailedになります
+#[utoipa::path(
+  post,
+  path = "/orders/{order_id}/payments",
+  tag = "payments",
+  params(("order_id" = String, Path, description = "`ORDER-<uuid>`形式の注文ID")),
+  responses(
+    (status = 201, description = "オーソリした支払い", body = PaymentResponse),
+    (status = 400, description = "入力の誤り", body = Problem, content_type = "application/problem+json"),
+    (status = 404, description = "注文がない", body = Problem, content_type = "application/problem+json"),
+    (status = 409, description = "注文の状態による競合", body = Problem, content_type = "application/problem+json"),
+    (status = 503, description = "決済サービスを利用できない", body = Problem, content_type = "application/problem+json"),
+  ),
+)]
+pub async fn authorize_payment(
+  State(state): State<AppState>,
+  Path(order_id): Path<String>,
+) -> Response {
+  match state.payment_processor.authorize_payment(AuthorizePayment { order_id }) {
+    Ok((payment, _)) => (StatusCode::CREATED, Json(PaymentResponse::from(&payment))).into_response(),
+    Err(e) => Problem::from(e).into_response(),
+  }
+}
+
+/// 支払いの売上を確定します
+#[utoipa::path(
+  post,
+  path = "/payments/{payment_id}/capture",
+  tag = "payments",
+  params(("payment_id" = String, Path, description = "`PAYMENT-<uuid>`形式の支払いID")),
+  responses(
+    (status = 200, description = "売上を確定した支払い", body = PaymentResponse),
+    (status = 404, description = "支払いがない", body = Problem, content_type = "application/problem+json"),
+    (status = 409, description = "支払いの状態による競合", body = Problem, content_type = "application/problem+json"),
+    (status = 503, description = "決済サービスを利用できない", body = Problem, content_type = "application/problem+json"),
+  ),
+)]
+pub async fn capture_payment(
+  State(state): State<AppState>,
+  Path(payment_id): Path<String>,
+) -> Response {
+  match state.payment_processor.capture_payment(CapturePayment { payment_id }) {
+    Ok((payment, _)) => (StatusCode::OK, Json(PaymentResponse::from(&payment))).into_response(),
+    Err(e) => Problem::from(e).into_response(),
+  }
+}
+
+/// 支払いを返金します
+#[utoipa::path(
+  post,
+  path = "/payments/{payment_id}/refunds",
+  tag = "payments",
+  params(("payment_id" = String, Path, description = "`PAYMENT-<uuid>`形式の支払いID")),
+  request_body = RefundPaymentRequest,
+  responses(
+    (status = 200, description = "返金した支払い", body = PaymentResponse),
+    (status = 400, description = "入力の誤り", body = Problem, content_type = "application/problem+json"),
+    (status = 404, description = "支払いがない", body = Problem, content_type = "application/problem+json"),
+    (status = 409, description = "支払いの状態による競合", body = Problem, content_type = "application/problem+json"),
+    (status = 503, description = "決済サービスを利用できない", body = Problem, content_type = "application/problem+json"),
+  ),
+)]
+pub async fn refund_payment(
+  State(state): State<AppState>,
+  Path(payment_id): Path<String>,
+  Json(request): Json<RefundPaymentRequest>,
+) -> Response {
+  match state.payment_processor.refund_payment(RefundPayment { payment_id, amount: request.amount }) {
+    Ok((payment, _)) => (StatusCode::OK, Json(PaymentResponse::from(&payment))).into_response(),
+    Err(e) => Problem::from(e).into_response(),
+  }
+}
+
+/// 決済サービスの結果が得られずに残った返金を再実行します
+///
+/// 再実行する返金がない場合は、支払いをそのまま返します
+#[utoipa::path(
+  post,
+  path = "/payments/{payment_id}/refunds/retry",
+  tag = "payments",
+  params(("payment_id" = String, Path, description = "`PAYMENT-<uuid>`形式の支払いID")),
+  responses(
+    (status = 200, description = "返金を再実行した支払い", body = PaymentResponse),
+    (status = 404, description = "支払いがない", body = Problem, content_type = "application/problem+json"),
+    (status = 409, description = "支払いの状態による競合", body = Problem, content_type = "application/problem+json"),
+    (status = 503, description = "決済サービスを利用できない", body = Problem, content_type = "application/problem+json"),
+  ),
+)]
+pub async fn retry_refunds(
+  State(state): State<AppState>,
+  Path(payment_id): Path<String>,
+) -> Response {
+  match state.payment_processor.retry_refunds(RetryRefunds { payment_id }) {
+    Ok((payment, _)) => (StatusCode::OK, Json(PaymentResponse::from(&payment))).into_response(),
+    Err(e) => Problem::from(e).into_response(),
+  }
+}
+
+/// 出荷済みの注文の明細を返品し、返金します
+#[utoipa::path(
+  post,
+  path = "/orders/{order_id}/returns",
+  tag = "payments",
+  params(("order_id" = String, Path, description = "`ORDER-<uuid>`形式の注文ID")),
+  request_body = ReturnItemsRequest,
+  responses(
+    (status = 201, description = "返品した明細と返金額", body = ReturnItemsResponse),
+    (status = 400, description = "入力の誤り", body = Problem, content_type = "application/problem+json"),
+    (status = 404, description = "注文がない", body = Problem, content_type = "application/problem+json"),
+    (status = 409, description = "出荷済みでない、または売上が確定した支払いがない", body = Problem, content_type = "application/problem+json"),
+    (status = 503, description = "決済サービスを利用できない", body = Problem, content_type = "application/problem+json"),
+  ),
+)]
+pub async fn return_items(
+  State(state): State<AppState>,
+  Path(order_id): Path<String>,
+  Json(request): Json<ReturnItemsRequest>,
+) -> Response {
+  let command = ReturnItems {
+    order_id,
+    items: request.items
+      .into_iter()
+      .map(|item| ReturnItemValue { order_item_id: item.order_item_id, quantity: item.quantity })
+      .collect(),
+  };
+  match state.payment_processor.return_items(command) {
+    Ok((order, events)) => {
+      let Some(OrderEvent::ItemsReturned(returned)) = events.first() else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+      };
+      let response = ReturnItemsResponse {
+        order_id: order.get_id().to_string(),
+        status: order.get_status().to_string(),
+        refund_amount: *returned.refund_amount.amount(),
+        items: returned.items
+          .iter()
+          .map(|item| ReturnedItemResponse {
+            order_item_id: item.order_item_id.to_string(),
+            quantity: item.quantity,
+            refund_amount: *item.refund_amount.amount(),
+          })
+          .collect(),
+      };
+      (StatusCode::CREATED, Json(response)).into_response()
+    }
+    Err(e) => Problem::from(e).into_response(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{app, AppState};
+  use axum::http::StatusCode;
+  use axum_test::TestServer;
+  use chrono::{TimeZone, Utc};
+  use command_domain::clock::FixedClock;
+  use command_domain::id_generator::SequentialIdGenerator;
+  use command_domain::order::order_event_publisher::NoopOrderEventPublisher;
+  use command_domain::product::product_quantity_limits::ProductQuantityLimits;
+  use command_domain::tax::tax_rule::TaxRules;
+  use serde_json::{json, Value};
+  use std::sync::Arc;
+
+  fn test_server() -> TestServer {
+    let clock = FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap());
+    let state = AppState::new(Arc::new(clock), Arc::new(SequentialIdGenerator::new(1)), Arc::new(TaxRules::default()), ProductQuantityLimits::default(), Arc::new(NoopOrderEventPublisher));
+    TestServer::new(app(state)).unwrap()
+  }
+
+  async fn place_order(server: &TestServer) -> Value {
+    let customer = server.post("/customers").json(&json!({ "name": "山田 太郎" })).await;
+    let order = server
+      .post("/orders")
+      .json(&json!({
+        "customer_id": customer.json::<Value>()["customer_id"],
+        "currency": "JPY",
+        "region": "JP",
+        "items": [
+          { "product_id": 1, "product_name": "hogehoge", "product_category": "general", "unit_price": 500, "quantity": 2 }
+        ],
+        "shipping_address": {
+          "recipient": "山田 太郎",
+          "country": "JP",
+          "postal_code": "100-0001",
+          "subdivision": "東京都",
+          "city": "千代田区",
+          "line1": "千代田1-1"
+        }
+      }))
+      .await;
+    order.json::<Value>()
+  }
+
+  #[tokio::test]
+  async fn test_authorize_capture_and_refund_payment_success() {
+    let server = test_server();
+    let order_id = place_order(&server).await["order_id"].as_str().unwrap().to_string();
+
+    let authorized = server.post(&format!("/orders/{}/payments", order_id)).await;
+    let payment_id = authorized.json::<Value>()["payment_id"].as_str().unwrap().to_string();
+    let captured = server.post(&format!("/payments/{}/capture", payment_id)).await;
+    let partial = server.post(&format!("/payments/{}/refunds", payment_id)).json(&json!({ "amount": "400" })).await;
+    let refunded = server.post(&format!("/payments/{}/refunds", payment_id)).json(&json!({})).await;
+    let retried = server.post(&format!("/payments/{}/refunds/retry", payment_id)).await;
+
+    // assert
+    authorized.assert_status(StatusCode::CREATED);
+    assert_eq!("authorized", authorized.json::<Value>()["status"]);
+    assert_eq!("1000", authorized.json::<Value>()["amount"]);
+    captured.assert_status(StatusCode::OK);
+    assert_eq!("captured", captured.json::<Value>()["status"]);
+    assert_eq!("partially_refunded", partial.json::<Value>()["status"]);
+    refunded.assert_status(StatusCode::OK);
+    assert_eq!("refunded", refunded.json::<Value>()["status"]);
+    assert_eq!("1000", refunded.json::<Value>()["refunded_amount"]);
+    assert_eq!("0", refunded.json::<Value>()["pending_refund_amount"]);
+    retried.assert_status(StatusCode::OK);
+    assert_eq!("refunded", retried.json::<Value>()["status"]);
+  }
+
+  #[tokio::test]
+  async fn test_return_items_success() {
+    let server = test_server();
+    let order = place_order(&server).await;
+    let order_id = order["order_id"].as_str().unwrap();
+    let order_item_id = order["items"][0]["order_item_id"].clone();
+    let authorized = server.post(&format!("/orders/{}/payments", order_id)).await;
+    let payment_id = authorized.json::<Value>()["payment_id"].as_str().unwrap().to_string();
+    server.post(&format!("/payments/{}/capture", payment_id)).await;
+    let shipped = server.post(&format!("/orders/{}/ship", order_id)).await;
+
+    let partial = server
+      .post(&format!("/orders/{}/returns", order_id))
+      .json(&json!({ "items": [{ "order_item_id": order_item_id, "quantity": 1 }] }))
+      .await;
+    let exceeded = server
+      .post(&format!("/orders/{}/returns", order_id))
+      .json(&json!({ "items": [{ "order_item_id": order_item_id, "quantity": 2 }] }))
+      .await;
+    let returned = server
+      .post(&format!("/orders/{}/returns", order_id))
+      .json(&json!({ "items": [{ "order_item_id": order_item_id, "quantity": 1 }] }))
+      .await;
+
+    // assert
+    shipped.assert_status(StatusCode::OK);
+    assert_eq!("shipped", shipped.json::<Value>()["status"]);
+    partial.assert_status(StatusCode::CREATED);
+    assert_eq!("500", partial.json::<Value>()["refund_amount"]);
+    assert_eq!("shipped", partial.json::<Value>()["status"]);
+    exceeded.assert_status(StatusCode::BAD_REQUEST);
+    returned.assert_status(StatusCode::CREATED);
+    assert_eq!("refunded", returned.json::<Value>()["status"]);
+    assert_eq!(1, returned.json::<Value>()["items"][0]["quantity"]);
+  }
+
+  #[tokio::test]
+  async fn test_return_items_failed() {
+    let server = test_server();
+    let order = place_order(&server).await;
+    let order_id = order["order_id"].as_str().unwrap();
+    let request = json!({ "items": [{ "order_item_id": order["items"][0]["order_item_id"], "quantity": 1 }] });
+
+    let not_shipped = server.post(&format!("/orders/{}/returns", order_id)).json(&request).await;
+    let ship_unpaid = server.post(&format!("/orders/{}/ship", order_id)).await;
+    let not_found = server
+      .post("/orders/ORDER-00000000-0000-0000-0000-000000000000/returns")
+      .json(&request)
+      .await;
+
+    // assert
+    not_shipped.assert_status(StatusCode::CONFLICT);
+    ship_unpaid.assert_status(StatusCode::CONFLICT);
+    not_found.assert_status(StatusCode::NOT_FOUND);
+  }
+
+  #[tokio::test]
+  async fn test_payment_failed() {
+    let server = test_server();
+    let order_id = place_order(&server).await["order_id"].as_str().unwrap().to_string();
+    let authorized = server.post(&format!("/orders/{}/payments", order_id)).await;
+    let payment_id = authorized.json::<Value>()["payment_id"].as_str().unwrap().to_string();
+
+    let duplicated = server.post(&format!("/orders/{}/payments", order_id)).await;
+    let not_captured = server.post(&format!("/payments/{}/refunds", payment_id)).json(&json!({})).await;
+    let order_not_found = server.post("/orders/ORDER-00000000-0000-0000-0000-000000000000/payments").await;
+    let payment_not_found = server.post("/payments/PAYMENT-00000000-0000-0000-0000-000000000000/capture").await;
+    let invalid_id = server.post("/payments/hoge/capture").await;
+
+    // assert
+    duplicated.assert_status(StatusCode::CONFLICT);
+    not_captured.assert_status(StatusCode::CONFLICT);
+    order_not_found.assert_status(StatusCode::NOT_FOUND);
+    payment_not_found.assert_status(StatusCode::NOT_FOUND);
+    invalid_id.assert_status(StatusCode::BAD_REQUEST);
+  }
+}
@@ -0,0 +1,152 @@
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use command_domain::error_code::ErrorCode;
+use command_processor::command_error::{CommandError, ErrorKind};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// エラーレスポンスのContent-Typeです
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// RFC 7807のProblem Detailsによるエラーレスポンスです
+///
+/// type: 問題の種類(エラーコードで区別するためabout:blank)
+///
+/// title: ステータスコードの説明
+///
+/// code: 機械可読なエラーコード
+///
+/// errors: 入力のフィールドごとのエラー
+#[derive(Serialize, Debug, ToSchema)]
+pub struct Problem {
+  #[serde(rename = "type")]
+  problem_type: &'static str,
+  title: &'static str,
+  #[serde(skip)]
+  status_code: StatusCode,
+  status: u16,
+  detail: String,
+  code: &'static str,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  errors: Vec<FieldProblem>,
+}
+
+/// 入力のフィールドのエラーです
+///
+/// field: `items[2].quantity` 形式のフィールドパス
+#[derive(Serialize, Debug, ToSchema)]
+pub struct FieldProblem {
+  field: String,
+  code: &'static str,
+  detail: String,
+}
+
+impl Problem {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `status_code` - ステータスコード
+  /// * `code` - 機械可読なエラーコード
+  /// * `detail` - 人が読むための説明
+  pub fn new(status_code: StatusCode, code: &'static str, detail: impl Into<String>) -> Self {
+    Self {
+      problem_type: "about:blank",
+      title: status_code.canonical_reason().unwrap_or("Unknown Error"),
+      status_code,
+      status: status_code.as_u16(),
+      detail: detail.into(),
+      code,
+      errors: vec![],
+    }
+  }
+}
+
+impl From<CommandError> for Problem {
+  fn from(e: CommandError) -> Self {
+    let detail = e.to_string();
+    let errors = e.field_errors()
+      .into_iter()
+      .map(|error| FieldProblem { field: error.field.to_string(), code: error.code, detail: error.message })
+      .collect();
+    Self { errors, ..Self::new(status_code(&e), e.code(), detail) }
+  }
+}
+
+impl IntoResponse for Problem {
+  fn into_response(self) -> Response {
+    (self.status_code, [(CONTENT_TYPE, PROBLEM_JSON)], Json(self)).into_response()
+  }
+}
+
+/// コマンドのエラーに対応するステータスコードを返します
+///
+/// 対象が見つからない場合は404、状態や同時更新による競合は409、決済サービスが利用できない場合は503、
+/// それ以外は入力の誤りとして400になります
+fn status_code(e: &CommandError) -> StatusCode {
+  match e.kind() {
+    ErrorKind::NotFound => StatusCode::NOT_FOUND,
+    ErrorKind::InvalidState | ErrorKind::Conflict => StatusCode::CONFLICT,
+    ErrorKind::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+    ErrorKind::Invalid => StatusCode::BAD_REQUEST,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use command_domain::customer::customer_error::CustomerError;
+  use command_domain::order::order_error::OrderError;
+  use command_domain::payment::payment_error::PaymentError;
+  use command_domain::payment::payment_provider::PaymentProviderError;
+  use command_domain::promotion::promotion_error::PromotionError;
+  use command_domain::repository::RepositoryError;
+  use command_domain::value_object::quantity::QuantityError;
+  use serde_json::{json, Value};
+
+  #[test]
+  fn test_problem_from_command_error() {
+    let error = CommandError::from(OrderError::from(QuantityError::NotPositive(0)).at_item(1));
+
+    let problem = serde_json::to_value(Problem::from(error)).unwrap();
+
+    // assert
+    assert_eq!(
+      json!({
+        "type": "about:blank",
+        "title": "Bad Request",
+        "status": 400,
+        "detail": "Invalid Order Item at index 1: Invalid Quantity: quantity must be greater than 0: 0",
+        "code": "quantity.not_positive",
+        "errors": [{
+          "field": "items[1].quantity",
+          "code": "quantity.not_positive",
+          "detail": "Invalid Order Item at index 1: Invalid Quantity: quantity must be greater than 0: 0",
+        }],
+      }),
+      problem,
+    );
+  }
+
+  #[test]
+  fn test_problem_status_code() {
+    let not_found = Problem::from(CommandError::from(OrderError::OrderNotFound("order-1".to_string())));
+    let customer_not_found = Problem::from(CommandError::from(CustomerError::CustomerNotFound("customer-1".to_string())));
+    let coupon_not_found = Problem::from(CommandError::from(PromotionError::CouponNotFound("SAVE100".to_string())));
+    let repository_not_found = Problem::from(CommandError::from(RepositoryError::NotFound("order-1".to_string())));
+    let invalid = Problem::from(CommandError::from(OrderError::EmptyOrderItems));
+    let conflict = Problem::from(CommandError::ConcurrencyConflict("order-1".to_string()));
+    let unavailable = Problem::from(CommandError::from(PaymentError::from(PaymentProviderError::Unavailable("down".to_string()))));
+
+    // assert
+    assert_eq!(StatusCode::NOT_FOUND, not_found.status_code);
+    assert_eq!(StatusCode::NOT_FOUND, customer_not_found.status_code);
+    assert_eq!(StatusCode::NOT_FOUND, coupon_not_found.status_code);
+    assert_eq!(StatusCode::NOT_FOUND, repository_not_found.status_code);
+    assert_eq!(StatusCode::BAD_REQUEST, invalid.status_code);
+    assert_eq!(StatusCode::CONFLICT, conflict.status_code);
+    assert_eq!(StatusCode::SERVICE_UNAVAILABLE, unavailable.status_code);
+    assert_eq!(Value::Null, serde_json::to_value(&conflict).unwrap()["errors"]);
+  }
+}
@@ -0,0 +1,163 @@
+use crate::order_handler::DiscountRequest;
+use crate::problem::Problem;
+use crate::AppState;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use chrono::{DateTime, Utc};
+use command_processor::command::CreatePromotion;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// プロモーション作成のリクエストです
+///
+/// categoryを省略した場合は注文全体への割引になります
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct CreatePromotionRequest {
+  coupon_code: String,
+  discount: DiscountRequest,
+  currency: String,
+  #[serde(default)]
+  category: Option<String>,
+  #[serde(default)]
+  stackable: bool,
+  valid_from: DateTime<Utc>,
+  #[serde(default)]
+  valid_until: Option<DateTime<Utc>>,
+  #[serde(default)]
+  usage_limit: Option<u32>,
+  #[serde(default)]
+  per_customer_limit: Option<u32>,
+}
+
+impl From<CreatePromotionRequest> for CreatePromotion {
+  fn from(value: CreatePromotionRequest) -> Self {
+    CreatePromotion {
+      coupon_code: value.coupon_code,
+      discount: value.discount.into(),
+      currency: value.currency,
+      category: value.category,
+      stackable: value.stackable,
+      valid_from: value.valid_from,
+      valid_until: value.valid_until,
+      usage_limit: value.usage_limit,
+      per_customer_limit: value.per_customer_limit,
+    }
+  }
+}
+
+/// プロモーション作成のレスポンスです
+#[derive(Serialize, Debug, ToSchema)]
+pub struct CreatePromotionResponse {
+  promotion_id: String,
+  coupon_code: String,
+}
+
+/// プロモーションを作成します
+///
+/// 同じクーポンコードのプロモーションがある場合は409を返します
+#[utoipa::path(
+  post,
+  path = "/promotions",
+  tag = "promotions",
+  request_body = CreatePromotionRequest,
+  responses(
+    (status = 201, description = "作成したプロモーション", body = CreatePromotionResponse),
+    (status = 400, description = "入力の誤り", body = Problem, content_type = "application/problem+json"),
+    (status = 409, description = "同じクーポンコードのプロモーションがある", body = Problem, content_type = "application/problem+json"),
+  ),
+)]
+pub async fn create_promotion(
+  State(state): State<AppState>,
+  Json(request): Json<CreatePromotionRequest>,
+) -> Response {
+  match state.promotion_processor.create_promotion(request.into()) {
+    Ok((promotion, _)) => (
+      StatusCode::CREATED,
+      Json(CreatePromotionResponse {
+        promotion_id: promotion.get_id().to_string(),
+        coupon_code: promotion.get_coupon_code().to_string(),
+      }),
+    ).into_response(),
+    Err(e) => Problem::from(e).into_response(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{app, AppState};
+  use axum::http::StatusCode;
+  use axum_test::TestServer;
+  use chrono::{TimeZone, Utc};
+  use command_domain::clock::FixedClock;
+  use command_domain::id_generator::SequentialIdGenerator;
+  use command_domain::order::order_event_publisher::NoopOrderEventPublisher;
+  use command_domain::product::product_quantity_limits::ProductQuantityLimits;
+  use command_domain::tax::tax_rule::TaxRules;
+  use serde_json::{json, Value};
+  use std::sync::Arc;
+
+  fn test_server() -> TestServer {
+    let clock = FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap());
+    let state = AppState::new(Arc::new(clock), Arc::new(SequentialIdGenerator::new(1)), Arc::new(TaxRules::default()), ProductQuantityLimits::default(), Arc::new(NoopOrderEventPublisher));
+    TestServer::new(app(state)).unwrap()
+  }
+
+  fn create_promotion_request() -> Value {
+    json!({
+      "coupon_code": "autumn",
+      "discount": { "type": "fixed_amount", "value": "100" },
+      "currency": "JPY",
+      "valid_from": "2024-10-01T00:00:00Z",
+      "usage_limit": 1
+    })
+  }
+
+  fn place_order_request(customer_id: &Value) -> Value {
+    json!({
+      "customer_id": customer_id,
+      "currency": "JPY",
+      "region": "JP",
+      "items": [
+        { "product_id": 1, "product_name": "hogehoge", "product_category": "general", "unit_price": 500, "quantity": 2 }
+      ],
+      "coupon_code": "AUTUMN",
+      "shipping_address": {
+        "recipient": "山田 太郎",
+        "country": "JP",
+        "postal_code": "100-0001",
+        "subdivision": "東京都",
+        "city": "千代田区",
+        "line1": "千代田1-1"
+      }
+    })
+  }
+
+  #[tokio::test]
+  async fn test_create_promotion_and_redeem_success() {
+    let server = test_server();
+    let customer = server.post("/customers").json(&json!({ "name": "山田 太郎" })).await;
+    let customer_id = customer.json::<Value>()["customer_id"].clone();
+    let created = server.post("/promotions").json(&create_promotion_request()).await;
+    let placed = server.post("/orders").json(&place_order_request(&customer_id)).await;
+    let exhausted = server.post("/orders").json(&place_order_request(&customer_id)).await;
+
+    // assert
+    created.assert_status(StatusCode::CREATED);
+    assert_eq!(created.json::<Value>()["coupon_code"], "AUTUMN");
+    placed.assert_status(StatusCode::CREATED);
+    assert_eq!(placed.json::<Value>()["total_price"], "900");
+    exhausted.assert_status(StatusCode::BAD_REQUEST);
+  }
+
+  #[tokio::test]
+  async fn test_create_promotion_duplicate_failed() {
+    let server = test_server();
+    server.post("/promotions").json(&create_promotion_request()).await;
+    let response = server.post("/promotions").json(&create_promotion_request()).await;
+
+    // assert
+    response.assert_status(StatusCode::CONFLICT);
+  }
+}
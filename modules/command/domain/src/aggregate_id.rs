@@ -1,3 +1,13 @@
+use crate::error_code::ErrorCode;
+use crate::id_generator::IdGenerator;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::str::FromStr;
+use thiserror::Error;
+use uuid::Uuid;
+
 /// 集約ID用のトレイトです
 ///
 /// 各集約IDはAggregateIdを実装しなければなりません
@@ -7,4 +17,268 @@
 pub trait AggregateId {
   fn type_name(&self) -> String;
   fn value(&self) -> String;
-}
\ No newline at end of file
+}
+
+/// IDの種類ごとのプレフィックスです
+///
+/// `PrefixedId`の型引数として、IDの種類を区別するために使います
+pub trait IdPrefix {
+  /// `Display`で付けるプレフィックス(例: `ORDER`)
+  const PREFIX: &'static str;
+}
+
+/// `<prefix>-<uuid>`形式で表現するIDです
+///
+/// `Display`は`<prefix>-<uuid>`形式、serdeはUUIDのみの形式で表現します。
+/// `FromStr`とデシリアライズはどちらの形式も受け付け、別のプレフィックスの場合はエラーにします
+///
+/// 各IDはプレフィックスを型引数にした型エイリアスとして定義します(例: `OrderId = PrefixedId<OrderIdPrefix>`)
+///
+/// 新しいIDは注入された`IdGenerator`を使って`generate`で生成します
+pub struct PrefixedId<P: IdPrefix> {
+  value: Uuid,
+  prefix: PhantomData<fn() -> P>,
+}
+
+impl<P: IdPrefix> PrefixedId<P> {
+  /// 指定したIdGeneratorでIDを生成します
+  ///
+  /// # Arguments
+  /// * `id_generator`: &dyn IdGenerator
+  ///
+  /// # Return
+  /// * `PrefixedId<P>`
+  pub fn generate(id_generator: &dyn IdGenerator) -> Self {
+    Self::from(id_generator.generate())
+  }
+}
+
+impl<P: IdPrefix> Clone for PrefixedId<P> {
+  fn clone(&self) -> Self {
+    Self::from(self.value)
+  }
+}
+
+impl<P: IdPrefix> PartialEq for PrefixedId<P> {
+  fn eq(&self, other: &Self) -> bool {
+    self.value == other.value
+  }
+}
+
+impl<P: IdPrefix> Eq for PrefixedId<P> {}
+
+impl<P: IdPrefix> PartialOrd for PrefixedId<P> {
+  fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl<P: IdPrefix> Ord for PrefixedId<P> {
+  fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    self.value.cmp(&other.value)
+  }
+}
+
+impl<P: IdPrefix> Hash for PrefixedId<P> {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.value.hash(state)
+  }
+}
+
+impl<P: IdPrefix> AggregateId for PrefixedId<P> {
+  fn type_name(&self) -> String {
+    P::PREFIX.to_string()
+  }
+  fn value(&self) -> String {
+    self.value.to_string()
+  }
+}
+
+impl<P: IdPrefix> Display for PrefixedId<P> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}-{}", P::PREFIX, self.value)
+  }
+}
+
+impl<P: IdPrefix> Debug for PrefixedId<P> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    Display::fmt(self, f)
+  }
+}
+
+impl<P: IdPrefix> From<Uuid> for PrefixedId<P> {
+  fn from(value: Uuid) -> Self {
+    Self { value, prefix: PhantomData }
+  }
+}
+
+impl<P: IdPrefix> FromStr for PrefixedId<P> {
+  type Err = AggregateIdError;
+
+  /// `<prefix>-<uuid>`形式とUUIDのみの形式を受け付けます
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Ok(Self::from(parse_prefixed_id(s, P::PREFIX)?))
+  }
+}
+
+impl<P: IdPrefix> Serialize for PrefixedId<P> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.value.serialize(serializer)
+  }
+}
+
+impl<'de, P: IdPrefix> Deserialize<'de> for PrefixedId<P> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    Self::from_str(&value).map_err(serde::de::Error::custom)
+  }
+}
+
+/// 集約IDのパースエラーです
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+pub enum AggregateIdError {
+  #[error("invalid id prefix: expected {expected}, but got {actual}")]
+  InvalidPrefix { expected: String, actual: String },
+
+  #[error("invalid id format: {0}")]
+  InvalidFormat(String),
+}
+
+impl ErrorCode for AggregateIdError {
+  fn code(&self) -> &'static str {
+    match self {
+      AggregateIdError::InvalidPrefix { .. } => "id.invalid_prefix",
+      AggregateIdError::InvalidFormat(_) => "id.invalid_format",
+    }
+  }
+}
+
+/// ハイフン区切りのUUIDの文字数です
+const HYPHENATED_UUID_LEN: usize = 36;
+
+/// `<prefix>-<uuid>`形式、またはUUIDのみの文字列をパースします
+///
+/// # Arguments
+/// * `value`: パース対象の文字列
+/// * `prefix`: 期待するプレフィックス
+///
+/// # Return
+/// * `Result<Uuid, AggregateIdError>`
+fn parse_prefixed_id(value: &str, prefix: &str) -> Result<Uuid, AggregateIdError> {
+  if let Some(uuid) = value.strip_prefix(prefix).and_then(|rest| rest.strip_prefix('-')) {
+    return Uuid::parse_str(uuid).map_err(|_| AggregateIdError::InvalidFormat(value.to_string()));
+  }
+  if let Ok(uuid) = Uuid::parse_str(value) {
+    return Ok(uuid);
+  }
+
+  // 別の集約のプレフィックスが付いている場合は型付きのエラーにします
+  let split_at = value.len().saturating_sub(HYPHENATED_UUID_LEN + 1);
+  if split_at > 0 && value.is_char_boundary(split_at) {
+    let (actual, rest) = value.split_at(split_at);
+    if let Some(uuid) = rest.strip_prefix('-') {
+      if Uuid::parse_str(uuid).is_ok() {
+        Err(AggregateIdError::InvalidPrefix {
+          expected: prefix.to_string(),
+          actual: actual.to_string(),
+        })?
+      }
+    }
+  }
+  Err(AggregateIdError::InvalidFormat(value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::id_generator::UuidV4Generator;
+  use rstest::rstest;
+
+  const UUID: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+
+  struct TestIdPrefix;
+
+  impl IdPrefix for TestIdPrefix {
+    const PREFIX: &'static str = "ORDER";
+  }
+
+  type TestId = PrefixedId<TestIdPrefix>;
+
+  #[test]
+  fn test_prefixed_id_from_str_round_trip_success() {
+    let id = TestId::generate(&UuidV4Generator);
+    let result = TestId::from_str(&id.to_string());
+
+    // assert
+    assert_eq!(id, result.unwrap());
+    assert_eq!(format!("ORDER-{}", id.value()), id.to_string());
+  }
+
+  #[test]
+  fn test_prefixed_id_from_str_raw_uuid_success() {
+    let result = TestId::from_str(UUID);
+
+    // assert
+    assert_eq!(TestId::from(Uuid::parse_str(UUID).unwrap()), result.unwrap())
+  }
+
+  #[test]
+  fn test_prefixed_id_from_str_failed() {
+    let result = TestId::from_str(&format!("ORDER_ITEM-{}", UUID));
+
+    // assert
+    assert!(matches!(result, Err(AggregateIdError::InvalidPrefix { .. })))
+  }
+
+  #[test]
+  fn test_prefixed_id_serde_success() {
+    let id = TestId::from(Uuid::parse_str(UUID).unwrap());
+    let json = serde_json::to_string(&id).unwrap();
+
+    // assert
+    assert_eq!(format!("\"{}\"", UUID), json);
+    assert_eq!(id, serde_json::from_str::<TestId>(&json).unwrap());
+    assert_eq!(id, serde_json::from_str::<TestId>(&format!("\"ORDER-{}\"", UUID)).unwrap());
+    assert!(serde_json::from_str::<TestId>(&format!("\"PAYMENT-{}\"", UUID)).is_err());
+  }
+
+  #[rstest]
+  #[case("ORDER-67e55044-10b1-426f-9247-bb680e5fe0c8")]
+  #[case("67e55044-10b1-426f-9247-bb680e5fe0c8")]
+  #[case("67e5504410b1426f9247bb680e5fe0c8")]
+  fn test_parse_prefixed_id_success(#[case] value: &str) {
+    let result = parse_prefixed_id(value, "ORDER");
+
+    // assert
+    assert_eq!(Uuid::parse_str(UUID).unwrap(), result.unwrap())
+  }
+
+  #[test]
+  fn test_parse_prefixed_id_invalid_prefix() {
+    let result = parse_prefixed_id(&format!("ORDER_ITEM-{}", UUID), "ORDER");
+
+    // assert
+    assert_eq!(
+      AggregateIdError::InvalidPrefix {
+        expected: "ORDER".to_string(),
+        actual: "ORDER_ITEM".to_string(),
+      },
+      result.unwrap_err()
+    )
+  }
+
+  #[rstest]
+  #[case("")]
+  #[case("ORDER-")]
+  #[case("ORDER-not-a-uuid")]
+  #[case("hogehoge")]
+  fn test_parse_prefixed_id_invalid_format(#[case] value: &str) {
+    let result = parse_prefixed_id(value, "ORDER");
+
+    // assert
+    assert_eq!(
+      AggregateIdError::InvalidFormat(value.to_string()),
+      result.unwrap_err()
+    )
+  }
+}
@@ -0,0 +1,106 @@
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Mutex;
+
+/// 現在日時を提供するトレイトです
+///
+/// コマンド処理やWrite APIでは`Utc::now()`を直接呼び出さず、
+/// このトレイト経由で日時を取得します
+pub trait Clock: Send + Sync {
+  /// 現在日時を返します
+  ///
+  /// # Return
+  /// * `DateTime<Utc>`
+  fn now(&self) -> DateTime<Utc>;
+}
+
+/// システム時刻を返すClockです
+///
+/// 本番環境ではこちらを使用します
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+  fn now(&self) -> DateTime<Utc> {
+    Utc::now()
+  }
+}
+
+/// 固定の日時を返すClockです
+///
+/// テストで使用します。`advance`で時間を進めることができます
+#[derive(Debug)]
+pub struct FixedClock {
+  now: Mutex<DateTime<Utc>>,
+}
+
+impl FixedClock {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `now`: 固定する日時
+  ///
+  /// # Return
+  /// * `FixedClock`
+  pub fn new(now: DateTime<Utc>) -> Self {
+    Self { now: Mutex::new(now) }
+  }
+
+  /// 日時を上書きします
+  ///
+  /// # Arguments
+  /// * `now`: 新しい日時
+  pub fn set(&self, now: DateTime<Utc>) {
+    *self.now.lock().unwrap() = now;
+  }
+
+  /// 日時を指定した期間だけ進めます
+  ///
+  /// # Arguments
+  /// * `duration`: 進める期間
+  pub fn advance(&self, duration: Duration) {
+    let mut now = self.now.lock().unwrap();
+    *now += duration;
+  }
+}
+
+impl Clock for FixedClock {
+  fn now(&self) -> DateTime<Utc> {
+    *self.now.lock().unwrap()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::TimeZone;
+
+  #[test]
+  fn test_fixed_clock_now_success() {
+    let now = Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap();
+    let clock = FixedClock::new(now);
+
+    // assert
+    assert_eq!(now, clock.now());
+    assert_eq!(clock.now(), clock.now());
+  }
+
+  #[test]
+  fn test_fixed_clock_advance_success() {
+    let now = Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap();
+    let clock = FixedClock::new(now);
+    clock.advance(Duration::minutes(30));
+
+    // assert
+    assert_eq!(now + Duration::minutes(30), clock.now());
+  }
+
+  #[test]
+  fn test_fixed_clock_set_success() {
+    let clock = FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap());
+    let next = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    clock.set(next);
+
+    // assert
+    assert_eq!(next, clock.now());
+  }
+}
@@ -0,0 +1,322 @@
+pub mod customer_error;
+pub mod customer_event;
+pub mod customer_id;
+pub mod customer_limits;
+pub mod customer_name;
+pub mod customer_repository;
+
+use crate::clock::Clock;
+use crate::customer::customer_error::CustomerError;
+use crate::customer::customer_event::{CustomerEvent, CustomerRegistered, OrderAccepted, OrderAmountChanged, OrderReleased};
+use crate::customer::customer_id::CustomerId;
+use crate::customer::customer_limits::CustomerLimits;
+use crate::customer::customer_name::CustomerName;
+use crate::order::order_id::OrderId;
+use crate::value_object::money::{Money, MoneyError};
+use std::collections::BTreeMap;
+
+/// 顧客の集約です
+///
+/// 未完了の注文とその支払総額を保持し、
+/// 注文の受付時に未完了の注文数と与信枠の上限を検証します
+#[derive(Debug, Clone, PartialEq)]
+pub struct Customer {
+  /// 顧客ID
+  id: CustomerId,
+
+  /// 顧客名
+  name: CustomerName,
+
+  /// 注文の上限
+  limits: CustomerLimits,
+
+  /// 未完了の注文と支払総額
+  open_orders: BTreeMap<OrderId, Money>,
+}
+
+impl Customer {
+  /// 顧客を登録します
+  ///
+  /// # Arguments
+  /// * `id`: CustomerId
+  /// * `clock`: 登録日時の取得元
+  /// * `name`: 顧客名
+  /// * `limits`: 注文の上限
+  ///
+  /// # Return
+  /// * `Result<(Customer, Vec<CustomerEvent>), CustomerError>`
+  pub fn register(
+    id: CustomerId,
+    clock: &dyn Clock,
+    name: &str,
+    limits: CustomerLimits,
+  ) -> Result<(Self, Vec<CustomerEvent>), CustomerError> {
+    let name = CustomerName::new(name)?;
+    let event = CustomerEvent::CustomerRegistered(CustomerRegistered {
+      customer_id: id.clone(),
+      occurred_at: clock.now(),
+      name: name.to_string(),
+    });
+    let customer = Self { id, name, limits, open_orders: BTreeMap::new() };
+    Ok((customer, vec![event]))
+  }
+
+  /// 注文を未完了の注文として受け付けます
+  ///
+  /// 与信枠がある場合、注文の通貨は与信枠の通貨と一致していなければなりません
+  ///
+  /// # Arguments
+  /// * `order_id`: 受け付ける注文のID
+  /// * `amount`: 注文の支払総額
+  /// * `clock`: 受付日時の取得元
+  ///
+  /// # Return
+  /// * `Result<CustomerEvent, CustomerError>`
+  pub fn accept_order(
+    &mut self,
+    order_id: &OrderId,
+    amount: Money,
+    clock: &dyn Clock,
+  ) -> Result<CustomerEvent, CustomerError> {
+    if self.open_orders.contains_key(order_id) {
+      Err(CustomerError::OrderAlreadyAccepted(order_id.clone()))?
+    }
+    if let Some(limit) = self.limits.max_open_orders() {
+      if self.open_orders.len() >= limit as usize {
+        Err(CustomerError::OpenOrderLimitReached { customer_id: self.id.to_string(), limit })?
+      }
+    }
+    self.ensure_credit(amount)?;
+
+    self.open_orders.insert(order_id.clone(), amount);
+    Ok(CustomerEvent::OrderAccepted(OrderAccepted {
+      customer_id: self.id.clone(),
+      occurred_at: clock.now(),
+      order_id: order_id.clone(),
+      amount,
+    }))
+  }
+
+  /// 注文を未完了の注文から外します
+  ///
+  /// 注文の完了・取り消しのほか、注文の確定に失敗した場合の補償にも使用します
+  ///
+  /// # Arguments
+  /// * `order_id`: 外す注文のID
+  /// * `clock`: 日時の取得元
+  ///
+  /// # Return
+  /// * `Result<CustomerEvent, CustomerError>`
+  pub fn release_order(&mut self, order_id: &OrderId, clock: &dyn Clock) -> Result<CustomerEvent, CustomerError> {
+    if self.open_orders.remove(order_id).is_none() {
+      Err(CustomerError::OrderNotOpen(order_id.clone()))?
+    }
+    Ok(CustomerEvent::OrderReleased(OrderReleased {
+      customer_id: self.id.clone(),
+      occurred_at: clock.now(),
+      order_id: order_id.clone(),
+    }))
+  }
+
+  /// 未完了の注文の支払総額を変更します
+  ///
+  /// 注文に明細を追加した場合などに使用します。与信枠は変更する注文を除いた未完了の注文に対して検証します
+  ///
+  /// # Arguments
+  /// * `order_id`: 変更する注文のID
+  /// * `amount`: 変更後の支払総額
+  /// * `clock`: 変更日時の取得元
+  ///
+  /// # Return
+  /// * `Result<CustomerEvent, CustomerError>`
+  pub fn change_order_amount(
+    &mut self,
+    order_id: &OrderId,
+    amount: Money,
+    clock: &dyn Clock,
+  ) -> Result<CustomerEvent, CustomerError> {
+    let previous_amount = self.open_orders
+      .remove(order_id)
+      .ok_or_else(|| CustomerError::OrderNotOpen(order_id.clone()))?;
+    if let Err(e) = self.ensure_credit(amount) {
+      self.open_orders.insert(order_id.clone(), previous_amount);
+      Err(e)?
+    }
+    self.open_orders.insert(order_id.clone(), amount);
+    Ok(CustomerEvent::OrderAmountChanged(OrderAmountChanged {
+      customer_id: self.id.clone(),
+      occurred_at: clock.now(),
+      order_id: order_id.clone(),
+      previous_amount,
+      amount,
+    }))
+  }
+
+  /// 未完了の注文に支払総額を加えても与信枠を超えないことを検証します
+  fn ensure_credit(&self, amount: Money) -> Result<(), CustomerError> {
+    let Some(credit_limit) = self.limits.credit_limit() else {
+      return Ok(());
+    };
+    if amount.currency() != credit_limit.currency() {
+      Err(MoneyError::CurrencyMismatch { expected: credit_limit.currency(), actual: amount.currency() })?
+    }
+    let available = credit_limit.sub(&self.calc_outstanding(credit_limit)?)?;
+    if amount.amount() > available.amount() {
+      Err(CustomerError::CreditLimitExceeded { customer_id: self.id.to_string(), amount, available })?
+    }
+    Ok(())
+  }
+
+  /// 未完了の注文の支払総額の合計を計算します
+  fn calc_outstanding(&self, credit_limit: &Money) -> Result<Money, CustomerError> {
+    let outstanding = self.open_orders
+      .values()
+      .try_fold(Money::zero(credit_limit.currency()), |total, amount| total.add(amount))?;
+    Ok(outstanding)
+  }
+
+  /// 顧客IDのゲッター
+  pub fn get_id(&self) -> &CustomerId { &self.id }
+
+  /// 顧客名のゲッター
+  pub fn get_name(&self) -> &CustomerName { &self.name }
+
+  /// 注文の上限のゲッター
+  pub fn get_limits(&self) -> &CustomerLimits { &self.limits }
+
+  /// 未完了の注文のゲッター
+  pub fn get_open_orders(&self) -> &BTreeMap<OrderId, Money> { &self.open_orders }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::clock::FixedClock;
+  use crate::id_generator::UuidV4Generator;
+  use crate::value_object::currency::Currency;
+  use chrono::{TimeZone, Utc};
+  use rust_decimal::Decimal;
+
+  fn clock() -> FixedClock {
+    FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap())
+  }
+
+  fn jpy(value: i64) -> Money {
+    Money::new(Decimal::from(value), Currency::JPY).unwrap()
+  }
+
+  fn customer(max_open_orders: Option<u32>, credit_limit: Option<Money>) -> Customer {
+    let limits = CustomerLimits::new(max_open_orders, credit_limit).unwrap();
+    Customer::register(CustomerId::generate(&UuidV4Generator), &clock(), "山田 太郎", limits).unwrap().0
+  }
+
+  #[test]
+  fn test_customer_register_success() {
+    let (customer, events) = Customer::register(
+      CustomerId::generate(&UuidV4Generator), &clock(), "  山田 太郎 ", CustomerLimits::unlimited(),
+    ).unwrap();
+
+    // assert
+    assert_eq!("山田 太郎", customer.get_name().value());
+    assert!(matches!(&events[0], CustomerEvent::CustomerRegistered(registered) if &registered.customer_id == customer.get_id()));
+  }
+
+  #[test]
+  fn test_customer_register_failed() {
+    // assert
+    assert_eq!(
+      Err(CustomerError::NameEmpty),
+      Customer::register(CustomerId::generate(&UuidV4Generator), &clock(), " ", CustomerLimits::unlimited()).map(|_| ())
+    );
+    assert_eq!(Err(CustomerError::InvalidMaxOpenOrders), CustomerLimits::new(Some(0), None));
+    assert_eq!(Err(CustomerError::NegativeCreditLimit(jpy(-1))), CustomerLimits::new(None, Some(jpy(-1))));
+  }
+
+  #[test]
+  fn test_customer_accept_order_open_order_limit_failed() {
+    let mut customer = customer(Some(2), None);
+    customer.accept_order(&OrderId::generate(&UuidV4Generator), jpy(1000), &clock()).unwrap();
+    let released = OrderId::generate(&UuidV4Generator);
+    customer.accept_order(&released, jpy(1000), &clock()).unwrap();
+
+    let result = customer.accept_order(&OrderId::generate(&UuidV4Generator), jpy(1000), &clock());
+    customer.release_order(&released, &clock()).unwrap();
+
+    // assert
+    assert!(matches!(result, Err(CustomerError::OpenOrderLimitReached { limit: 2, .. })));
+    assert!(customer.accept_order(&OrderId::generate(&UuidV4Generator), jpy(1000), &clock()).is_ok());
+  }
+
+  #[test]
+  fn test_customer_accept_order_credit_limit_failed() {
+    let mut customer = customer(None, Some(jpy(5000)));
+    customer.accept_order(&OrderId::generate(&UuidV4Generator), jpy(3000), &clock()).unwrap();
+
+    let exceeded = customer.accept_order(&OrderId::generate(&UuidV4Generator), jpy(2001), &clock());
+    let other_currency = customer.accept_order(
+      &OrderId::generate(&UuidV4Generator), Money::new(Decimal::from(10), Currency::USD).unwrap(), &clock(),
+    );
+
+    // assert
+    assert!(matches!(
+      exceeded,
+      Err(CustomerError::CreditLimitExceeded { available, .. }) if available == jpy(2000)
+    ));
+    assert!(matches!(other_currency, Err(CustomerError::InvalidMoney(MoneyError::CurrencyMismatch { .. }))));
+    assert!(customer.accept_order(&OrderId::generate(&UuidV4Generator), jpy(2000), &clock()).is_ok());
+    assert_eq!(2, customer.get_open_orders().len());
+  }
+
+  #[test]
+  fn test_customer_release_order_failed() {
+    let mut customer = customer(None, None);
+    let order_id = OrderId::generate(&UuidV4Generator);
+
+    let result = customer.release_order(&order_id, &clock());
+
+    // assert
+    assert_eq!(Err(CustomerError::OrderNotOpen(order_id)), result);
+  }
+
+  #[test]
+  fn test_customer_change_order_amount() {
+    let mut customer = customer(None, Some(jpy(5000)));
+    let order_id = OrderId::generate(&UuidV4Generator);
+    customer.accept_order(&OrderId::generate(&UuidV4Generator), jpy(2000), &clock()).unwrap();
+    customer.accept_order(&order_id, jpy(1000), &clock()).unwrap();
+
+    let exceeded = customer.change_order_amount(&order_id, jpy(3001), &clock());
+    let changed = customer.change_order_amount(&order_id, jpy(3000), &clock()).unwrap();
+    let not_open = customer.change_order_amount(&OrderId::generate(&UuidV4Generator), jpy(1), &clock());
+
+    // assert
+    assert!(matches!(exceeded, Err(CustomerError::CreditLimitExceeded { available, .. }) if available == jpy(3000)));
+    assert!(matches!(
+      changed,
+      CustomerEvent::OrderAmountChanged(changed) if changed.previous_amount == jpy(1000) && changed.amount == jpy(3000)
+    ));
+    assert!(matches!(not_open, Err(CustomerError::OrderNotOpen(_))));
+    assert_eq!(Some(&jpy(3000)), customer.get_open_orders().get(&order_id));
+  }
+
+  #[test]
+  fn test_customer_events_serde() {
+    let (mut customer, mut events) = Customer::register(
+      CustomerId::generate(&UuidV4Generator), &clock(), "山田 太郎", CustomerLimits::new(Some(2), Some(jpy(5000))).unwrap(),
+    ).unwrap();
+    let order_id = OrderId::generate(&UuidV4Generator);
+    events.push(customer.accept_order(&order_id, jpy(1000), &clock()).unwrap());
+    events.push(customer.change_order_amount(&order_id, jpy(1500), &clock()).unwrap());
+    events.push(customer.release_order(&order_id, &clock()).unwrap());
+
+    let json = events.iter().map(|event| serde_json::to_value(event).unwrap()).collect::<Vec<_>>();
+    let deserialized = json.iter().map(|value| serde_json::from_value::<CustomerEvent>(value.clone()).unwrap()).collect::<Vec<_>>();
+
+    // assert
+    assert_eq!(
+      vec!["CustomerRegistered", "OrderAccepted", "OrderAmountChanged", "OrderReleased"],
+      json.iter().map(|value| value["type"].as_str().unwrap()).collect::<Vec<_>>(),
+    );
+    assert_eq!(events, deserialized);
+  }
+}
@@ -0,0 +1,73 @@
+use crate::aggregate_id::AggregateIdError;
+use crate::error_code::{ErrorCode, FieldPath};
+use crate::order::order_id::OrderId;
+use crate::value_object::money::{Money, MoneyError};
+use thiserror::Error;
+
+/// 顧客のエラーです
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+pub enum CustomerError {
+  #[error("customer {0} is not found")]
+  CustomerNotFound(String),
+
+  #[error("Invalid Customer ID: {0}")]
+  InvalidCustomerId(#[from] AggregateIdError),
+
+  #[error("customer name must not be empty")]
+  NameEmpty,
+
+  #[error("customer name must be at most {max} characters: {actual}")]
+  NameTooLong { max: usize, actual: usize },
+
+  #[error("max open orders must be greater than 0")]
+  InvalidMaxOpenOrders,
+
+  #[error("credit limit must not be negative: {0}")]
+  NegativeCreditLimit(Money),
+
+  #[error("customer {customer_id} has reached the limit of {limit} open orders")]
+  OpenOrderLimitReached { customer_id: String, limit: u32 },
+
+  #[error("order amount {amount} exceeds the available credit {available} of customer {customer_id}")]
+  CreditLimitExceeded { customer_id: String, amount: Money, available: Money },
+
+  #[error("order {0} is already accepted")]
+  OrderAlreadyAccepted(OrderId),
+
+  #[error("order {0} is not an open order of the customer")]
+  OrderNotOpen(OrderId),
+
+  #[error("Invalid Money: {0}")]
+  InvalidMoney(#[from] MoneyError),
+}
+
+impl ErrorCode for CustomerError {
+  fn code(&self) -> &'static str {
+    match self {
+      CustomerError::CustomerNotFound(_) => "customer.not_found",
+      CustomerError::InvalidCustomerId(e) => e.code(),
+      CustomerError::NameEmpty => "customer.name_empty",
+      CustomerError::NameTooLong { .. } => "customer.name_too_long",
+      CustomerError::InvalidMaxOpenOrders => "customer.invalid_max_open_orders",
+      CustomerError::NegativeCreditLimit(_) => "customer.negative_credit_limit",
+      CustomerError::OpenOrderLimitReached { .. } => "customer.open_order_limit_reached",
+      CustomerError::CreditLimitExceeded { .. } => "customer.credit_limit_exceeded",
+      CustomerError::OrderAlreadyAccepted(_) => "customer.order_already_accepted",
+      CustomerError::OrderNotOpen(_) => "customer.order_not_open",
+      CustomerError::InvalidMoney(e) => e.code(),
+    }
+  }
+
+  fn field_path(&self) -> Option<FieldPath> {
+    match self {
+      CustomerError::CustomerNotFound(_) | CustomerError::InvalidCustomerId(_) => Some(FieldPath::new("customer_id")),
+      CustomerError::NameEmpty | CustomerError::NameTooLong { .. } => Some(FieldPath::new("name")),
+      CustomerError::InvalidMaxOpenOrders => Some(FieldPath::new("max_open_orders")),
+      CustomerError::NegativeCreditLimit(_) | CustomerError::InvalidMoney(MoneyError::InvalidScale { .. }) => {
+        Some(FieldPath::new("credit_limit").field("amount"))
+      }
+      CustomerError::InvalidMoney(MoneyError::InvalidCurrency(_)) => Some(FieldPath::new("credit_limit").field("currency")),
+      _ => None,
+    }
+  }
+}
@@ -0,0 +1,76 @@
+use crate::customer::customer_id::CustomerId;
+use crate::order::order_id::OrderId;
+use crate::value_object::money::Money;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 顧客のイベントです
+///
+/// シリアライズすると、`type`にイベント名を持つJSONオブジェクトになります
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum CustomerEvent {
+  CustomerRegistered(CustomerRegistered),
+  OrderAccepted(OrderAccepted),
+  OrderReleased(OrderReleased),
+  OrderAmountChanged(OrderAmountChanged),
+}
+
+impl CustomerEvent {
+  /// イベントが発生した顧客のIDを返します
+  pub fn customer_id(&self) -> &CustomerId {
+    match self {
+      CustomerEvent::CustomerRegistered(event) => &event.customer_id,
+      CustomerEvent::OrderAccepted(event) => &event.customer_id,
+      CustomerEvent::OrderReleased(event) => &event.customer_id,
+      CustomerEvent::OrderAmountChanged(event) => &event.customer_id,
+    }
+  }
+
+  /// イベントの発生日時を返します
+  pub fn occurred_at(&self) -> &DateTime<Utc> {
+    match self {
+      CustomerEvent::CustomerRegistered(event) => &event.occurred_at,
+      CustomerEvent::OrderAccepted(event) => &event.occurred_at,
+      CustomerEvent::OrderReleased(event) => &event.occurred_at,
+      CustomerEvent::OrderAmountChanged(event) => &event.occurred_at,
+    }
+  }
+}
+
+/// 顧客が登録されたイベントです
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomerRegistered {
+  pub customer_id: CustomerId,
+  pub occurred_at: DateTime<Utc>,
+  pub name: String,
+}
+
+/// 顧客の未完了の注文として受け付けたイベントです
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderAccepted {
+  pub customer_id: CustomerId,
+  pub occurred_at: DateTime<Utc>,
+  pub order_id: OrderId,
+  pub amount: Money,
+}
+
+/// 未完了の注文から外したイベントです
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderReleased {
+  pub customer_id: CustomerId,
+  pub occurred_at: DateTime<Utc>,
+  pub order_id: OrderId,
+}
+
+/// 未完了の注文の支払総額が変わったイベントです
+///
+/// previous_amountには変更前の支払総額を記録します
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderAmountChanged {
+  pub customer_id: CustomerId,
+  pub occurred_at: DateTime<Utc>,
+  pub order_id: OrderId,
+  pub previous_amount: Money,
+  pub amount: Money,
+}
@@ -0,0 +1,13 @@
+use crate::aggregate_id::{IdPrefix, PrefixedId};
+
+/// 顧客IDのプレフィックスです
+pub struct CustomerIdPrefix;
+
+impl IdPrefix for CustomerIdPrefix {
+  const PREFIX: &'static str = "CUSTOMER";
+}
+
+/// 顧客IDです
+///
+/// `Display`は`CUSTOMER-<uuid>`形式、serdeはUUIDのみの形式で表現します
+pub type CustomerId = PrefixedId<CustomerIdPrefix>;
@@ -0,0 +1,47 @@
+use crate::customer::customer_error::CustomerError;
+use crate::value_object::money::Money;
+use rust_decimal::Decimal;
+
+/// 顧客ごとの注文の上限です
+///
+/// max_open_orders: 未完了の注文数の上限(無制限の場合はNone)
+///
+/// credit_limit: 未完了の注文の支払総額の合計の上限(無制限の場合はNone)
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct CustomerLimits {
+  max_open_orders: Option<u32>,
+  credit_limit: Option<Money>,
+}
+
+impl CustomerLimits {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `max_open_orders`: 未完了の注文数の上限
+  /// * `credit_limit`: 与信枠
+  ///
+  /// # Return
+  /// * `Result<CustomerLimits, CustomerError>`
+  pub fn new(max_open_orders: Option<u32>, credit_limit: Option<Money>) -> Result<Self, CustomerError> {
+    if max_open_orders == Some(0) {
+      Err(CustomerError::InvalidMaxOpenOrders)?
+    }
+    if let Some(credit_limit) = credit_limit {
+      if credit_limit.amount() < &Decimal::ZERO {
+        Err(CustomerError::NegativeCreditLimit(credit_limit))?
+      }
+    }
+    Ok(Self { max_open_orders, credit_limit })
+  }
+
+  /// 上限なしを返します
+  pub fn unlimited() -> Self {
+    Self::default()
+  }
+
+  /// 未完了の注文数の上限のゲッター
+  pub fn max_open_orders(&self) -> Option<u32> { self.max_open_orders }
+
+  /// 与信枠のゲッター
+  pub fn credit_limit(&self) -> Option<&Money> { self.credit_limit.as_ref() }
+}
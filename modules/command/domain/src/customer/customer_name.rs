@@ -0,0 +1,40 @@
+use crate::customer::customer_error::CustomerError;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// 顧客名です
+///
+/// 前後の空白を除いて保持します
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CustomerName(String);
+
+/// 顧客名の最大文字数です
+const MAX_CUSTOMER_NAME_LEN: usize = 100;
+
+impl CustomerName {
+  pub fn new(value: &str) -> Result<Self, CustomerError> {
+    let value = value.trim();
+    if value.is_empty() { Err(CustomerError::NameEmpty)? }
+    let len = value.chars().count();
+    if len > MAX_CUSTOMER_NAME_LEN {
+      Err(CustomerError::NameTooLong { max: MAX_CUSTOMER_NAME_LEN, actual: len })?
+    }
+    Ok(Self(value.to_string()))
+  }
+
+  /// Getter
+  pub fn value(&self) -> &str { &self.0 }
+}
+
+impl FromStr for CustomerName {
+  type Err = CustomerError;
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Self::new(s)
+  }
+}
+
+impl Display for CustomerName {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
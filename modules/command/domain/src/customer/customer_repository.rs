@@ -0,0 +1,41 @@
+use crate::customer::customer_id::CustomerId;
+use crate::customer::Customer;
+use crate::repository::{RepositoryError, Versioned};
+
+/// 顧客の永続化を行うリポジトリのトレイトです
+///
+/// 同時に行われた注文で未完了の注文数や与信枠の上限を超えないよう、
+/// 更新時はバージョンによる楽観的排他制御を行います
+pub trait CustomerRepository: Send + Sync {
+  /// 顧客IDに対応する顧客を取得します
+  ///
+  /// # Arguments
+  /// * `customer_id`: &CustomerId
+  ///
+  /// # Return
+  /// * `Result<Option<Versioned<Customer>>, RepositoryError>`
+  fn find_by_id(&self, customer_id: &CustomerId) -> Result<Option<Versioned<Customer>>, RepositoryError>;
+
+  /// 新しい顧客を保存します
+  ///
+  /// 同じIDの顧客がある場合は`AlreadyExists`を返します
+  ///
+  /// # Arguments
+  /// * `customer`: Customer
+  ///
+  /// # Return
+  /// * `Result<(), RepositoryError>`
+  fn insert(&self, customer: Customer) -> Result<(), RepositoryError>;
+
+  /// 顧客を更新します
+  ///
+  /// 保存済みのバージョンがexpected_versionと異なる場合は`VersionConflict`を返します
+  ///
+  /// # Arguments
+  /// * `customer`: Customer
+  /// * `expected_version`: 取得時のバージョン
+  ///
+  /// # Return
+  /// * `Result<(), RepositoryError>`
+  fn update(&self, customer: Customer, expected_version: u64) -> Result<(), RepositoryError>;
+}
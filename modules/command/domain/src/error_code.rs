@@ -0,0 +1,117 @@
+use std::fmt::{Display, Formatter};
+
+/// 機械可読なエラーコードを提供するトレイトです
+///
+/// クライアントがエラーメッセージをローカライズしたり、エラーの種類で処理を分岐したりするために使います
+pub trait ErrorCode: std::error::Error {
+  /// バリアントごとに安定したエラーコードを返します
+  ///
+  /// # Return
+  /// `quantity.not_positive` のような `<対象>.<理由>` 形式のコード
+  fn code(&self) -> &'static str;
+
+  /// エラーの原因となった入力のフィールドパスを返します
+  ///
+  /// # Return
+  /// フィールドを特定できない場合はNone
+  fn field_path(&self) -> Option<FieldPath> {
+    None
+  }
+
+  /// 入力のフィールドごとのエラーを返します
+  ///
+  /// 複数のエラーを集約したエラーは、集約したすべてのエラーを返します
+  ///
+  /// # Return
+  /// フィールドを特定できない場合は空
+  fn field_errors(&self) -> Vec<FieldError> {
+    self.field_path()
+      .map(|field| FieldError { field, code: self.code(), message: self.to_string() })
+      .into_iter()
+      .collect()
+  }
+}
+
+/// 入力のフィールドのエラーです
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FieldError {
+  pub field: FieldPath,
+  pub code: &'static str,
+  pub message: String,
+}
+
+/// フィールドパスの要素です
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum FieldSegment {
+  Name(&'static str),
+  Index(usize),
+}
+
+/// 入力のフィールドを指すパスです
+///
+/// `items[2].quantity` のように表示されます
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FieldPath {
+  segments: Vec<FieldSegment>,
+}
+
+impl Display for FieldPath {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    for (i, segment) in self.segments.iter().enumerate() {
+      match segment {
+        FieldSegment::Name(name) if i == 0 => write!(f, "{}", name)?,
+        FieldSegment::Name(name) => write!(f, ".{}", name)?,
+        FieldSegment::Index(index) => write!(f, "[{}]", index)?,
+      }
+    }
+    Ok(())
+  }
+}
+
+impl FieldPath {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `name` - 先頭のフィールド名
+  pub fn new(name: &'static str) -> Self {
+    Self { segments: vec![FieldSegment::Name(name)] }
+  }
+
+  /// 子のフィールドを追加します
+  pub fn field(mut self, name: &'static str) -> Self {
+    self.segments.push(FieldSegment::Name(name));
+    self
+  }
+
+  /// 配列の添字を追加します
+  pub fn index(mut self, index: usize) -> Self {
+    self.segments.push(FieldSegment::Index(index));
+    self
+  }
+
+  /// 子のパスを連結します
+  ///
+  /// # Arguments
+  /// * `child` - このパスからの相対パス
+  pub fn join(mut self, child: FieldPath) -> Self {
+    self.segments.extend(child.segments);
+    self
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_field_path_display() {
+    let path = FieldPath::new("items").index(2).field("quantity");
+    let joined = FieldPath::new("shipping_address").join(FieldPath::new("postal_code"));
+    let nested = FieldPath::new("items").index(0).join(FieldPath::new("discount").field("rate"));
+
+    // assert
+    assert_eq!("items[2].quantity", path.to_string());
+    assert_eq!("shipping_address.postal_code", joined.to_string());
+    assert_eq!("items[0].discount.rate", nested.to_string());
+  }
+}
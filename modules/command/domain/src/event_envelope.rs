@@ -0,0 +1,93 @@
+use crate::event_id::EventId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// イベントのメタデータです
+///
+/// trace_context: イベントを発生させたリクエストのトレースコンテキスト(W3C Trace Contextの`traceparent`・`tracestate`)。
+/// 投影処理のスパンを元のリクエストのスパンに関連付けるために使います
+///
+/// event_id: 配信時に付けるイベントID。同じイベントを再送した場合も同じIDになるため、投影処理は適用済みのIDを読み飛ばします
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EventMetadata {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub event_id: Option<EventId>,
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub trace_context: BTreeMap<String, String>,
+}
+
+impl EventMetadata {
+  /// トレースコンテキストを設定します
+  ///
+  /// # Arguments
+  /// * `trace_context`: トレースコンテキスト
+  ///
+  /// # Return
+  /// * `EventMetadata`
+  pub fn with_trace_context(mut self, trace_context: BTreeMap<String, String>) -> Self {
+    self.trace_context = trace_context;
+    self
+  }
+
+  /// イベントIDを設定します
+  ///
+  /// # Arguments
+  /// * `event_id`: イベントID
+  ///
+  /// # Return
+  /// * `EventMetadata`
+  pub fn with_event_id(mut self, event_id: EventId) -> Self {
+    self.event_id = Some(event_id);
+    self
+  }
+}
+
+/// メタデータを付けたイベントです
+///
+/// ストアへの保存や読み取り側への配信では、イベントをこの形で扱います
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventEnvelope<E> {
+  pub metadata: EventMetadata,
+  pub event: E,
+}
+
+impl<E> EventEnvelope<E> {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `event`: イベント
+  /// * `metadata`: EventMetadata
+  ///
+  /// # Return
+  /// * `EventEnvelope<E>`
+  pub fn new(event: E, metadata: EventMetadata) -> Self {
+    Self { metadata, event }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::id_generator::SequentialIdGenerator;
+
+  #[test]
+  fn test_event_envelope_serde() {
+    let trace_context = BTreeMap::from([(
+      "traceparent".to_string(),
+      "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01".to_string(),
+    )]);
+    let metadata = EventMetadata::default()
+      .with_event_id(EventId::generate(&SequentialIdGenerator::new(1)))
+      .with_trace_context(trace_context);
+    let envelope = EventEnvelope::new("OrderPlaced".to_string(), metadata);
+
+    let json = serde_json::to_value(&envelope).unwrap();
+
+    // assert
+    assert_eq!("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01", json["metadata"]["trace_context"]["traceparent"]);
+    assert_eq!("00000000-0000-0001-0000-000000000001", json["metadata"]["event_id"]);
+    assert_eq!(envelope, serde_json::from_value::<EventEnvelope<String>>(json).unwrap());
+    let without_context = serde_json::to_value(EventEnvelope::new("OrderPlaced", EventMetadata::default())).unwrap();
+    assert_eq!(serde_json::json!({ "metadata": {}, "event": "OrderPlaced" }), without_context);
+  }
+}
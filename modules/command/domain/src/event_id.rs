@@ -0,0 +1,14 @@
+use crate::aggregate_id::{IdPrefix, PrefixedId};
+
+/// イベントIDのプレフィックスです
+pub struct EventIdPrefix;
+
+impl IdPrefix for EventIdPrefix {
+  const PREFIX: &'static str = "EVENT";
+}
+
+/// イベントIDです
+///
+/// 配信したイベントを1件ずつ識別し、読み取り側で重複して投影しないために使います。
+/// `Display`は`EVENT-<uuid>`形式、serdeはUUIDのみの形式で表現します
+pub type EventId = PrefixedId<EventIdPrefix>;
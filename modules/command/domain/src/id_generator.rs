@@ -0,0 +1,110 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use uuid::Uuid;
+
+/// ID生成用のトレイトです
+///
+/// 集約IDやエンティティIDはこのトレイト経由で生成します
+pub trait IdGenerator: Send + Sync {
+  /// 新しいIDを生成します
+  ///
+  /// # Return
+  /// * `Uuid`
+  fn generate(&self) -> Uuid;
+}
+
+/// UUIDv4(ランダム)でIDを生成します
+///
+/// デフォルトの生成方式です
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidV4Generator;
+
+impl IdGenerator for UuidV4Generator {
+  fn generate(&self) -> Uuid {
+    Uuid::new_v4()
+  }
+}
+
+/// UUIDv7(時刻順)でIDを生成します
+///
+/// 生成順にソートされるため、ストアの局所性を高めたい場合に使用します
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidV7Generator;
+
+impl IdGenerator for UuidV7Generator {
+  fn generate(&self) -> Uuid {
+    Uuid::now_v7()
+  }
+}
+
+/// シード値から連番でIDを生成します
+///
+/// 同じシード値からは常に同じ順序で同じIDが生成されるため、テストで使用します
+#[derive(Debug)]
+pub struct SequentialIdGenerator {
+  seed: u64,
+  counter: AtomicU64,
+}
+
+impl SequentialIdGenerator {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `seed`: シード値。UUIDの上位64bitになります
+  ///
+  /// # Return
+  /// * `SequentialIdGenerator`
+  pub fn new(seed: u64) -> Self {
+    Self { seed, counter: AtomicU64::new(1) }
+  }
+}
+
+impl IdGenerator for SequentialIdGenerator {
+  fn generate(&self) -> Uuid {
+    let sequence = self.counter.fetch_add(1, Ordering::SeqCst);
+    Uuid::from_u64_pair(self.seed, sequence)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_uuid_v4_generator_success() {
+    let result = UuidV4Generator.generate();
+
+    // assert
+    assert_eq!(Some(uuid::Version::Random), result.get_version())
+  }
+
+  #[test]
+  fn test_uuid_v7_generator_is_time_ordered() {
+    let generator = UuidV7Generator;
+    let first = generator.generate();
+    let second = generator.generate();
+
+    // assert
+    assert_eq!(Some(uuid::Version::SortRand), first.get_version());
+    assert!(first < second)
+  }
+
+  #[test]
+  fn test_sequential_id_generator_is_deterministic() {
+    let generator1 = SequentialIdGenerator::new(42);
+    let generator2 = SequentialIdGenerator::new(42);
+
+    // assert
+    assert_eq!(
+      "00000000-0000-002a-0000-000000000001",
+      generator1.generate().to_string()
+    );
+    assert_eq!(
+      "00000000-0000-002a-0000-000000000002",
+      generator1.generate().to_string()
+    );
+    assert_eq!(
+      "00000000-0000-002a-0000-000000000001",
+      generator2.generate().to_string()
+    );
+  }
+}
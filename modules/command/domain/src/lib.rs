@@ -1,10 +1,16 @@
-use uuid::Uuid;
-
-mod aggregate_id;
+pub mod aggregate_id;
+pub mod clock;
+pub mod customer;
+pub mod error_code;
+pub mod event_envelope;
+pub mod event_id;
+pub mod id_generator;
 pub mod order;
+pub mod payment;
 pub mod value_object;
-mod product;
-
-pub fn generate_id() -> Uuid {
-  Uuid::new_v4()
-}
\ No newline at end of file
+pub mod product;
+pub mod promotion;
+pub mod repository;
+pub mod shipping;
+pub mod tax;
+pub mod validation;
@@ -1,133 +1,825 @@
 pub mod order_id;
-mod order_error;
-mod order_item;
-mod order_item_id;
+pub mod order_discount;
+pub mod order_error;
+pub mod order_event;
+pub mod order_event_publisher;
+pub mod order_item;
+pub mod order_item_id;
+pub mod order_pricing;
+pub mod order_repository;
+pub mod order_return;
+pub mod order_status;
 
+use crate::clock::Clock;
+use crate::customer::customer_id::CustomerId;
+use crate::order::order_discount::OrderDiscount;
 use crate::order::order_error::OrderError;
+use crate::order::order_event::{
+  ItemsReturned, LineDiscountApplied, OrderDiscountApplied, OrderEvent, OrderItemPlaced, OrderItemsAdded, OrderPlaced,
+  OrderShipped, OrderStatusChanged, ReturnedItem, ShippingAddressChanged,
+};
 use crate::order::order_id::OrderId;
 use crate::order::order_item::OrderItem;
-use crate::value_object::price::Price;
+use crate::order::order_item_id::OrderItemId;
+use crate::order::order_pricing::OrderPricing;
+use crate::order::order_return::ReturnItem;
+use crate::order::order_status::OrderStatus;
+use crate::payment::payment_event::PaymentEvent;
+use crate::shipping::shipping_address::ShippingAddress;
+use crate::shipping::shipping_details::ShippingDetails;
+use crate::tax::region::Region;
+use crate::tax::tax_breakdown::{TaxBreakdown, TaxableLine};
+use crate::tax::tax_rule::{TaxInclusion, TaxRule};
+use crate::value_object::currency::Currency;
+use crate::value_object::discount::DiscountKind;
+use crate::value_object::money::{Money, MoneyError};
+use crate::value_object::rounding_policy::{RoundingPolicy, RoundingScope};
 use chrono;
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Order {
   /// 注文ID
   id: OrderId,
 
+  /// 注文した顧客のID
+  customer_id: CustomerId,
+
   /// 注文日時
   ordered_at: DateTime<Utc>,
 
-  /// 合計金額
-  total_price: Price,
+  /// 注文の通貨
+  currency: Currency,
+
+  /// 金額の丸めポリシー
+  rounding_policy: RoundingPolicy,
+
+  /// 税率を判定する地域
+  region: Region,
+
+  /// 合計金額(税抜)
+  total_price: Money,
+
+  /// 税額の内訳
+  tax_breakdown: TaxBreakdown,
+
+  /// 外税を加算した支払総額
+  grand_total: Money,
 
   /// 注文アイテム
   order_items: Vec<OrderItem>,
+
+  /// 注文全体に対する割引
+  order_discounts: Vec<OrderDiscount>,
+
+  /// 配送先と配送方法
+  shipping: ShippingDetails,
+
+  /// 注文の状態
+  status: OrderStatus,
+
+  /// 出荷日時
+  ///
+  /// 出荷後に返金されて状態が変わっても出荷済みであることを判定できるよう、状態とは別に保持します
+  shipped_at: Option<DateTime<Utc>>,
+
+  /// 注文アイテムごとの返品済みの数量
+  returned_quantities: BTreeMap<OrderItemId, i32>,
 }
 
 impl Order {
-  /// コンストラクタです
+  /// 外部から呼び出すコンストラクタです
+  ///
+  /// 注文日時は`clock`から取得します。
+  /// 注文アイテムはすべて注文の通貨と一致していなければなりません。
+  /// 注文確定のイベントに続けて、適用された割引ごとに割引のイベントを返します
   ///
   /// # Argument
   /// * `id`: OrderId
-  /// * `created_at`: DateTime<Utc>
-  /// * `total_price`: Price
+  /// * `customer_id`: 注文した顧客のID
+  /// * `clock`: &dyn Clock
+  /// * `pricing`: 通貨・丸め・税率の設定
   /// * `order_items`: Vec<OrderItem>
+  /// * `order_discounts`: Vec<OrderDiscount>
+  /// * `shipping`: 配送先と配送方法
   ///
   /// # Return
-  /// * `Order`
-  fn new(
+  /// * `Result<(Order, Vec<OrderEvent>), OrderError>`
+  pub fn place_order(
     id: OrderId,
-    ordered_at: DateTime<Utc>,
-    total_price: Price,
+    customer_id: CustomerId,
+    clock: &dyn Clock,
+    pricing: OrderPricing,
     order_items: Vec<OrderItem>,
-  ) -> Self {
-    Order {
+    order_discounts: Vec<OrderDiscount>,
+    shipping: ShippingDetails,
+  ) -> Result<(Self, Vec<OrderEvent>), OrderError> {
+    let OrderTotals { subtotal, applied_discounts, total_price, tax_breakdown, grand_total } =
+      Self::calc_totals(&pricing, &order_items, &order_discounts)?;
+    let OrderPricing { currency, rounding_policy, region, .. } = pricing;
+    let order = Order {
       id,
-      ordered_at,
+      customer_id,
+      ordered_at: clock.now(),
+      currency,
+      rounding_policy,
+      region,
       total_price,
+      tax_breakdown,
+      grand_total,
       order_items,
-    }
+      order_discounts: order_discounts.clone(),
+      shipping,
+      status: OrderStatus::Placed,
+      shipped_at: None,
+      returned_quantities: BTreeMap::new(),
+    };
+
+    let mut events = vec![OrderEvent::OrderPlaced(OrderPlaced {
+      order_id: order.id.clone(),
+      customer_id: order.customer_id.clone(),
+      occurred_at: order.ordered_at,
+      currency,
+      rounding_policy,
+      region: order.region.clone(),
+      order_items: order.order_items.iter().map(|item| Self::item_placed(rounding_policy, item)).collect::<Result<_, _>>()?,
+      subtotal,
+      total_price,
+      tax: order.tax_breakdown.clone(),
+      grand_total,
+      shipping_address: order.shipping.address.clone(),
+      delivery_method: order.shipping.delivery_method,
+    })];
+    events.extend(order.line_discount_events(&order.order_items, order.ordered_at));
+    events.extend(order
+      .order_discount_events(&applied_discounts, order.ordered_at)
+      .into_iter()
+      .map(OrderEvent::OrderDiscountApplied));
+    Ok((order, events))
   }
 
-  /// 外部から呼び出すコンストラクタです
+  /// 確定後、支払い前の注文に明細を追加します
   ///
-  /// # Argument
-  /// * `id`: OrderId
-  /// * `created_at`: DateTime<Utc>
-  /// * `total_price`: Price
-  /// * `order_items`: Vec<OrderItem>
+  /// 追加後の明細で小計・注文割引・税額・支払総額を再計算します。
+  /// 注文割引は確定時の割引をそのまま使用するため、クーポンによる明細割引は追加した明細には適用されません。
+  /// 明細追加のイベントに続けて、追加した明細の明細割引のイベントを返します
+  ///
+  /// # Arguments
+  /// * `order_items`: 追加する明細
+  /// * `tax_rule`: 税率のルール
+  /// * `clock`: 追加日時の取得元
   ///
   /// # Return
-  /// * `Result<Order, OrderError>`
-  pub fn place_order(
-    id: OrderId,
-    ordered_at: DateTime<Utc>,
+  /// * `Result<Vec<OrderEvent>, OrderError>`
+  pub fn add_items(
+    &mut self,
     order_items: Vec<OrderItem>,
-  ) -> Result<Self, OrderError> {
-    Ok(Order::new(
-      id,
-      ordered_at,
-      Self::calc_total_price(&order_items)?,
-      order_items,
-    ))
+    tax_rule: &dyn TaxRule,
+    clock: &dyn Clock,
+  ) -> Result<Vec<OrderEvent>, OrderError> {
+    if self.status != OrderStatus::Placed {
+      Err(OrderError::InvalidStatus { order_id: self.id.clone(), status: self.status, action: "add items" })?
+    }
+    if order_items.is_empty() {
+      Err(OrderError::EmptyOrderItems)?
+    }
+    let pricing = OrderPricing {
+      currency: self.currency,
+      rounding_policy: self.rounding_policy,
+      region: self.region.clone(),
+      tax_rule,
+    };
+    let all_items = self.order_items.iter().cloned().chain(order_items.iter().cloned()).collect::<Vec<OrderItem>>();
+    let occurred_at = clock.now();
+    let totals = Self::calc_totals(&pricing, &all_items, &self.order_discounts)?;
+    let order_discounts = self.order_discount_events(&totals.applied_discounts, occurred_at);
+    let OrderTotals { subtotal, total_price, tax_breakdown, grand_total, .. } = totals;
+    let placed_items = order_items
+      .iter()
+      .map(|item| Self::item_placed(self.rounding_policy, item))
+      .collect::<Result<_, _>>()?;
+
+    self.order_items = all_items;
+    self.total_price = total_price;
+    self.tax_breakdown = tax_breakdown;
+    self.grand_total = grand_total;
+    let mut events = vec![OrderEvent::OrderItemsAdded(OrderItemsAdded {
+      order_id: self.id.clone(),
+      occurred_at,
+      order_items: placed_items,
+      subtotal,
+      order_discounts,
+      total_price,
+      tax: self.tax_breakdown.clone(),
+      grand_total,
+    })];
+    events.extend(self.line_discount_events(&order_items, occurred_at));
+    Ok(events)
+  }
+
+  /// 出荷前の注文の配送先の住所を変更します
+  ///
+  /// 出荷後は返金などで状態が変わっていても変更できません
+  ///
+  /// # Arguments
+  /// * `address`: 変更後の住所
+  /// * `clock`: 変更日時の取得元
+  ///
+  /// # Return
+  /// * `Result<OrderEvent, OrderError>`
+  pub fn change_shipping_address(
+    &mut self,
+    address: ShippingAddress,
+    clock: &dyn Clock,
+  ) -> Result<OrderEvent, OrderError> {
+    if self.shipped_at.is_some() {
+      Err(OrderError::AlreadyShipped(self.id.clone()))?
+    }
+    let previous_address = std::mem::replace(&mut self.shipping.address, address);
+    Ok(OrderEvent::ShippingAddressChanged(ShippingAddressChanged {
+      order_id: self.id.clone(),
+      occurred_at: clock.now(),
+      previous_address,
+      address: self.shipping.address.clone(),
+    }))
   }
 
-  /// 合計金額を計算します
-  pub fn calc_total_price(items: &Vec<OrderItem>) -> Result<Price, OrderError> {
-    let price: Decimal = items.iter()
-      .try_fold(Decimal::ZERO, |acc, item| -> Result<Decimal, OrderError> {
-        let unit_price = item.get_unit_price();
-        let discount = item.get_discount();
-        let quantity = Decimal::from(item.get_quantity());
+  /// 支払い済みの注文を出荷済みにします
+  ///
+  /// # Arguments
+  /// * `clock`: 出荷日時の取得元
+  ///
+  /// # Return
+  /// * `Result<OrderEvent, OrderError>`
+  pub fn ship(&mut self, clock: &dyn Clock) -> Result<OrderEvent, OrderError> {
+    match self.status {
+      OrderStatus::Paid => {}
+      OrderStatus::Shipped => Err(OrderError::AlreadyShipped(self.id.clone()))?,
+      status => Err(OrderError::InvalidStatus { order_id: self.id.clone(), status, action: "ship" })?,
+    }
+    let shipped_at = clock.now();
+    self.status = OrderStatus::Shipped;
+    self.shipped_at = Some(shipped_at);
+    Ok(OrderEvent::OrderShipped(OrderShipped { order_id: self.id.clone(), occurred_at: shipped_at }))
+  }
+
+  /// 支払いのイベントに応じて注文の状態を変更します
+  ///
+  /// - PaymentAuthorized: Placed / PaymentFailed → PaymentAuthorized
+  /// - PaymentCaptured: PaymentAuthorized → Paid
+  /// - PaymentFailed: Placed / PaymentAuthorized → PaymentFailed
+  /// - PaymentRefunded(全額): Paid / Shipped → Refunded(一部返金、返金の予約・取り消しでは状態を変更しません)
+  ///
+  /// # Arguments
+  /// * `event`: 支払いのイベント
+  /// * `clock`: 日時の取得元
+  ///
+  /// # Return
+  /// * `Result<Option<OrderEvent>, OrderError>`: 状態が変わった場合はOrderStatusChanged
+  pub fn apply_payment_event(&mut self, event: &PaymentEvent, clock: &dyn Clock) -> Result<Option<OrderEvent>, OrderError> {
+    if event.order_id() != &self.id {
+      Err(OrderError::PaymentOrderMismatch { expected: self.id.clone(), actual: event.order_id().clone() })?
+    }
+    let (allowed, next, action): (&[OrderStatus], OrderStatus, &'static str) = match event {
+      PaymentEvent::PaymentAuthorized(_) => (
+        &[OrderStatus::Placed, OrderStatus::PaymentFailed], OrderStatus::PaymentAuthorized, "authorize payment",
+      ),
+      PaymentEvent::PaymentCaptured(_) => (&[OrderStatus::PaymentAuthorized], OrderStatus::Paid, "capture payment"),
+      PaymentEvent::PaymentFailed(_) => (
+        &[OrderStatus::Placed, OrderStatus::PaymentAuthorized], OrderStatus::PaymentFailed, "fail payment",
+      ),
+      PaymentEvent::PaymentRefunded(refunded) if refunded.fully_refunded => (
+        &[OrderStatus::Paid, OrderStatus::Shipped], OrderStatus::Refunded, "refund payment",
+      ),
+      PaymentEvent::PaymentRefunded(_) | PaymentEvent::RefundRequested(_) | PaymentEvent::RefundCancelled(_) => return Ok(None),
+    };
+    if !allowed.contains(&self.status) {
+      Err(OrderError::InvalidStatus { order_id: self.id.clone(), status: self.status, action })?
+    }
+    let previous_status = std::mem::replace(&mut self.status, next);
+    Ok(Some(OrderEvent::OrderStatusChanged(OrderStatusChanged {
+      order_id: self.id.clone(),
+      occurred_at: clock.now(),
+      previous_status,
+      status: next,
+    })))
+  }
+
+  /// 出荷済みの注文の明細を返品します
+  ///
+  /// 返金額は明細ごとの支払額を数量で按分して計算します。
+  /// 明細のすべての数量を返品した時点で返金額の合計が明細の支払額と一致するよう端数を調整するため、
+  /// 返金額が明細の支払額を超えることはありません。
+  /// 同じ注文アイテムを複数指定した場合は数量を合算します
+  ///
+  /// # Arguments
+  /// * `items`: 返品する明細と数量
+  /// * `clock`: 返品日時の取得元
+  ///
+  /// # Return
+  /// * `Result<ItemsReturned, OrderError>`
+  pub fn return_items(&mut self, items: Vec<ReturnItem>, clock: &dyn Clock) -> Result<ItemsReturned, OrderError> {
+    if self.status != OrderStatus::Shipped {
+      Err(OrderError::InvalidStatus { order_id: self.id.clone(), status: self.status, action: "return items" })?
+    }
+    if items.is_empty() {
+      Err(OrderError::EmptyReturnItems)?
+    }
+    let mut requested: Vec<(OrderItemId, i32)> = Vec::with_capacity(items.len());
+    for item in items {
+      match requested.iter_mut().find(|(order_item_id, _)| order_item_id == &item.order_item_id) {
+        Some((_, quantity)) => *quantity = quantity.saturating_add(item.quantity.value()),
+        None => requested.push((item.order_item_id, item.quantity.value())),
+      }
+    }
+
+    let mut returned_items = Vec::with_capacity(requested.len());
+    let mut refund_amount = Money::zero(self.currency);
+    for (order_item_id, quantity) in requested {
+      let ordered = self.order_items
+        .iter()
+        .find(|item| item.get_order_item_id() == &order_item_id)
+        .ok_or_else(|| OrderError::OrderItemNotFound(order_item_id.clone()))?
+        .get_quantity();
+      let returned = self.get_returned_quantity(&order_item_id);
+      if quantity > ordered - returned {
+        Err(OrderError::ReturnQuantityExceeded {
+          order_item_id: order_item_id.clone(),
+          requested: quantity,
+          returnable: ordered - returned,
+        })?
+      }
+      let paid = self.calc_line_paid_amount(&order_item_id)?;
+      let amount = self.calc_line_refund(&paid, ordered, returned + quantity)?
+        .sub(&self.calc_line_refund(&paid, ordered, returned)?)?;
+      refund_amount = refund_amount.add(&amount)?;
+      returned_items.push(ReturnedItem { order_item_id, quantity, refund_amount: amount });
+    }
+    for item in &returned_items {
+      *self.returned_quantities.entry(item.order_item_id.clone()).or_insert(0) += item.quantity;
+    }
+    Ok(ItemsReturned {
+      order_id: self.id.clone(),
+      occurred_at: clock.now(),
+      items: returned_items,
+      refund_amount,
+    })
+  }
+
+  /// 明細ごとの支払額を計算します
+  ///
+  /// 明細割引と按分した注文割引を差し引いた金額に、外税を加算した金額です。
+  /// すべての明細の支払額の合計は支払総額と一致します
+  ///
+  /// # Arguments
+  /// * `order_item_id`: 注文アイテムのID
+  ///
+  /// # Return
+  /// * `Result<Money, OrderError>`
+  pub fn calc_line_paid_amount(&self, order_item_id: &OrderItemId) -> Result<Money, OrderError> {
+    let line = self.tax_breakdown
+      .lines()
+      .iter()
+      .find(|line| &line.order_item_id == order_item_id)
+      .ok_or_else(|| OrderError::OrderItemNotFound(order_item_id.clone()))?;
+    let paid = match line.treatment.inclusion() {
+      TaxInclusion::Exclusive => line.taxable_amount.add(&line.tax_amount)?,
+      TaxInclusion::Inclusive => line.taxable_amount,
+    };
+    Ok(paid)
+  }
+
+  /// 明細の数量のうち`returned`個を返品した時点の返金額の累計を計算します
+  fn calc_line_refund(&self, paid: &Money, ordered: i32, returned: i32) -> Result<Money, MoneyError> {
+    if returned >= ordered {
+      Ok(*paid)
+    } else {
+      Ok(self.rounding_policy.round(&paid.multiply(Decimal::from(returned) / Decimal::from(ordered))?))
+    }
+  }
+
+  /// 注文IDのゲッター
+  pub fn get_id(&self) -> &OrderId { &self.id }
+
+  /// 顧客IDのゲッター
+  pub fn get_customer_id(&self) -> &CustomerId { &self.customer_id }
+
+  /// 注文日時のゲッター
+  pub fn get_ordered_at(&self) -> &DateTime<Utc> { &self.ordered_at }
+
+  /// 通貨のゲッター
+  pub fn get_currency(&self) -> Currency { self.currency }
 
-        let item_total = unit_price * quantity;
-        let discounted = item_total - (item_total * discount / Decimal::from(100));
-        Ok(acc + discounted)
+  /// 丸めポリシーのゲッター
+  pub fn get_rounding_policy(&self) -> RoundingPolicy { self.rounding_policy }
+
+  /// 地域のゲッター
+  pub fn get_region(&self) -> &Region { &self.region }
+
+  /// 合計金額(税抜)のゲッター
+  pub fn get_total_price(&self) -> &Money { &self.total_price }
+
+  /// 税額の内訳のゲッター
+  pub fn get_tax_breakdown(&self) -> &TaxBreakdown { &self.tax_breakdown }
+
+  /// 支払総額のゲッター
+  pub fn get_grand_total(&self) -> &Money { &self.grand_total }
+
+  /// 注文アイテムのゲッター
+  pub fn get_order_items(&self) -> &[OrderItem] { &self.order_items }
+
+  /// 注文割引のゲッター
+  pub fn get_order_discounts(&self) -> &[OrderDiscount] { &self.order_discounts }
+
+  /// 配送情報のゲッター
+  pub fn get_shipping(&self) -> &ShippingDetails { &self.shipping }
+
+  /// 注文の状態のゲッター
+  pub fn get_status(&self) -> OrderStatus { self.status }
+
+  /// 出荷日時のゲッター
+  pub fn get_shipped_at(&self) -> Option<&DateTime<Utc>> { self.shipped_at.as_ref() }
+
+  /// 注文アイテムの返品済みの数量を返します
+  pub fn get_returned_quantity(&self, order_item_id: &OrderItemId) -> i32 {
+    self.returned_quantities.get(order_item_id).copied().unwrap_or(0)
+  }
+
+  /// 明細・注文割引から小計・税額・支払総額を計算します
+  fn calc_totals<'a>(
+    pricing: &OrderPricing,
+    order_items: &[OrderItem],
+    order_discounts: &'a [OrderDiscount],
+  ) -> Result<OrderTotals<'a>, OrderError> {
+    let subtotal = Self::calc_subtotal(pricing.currency, pricing.rounding_policy, order_items)?;
+    let applied_discounts = Self::calc_order_discounts(pricing.rounding_policy, &subtotal, order_discounts)?;
+    let total_price = Self::apply_order_discounts(&subtotal, &applied_discounts)?;
+    let order_discount_total = subtotal.sub(&total_price)?;
+    let taxable_lines = Self::calc_taxable_lines(pricing.rounding_policy, order_items, &order_discount_total)?;
+    let tax_breakdown = TaxBreakdown::calculate(
+      pricing.currency, pricing.rounding_policy, &pricing.region, pricing.tax_rule, &taxable_lines,
+    )?;
+    let grand_total = total_price.add(tax_breakdown.exclusive_tax())?;
+    Ok(OrderTotals { subtotal, applied_discounts, total_price, tax_breakdown, grand_total })
+  }
+
+  /// 明細をイベントに記録する形式に変換します
+  fn item_placed(rounding_policy: RoundingPolicy, item: &OrderItem) -> Result<OrderItemPlaced, MoneyError> {
+    Ok(OrderItemPlaced {
+      order_item_id: item.get_order_item_id().clone(),
+      product_id: item.get_product_id(),
+      product_name: item.get_product_name().to_string(),
+      product_category: item.get_product_category().clone(),
+      unit_price: *item.get_unit_price_money(),
+      discount: item.get_discount().clone(),
+      quantity: item.get_quantity(),
+      line_total: Self::calc_line_total(rounding_policy, item)?,
+    })
+  }
+
+  /// 割引のある明細ごとに明細割引のイベントを作成します
+  fn line_discount_events(&self, order_items: &[OrderItem], occurred_at: DateTime<Utc>) -> Vec<OrderEvent> {
+    order_items
+      .iter()
+      .filter(|item| !item.get_discount().is_zero())
+      .map(|item| OrderEvent::LineDiscountApplied(LineDiscountApplied {
+        order_id: self.id.clone(),
+        order_item_id: item.get_order_item_id().clone(),
+        occurred_at,
+        discount: item.get_discount().clone(),
+        amount: self.rounding_policy.round(item.get_discount_amount()),
+      }))
+      .collect()
+  }
+
+  /// 適用した注文割引ごとに注文割引のイベントを作成します
+  fn order_discount_events(
+    &self,
+    applied_discounts: &[(&OrderDiscount, Money)],
+    occurred_at: DateTime<Utc>,
+  ) -> Vec<OrderDiscountApplied> {
+    applied_discounts
+      .iter()
+      .map(|(discount, amount)| OrderDiscountApplied {
+        order_id: self.id.clone(),
+        occurred_at,
+        discount: discount.get_discount().clone(),
+        coupon_code: discount.get_coupon_code().cloned(),
+        amount: *amount,
+      })
+      .collect()
+  }
+
+  /// 明細金額を通貨の補助単位に丸めて計算します
+  pub fn calc_line_total(rounding_policy: RoundingPolicy, item: &OrderItem) -> Result<Money, MoneyError> {
+    Ok(rounding_policy.round(&item.calc_line_total()?))
+  }
+
+  /// 合計金額(税抜)を計算します
+  ///
+  /// 明細割引を適用した小計から、注文割引を差し引いた金額です
+  pub fn calc_total_price(
+    currency: Currency,
+    rounding_policy: RoundingPolicy,
+    items: &[OrderItem],
+    order_discounts: &[OrderDiscount],
+  ) -> Result<Money, OrderError> {
+    let subtotal = Self::calc_subtotal(currency, rounding_policy, items)?;
+    let applied_discounts = Self::calc_order_discounts(rounding_policy, &subtotal, order_discounts)?;
+    Self::apply_order_discounts(&subtotal, &applied_discounts)
+  }
+
+  /// 明細割引を適用した小計を計算します
+  ///
+  /// PerLineの場合は丸めた明細金額を合計し、PerOrderの場合は合計してから丸めます。
+  /// 注文の通貨と異なる注文アイテムが含まれる場合はエラーになります
+  pub fn calc_subtotal(
+    currency: Currency,
+    rounding_policy: RoundingPolicy,
+    items: &[OrderItem],
+  ) -> Result<Money, OrderError> {
+    if items.is_empty() {
+      Err(OrderError::EmptyOrderItems)?
+    }
+    let price = items.iter()
+      .try_fold(Money::zero(currency), |acc, item| -> Result<Money, OrderError> {
+        if item.get_currency() != currency {
+          Err(MoneyError::CurrencyMismatch { expected: currency, actual: item.get_currency() })?
+        }
+        let line_total = match rounding_policy.scope() {
+          RoundingScope::PerLine => Self::calc_line_total(rounding_policy, item)?,
+          RoundingScope::PerOrder => item.calc_line_total()?,
+        };
+        Ok(acc.add(&line_total)?)
       })?;
-    let result = Price::try_from(Decimal::from(price))?;
-    Ok(result)
+    Ok(rounding_policy.round(&price))
+  }
+
+  /// 注文割引ごとの割引額を適用順に計算します
+  ///
+  /// 割引額は丸めポリシーで丸めます。固定金額の割引は残りの金額を上限とします
+  fn calc_order_discounts<'a>(
+    rounding_policy: RoundingPolicy,
+    subtotal: &Money,
+    order_discounts: &'a [OrderDiscount],
+  ) -> Result<Vec<(&'a OrderDiscount, Money)>, OrderError> {
+    OrderDiscount::validate_stacking(order_discounts)?;
+    let mut remaining = *subtotal;
+    let mut applied = Vec::with_capacity(order_discounts.len());
+    for order_discount in OrderDiscount::in_application_order(order_discounts) {
+      let discount = order_discount.get_discount();
+      let amount = match discount.kind() {
+        DiscountKind::FixedAmount(fixed)
+          if fixed.currency() == remaining.currency() && fixed.amount() > remaining.amount() => remaining,
+        _ => rounding_policy.round(&discount.calc_amount(&remaining)?),
+      };
+      remaining = remaining.sub(&amount)?;
+      applied.push((order_discount, amount));
+    }
+    Ok(applied)
+  }
+
+  /// 小計から注文割引額を差し引きます
+  fn apply_order_discounts(
+    subtotal: &Money,
+    applied_discounts: &[(&OrderDiscount, Money)],
+  ) -> Result<Money, OrderError> {
+    let total = applied_discounts
+      .iter()
+      .try_fold(*subtotal, |acc, (_, amount)| acc.sub(amount))?;
+    Ok(total)
+  }
+
+  /// 課税対象の明細を計算します
+  ///
+  /// 注文割引は明細金額の比率で按分し、端数は最後の明細で調整します
+  fn calc_taxable_lines(
+    rounding_policy: RoundingPolicy,
+    items: &[OrderItem],
+    order_discount_total: &Money,
+  ) -> Result<Vec<TaxableLine>, OrderError> {
+    let line_totals = items.iter()
+      .map(|item| Self::calc_line_total(rounding_policy, item))
+      .collect::<Result<Vec<Money>, MoneyError>>()?;
+    let base = line_totals.iter()
+      .try_fold(Money::zero(order_discount_total.currency()), |acc, line_total| acc.add(line_total))?;
+
+    let mut remaining = *order_discount_total;
+    let mut taxable_lines = Vec::with_capacity(items.len());
+    for (index, (item, line_total)) in items.iter().zip(line_totals).enumerate() {
+      let allocated = if base.amount().is_zero() {
+        Money::zero(line_total.currency())
+      } else if index + 1 == items.len() {
+        remaining
+      } else {
+        rounding_policy.round(&order_discount_total.multiply(line_total.amount() / base.amount())?)
+      };
+      let allocated = if allocated.amount() > line_total.amount() { line_total } else { allocated };
+      remaining = remaining.sub(&allocated)?;
+      taxable_lines.push(TaxableLine {
+        order_item_id: item.get_order_item_id().clone(),
+        product_category: item.get_product_category().clone(),
+        amount: line_total.sub(&allocated)?,
+      });
+    }
+    Ok(taxable_lines)
+  }
+}
+
+/// 注文の金額の計算結果です
+struct OrderTotals<'a> {
+  subtotal: Money,
+  applied_discounts: Vec<(&'a OrderDiscount, Money)>,
+  total_price: Money,
+  tax_breakdown: TaxBreakdown,
+  grand_total: Money,
+}
+
+/// 注文のデシリアライズ用の値です
+#[derive(Deserialize)]
+struct OrderValue {
+  id: OrderId,
+  customer_id: CustomerId,
+  ordered_at: DateTime<Utc>,
+  currency: Currency,
+  rounding_policy: RoundingPolicy,
+  region: Region,
+  total_price: Money,
+  tax_breakdown: TaxBreakdown,
+  grand_total: Money,
+  order_items: Vec<OrderItem>,
+  order_discounts: Vec<OrderDiscount>,
+  shipping: ShippingDetails,
+  status: OrderStatus,
+  #[serde(default)]
+  shipped_at: Option<DateTime<Utc>>,
+  returned_quantities: BTreeMap<OrderItemId, i32>,
+}
+
+impl<'de> Deserialize<'de> for Order {
+  /// 合計金額・支払総額・返品数量が注文アイテムと矛盾する場合はエラーにします
+  ///
+  /// 注文アイテムが空の場合や、注文の通貨と異なる金額が含まれる場合、出荷日時が状態と矛盾する場合もエラーになります
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let value = OrderValue::deserialize(deserializer)?;
+    let total_price = Self::calc_total_price(
+      value.currency,
+      value.rounding_policy,
+      &value.order_items,
+      &value.order_discounts,
+    )
+      .map_err(serde::de::Error::custom)?;
+    if total_price != value.total_price {
+      Err(serde::de::Error::custom("total price does not match order items"))?
+    }
+    let grand_total = total_price
+      .add(value.tax_breakdown.exclusive_tax())
+      .map_err(serde::de::Error::custom)?;
+    if grand_total != value.grand_total {
+      Err(serde::de::Error::custom("grand total does not match total price and tax"))?
+    }
+    let shipment_required = value.status == OrderStatus::Shipped;
+    let shipment_allowed = matches!(value.status, OrderStatus::Shipped | OrderStatus::Refunded);
+    if (shipment_required && value.shipped_at.is_none()) || (!shipment_allowed && value.shipped_at.is_some()) {
+      Err(serde::de::Error::custom("shipped_at does not match order status"))?
+    }
+    for (order_item_id, &quantity) in &value.returned_quantities {
+      let ordered = value.order_items
+        .iter()
+        .find(|item| item.get_order_item_id() == order_item_id)
+        .ok_or_else(|| serde::de::Error::custom(OrderError::OrderItemNotFound(order_item_id.clone())))?
+        .get_quantity();
+      if quantity < 1 || ordered < quantity {
+        Err(serde::de::Error::custom(OrderError::ReturnQuantityExceeded {
+          order_item_id: order_item_id.clone(),
+          requested: quantity,
+          returnable: ordered,
+        }))?
+      }
+    }
+    Ok(Self {
+      id: value.id,
+      customer_id: value.customer_id,
+      ordered_at: value.ordered_at,
+      currency: value.currency,
+      rounding_policy: value.rounding_policy,
+      region: value.region,
+      total_price,
+      tax_breakdown: value.tax_breakdown,
+      grand_total,
+      order_items: value.order_items,
+      order_discounts: value.order_discounts,
+      shipping: value.shipping,
+      status: value.status,
+      shipped_at: value.shipped_at,
+      returned_quantities: value.returned_quantities,
+    })
   }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::clock::FixedClock;
+  use crate::id_generator::UuidV4Generator;
+  use crate::order::order_item::OrderItemParams;
   use crate::order::order_item_id::OrderItemId;
+  use crate::payment::payment_id::PaymentId;
+  use crate::payment::refund_id::RefundId;
+  use crate::payment::Payment;
+  use crate::product::product_category::ProductCategory;
+  use crate::shipping::delivery_method::DeliveryMethod;
+  use crate::tax::tax_rule::{RegionalTaxRule, TaxInclusion, TaxRate, TaxRule, TaxRules, TaxTreatment};
+  use crate::value_object::coupon_code::CouponCode;
+  use crate::value_object::discount::{Discount, DiscountError};
+  use crate::value_object::quantity::Quantity;
+  use crate::value_object::rounding_policy::RoundingMode;
+  use chrono::TimeZone;
+  use proptest::prelude::*;
+  use rstest::rstest;
+  use rust_decimal::Decimal;
+  use std::str::FromStr;
+  use std::sync::Arc;
+
+  fn fixed_clock() -> FixedClock {
+    FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap())
+  }
+
+  fn shipping() -> ShippingDetails {
+    ShippingDetails {
+      address: address("100-0001"),
+      delivery_method: DeliveryMethod::Standard,
+    }
+  }
+
+  fn address(postal_code: &str) -> ShippingAddress {
+    ShippingAddress::new("山田 太郎", "JP", postal_code, Some("東京都"), "千代田区", "千代田1-1", None).unwrap()
+  }
+
+  fn pricing(currency: Currency, rounding_policy: RoundingPolicy, tax_rule: &dyn TaxRule) -> OrderPricing<'_> {
+    OrderPricing {
+      currency,
+      rounding_policy,
+      region: Region::from_str("JP").unwrap(),
+      tax_rule,
+    }
+  }
+
+  fn jpy(value: i64) -> Money {
+    Money::new(Decimal::from(value), Currency::JPY).unwrap()
+  }
+
+  fn jpy_item(unit_price: i64, discount: Discount, quantity: i32) -> OrderItem {
+    OrderItem::place_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id: 1,
+      product_name: "hogehoge",
+      product_category: "general",
+      unit_price: Decimal::from(unit_price),
+      currency: "JPY",
+      discount,
+      quantity,
+    }).unwrap()
+  }
+
   #[test]
   fn test_order_calc_total_price_success() {
-    let data1 = OrderItem::place_order_item(
-      OrderItemId::new(),
-      1,
-      "hogehoge",
-      500,
-      1,
-      2,
-    ).unwrap();
-    let data2 = OrderItem::place_order_item(
-      OrderItemId::new(),
-      2,
-      "fugafuga",
-      100,
-      1,
-      10,
-    ).unwrap();
+    let data1 = OrderItem::place_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id: 1,
+      product_name: "hogehoge",
+      product_category: "general",
+      unit_price: Decimal::from(500),
+      currency: "JPY",
+      discount: Discount::try_from(1).unwrap(),
+      quantity: 2,
+    }).unwrap();
+    let data2 = OrderItem::place_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id: 2,
+      product_name: "fugafuga",
+      product_category: "general",
+      unit_price: Decimal::from(100),
+      currency: "JPY",
+      discount: Discount::try_from(1).unwrap(),
+      quantity: 10,
+    }).unwrap();
     let vec: Vec<OrderItem> = vec![data1.clone(), data2.clone()];
-    let result = Order::calc_total_price(&vec);
+    let result = Order::calc_total_price(Currency::JPY, Currency::JPY.default_rounding_policy(), &vec, &[]);
 
     let expected_value = {
       let data1_unit_price = data1.get_unit_price();
       let data1_quantity = Decimal::from(data1.get_quantity());
-      let data1_discount = data1.get_discount();
       let data1_item_total = data1_unit_price * data1_quantity;
-      let data1_discounted = data1_item_total - (data1_item_total * data1_discount / Decimal::from(100));
+      let data1_discounted = data1_item_total - (data1_item_total * Decimal::from(1) / Decimal::from(100));
 
       let data2_unit_price = data2.get_unit_price();
       let data2_quantity = Decimal::from(data2.get_quantity());
-      let data2_discount = data2.get_discount();
       let data2_item_total = data2_unit_price * data2_quantity;
-      let data2_discounted = data2_item_total - (data2_item_total * data2_discount / Decimal::from(100));
+      let data2_discounted = data2_item_total - (data2_item_total * Decimal::from(1) / Decimal::from(100));
 
       data1_discounted + data2_discounted
     };
@@ -135,53 +827,714 @@ mod tests {
     // assert
     assert_eq!(
       &expected_value,
-      result.unwrap().value()
+      result.unwrap().amount()
     )
   }
 
+  #[test]
+  fn test_order_calc_total_price_currency_mismatch_failed() {
+    let data1 = jpy_item(500, Discount::none(), 1);
+    let data2 = OrderItem::place_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id: 2,
+      product_name: "fugafuga",
+      product_category: "general",
+      unit_price: Decimal::new(999, 2),
+      currency: "USD",
+      discount: Discount::none(),
+      quantity: 1,
+    }).unwrap();
+    let result = Order::calc_total_price(Currency::JPY, Currency::JPY.default_rounding_policy(), &[data1, data2], &[]);
+
+    // assert
+    assert!(matches!(
+      result,
+      Err(OrderError::InvalidMoney(MoneyError::CurrencyMismatch {
+        expected: Currency::JPY,
+        actual: Currency::USD,
+      }))
+    ))
+  }
+
   #[test]
   fn test_order_place_order_success() {
-    let order_id = OrderId::new();
-    let ordered_at = Utc::now();
-    let data1 = OrderItem::place_order_item(
-      OrderItemId::new(),
-      1,
-      "hogehoge",
-      500,
-      1,
-      2,
-    ).unwrap();
-    let data2 = OrderItem::place_order_item(
-      OrderItemId::new(),
-      2,
-      "fugafuga",
-      100,
-      1,
-      10,
-    ).unwrap();
+    let order_id = OrderId::generate(&UuidV4Generator);
+    let clock = fixed_clock();
+    let data1 = OrderItem::place_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id: 1,
+      product_name: "hogehoge",
+      product_category: "general",
+      unit_price: Decimal::new(1050, 2),
+      currency: "USD",
+      discount: Discount::try_from(1).unwrap(),
+      quantity: 2,
+    }).unwrap();
+    let data2 = OrderItem::place_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id: 2,
+      product_name: "fugafuga",
+      product_category: "general",
+      unit_price: Decimal::from(100),
+      currency: "USD",
+      discount: Discount::try_from(1).unwrap(),
+      quantity: 10,
+    }).unwrap();
     let order_items: Vec<OrderItem> = vec![data1.clone(), data2.clone()];
 
     let result = Order::place_order(
       order_id.clone(),
-      ordered_at.clone(),
+      CustomerId::generate(&UuidV4Generator),
+      &clock,
+      pricing(Currency::USD, Currency::USD.default_rounding_policy(), &TaxRules::default()),
       order_items,
+      vec![],
+      shipping(),
     );
 
     // assert
     assert!(result.is_ok());
-    assert_eq!(result.unwrap().id, order_id);
+    let (order, events) = result.unwrap();
+    assert_eq!(order.id, order_id);
+    assert_eq!(order.ordered_at, clock.now());
+    assert_eq!(Currency::USD, order.get_total_price().currency());
+    let OrderEvent::OrderPlaced(placed) = &events[0] else { panic!("OrderPlaced expected") };
+    assert_eq!(order_id, placed.order_id);
+    assert_eq!(clock.now(), placed.occurred_at);
+    assert_eq!(order.total_price, placed.total_price);
+    assert_eq!(Currency::USD.default_rounding_policy(), placed.rounding_policy);
+    assert_eq!(2, placed.order_items.len());
+    assert_eq!(3, events.len());
+    assert!(events[1..].iter().all(|event| matches!(event, OrderEvent::LineDiscountApplied(_))));
+  }
+
+  #[test]
+  fn test_order_place_order_with_discounts_success() {
+    // 1000円×2から300円引き = 1700円、10%引き = 1530円、200円引き = 1330円
+    let items = vec![jpy_item(1000, Discount::fixed_amount(jpy(300)).unwrap(), 2)];
+    let order_discounts = vec![
+      OrderDiscount::new(Discount::fixed_amount(jpy(200)).unwrap(), None, true),
+      OrderDiscount::new(Discount::try_from(10).unwrap(), Some(CouponCode::new("WELCOME").unwrap()), true),
+    ];
+
+    let (order, events) = Order::place_order(
+      OrderId::generate(&UuidV4Generator),
+      CustomerId::generate(&UuidV4Generator),
+      &fixed_clock(),
+      pricing(Currency::JPY, Currency::JPY.default_rounding_policy(), &TaxRules::default()),
+      items,
+      order_discounts,
+      shipping(),
+    ).unwrap();
+
+    // assert
+    assert_eq!(&jpy(1330), order.get_total_price());
+    let OrderEvent::OrderPlaced(placed) = &events[0] else { panic!("OrderPlaced expected") };
+    assert_eq!(jpy(1700), placed.subtotal);
+    let OrderEvent::LineDiscountApplied(line_discount) = &events[1] else { panic!("LineDiscountApplied expected") };
+    assert_eq!(jpy(300), line_discount.amount);
+    let OrderEvent::OrderDiscountApplied(coupon) = &events[2] else { panic!("OrderDiscountApplied expected") };
+    assert_eq!(Some(CouponCode::new("WELCOME").unwrap()), coupon.coupon_code);
+    assert_eq!(jpy(170), coupon.amount);
+    let OrderEvent::OrderDiscountApplied(fixed) = &events[3] else { panic!("OrderDiscountApplied expected") };
+    assert_eq!(jpy(200), fixed.amount);
+  }
+
+  #[test]
+  fn test_order_place_order_with_tax_success() {
+    // 食品(8%外税) 1000円、一般(10%外税) 2000円、注文割引300円を1:2で按分
+    let rate = |value: i64| TaxRate::try_from(Decimal::from(value)).unwrap();
+    let tax_rules = TaxRules::default()
+      .with_rule(Arc::new(RegionalTaxRule::new(
+        Region::from_str("JP").unwrap(),
+        Some(ProductCategory::new("food").unwrap()),
+        TaxTreatment::new(rate(8), TaxInclusion::Exclusive),
+      )))
+      .with_rule(Arc::new(RegionalTaxRule::new(
+        Region::from_str("JP").unwrap(),
+        None,
+        TaxTreatment::new(rate(10), TaxInclusion::Exclusive),
+      )));
+    let food = OrderItem::place_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id: 1,
+      product_name: "おにぎり",
+      product_category: "food",
+      unit_price: Decimal::from(1000),
+      currency: "JPY",
+      discount: Discount::none(),
+      quantity: 1,
+    }).unwrap();
+    let general = jpy_item(2000, Discount::none(), 1);
+    let order_discounts = vec![OrderDiscount::new(Discount::fixed_amount(jpy(300)).unwrap(), None, false)];
+
+    let (order, events) = Order::place_order(
+      OrderId::generate(&UuidV4Generator),
+      CustomerId::generate(&UuidV4Generator),
+      &fixed_clock(),
+      pricing(Currency::JPY, Currency::JPY.default_rounding_policy(), &tax_rules),
+      vec![food, general],
+      order_discounts,
+      shipping(),
+    ).unwrap();
+
+    // assert
+    // 食品: (1000 - 100) × 8% = 72、一般: (2000 - 200) × 10% = 180
+    let lines = order.get_tax_breakdown().lines();
+    assert_eq!(jpy(900), lines[0].taxable_amount);
+    assert_eq!(jpy(72), lines[0].tax_amount);
+    assert_eq!(jpy(1800), lines[1].taxable_amount);
+    assert_eq!(jpy(180), lines[1].tax_amount);
+    assert_eq!(&jpy(2700), order.get_total_price());
+    assert_eq!(&jpy(252), order.get_tax_breakdown().total_tax());
+    assert_eq!(&jpy(2952), order.get_grand_total());
+    let OrderEvent::OrderPlaced(placed) = &events[0] else { panic!("OrderPlaced expected") };
+    assert_eq!(order.get_tax_breakdown(), &placed.tax);
+    assert_eq!(jpy(2952), placed.grand_total);
+  }
+
+  #[test]
+  fn test_order_place_order_with_inclusive_tax_success() {
+    let tax_rules = TaxRules::default().with_rule(Arc::new(RegionalTaxRule::new(
+      Region::from_str("JP").unwrap(),
+      None,
+      TaxTreatment::new(TaxRate::try_from(Decimal::from(10)).unwrap(), TaxInclusion::Inclusive),
+    )));
+
+    let (order, _) = Order::place_order(
+      OrderId::generate(&UuidV4Generator),
+      CustomerId::generate(&UuidV4Generator),
+      &fixed_clock(),
+      pricing(Currency::JPY, Currency::JPY.default_rounding_policy(), &tax_rules),
+      vec![jpy_item(1100, Discount::none(), 1)],
+      vec![],
+      shipping(),
+    ).unwrap();
+
+    // assert
+    assert_eq!(&jpy(100), order.get_tax_breakdown().total_tax());
+    assert_eq!(&jpy(1100), order.get_grand_total());
+  }
+
+  #[test]
+  fn test_order_calc_total_price_fixed_discount_is_capped() {
+    let items = vec![jpy_item(500, Discount::none(), 1)];
+    let order_discounts = vec![OrderDiscount::new(Discount::fixed_amount(jpy(1000)).unwrap(), None, false)];
+    let result = Order::calc_total_price(Currency::JPY, Currency::JPY.default_rounding_policy(), &items, &order_discounts);
+
+    // assert
+    assert_eq!(jpy(0), result.unwrap())
+  }
+
+  #[test]
+  fn test_order_calc_total_price_not_stackable_failed() {
+    let items = vec![jpy_item(500, Discount::none(), 1)];
+    let order_discounts = vec![
+      OrderDiscount::new(Discount::try_from(10).unwrap(), None, false),
+      OrderDiscount::new(Discount::try_from(5).unwrap(), None, true),
+    ];
+    let result = Order::calc_total_price(Currency::JPY, Currency::JPY.default_rounding_policy(), &items, &order_discounts);
+
+    // assert
+    assert!(matches!(result, Err(OrderError::InvalidDiscount(DiscountError::NotStackable))))
+  }
+
+  #[test]
+  fn test_order_item_fixed_discount_exceeds_line_failed() {
+    let result = OrderItem::place_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id: 1,
+      product_name: "hogehoge",
+      product_category: "general",
+      unit_price: Decimal::from(100),
+      currency: "JPY",
+      discount: Discount::fixed_amount(jpy(201)).unwrap(),
+      quantity: 2,
+    });
+
+    // assert
+    assert!(matches!(result, Err(OrderError::InvalidDiscount(DiscountError::ExceedsAmount { .. }))))
+  }
+
+  #[rstest]
+  #[case(RoundingScope::PerLine, Decimal::from(21))]
+  #[case(RoundingScope::PerOrder, Decimal::from(20))]
+  fn test_order_calc_total_price_rounding_scope(#[case] scope: RoundingScope, #[case] expected: Decimal) {
+    // 10円の33%引き(6.7円)を3明細: 明細ごとなら7×3、注文全体なら20.1を丸めて20
+    let items: Vec<OrderItem> = (0..3)
+      .map(|_| jpy_item(10, Discount::try_from(33).unwrap(), 1))
+      .collect();
+    let policy = RoundingPolicy::new(RoundingMode::HalfUp, scope);
+    let result = Order::calc_total_price(Currency::JPY, policy, &items, &[]).unwrap();
+
+    // assert
+    assert_eq!(&expected, result.amount());
+    assert_eq!(0, result.amount().scale())
   }
 
   #[test]
   fn test_order_place_order_failed() {
-    let order_id = OrderId::new();
-    let ordered_at = Utc::now();
+    let order_id = OrderId::generate(&UuidV4Generator);
+    let clock = fixed_clock();
     let order_items: Vec<OrderItem> = vec![];
 
     let result = Order::place_order(
-      order_id, ordered_at, order_items,
+      order_id, CustomerId::generate(&UuidV4Generator), &clock, pricing(Currency::JPY, Currency::JPY.default_rounding_policy(), &TaxRules::default()), order_items, vec![], shipping(),
     );
 
     assert!(result.is_err())
   }
-}
\ No newline at end of file
+
+  fn placed_order() -> Order {
+    Order::place_order(
+      OrderId::generate(&UuidV4Generator),
+      CustomerId::generate(&UuidV4Generator),
+      &fixed_clock(),
+      pricing(Currency::JPY, Currency::JPY.default_rounding_policy(), &TaxRules::default()),
+      vec![jpy_item(1000, Discount::none(), 1)],
+      vec![],
+      shipping(),
+    ).unwrap().0
+  }
+
+  #[test]
+  fn test_order_change_shipping_address_success() {
+    let mut order = placed_order();
+
+    let event = order.change_shipping_address(address("150-0001"), &fixed_clock()).unwrap();
+
+    // assert
+    assert_eq!("150-0001", order.get_shipping().address.get_postal_code().value());
+    assert_eq!(DeliveryMethod::Standard, order.get_shipping().delivery_method);
+    let OrderEvent::ShippingAddressChanged(changed) = event else { panic!("ShippingAddressChanged expected") };
+    assert_eq!(address("100-0001"), changed.previous_address);
+    assert_eq!(address("150-0001"), changed.address);
+  }
+
+  #[test]
+  fn test_order_change_shipping_address_after_shipment_failed() {
+    let mut order = paid_order();
+    order.ship(&fixed_clock()).unwrap();
+
+    let result = order.change_shipping_address(address("150-0001"), &fixed_clock());
+
+    // assert
+    assert!(matches!(result, Err(OrderError::AlreadyShipped(_))));
+    assert_eq!(OrderStatus::Shipped, order.get_status());
+    assert_eq!(&address("100-0001"), &order.get_shipping().address);
+    assert!(matches!(order.ship(&fixed_clock()), Err(OrderError::AlreadyShipped(_))));
+  }
+
+  #[test]
+  fn test_order_change_shipping_address_after_refund_failed() {
+    let mut order = paid_order();
+    let mut payment = new_payment(&order);
+    payment.authorize("ref-1", &fixed_clock()).unwrap();
+    payment.capture(&fixed_clock()).unwrap();
+    order.ship(&fixed_clock()).unwrap();
+    let refunded = refund(&mut payment, *order.get_grand_total());
+    order.apply_payment_event(&refunded, &fixed_clock()).unwrap();
+
+    let result = order.change_shipping_address(address("150-0001"), &fixed_clock());
+
+    // assert
+    assert_eq!(OrderStatus::Refunded, order.get_status());
+    assert_eq!(Some(&fixed_clock().now()), order.get_shipped_at());
+    assert!(matches!(result, Err(OrderError::AlreadyShipped(_))));
+    assert_eq!(&address("100-0001"), &order.get_shipping().address);
+  }
+
+  #[test]
+  fn test_order_add_items_success() {
+    let (mut order, _) = Order::place_order(
+      OrderId::generate(&UuidV4Generator),
+      CustomerId::generate(&UuidV4Generator),
+      &fixed_clock(),
+      pricing(Currency::JPY, Currency::JPY.default_rounding_policy(), &TaxRules::default()),
+      vec![jpy_item(1000, Discount::none(), 1)],
+      vec![OrderDiscount::new(Discount::try_from(10).unwrap(), None, false)],
+      shipping(),
+    ).unwrap();
+    let added = jpy_item(500, Discount::try_from(10).unwrap(), 2);
+
+    let events = order.add_items(vec![added.clone()], &TaxRules::default(), &fixed_clock()).unwrap();
+
+    // assert
+    assert_eq!(2, order.get_order_items().len());
+    assert_eq!(&jpy(1710), order.get_total_price());
+    assert_eq!(&jpy(1710), order.get_grand_total());
+    assert_eq!(2, events.len());
+    let OrderEvent::OrderItemsAdded(items_added) = &events[0] else { panic!("OrderItemsAdded expected") };
+    assert_eq!(vec![added.get_order_item_id().clone()], items_added.order_items.iter().map(|item| item.order_item_id.clone()).collect::<Vec<_>>());
+    assert_eq!(jpy(1900), items_added.subtotal);
+    assert_eq!(vec![jpy(190)], items_added.order_discounts.iter().map(|discount| discount.amount).collect::<Vec<_>>());
+    assert!(matches!(&events[1], OrderEvent::LineDiscountApplied(applied) if applied.amount == jpy(100)));
+  }
+
+  #[test]
+  fn test_order_add_items_failed() {
+    let mut placed = placed_order();
+    let mut paid = paid_order();
+
+    let empty = placed.add_items(vec![], &TaxRules::default(), &fixed_clock());
+    let after_payment = paid.add_items(vec![jpy_item(500, Discount::none(), 1)], &TaxRules::default(), &fixed_clock());
+
+    // assert
+    assert!(matches!(empty, Err(OrderError::EmptyOrderItems)));
+    assert!(matches!(after_payment, Err(OrderError::InvalidStatus { status: OrderStatus::Paid, .. })));
+    assert_eq!(1, paid.get_order_items().len());
+  }
+
+  fn new_payment(order: &Order) -> Payment {
+    Payment::new(PaymentId::generate(&UuidV4Generator), order.get_id().clone(), *order.get_grand_total()).unwrap()
+  }
+
+  /// 返金を予約し、決済代行サービスで実行した結果を記録します
+  fn refund(payment: &mut Payment, amount: Money) -> PaymentEvent {
+    let refund_id = RefundId::generate(&UuidV4Generator);
+    payment.request_refund(refund_id.clone(), amount, &fixed_clock()).unwrap();
+    payment.complete_refund(&refund_id, &fixed_clock()).unwrap()
+  }
+
+  fn paid_order() -> Order {
+    let mut order = placed_order();
+    let mut payment = new_payment(&order);
+    let authorized = payment.authorize("ref-1", &fixed_clock()).unwrap();
+    order.apply_payment_event(&authorized, &fixed_clock()).unwrap();
+    let captured = payment.capture(&fixed_clock()).unwrap();
+    order.apply_payment_event(&captured, &fixed_clock()).unwrap();
+    order
+  }
+
+  #[test]
+  fn test_order_apply_payment_event_success() {
+    let mut order = placed_order();
+    let mut payment = new_payment(&order);
+
+    let failed = payment.fail("card declined", &fixed_clock()).unwrap();
+    let event = order.apply_payment_event(&failed, &fixed_clock()).unwrap();
+    let status_after_failure = order.get_status();
+    let mut retry = new_payment(&order);
+    let authorized = retry.authorize("ref-2", &fixed_clock()).unwrap();
+    order.apply_payment_event(&authorized, &fixed_clock()).unwrap();
+    let captured = retry.capture(&fixed_clock()).unwrap();
+    order.apply_payment_event(&captured, &fixed_clock()).unwrap();
+
+    // assert
+    assert_eq!(OrderStatus::PaymentFailed, status_after_failure);
+    assert!(matches!(
+      event,
+      Some(OrderEvent::OrderStatusChanged(changed))
+        if changed.previous_status == OrderStatus::Placed && changed.status == OrderStatus::PaymentFailed
+    ));
+    assert_eq!(OrderStatus::Paid, order.get_status());
+  }
+
+  #[test]
+  fn test_order_apply_payment_refund_success() {
+    let mut order = paid_order();
+    let mut payment = new_payment(&order);
+    payment.authorize("ref-1", &fixed_clock()).unwrap();
+    payment.capture(&fixed_clock()).unwrap();
+
+    let partial = refund(&mut payment, jpy(400));
+    let partial_event = order.apply_payment_event(&partial, &fixed_clock()).unwrap();
+    let full = refund(&mut payment, jpy(600));
+    order.apply_payment_event(&full, &fixed_clock()).unwrap();
+
+    // assert
+    assert_eq!(None, partial_event);
+    assert_eq!(OrderStatus::Refunded, order.get_status());
+    assert!(matches!(order.ship(&fixed_clock()), Err(OrderError::InvalidStatus { status: OrderStatus::Refunded, .. })));
+  }
+
+  #[test]
+  fn test_order_apply_payment_event_failed() {
+    let mut order = placed_order();
+    let mut other = new_payment(&placed_order());
+    let mut payment = new_payment(&order);
+    payment.authorize("ref-1", &fixed_clock()).unwrap();
+    let captured = payment.capture(&fixed_clock()).unwrap();
+    let other_authorized = other.authorize("ref-2", &fixed_clock()).unwrap();
+
+    // assert
+    assert!(matches!(
+      order.apply_payment_event(&captured, &fixed_clock()),
+      Err(OrderError::InvalidStatus { status: OrderStatus::Placed, action: "capture payment", .. })
+    ));
+    assert!(matches!(
+      order.apply_payment_event(&other_authorized, &fixed_clock()),
+      Err(OrderError::PaymentOrderMismatch { .. })
+    ));
+    assert!(matches!(order.ship(&fixed_clock()), Err(OrderError::InvalidStatus { status: OrderStatus::Placed, .. })));
+    assert_eq!(OrderStatus::Placed, order.get_status());
+  }
+
+  fn shipped_order(order_items: Vec<OrderItem>, order_discounts: Vec<OrderDiscount>, tax_rule: &dyn TaxRule) -> Order {
+    let (mut order, _) = Order::place_order(
+      OrderId::generate(&UuidV4Generator),
+      CustomerId::generate(&UuidV4Generator),
+      &fixed_clock(),
+      pricing(Currency::JPY, Currency::JPY.default_rounding_policy(), tax_rule),
+      order_items,
+      order_discounts,
+      shipping(),
+    ).unwrap();
+    let mut payment = new_payment(&order);
+    let authorized = payment.authorize("ref-1", &fixed_clock()).unwrap();
+    order.apply_payment_event(&authorized, &fixed_clock()).unwrap();
+    let captured = payment.capture(&fixed_clock()).unwrap();
+    order.apply_payment_event(&captured, &fixed_clock()).unwrap();
+    order.ship(&fixed_clock()).unwrap();
+    order
+  }
+
+  fn return_item(order_item_id: &OrderItemId, quantity: i32) -> ReturnItem {
+    ReturnItem { order_item_id: order_item_id.clone(), quantity: Quantity::try_from(quantity).unwrap() }
+  }
+
+  #[test]
+  fn test_order_return_items_success() {
+    // 1000円×3から10%引き = 2700円、600円×1、注文割引100円を2700:600で按分(82円、18円)
+    let mut order = shipped_order(
+      vec![jpy_item(1000, Discount::try_from(10).unwrap(), 3), jpy_item(600, Discount::none(), 1)],
+      vec![OrderDiscount::new(Discount::fixed_amount(jpy(100)).unwrap(), None, false)],
+      &TaxRules::default(),
+    );
+    let first = order.get_order_items()[0].get_order_item_id().clone();
+    let second = order.get_order_items()[1].get_order_item_id().clone();
+
+    let first_return = order.return_items(vec![return_item(&first, 1)], &fixed_clock()).unwrap();
+    let second_return = order.return_items(
+      vec![return_item(&first, 1), return_item(&second, 1), return_item(&first, 1)],
+      &fixed_clock(),
+    ).unwrap();
+
+    // assert
+    assert_eq!(jpy(2618), order.calc_line_paid_amount(&first).unwrap());
+    assert_eq!(jpy(873), first_return.refund_amount);
+    assert_eq!(2, second_return.items.len());
+    assert_eq!(ReturnedItem { order_item_id: first.clone(), quantity: 2, refund_amount: jpy(1745) }, second_return.items[0]);
+    assert_eq!(ReturnedItem { order_item_id: second.clone(), quantity: 1, refund_amount: jpy(582) }, second_return.items[1]);
+    assert_eq!(jpy(2327), second_return.refund_amount);
+    assert_eq!(order.get_grand_total(), &first_return.refund_amount.add(&second_return.refund_amount).unwrap());
+    assert_eq!(3, order.get_returned_quantity(&first));
+  }
+
+  #[test]
+  fn test_order_return_items_with_tax_success() {
+    let rate = TaxRate::try_from(Decimal::from(10)).unwrap();
+    let tax_rules = TaxRules::default().with_rule(Arc::new(RegionalTaxRule::new(
+      Region::from_str("JP").unwrap(),
+      None,
+      TaxTreatment::new(rate, TaxInclusion::Exclusive),
+    )));
+    let mut order = shipped_order(
+      vec![jpy_item(333, Discount::try_from(5).unwrap(), 7), jpy_item(150, Discount::none(), 3)],
+      vec![OrderDiscount::new(Discount::fixed_amount(jpy(99)).unwrap(), None, false)],
+      &tax_rules,
+    );
+    let items = order.get_order_items()
+      .iter()
+      .map(|item| (item.get_order_item_id().clone(), item.get_quantity()))
+      .collect::<Vec<_>>();
+
+    let refunds = items
+      .iter()
+      .flat_map(|(order_item_id, quantity)| (0..*quantity).map(move |_| order_item_id.clone()))
+      .map(|order_item_id| order.return_items(vec![return_item(&order_item_id, 1)], &fixed_clock()).unwrap().refund_amount)
+      .collect::<Vec<Money>>();
+
+    // assert
+    let total = refunds.iter().try_fold(jpy(0), |acc, refund| acc.add(refund)).unwrap();
+    assert_eq!(order.get_grand_total(), &total);
+    assert!(matches!(
+      order.return_items(vec![return_item(&items[0].0, 1)], &fixed_clock()),
+      Err(OrderError::ReturnQuantityExceeded { requested: 1, returnable: 0, .. })
+    ));
+  }
+
+  #[test]
+  fn test_order_return_items_failed() {
+    let mut order = shipped_order(vec![jpy_item(1000, Discount::none(), 2)], vec![], &TaxRules::default());
+    let order_item_id = order.get_order_items()[0].get_order_item_id().clone();
+    let mut not_shipped = paid_order();
+    let not_shipped_item_id = not_shipped.get_order_items()[0].get_order_item_id().clone();
+
+    // assert
+    assert!(matches!(order.return_items(vec![], &fixed_clock()), Err(OrderError::EmptyReturnItems)));
+    assert!(matches!(
+      order.return_items(vec![return_item(&OrderItemId::generate(&UuidV4Generator), 1)], &fixed_clock()),
+      Err(OrderError::OrderItemNotFound(_))
+    ));
+    assert!(matches!(
+      order.return_items(vec![return_item(&order_item_id, 2), return_item(&order_item_id, 1)], &fixed_clock()),
+      Err(OrderError::ReturnQuantityExceeded { requested: 3, returnable: 2, .. })
+    ));
+    assert!(matches!(
+      not_shipped.return_items(vec![return_item(&not_shipped_item_id, 1)], &fixed_clock()),
+      Err(OrderError::InvalidStatus { status: OrderStatus::Paid, action: "return items", .. })
+    ));
+    assert_eq!(0, order.get_returned_quantity(&order_item_id));
+  }
+
+  #[test]
+  fn test_order_serde() {
+    let mut order = shipped_order(
+      vec![jpy_item(1000, Discount::try_from(10).unwrap(), 3), jpy_item(600, Discount::none(), 1)],
+      vec![OrderDiscount::new(Discount::fixed_amount(jpy(100)).unwrap(), Some(CouponCode::new("SAVE100").unwrap()), false)],
+      &TaxRules::default(),
+    );
+    let order_item_id = order.get_order_items()[0].get_order_item_id().clone();
+    order.return_items(vec![return_item(&order_item_id, 1)], &fixed_clock()).unwrap();
+
+    let json = serde_json::to_value(&order).unwrap();
+    let deserialized = serde_json::from_value::<Order>(json.clone()).unwrap();
+
+    // assert
+    assert_eq!("shipped", json["status"]);
+    assert_eq!(json, serde_json::to_value(&deserialized).unwrap());
+    assert_eq!(1, deserialized.get_returned_quantity(&order_item_id));
+  }
+
+  #[test]
+  fn test_order_deserialize_failed() {
+    let order = placed_order();
+    let order_item_id = order.get_order_items()[0].get_order_item_id().to_string();
+    let json = serde_json::to_value(&order).unwrap();
+    let invalid = |f: &dyn Fn(&mut serde_json::Value)| {
+      let mut value = json.clone();
+      f(&mut value);
+      serde_json::from_value::<Order>(value)
+    };
+
+    // assert
+    assert!(invalid(&|value| value["order_items"] = serde_json::json!([])).is_err());
+    assert!(invalid(&|value| value["total_price"]["amount"] = serde_json::json!("1")).is_err());
+    assert!(invalid(&|value| value["grand_total"]["amount"] = serde_json::json!("1")).is_err());
+    assert!(invalid(&|value| value["currency"] = serde_json::json!("USD")).is_err());
+    assert!(invalid(&|value| value["returned_quantities"] = serde_json::json!({ order_item_id.clone(): 2 })).is_err());
+    assert!(invalid(&|value| value["returned_quantities"] = serde_json::json!({ OrderItemId::generate(&UuidV4Generator).to_string(): 1 })).is_err());
+    assert!(invalid(&|value| value["shipped_at"] = serde_json::json!("2024-10-01T09:00:00Z")).is_err());
+    assert!(invalid(&|value| value["status"] = serde_json::json!("shipped")).is_err());
+    assert!(invalid(&|_| {}).is_ok());
+  }
+
+  /// 明細(単価の補助単位での値, 割引率, 数量)を生成します
+  fn order_item_strategy() -> impl Strategy<Value = (i64, i32, i32)> {
+    (1i64..1_000_000, 0i32..=100, 1i32..100)
+  }
+
+  fn rounding_mode_strategy() -> impl Strategy<Value = RoundingMode> {
+    prop_oneof![Just(RoundingMode::Bankers), Just(RoundingMode::HalfUp)]
+  }
+
+  fn currency_strategy() -> impl Strategy<Value = Currency> {
+    prop_oneof![Just(Currency::JPY), Just(Currency::USD), Just(Currency::KWD)]
+  }
+
+  fn order_items(currency: Currency, lines: &[(i64, i32, i32)]) -> Vec<OrderItem> {
+    lines.iter()
+      .enumerate()
+      .map(|(i, (unit_price, discount, quantity))| OrderItem::place_order_item(OrderItemParams {
+        order_item_id: OrderItemId::generate(&UuidV4Generator),
+        product_id: i as i32,
+        product_name: "hogehoge",
+        product_category: "general",
+        unit_price: Decimal::new(*unit_price, currency.minor_units()),
+        currency: currency.code(),
+        discount: Discount::try_from(*discount).unwrap(),
+        quantity: *quantity,
+      }).unwrap())
+      .collect()
+  }
+
+  proptest! {
+    #[test]
+    fn prop_order_per_line_total_equals_sum_of_lines(
+      currency in currency_strategy(),
+      mode in rounding_mode_strategy(),
+      lines in prop::collection::vec(order_item_strategy(), 1..10),
+      order_discount in 0i32..=50,
+    ) {
+      let policy = RoundingPolicy::new(mode, RoundingScope::PerLine);
+      let order_discounts = vec![OrderDiscount::new(Discount::try_from(order_discount).unwrap(), None, false)];
+      let (order, events) = Order::place_order(
+        OrderId::generate(&UuidV4Generator), CustomerId::generate(&UuidV4Generator), &fixed_clock(), pricing(currency, policy, &TaxRules::default()), order_items(currency, &lines), order_discounts, shipping(),
+      ).unwrap();
+      let OrderEvent::OrderPlaced(placed) = &events[0] else { panic!("OrderPlaced expected") };
+      let line_sum = placed.order_items
+        .iter()
+        .fold(Money::zero(currency), |acc, item| acc.add(&item.line_total).unwrap());
+      let order_discount_sum = events
+        .iter()
+        .filter_map(|event| match event {
+          OrderEvent::OrderDiscountApplied(applied) => Some(applied.amount),
+          _ => None,
+        })
+        .fold(Money::zero(currency), |acc, amount| acc.add(&amount).unwrap());
+
+      prop_assert_eq!(&line_sum, &placed.subtotal);
+      let expected_total = line_sum.sub(&order_discount_sum).unwrap();
+      prop_assert_eq!(expected_total.amount(), order.get_total_price().amount());
+      prop_assert_eq!(currency.minor_units(), order.get_total_price().amount().scale());
+      for item in &placed.order_items {
+        prop_assert_eq!(currency.minor_units(), item.line_total.amount().scale());
+      }
+    }
+
+    #[test]
+    fn prop_order_per_order_total_is_rounded_once(
+      currency in currency_strategy(),
+      mode in rounding_mode_strategy(),
+      lines in prop::collection::vec(order_item_strategy(), 1..10),
+    ) {
+      let policy = RoundingPolicy::new(mode, RoundingScope::PerOrder);
+      let items = order_items(currency, &lines);
+      let raw_sum = items
+        .iter()
+        .fold(Money::zero(currency), |acc, item| acc.add(&item.calc_line_total().unwrap()).unwrap());
+      let result = Order::calc_total_price(currency, policy, &items, &[]).unwrap();
+
+      prop_assert_eq!(policy.round(&raw_sum), result);
+      prop_assert_eq!(currency.minor_units(), result.amount().scale());
+    }
+  }
+
+  #[test]
+  fn test_order_events_serde() {
+    let (mut order, mut events) = Order::place_order(
+      OrderId::generate(&UuidV4Generator),
+      CustomerId::generate(&UuidV4Generator),
+      &fixed_clock(),
+      pricing(Currency::JPY, Currency::JPY.default_rounding_policy(), &TaxRules::default()),
+      vec![jpy_item(1000, Discount::try_from(10).unwrap(), 2)],
+      vec![OrderDiscount::new(Discount::fixed_amount(jpy(100)).unwrap(), Some(CouponCode::new("SAVE100").unwrap()), false)],
+      shipping(),
+    ).unwrap();
+    events.extend(order.add_items(vec![jpy_item(500, Discount::none(), 1)], &TaxRules::default(), &fixed_clock()).unwrap());
+    events.push(order.change_shipping_address(address("150-0001"), &fixed_clock()).unwrap());
+    let mut payment = new_payment(&order);
+    let authorized = payment.authorize("ref-1", &fixed_clock()).unwrap();
+    events.extend(order.apply_payment_event(&authorized, &fixed_clock()).unwrap());
+    let captured = payment.capture(&fixed_clock()).unwrap();
+    events.extend(order.apply_payment_event(&captured, &fixed_clock()).unwrap());
+    events.push(order.ship(&fixed_clock()).unwrap());
+    let order_item_id = order.get_order_items()[0].get_order_item_id().clone();
+    events.push(OrderEvent::ItemsReturned(order.return_items(vec![return_item(&order_item_id, 1)], &fixed_clock()).unwrap()));
+
+    let json = events.iter().map(|event| serde_json::to_value(event).unwrap()).collect::<Vec<_>>();
+    let deserialized = json.iter().map(|value| serde_json::from_value::<OrderEvent>(value.clone()).unwrap()).collect::<Vec<_>>();
+
+    // assert
+    assert_eq!(
+      vec![
+        "OrderPlaced", "LineDiscountApplied", "OrderDiscountApplied", "OrderItemsAdded", "ShippingAddressChanged",
+        "OrderStatusChanged", "OrderStatusChanged", "OrderShipped", "ItemsReturned",
+      ],
+      json.iter().map(|value| value["type"].as_str().unwrap()).collect::<Vec<_>>(),
+    );
+    assert_eq!(events, deserialized);
+    assert!(serde_json::from_value::<OrderEvent>(serde_json::json!({ "type": "OrderCancelled" })).is_err());
+  }
+}
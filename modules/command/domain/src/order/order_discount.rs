@@ -0,0 +1,129 @@
+use crate::value_object::coupon_code::CouponCode;
+use crate::value_object::discount::{Discount, DiscountError, DiscountKind};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// 注文全体に対する割引です
+///
+/// 積み上げのルールは以下の通りです
+///
+/// - 同じクーポンコードは1注文につき1回のみ使用できます
+/// - 積み上げ不可(stackable = false)の割引は、他の注文割引と併用できません
+/// - 割合の割引を先に、固定金額の割引を後に適用します
+/// - 固定金額の割引は残りの金額を上限とし、合計金額が負になることはありません
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct OrderDiscount {
+  discount: Discount,
+  coupon_code: Option<CouponCode>,
+  stackable: bool,
+}
+
+impl OrderDiscount {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `discount`: Discount
+  /// * `coupon_code`: クーポンによる割引の場合はクーポンコード
+  /// * `stackable`: 他の注文割引と併用できる場合true
+  ///
+  /// # Return
+  /// * `OrderDiscount`
+  pub fn new(discount: Discount, coupon_code: Option<CouponCode>, stackable: bool) -> Self {
+    Self { discount, coupon_code, stackable }
+  }
+
+  /// 注文割引の組み合わせが積み上げのルールを満たすか検証します
+  ///
+  /// # Arguments
+  /// * `discounts`: 注文割引の一覧
+  ///
+  /// # Return
+  /// * `Result<(), DiscountError>`
+  pub fn validate_stacking(discounts: &[OrderDiscount]) -> Result<(), DiscountError> {
+    let mut coupon_codes = HashSet::new();
+    for discount in discounts {
+      if let Some(code) = &discount.coupon_code {
+        if !coupon_codes.insert(code) {
+          Err(DiscountError::DuplicateCoupon(code.to_string()))?
+        }
+      }
+    }
+    if discounts.len() > 1 && discounts.iter().any(|discount| !discount.stackable) {
+      Err(DiscountError::NotStackable)?
+    }
+    Ok(())
+  }
+
+  /// 適用順(割合→固定金額)に並べ替えた注文割引を返します
+  pub fn in_application_order(discounts: &[OrderDiscount]) -> Vec<&OrderDiscount> {
+    let mut sorted: Vec<&OrderDiscount> = discounts.iter().collect();
+    sorted.sort_by_key(|discount| match discount.discount.kind() {
+      DiscountKind::Percentage(_) => 0,
+      DiscountKind::FixedAmount(_) => 1,
+    });
+    sorted
+  }
+
+  /// 割引のゲッター
+  pub fn get_discount(&self) -> &Discount { &self.discount }
+
+  /// クーポンコードのゲッター
+  pub fn get_coupon_code(&self) -> Option<&CouponCode> { self.coupon_code.as_ref() }
+
+  /// 積み上げ可否のゲッター
+  pub fn is_stackable(&self) -> bool { self.stackable }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::value_object::currency::Currency;
+  use crate::value_object::money::Money;
+  use rust_decimal::Decimal;
+
+  fn coupon(code: &str, stackable: bool) -> OrderDiscount {
+    OrderDiscount::new(Discount::try_from(10).unwrap(), Some(CouponCode::new(code).unwrap()), stackable)
+  }
+
+  #[test]
+  fn test_order_discount_validate_stacking_success() {
+    let discounts = vec![coupon("WELCOME", true), coupon("SUMMER", true)];
+
+    // assert
+    assert!(OrderDiscount::validate_stacking(&discounts).is_ok());
+    assert!(OrderDiscount::validate_stacking(&[coupon("WELCOME", false)]).is_ok());
+  }
+
+  #[test]
+  fn test_order_discount_validate_stacking_duplicate_failed() {
+    let discounts = vec![coupon("WELCOME", true), coupon("welcome", true)];
+
+    // assert
+    assert_eq!(
+      Err(DiscountError::DuplicateCoupon("WELCOME".to_string())),
+      OrderDiscount::validate_stacking(&discounts)
+    )
+  }
+
+  #[test]
+  fn test_order_discount_validate_stacking_not_stackable_failed() {
+    let discounts = vec![coupon("WELCOME", false), coupon("SUMMER", true)];
+
+    // assert
+    assert_eq!(Err(DiscountError::NotStackable), OrderDiscount::validate_stacking(&discounts))
+  }
+
+  #[test]
+  fn test_order_discount_in_application_order_success() {
+    let fixed = OrderDiscount::new(
+      Discount::fixed_amount(Money::new(Decimal::from(100), Currency::JPY).unwrap()).unwrap(),
+      None,
+      true,
+    );
+    let percentage = coupon("WELCOME", true);
+    let discounts = vec![fixed.clone(), percentage.clone()];
+
+    // assert
+    assert_eq!(vec![&percentage, &fixed], OrderDiscount::in_application_order(&discounts))
+  }
+}
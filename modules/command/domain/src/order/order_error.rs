@@ -1,5 +1,15 @@
+use crate::aggregate_id::AggregateIdError;
+use crate::error_code::{ErrorCode, FieldPath};
+use crate::order::order_id::OrderId;
+use crate::order::order_item_id::OrderItemId;
+use crate::order::order_status::OrderStatus;
+use crate::product::product_category::ProductCategoryError;
 use crate::product::product_name::ProductNameError;
+use crate::shipping::shipping_error::ShippingError;
+use crate::tax::region::RegionError;
+use crate::value_object::coupon_code::CouponCodeError;
 use crate::value_object::discount::DiscountError;
+use crate::value_object::money::MoneyError;
 use crate::value_object::price::PriceError;
 use crate::value_object::quantity::QuantityError;
 use thiserror;
@@ -8,15 +18,168 @@ use thiserror::Error;
 /// 注文のエラーです
 #[derive(Debug, Error)]
 pub enum OrderError {
-  #[error("Quantity must be greater than or equal to 0 {0:?}")]
-  InvalidQuantityError(#[from] QuantityError),
+  #[error("Invalid Quantity: {0}")]
+  InvalidQuantity(#[from] QuantityError),
 
   #[error("Price must be at least 1 {0:?}")]
-  InvalidPriceError(#[from] PriceError),
+  InvalidPrice(#[from] PriceError),
 
-  #[error("Discount must be at least 0 {0:?}")]
-  InvalidDiscountError(#[from] DiscountError),
+  #[error("Invalid Discount: {0}")]
+  InvalidDiscount(#[from] DiscountError),
+
+  #[error("Invalid Coupon Code: {0}")]
+  InvalidCouponCode(#[from] CouponCodeError),
 
   #[error("Invalid Product Name: {0}")]
   InvalidProductName(#[from] ProductNameError),
+
+  #[error("Invalid Product Category: {0}")]
+  InvalidProductCategory(#[from] ProductCategoryError),
+
+  #[error("Invalid Region: {0}")]
+  InvalidRegion(#[from] RegionError),
+
+  #[error("Invalid Money: {0}")]
+  InvalidMoney(#[from] MoneyError),
+
+  #[error("Invalid Shipping: {0}")]
+  InvalidShipping(#[from] ShippingError),
+
+  #[error("Order must have at least one item")]
+  EmptyOrderItems,
+
+  #[error("Invalid Order Item at index {index}: {error}")]
+  InvalidOrderItem { index: usize, error: Box<OrderError> },
+
+  #[error("Invalid Order Discount at index {index}: {error}")]
+  InvalidOrderDiscount { index: usize, error: Box<OrderError> },
+
+  #[error("Invalid Order ID: {0}")]
+  InvalidOrderId(#[from] AggregateIdError),
+
+  #[error("Order not found: {0}")]
+  OrderNotFound(String),
+
+  #[error("Order {0} has already been shipped")]
+  AlreadyShipped(OrderId),
+
+  #[error("Order {order_id} cannot {action} in status {status}")]
+  InvalidStatus { order_id: OrderId, status: OrderStatus, action: &'static str },
+
+  #[error("Payment event for order {actual} was applied to order {expected}")]
+  PaymentOrderMismatch { expected: OrderId, actual: OrderId },
+
+  #[error("Return must have at least one item")]
+  EmptyReturnItems,
+
+  #[error("Order item not found: {0}")]
+  OrderItemNotFound(OrderItemId),
+
+  #[error("Cannot return {requested} of order item {order_item_id}, only {returnable} returnable")]
+  ReturnQuantityExceeded { order_item_id: OrderItemId, requested: i32, returnable: i32 },
+}
+
+impl OrderError {
+  /// 注文明細の入力のエラーとして、明細の添字を付けます
+  ///
+  /// # Arguments
+  /// * `index` - 注文明細の添字
+  pub fn at_item(self, index: usize) -> Self {
+    OrderError::InvalidOrderItem { index, error: Box::new(self) }
+  }
+
+  /// 注文全体への割引の入力のエラーとして、割引の添字を付けます
+  ///
+  /// # Arguments
+  /// * `index` - 割引の添字
+  pub fn at_discount(self, index: usize) -> Self {
+    OrderError::InvalidOrderDiscount { index, error: Box::new(self) }
+  }
+}
+
+impl ErrorCode for OrderError {
+  fn code(&self) -> &'static str {
+    match self {
+      OrderError::InvalidQuantity(e) => e.code(),
+      OrderError::InvalidPrice(e) => e.code(),
+      OrderError::InvalidDiscount(e) => e.code(),
+      OrderError::InvalidCouponCode(e) => e.code(),
+      OrderError::InvalidProductName(e) => e.code(),
+      OrderError::InvalidProductCategory(e) => e.code(),
+      OrderError::InvalidRegion(e) => e.code(),
+      OrderError::InvalidMoney(e) => e.code(),
+      OrderError::InvalidShipping(e) => e.code(),
+      OrderError::EmptyOrderItems => "order.empty_items",
+      OrderError::InvalidOrderItem { error, .. } | OrderError::InvalidOrderDiscount { error, .. } => error.code(),
+      OrderError::InvalidOrderId(e) => e.code(),
+      OrderError::OrderNotFound(_) => "order.not_found",
+      OrderError::AlreadyShipped(_) => "order.already_shipped",
+      OrderError::InvalidStatus { .. } => "order.invalid_status",
+      OrderError::PaymentOrderMismatch { .. } => "order.payment_order_mismatch",
+      OrderError::EmptyReturnItems => "order.empty_return_items",
+      OrderError::OrderItemNotFound(_) => "order.item_not_found",
+      OrderError::ReturnQuantityExceeded { .. } => "order.return_quantity_exceeded",
+    }
+  }
+
+  /// 注文の入力からのパスを返します
+  fn field_path(&self) -> Option<FieldPath> {
+    match self {
+      OrderError::InvalidQuantity(QuantityError::ExceedsMaximum { .. }) => Some(FieldPath::new("items")),
+      OrderError::InvalidQuantity(_) => Some(FieldPath::new("quantity")),
+      OrderError::InvalidPrice(_) => Some(FieldPath::new("unit_price")),
+      OrderError::InvalidDiscount(e) => e.field_path(),
+      OrderError::InvalidCouponCode(_) => Some(FieldPath::new("coupon_code")),
+      OrderError::InvalidProductName(_) => Some(FieldPath::new("product_name")),
+      OrderError::InvalidProductCategory(_) => Some(FieldPath::new("product_category")),
+      OrderError::InvalidRegion(_) => Some(FieldPath::new("region")),
+      OrderError::InvalidMoney(MoneyError::InvalidCurrency(_)) => Some(FieldPath::new("currency")),
+      OrderError::InvalidShipping(ShippingError::UnknownDeliveryMethod(_)) => Some(FieldPath::new("delivery_method")),
+      OrderError::InvalidShipping(e) => e.field_path().map(|path| FieldPath::new("shipping_address").join(path)),
+      OrderError::EmptyOrderItems | OrderError::EmptyReturnItems => Some(FieldPath::new("items")),
+      OrderError::InvalidOrderItem { index, error } => {
+        error.field_path().map(|path| FieldPath::new("items").index(*index).join(path))
+      }
+      OrderError::InvalidOrderDiscount { index, error } => {
+        error.field_path().map(|path| FieldPath::new("discounts").index(*index).join(path))
+      }
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::value_object::currency::Currency;
+  use crate::value_object::money::Money;
+  use rust_decimal::Decimal;
+
+  #[test]
+  fn test_order_error_code_and_field_path() {
+    let quantity = OrderError::from(QuantityError::NotPositive(0)).at_item(2);
+    let coupon = OrderError::from(CouponCodeError::Invalid("!".to_string())).at_discount(1);
+    let postal_code = OrderError::from(ShippingError::InvalidPostalCode { country: "JP".to_string(), value: "1".to_string() });
+    let not_stackable = OrderError::from(DiscountError::NotStackable);
+
+    // assert
+    assert_eq!("quantity.not_positive", quantity.code());
+    assert_eq!(Some("items[2].quantity".to_string()), quantity.field_path().map(|path| path.to_string()));
+    assert_eq!("coupon_code.invalid", coupon.code());
+    assert_eq!(Some("discounts[1].coupon_code".to_string()), coupon.field_path().map(|path| path.to_string()));
+    assert_eq!("shipping.invalid_postal_code", postal_code.code());
+    assert_eq!(Some("shipping_address.postal_code".to_string()), postal_code.field_path().map(|path| path.to_string()));
+    assert_eq!("discount.not_stackable", not_stackable.code());
+    assert_eq!(None, not_stackable.field_path());
+  }
+
+  #[test]
+  fn test_order_error_item_discount_field_path() {
+    let negative = Money::new(Decimal::from(-1), Currency::JPY).unwrap();
+    let error = OrderError::from(DiscountError::NegativeAmount(negative)).at_item(0);
+
+    // assert
+    assert_eq!("discount.negative_amount", error.code());
+    assert_eq!(Some("items[0].discount.value".to_string()), error.field_path().map(|path| path.to_string()));
+  }
 }
@@ -0,0 +1,191 @@
+use crate::customer::customer_id::CustomerId;
+use crate::order::order_id::OrderId;
+use crate::order::order_item_id::OrderItemId;
+use crate::order::order_status::OrderStatus;
+use crate::product::product_category::ProductCategory;
+use crate::shipping::delivery_method::DeliveryMethod;
+use crate::shipping::shipping_address::ShippingAddress;
+use crate::tax::region::Region;
+use crate::tax::tax_breakdown::TaxBreakdown;
+use crate::value_object::coupon_code::CouponCode;
+use crate::value_object::currency::Currency;
+use crate::value_object::discount::Discount;
+use crate::value_object::money::Money;
+use crate::value_object::rounding_policy::RoundingPolicy;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 注文のドメインイベントです
+///
+/// シリアライズすると、`type`にイベント名を持つJSONオブジェクトになります
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum OrderEvent {
+  OrderPlaced(OrderPlaced),
+  OrderItemsAdded(OrderItemsAdded),
+  LineDiscountApplied(LineDiscountApplied),
+  OrderDiscountApplied(OrderDiscountApplied),
+  ShippingAddressChanged(ShippingAddressChanged),
+  OrderShipped(OrderShipped),
+  OrderStatusChanged(OrderStatusChanged),
+  ItemsReturned(ItemsReturned),
+}
+
+impl OrderEvent {
+  /// イベントが発生した注文のIDを返します
+  pub fn order_id(&self) -> &OrderId {
+    match self {
+      OrderEvent::OrderPlaced(event) => &event.order_id,
+      OrderEvent::OrderItemsAdded(event) => &event.order_id,
+      OrderEvent::LineDiscountApplied(event) => &event.order_id,
+      OrderEvent::OrderDiscountApplied(event) => &event.order_id,
+      OrderEvent::ShippingAddressChanged(event) => &event.order_id,
+      OrderEvent::OrderShipped(event) => &event.order_id,
+      OrderEvent::OrderStatusChanged(event) => &event.order_id,
+      OrderEvent::ItemsReturned(event) => &event.order_id,
+    }
+  }
+
+  /// イベントの発生日時を返します
+  pub fn occurred_at(&self) -> &DateTime<Utc> {
+    match self {
+      OrderEvent::OrderPlaced(event) => &event.occurred_at,
+      OrderEvent::OrderItemsAdded(event) => &event.occurred_at,
+      OrderEvent::LineDiscountApplied(event) => &event.occurred_at,
+      OrderEvent::OrderDiscountApplied(event) => &event.occurred_at,
+      OrderEvent::ShippingAddressChanged(event) => &event.occurred_at,
+      OrderEvent::OrderShipped(event) => &event.occurred_at,
+      OrderEvent::OrderStatusChanged(event) => &event.occurred_at,
+      OrderEvent::ItemsReturned(event) => &event.occurred_at,
+    }
+  }
+}
+
+/// 注文が確定されたイベントです
+///
+/// 明細金額と合計金額は`rounding_policy`で丸めた値を記録します。
+///
+/// - subtotal: 明細割引を適用した後の小計
+/// - total_price: 注文割引も適用した税抜(外税を含まない)の合計金額
+/// - tax: 明細ごと・税率ごとの税額の内訳
+/// - grand_total: total_priceに外税を加算した支払総額
+/// - shipping_address: 配送先の住所
+/// - delivery_method: 配送方法
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderPlaced {
+  pub order_id: OrderId,
+  pub customer_id: CustomerId,
+  pub occurred_at: DateTime<Utc>,
+  pub currency: Currency,
+  pub rounding_policy: RoundingPolicy,
+  pub region: Region,
+  pub order_items: Vec<OrderItemPlaced>,
+  pub subtotal: Money,
+  pub total_price: Money,
+  pub tax: TaxBreakdown,
+  pub grand_total: Money,
+  pub shipping_address: ShippingAddress,
+  pub delivery_method: DeliveryMethod,
+}
+
+/// 確定された注文の明細です
+///
+/// line_totalは明細割引を適用した後の金額です
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderItemPlaced {
+  pub order_item_id: OrderItemId,
+  pub product_id: i32,
+  pub product_name: String,
+  pub product_category: ProductCategory,
+  pub unit_price: Money,
+  pub discount: Discount,
+  pub quantity: i32,
+  pub line_total: Money,
+}
+
+/// 確定後の注文に明細が追加されたイベントです
+///
+/// order_itemsには追加した明細のみを記録し、金額は追加後の注文全体の値を記録します。
+///
+/// - subtotal: 明細割引を適用した後の小計
+/// - order_discounts: 追加後の小計で再計算した注文割引
+/// - total_price: 注文割引も適用した税抜の合計金額
+/// - tax: 追加後のすべての明細の税額の内訳
+/// - grand_total: total_priceに外税を加算した支払総額
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderItemsAdded {
+  pub order_id: OrderId,
+  pub occurred_at: DateTime<Utc>,
+  pub order_items: Vec<OrderItemPlaced>,
+  pub subtotal: Money,
+  pub order_discounts: Vec<OrderDiscountApplied>,
+  pub total_price: Money,
+  pub tax: TaxBreakdown,
+  pub grand_total: Money,
+}
+
+/// 明細に割引が適用されたイベントです
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineDiscountApplied {
+  pub order_id: OrderId,
+  pub order_item_id: OrderItemId,
+  pub occurred_at: DateTime<Utc>,
+  pub discount: Discount,
+  pub amount: Money,
+}
+
+/// 注文全体に割引が適用されたイベントです
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderDiscountApplied {
+  pub order_id: OrderId,
+  pub occurred_at: DateTime<Utc>,
+  pub discount: Discount,
+  pub coupon_code: Option<CouponCode>,
+  pub amount: Money,
+}
+
+/// 出荷前に配送先の住所が変更されたイベントです
+///
+/// previous_addressには変更前の住所を記録します
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShippingAddressChanged {
+  pub order_id: OrderId,
+  pub occurred_at: DateTime<Utc>,
+  pub previous_address: ShippingAddress,
+  pub address: ShippingAddress,
+}
+
+/// 注文が出荷されたイベントです
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderShipped {
+  pub order_id: OrderId,
+  pub occurred_at: DateTime<Utc>,
+}
+
+/// 支払いのイベントによって注文の状態が変わったイベントです
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderStatusChanged {
+  pub order_id: OrderId,
+  pub occurred_at: DateTime<Utc>,
+  pub previous_status: OrderStatus,
+  pub status: OrderStatus,
+}
+
+/// 出荷済みの注文の明細が返品されたイベントです
+///
+/// refund_amountは返品した明細の返金額の合計です
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemsReturned {
+  pub order_id: OrderId,
+  pub occurred_at: DateTime<Utc>,
+  pub items: Vec<ReturnedItem>,
+  pub refund_amount: Money,
+}
+
+/// 返品された明細です
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReturnedItem {
+  pub order_item_id: OrderItemId,
+  pub quantity: i32,
+  pub refund_amount: Money,
+}
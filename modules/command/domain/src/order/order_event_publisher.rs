@@ -0,0 +1,21 @@
+use crate::order::order_event::OrderEvent;
+
+/// 保存した注文のイベントを読み取り側へ配信するトレイトです
+///
+/// 注文を保存した後に呼び出すため、配信の失敗で保存を取り消さないよう、エラーを返しません。
+/// 配信できなかったイベントの再送は実装側で行います
+pub trait OrderEventPublisher: Send + Sync {
+  /// 保存した注文のイベントを配信します
+  ///
+  /// # Arguments
+  /// * `events`: 保存した注文のイベント(発生順)
+  fn publish(&self, events: &[OrderEvent]);
+}
+
+/// 何も配信しないOrderEventPublisherです
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopOrderEventPublisher;
+
+impl OrderEventPublisher for NoopOrderEventPublisher {
+  fn publish(&self, _events: &[OrderEvent]) {}
+}
@@ -1,63 +1,134 @@
 use crate::order::order_error::OrderError;
 use crate::order::order_item_id::OrderItemId;
+use crate::product::product_category::ProductCategory;
 use crate::product::product_name::ProductName;
-use crate::value_object::discount::Discount;
+use crate::validation::{zip, Validated, ValidationErrors};
+use crate::value_object::currency::Currency;
+use crate::value_object::discount::{Discount, DiscountError};
+use crate::value_object::money::{Money, MoneyError};
 use crate::value_object::price::Price;
 use crate::value_object::quantity::Quantity;
 use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize};
 use std::str::FromStr;
 
+/// 注文アイテムの生成に使う値です
+///
+/// 値オブジェクトへの変換と検証は`OrderItem::validate_order_item`で行います
+///
+/// currency: ISO-4217の通貨コード
+///
+/// discount: 明細に対する割引
 #[derive(Debug, Clone)]
+pub struct OrderItemParams<'a> {
+  pub order_item_id: OrderItemId,
+  pub product_id: i32,
+  pub product_name: &'a str,
+  pub product_category: &'a str,
+  pub unit_price: Decimal,
+  pub currency: &'a str,
+  pub discount: Discount,
+  pub quantity: i32,
+}
+
+/// 注文アイテムです
+///
+/// 割引額は単価・数量・割引から計算するため、シリアライズしません
+#[derive(Debug, Clone, Serialize)]
 pub struct OrderItem {
   order_item_id: OrderItemId,
   product_id: i32,
   product_name: ProductName,
+  product_category: ProductCategory,
   unit_price: Price,
   discount: Discount,
+  #[serde(skip_serializing)]
+  discount_amount: Money,
   quantity: Quantity,
 }
 
 impl OrderItem {
-  fn new(order_item_id: OrderItemId,
-         product_id: i32,
-         product_name: ProductName,
-         unit_price: Price,
-         discount: Discount,
-         quantity: Quantity) -> Self {
-    Self {
+  /// 外部から呼び出すコンストラクタです
+  ///
+  /// `validate_order_item`で検証し、最初のエラーのみを返します
+  ///
+  /// # Argument
+  /// * `params`: OrderItemParams
+  ///
+  /// # Return
+  /// * `Result<OrderItem, OrderError>`
+  pub fn place_order_item(params: OrderItemParams) -> Result<Self, OrderError> {
+    Self::validate_order_item(params).map_err(ValidationErrors::into_first)
+  }
+
+  /// すべての値を検証してから生成するコンストラクタです
+  ///
+  /// 最初のエラーで止めずに単価・数量・商品名・商品カテゴリーのエラーをすべて返します。
+  /// 割引額の検証は単価と数量が正しい場合のみ行います
+  ///
+  /// # Argument
+  /// * `params`: OrderItemParams
+  ///
+  /// # Return
+  /// * `Validated<OrderItem, OrderError>`
+  pub fn validate_order_item(params: OrderItemParams) -> Validated<Self, OrderError> {
+    let OrderItemParams {
       order_item_id,
       product_id,
       product_name,
+      product_category,
       unit_price,
+      currency,
       discount,
       quantity,
-    }
-  }
-
-  pub fn place_order_item(
-    order_item_id: OrderItemId,
-    product_id: i32,
-    product_name: &str,
-    unit_price: i32,
-    discount: i32,
-    quantity: i32,
-  ) -> Result<Self, OrderError> {
-    Ok(OrderItem::new(
+    } = params;
+    let unit_price = Money::parse(unit_price, currency)
+      .map_err(OrderError::from)
+      .and_then(|unit_price| Ok(Price::try_from(unit_price)?))
+      .map_err(ValidationErrors::from);
+    let quantity = Quantity::try_from(quantity).map_err(|e| ValidationErrors::from(OrderError::from(e)));
+    let product_name = ProductName::from_str(product_name).map_err(|e| ValidationErrors::from(OrderError::from(e)));
+    let product_category = ProductCategory::from_str(product_category).map_err(|e| ValidationErrors::from(OrderError::from(e)));
+    let (((unit_price, quantity), product_name), product_category) =
+      zip(zip(zip(unit_price, quantity), product_name), product_category)?;
+    let item_total = unit_price.money().times(quantity.value()).map_err(OrderError::from)?;
+    let discount_amount = discount.calc_amount(&item_total).map_err(OrderError::from)?;
+    Ok(OrderItem {
       order_item_id,
       product_id,
-      ProductName::from_str(product_name)?,
-      Price::try_from(Decimal::from(unit_price))?,
-      Discount::try_from(discount)?,
-      Quantity::try_from(quantity)?,
-    ))
+      product_name,
+      product_category,
+      unit_price,
+      discount,
+      discount_amount,
+      quantity,
+    })
   }
 
+  /// 注文アイテムIDのゲッター
+  pub fn get_order_item_id(&self) -> &OrderItemId { &self.order_item_id }
+
+  /// 商品IDのゲッター
+  pub fn get_product_id(&self) -> i32 { self.product_id }
+
+  /// 商品名のゲッター
+  pub fn get_product_name(&self) -> &ProductName { &self.product_name }
+
+  /// 商品カテゴリーのゲッター
+  pub fn get_product_category(&self) -> &ProductCategory { &self.product_category }
+
   /// 価格のゲッター
   /// 参照を返します。
   ///
   /// # return
   /// * `unit_price`: i32
-  pub fn get_unit_price(&self) -> &Decimal { &self.unit_price.value() }
+  pub fn get_unit_price(&self) -> &Decimal { self.unit_price.value() }
+
+  /// 通貨付きの単価のゲッター
+  pub fn get_unit_price_money(&self) -> &Money { self.unit_price.money() }
+
+  /// 通貨のゲッター
+  pub fn get_currency(&self) -> Currency { self.unit_price.money().currency() }
 
   /// 数量のゲッター
   /// 参照を返します。
@@ -70,6 +141,180 @@ impl OrderItem {
   /// 参照を返します。
   ///
   /// # return
-  /// * `discount`: i32
-  pub fn get_discount(&self) -> &Decimal { &self.discount.value() }
-}
\ No newline at end of file
+  /// * `discount`: Discount
+  pub fn get_discount(&self) -> &Discount { &self.discount }
+
+  /// 割引額のゲッター
+  /// 丸め前の金額を返します。
+  ///
+  /// # return
+  /// * `discount_amount`: Money
+  pub fn get_discount_amount(&self) -> &Money { &self.discount_amount }
+
+  /// 明細に割引を適用します
+  ///
+  /// 明細割引は1つのみのため、既に割引がある明細には適用できません
+  ///
+  /// # Arguments
+  /// * `discount`: Discount
+  ///
+  /// # Return
+  /// * `Result<OrderItem, DiscountError>`
+  pub fn apply_discount(self, discount: Discount) -> Result<Self, DiscountError> {
+    if !self.discount.is_zero() {
+      Err(DiscountError::NotStackable)?
+    }
+    let discount_amount = discount.calc_amount(&self.unit_price.money().times(self.quantity.value())?)?;
+    Ok(Self { discount, discount_amount, ..self })
+  }
+
+  /// 割引後の明細金額を計算します
+  ///
+  /// 丸めは行わないため、補助単位より細かい金額になる場合があります
+  ///
+  /// # return
+  /// * `Result<Money, MoneyError>`: 明細金額が桁あふれする場合と、割引額の通貨が異なる場合はエラー
+  pub fn calc_line_total(&self) -> Result<Money, MoneyError> {
+    self.unit_price.money().times(self.quantity.value())?.sub(&self.discount_amount)
+  }
+}
+
+/// 注文アイテムのデシリアライズ用の値です
+#[derive(Deserialize)]
+struct OrderItemValue {
+  order_item_id: OrderItemId,
+  product_id: i32,
+  product_name: ProductName,
+  product_category: ProductCategory,
+  unit_price: Price,
+  discount: Discount,
+  quantity: Quantity,
+}
+
+impl<'de> Deserialize<'de> for OrderItem {
+  /// 割引額は単価と数量から計算し直します
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let value = OrderItemValue::deserialize(deserializer)?;
+    let item_total = value.unit_price.money().times(value.quantity.value()).map_err(serde::de::Error::custom)?;
+    let discount_amount = value.discount.calc_amount(&item_total).map_err(serde::de::Error::custom)?;
+    Ok(OrderItem {
+      order_item_id: value.order_item_id,
+      product_id: value.product_id,
+      product_name: value.product_name,
+      product_category: value.product_category,
+      unit_price: value.unit_price,
+      discount: value.discount,
+      discount_amount,
+      quantity: value.quantity,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::id_generator::UuidV4Generator;
+  use crate::value_object::quantity::QuantityError;
+
+  #[test]
+  fn test_validate_order_item_success() {
+    let result = OrderItem::validate_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id: 1,
+      product_name: "hogehoge",
+      product_category: "general",
+      unit_price: Decimal::from(500),
+      currency: "JPY",
+      discount: Discount::none(),
+      quantity: 2,
+    });
+
+    // assert
+    let order_item = result.unwrap();
+    assert_eq!(2, order_item.get_quantity());
+    assert_eq!(&Decimal::from(1000), order_item.calc_line_total().unwrap().amount());
+  }
+
+  #[test]
+  fn test_validate_order_item_collects_all_errors() {
+    let result = OrderItem::validate_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id: 1,
+      product_name: "",
+      product_category: "",
+      unit_price: Decimal::from(-500),
+      currency: "JPY",
+      discount: Discount::none(),
+      quantity: 0,
+    });
+
+    // assert
+    let errors = result.unwrap_err().into_vec();
+    assert_eq!(4, errors.len());
+    assert!(matches!(errors[0], OrderError::InvalidPrice(_)));
+    assert!(matches!(errors[1], OrderError::InvalidQuantity(QuantityError::NotPositive(0))));
+    assert!(matches!(errors[2], OrderError::InvalidProductName(_)));
+    assert!(matches!(errors[3], OrderError::InvalidProductCategory(_)));
+  }
+
+  #[test]
+  fn test_validate_order_item_overflow_failed() {
+    let result = OrderItem::validate_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id: 1,
+      product_name: "hogehoge",
+      product_category: "general",
+      unit_price: Decimal::MAX,
+      currency: "JPY",
+      discount: Discount::none(),
+      quantity: 2,
+    });
+
+    // assert
+    let errors = result.unwrap_err().into_vec();
+    assert!(matches!(errors[..], [OrderError::InvalidMoney(MoneyError::Overflow { .. })]));
+  }
+
+  #[test]
+  fn test_calc_line_total_currency_mismatch_failed() {
+    let item = OrderItem::place_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id: 1,
+      product_name: "hogehoge",
+      product_category: "general",
+      unit_price: Decimal::from(500),
+      currency: "JPY",
+      discount: Discount::none(),
+      quantity: 2,
+    }).unwrap();
+    let item = OrderItem { discount_amount: Money::parse(Decimal::ONE, "USD").unwrap(), ..item };
+
+    // assert
+    assert!(matches!(item.calc_line_total(), Err(MoneyError::CurrencyMismatch { .. })));
+  }
+
+  #[test]
+  fn test_order_item_serde() {
+    let item = OrderItem::place_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id: 1,
+      product_name: "hogehoge",
+      product_category: "general",
+      unit_price: Decimal::from(1000),
+      currency: "JPY",
+      discount: Discount::try_from(10).unwrap(),
+      quantity: 3,
+    }).unwrap();
+
+    let json = serde_json::to_value(&item).unwrap();
+    let deserialized = serde_json::from_value::<OrderItem>(json.clone()).unwrap();
+
+    // assert
+    assert_eq!(None, json.get("discount_amount"));
+    assert_eq!(item.get_order_item_id(), deserialized.get_order_item_id());
+    assert_eq!(item.get_discount_amount(), deserialized.get_discount_amount());
+    let mut invalid = json;
+    invalid["quantity"] = serde_json::json!(0);
+    assert!(serde_json::from_value::<OrderItem>(invalid).is_err());
+  }
+}
@@ -1,35 +1,13 @@
-use crate::aggregate_id::AggregateId;
-use crate::generate_id;
-use std::fmt::{Display, Formatter};
-use uuid::Uuid;
+use crate::aggregate_id::{IdPrefix, PrefixedId};
 
-#[derive(Debug, Clone)]
-pub struct OrderItemId {
-  value: Uuid,
-}
-
-const ORDER_ITEM_PREFIX: &str = "ORDER_ITEM";
-
-impl OrderItemId {
-  pub fn new() -> Self {
-    let value = generate_id();
-    Self { value }
-  }
-}
-
-impl AggregateId for OrderItemId {
-  fn type_name(&self) -> String { ORDER_ITEM_PREFIX.to_string() }
-  fn value(&self) -> String { self.value.to_string() }
-}
+/// 注文アイテムIDのプレフィックスです
+pub struct OrderItemIdPrefix;
 
-impl Display for OrderItemId {
-  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-    write!(f, "{}-{}", self.type_name(), self.value)
-  }
+impl IdPrefix for OrderItemIdPrefix {
+  const PREFIX: &'static str = "ORDER_ITEM";
 }
 
-impl From<Uuid> for OrderItemId {
-  fn from(value: Uuid) -> Self {
-    Self { value }
-  }
-}
\ No newline at end of file
+/// 注文アイテムIDです
+///
+/// `Display`は`ORDER_ITEM-<uuid>`形式、serdeはUUIDのみの形式で表現します
+pub type OrderItemId = PrefixedId<OrderItemIdPrefix>;
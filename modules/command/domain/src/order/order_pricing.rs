@@ -0,0 +1,21 @@
+use crate::tax::region::Region;
+use crate::tax::tax_rule::TaxRule;
+use crate::value_object::currency::Currency;
+use crate::value_object::rounding_policy::RoundingPolicy;
+
+/// 注文の金額計算に使用する設定です
+///
+/// currency: 注文の通貨
+///
+/// rounding_policy: 金額の丸めポリシー
+///
+/// region: 税率を判定する地域
+///
+/// tax_rule: 税率のルール
+#[derive(Debug, Clone)]
+pub struct OrderPricing<'a> {
+  pub currency: Currency,
+  pub rounding_policy: RoundingPolicy,
+  pub region: Region,
+  pub tax_rule: &'a dyn TaxRule,
+}
@@ -0,0 +1,41 @@
+use crate::order::order_id::OrderId;
+use crate::order::Order;
+use crate::repository::{RepositoryError, Versioned};
+
+/// 注文の永続化を行うリポジトリのトレイトです
+///
+/// 出荷と配送先の変更が同時に行われても一方が失われないよう、
+/// 更新時はバージョンによる楽観的排他制御を行います
+pub trait OrderRepository: Send + Sync {
+  /// 注文IDに対応する注文を取得します
+  ///
+  /// # Arguments
+  /// * `order_id`: &OrderId
+  ///
+  /// # Return
+  /// * `Result<Option<Versioned<Order>>, RepositoryError>`
+  fn find_by_id(&self, order_id: &OrderId) -> Result<Option<Versioned<Order>>, RepositoryError>;
+
+  /// 新しい注文を保存します
+  ///
+  /// 同じIDの注文がある場合は`AlreadyExists`を返します
+  ///
+  /// # Arguments
+  /// * `order`: Order
+  ///
+  /// # Return
+  /// * `Result<(), RepositoryError>`
+  fn insert(&self, order: Order) -> Result<(), RepositoryError>;
+
+  /// 注文を更新します
+  ///
+  /// 保存済みのバージョンがexpected_versionと異なる場合は`VersionConflict`を返します
+  ///
+  /// # Arguments
+  /// * `order`: Order
+  /// * `expected_version`: 取得時のバージョン
+  ///
+  /// # Return
+  /// * `Result<(), RepositoryError>`
+  fn update(&self, order: Order, expected_version: u64) -> Result<(), RepositoryError>;
+}
@@ -0,0 +1,13 @@
+use crate::order::order_item_id::OrderItemId;
+use crate::value_object::quantity::Quantity;
+
+/// 返品する明細と数量です
+///
+/// order_item_id: 返品する注文アイテムのID
+///
+/// quantity: 返品する数量
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReturnItem {
+  pub order_item_id: OrderItemId,
+  pub quantity: Quantity,
+}
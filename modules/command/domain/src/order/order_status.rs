@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// 注文の状態です
+///
+/// Placed: 確定済み(未払い)
+///
+/// PaymentAuthorized: 支払いの承認済み
+///
+/// Paid: 支払いの売上確定済み
+///
+/// PaymentFailed: 支払いの失敗(新しい支払いで再承認できます)
+///
+/// Shipped: 出荷済み
+///
+/// Refunded: 全額返金済み
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderStatus {
+  Placed,
+  PaymentAuthorized,
+  Paid,
+  PaymentFailed,
+  Shipped,
+  Refunded,
+}
+
+impl OrderStatus {
+  /// 状態を表す文字列を返します
+  pub fn code(&self) -> &'static str {
+    match self {
+      OrderStatus::Placed => "placed",
+      OrderStatus::PaymentAuthorized => "payment_authorized",
+      OrderStatus::Paid => "paid",
+      OrderStatus::PaymentFailed => "payment_failed",
+      OrderStatus::Shipped => "shipped",
+      OrderStatus::Refunded => "refunded",
+    }
+  }
+
+  /// 支払いを承認できる状態の場合trueを返します
+  pub fn is_awaiting_payment(&self) -> bool {
+    matches!(self, OrderStatus::Placed | OrderStatus::PaymentFailed)
+  }
+}
+
+impl Display for OrderStatus {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.code())
+  }
+}
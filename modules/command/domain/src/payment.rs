@@ -0,0 +1,446 @@
+pub mod payment_error;
+pub mod payment_event;
+pub mod payment_id;
+pub mod payment_provider;
+pub mod payment_repository;
+pub mod payment_status;
+pub mod pending_refund;
+pub mod refund_id;
+
+use crate::clock::Clock;
+use crate::order::order_id::OrderId;
+use crate::payment::payment_error::PaymentError;
+use crate::payment::payment_event::{
+  PaymentAuthorized, PaymentCaptured, PaymentEvent, PaymentFailed, PaymentRefunded, RefundCancelled, RefundRequested,
+};
+use crate::payment::payment_id::PaymentId;
+use crate::payment::payment_status::PaymentStatus;
+use crate::payment::pending_refund::PendingRefund;
+use crate::payment::refund_id::RefundId;
+use crate::value_object::money::{Money, MoneyError};
+
+/// 注文の支払いの集約です
+///
+/// 状態は次のように遷移します
+///
+/// - Pending → Authorized → Captured → PartiallyRefunded → Refunded
+/// - Pending / Authorized → Failed
+///
+/// 決済代行サービスの呼び出しは`PaymentProvider`で行い、この集約はその結果を記録します。
+/// 返金は呼び出しの前に`request_refund`で予約し、結果に応じて`complete_refund`か`cancel_refund`で確定します
+#[derive(Debug, Clone, PartialEq)]
+pub struct Payment {
+  /// 支払いID
+  id: PaymentId,
+
+  /// 支払い対象の注文のID
+  order_id: OrderId,
+
+  /// 支払い金額
+  amount: Money,
+
+  /// 支払いの状態
+  status: PaymentStatus,
+
+  /// 決済代行サービスでの取引の参照
+  provider_reference: Option<String>,
+
+  /// 返金済みの金額
+  refunded_amount: Money,
+
+  /// 決済代行サービスでの実行を待っている返金
+  pending_refunds: Vec<PendingRefund>,
+}
+
+impl Payment {
+  /// 未承認の支払いを作成します
+  ///
+  /// # Arguments
+  /// * `id`: PaymentId
+  /// * `order_id`: 支払い対象の注文のID
+  /// * `amount`: 支払い金額(0より大きい金額)
+  ///
+  /// # Return
+  /// * `Result<Payment, PaymentError>`
+  pub fn new(id: PaymentId, order_id: OrderId, amount: Money) -> Result<Self, PaymentError> {
+    if amount.amount().is_sign_negative() || amount.amount().is_zero() {
+      Err(PaymentError::InvalidAmount(amount))?
+    }
+    Ok(Self {
+      id,
+      order_id,
+      refunded_amount: Money::zero(amount.currency()),
+      amount,
+      status: PaymentStatus::Pending,
+      provider_reference: None,
+      pending_refunds: vec![],
+    })
+  }
+
+  /// 決済代行サービスで承認された支払いを記録します
+  ///
+  /// # Arguments
+  /// * `provider_reference`: 決済代行サービスでの取引の参照
+  /// * `clock`: 承認日時の取得元
+  ///
+  /// # Return
+  /// * `Result<PaymentEvent, PaymentError>`
+  pub fn authorize(&mut self, provider_reference: &str, clock: &dyn Clock) -> Result<PaymentEvent, PaymentError> {
+    self.ensure_status(&[PaymentStatus::Pending], "authorize")?;
+    self.status = PaymentStatus::Authorized;
+    self.provider_reference = Some(provider_reference.to_string());
+    Ok(PaymentEvent::PaymentAuthorized(PaymentAuthorized {
+      payment_id: self.id.clone(),
+      order_id: self.order_id.clone(),
+      occurred_at: clock.now(),
+      amount: self.amount,
+      provider_reference: provider_reference.to_string(),
+    }))
+  }
+
+  /// 承認済みの支払いの売上確定を記録します
+  ///
+  /// # Arguments
+  /// * `clock`: 売上確定日時の取得元
+  ///
+  /// # Return
+  /// * `Result<PaymentEvent, PaymentError>`
+  pub fn capture(&mut self, clock: &dyn Clock) -> Result<PaymentEvent, PaymentError> {
+    self.ensure_status(&[PaymentStatus::Authorized], "capture")?;
+    self.status = PaymentStatus::Captured;
+    Ok(PaymentEvent::PaymentCaptured(PaymentCaptured {
+      payment_id: self.id.clone(),
+      order_id: self.order_id.clone(),
+      occurred_at: clock.now(),
+      amount: self.amount,
+    }))
+  }
+
+  /// 売上確定済みの支払いの返金を予約します
+  ///
+  /// 決済代行サービスで返金する前に呼び出し、予約した金額を返金できる金額から差し引きます。
+  /// 返金額の累計(予約中の返金を含む)は支払い金額を超えられません
+  ///
+  /// # Arguments
+  /// * `refund_id`: 返金ID
+  /// * `amount`: 返金額
+  /// * `clock`: 予約日時の取得元
+  ///
+  /// # Return
+  /// * `Result<PaymentEvent, PaymentError>`
+  pub fn request_refund(&mut self, refund_id: RefundId, amount: Money, clock: &dyn Clock) -> Result<PaymentEvent, PaymentError> {
+    self.ensure_status(&[PaymentStatus::Captured, PaymentStatus::PartiallyRefunded], "refund")?;
+    if amount.currency() != self.amount.currency() {
+      Err(MoneyError::CurrencyMismatch { expected: self.amount.currency(), actual: amount.currency() })?
+    }
+    if amount.amount().is_sign_negative() || amount.amount().is_zero() {
+      Err(PaymentError::InvalidAmount(amount))?
+    }
+    let refundable = self.get_refundable_amount();
+    if amount.amount() > refundable.amount() {
+      Err(PaymentError::RefundExceedsCaptured { requested: amount, refundable })?
+    }
+
+    self.pending_refunds.push(PendingRefund { refund_id: refund_id.clone(), amount });
+    Ok(PaymentEvent::RefundRequested(RefundRequested {
+      payment_id: self.id.clone(),
+      order_id: self.order_id.clone(),
+      occurred_at: clock.now(),
+      refund_id,
+      amount,
+    }))
+  }
+
+  /// 決済代行サービスで実行した返金を記録します
+  ///
+  /// 累計が支払い金額に達した場合はRefunded、それ以外はPartiallyRefundedになります
+  ///
+  /// # Arguments
+  /// * `refund_id`: 予約した返金の返金ID
+  /// * `clock`: 返金日時の取得元
+  ///
+  /// # Return
+  /// * `Result<PaymentEvent, PaymentError>`
+  pub fn complete_refund(&mut self, refund_id: &RefundId, clock: &dyn Clock) -> Result<PaymentEvent, PaymentError> {
+    let pending = self.take_pending_refund(refund_id)?;
+    self.refunded_amount = self.refunded_amount.add(&pending.amount)?;
+    self.status = if self.refunded_amount == self.amount {
+      PaymentStatus::Refunded
+    } else {
+      PaymentStatus::PartiallyRefunded
+    };
+    Ok(PaymentEvent::PaymentRefunded(PaymentRefunded {
+      payment_id: self.id.clone(),
+      order_id: self.order_id.clone(),
+      occurred_at: clock.now(),
+      refund_id: pending.refund_id,
+      amount: pending.amount,
+      refunded_total: self.refunded_amount,
+      fully_refunded: self.status == PaymentStatus::Refunded,
+    }))
+  }
+
+  /// 決済代行サービスが拒否した返金の予約を取り消します
+  ///
+  /// 予約した金額は再び返金できるようになります
+  ///
+  /// # Arguments
+  /// * `refund_id`: 予約した返金の返金ID
+  /// * `reason`: 拒否の理由
+  /// * `clock`: 日時の取得元
+  ///
+  /// # Return
+  /// * `Result<PaymentEvent, PaymentError>`
+  pub fn cancel_refund(&mut self, refund_id: &RefundId, reason: &str, clock: &dyn Clock) -> Result<PaymentEvent, PaymentError> {
+    let pending = self.take_pending_refund(refund_id)?;
+    Ok(PaymentEvent::RefundCancelled(RefundCancelled {
+      payment_id: self.id.clone(),
+      order_id: self.order_id.clone(),
+      occurred_at: clock.now(),
+      refund_id: pending.refund_id,
+      amount: pending.amount,
+      reason: reason.to_string(),
+    }))
+  }
+
+  /// 予約中の返金を取り出します
+  fn take_pending_refund(&mut self, refund_id: &RefundId) -> Result<PendingRefund, PaymentError> {
+    let Some(index) = self.pending_refunds.iter().position(|pending| &pending.refund_id == refund_id) else {
+      Err(PaymentError::RefundNotPending { payment_id: self.id.to_string(), refund_id: refund_id.to_string() })?
+    };
+    Ok(self.pending_refunds.remove(index))
+  }
+
+  /// 承認・売上確定が拒否された支払いを記録します
+  ///
+  /// # Arguments
+  /// * `reason`: 拒否の理由
+  /// * `clock`: 日時の取得元
+  ///
+  /// # Return
+  /// * `Result<PaymentEvent, PaymentError>`
+  pub fn fail(&mut self, reason: &str, clock: &dyn Clock) -> Result<PaymentEvent, PaymentError> {
+    self.ensure_status(&[PaymentStatus::Pending, PaymentStatus::Authorized], "fail")?;
+    self.status = PaymentStatus::Failed;
+    Ok(PaymentEvent::PaymentFailed(PaymentFailed {
+      payment_id: self.id.clone(),
+      order_id: self.order_id.clone(),
+      occurred_at: clock.now(),
+      reason: reason.to_string(),
+    }))
+  }
+
+  /// 現在の状態で操作できることを検証します
+  fn ensure_status(&self, allowed: &[PaymentStatus], action: &'static str) -> Result<(), PaymentError> {
+    if !allowed.contains(&self.status) {
+      Err(PaymentError::InvalidTransition { payment_id: self.id.to_string(), status: self.status, action })?
+    }
+    Ok(())
+  }
+
+  /// 支払いIDのゲッター
+  pub fn get_id(&self) -> &PaymentId { &self.id }
+
+  /// 注文IDのゲッター
+  pub fn get_order_id(&self) -> &OrderId { &self.order_id }
+
+  /// 支払い金額のゲッター
+  pub fn get_amount(&self) -> &Money { &self.amount }
+
+  /// 支払いの状態のゲッター
+  pub fn get_status(&self) -> PaymentStatus { self.status }
+
+  /// 決済代行サービスでの取引の参照のゲッター
+  pub fn get_provider_reference(&self) -> Option<&str> { self.provider_reference.as_deref() }
+
+  /// 返金済みの金額のゲッター
+  pub fn get_refunded_amount(&self) -> &Money { &self.refunded_amount }
+
+  /// 予約中の返金のゲッター
+  pub fn get_pending_refunds(&self) -> &[PendingRefund] { &self.pending_refunds }
+
+  /// 予約中の返金額の合計を返します
+  pub fn get_pending_refund_amount(&self) -> Money {
+    // 予約時に支払い金額と同じ通貨であることを検証済みです
+    self.pending_refunds
+      .iter()
+      .try_fold(Money::zero(self.amount.currency()), |total, pending| total.add(&pending.amount))
+      .unwrap_or(Money::zero(self.amount.currency()))
+  }
+
+  /// 返金できる残りの金額(予約中の返金を除く)を返します
+  pub fn get_refundable_amount(&self) -> Money {
+    match self.status {
+      // 支払い金額・返金額・予約中の返金額は同じ通貨であることを検証済みです
+      PaymentStatus::Captured | PaymentStatus::PartiallyRefunded => self.amount
+        .sub(&self.refunded_amount)
+        .and_then(|rest| rest.sub(&self.get_pending_refund_amount()))
+        .unwrap_or(Money::zero(self.amount.currency())),
+      _ => Money::zero(self.amount.currency()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::clock::FixedClock;
+  use crate::id_generator::UuidV4Generator;
+  use crate::value_object::currency::Currency;
+  use chrono::{TimeZone, Utc};
+  use rust_decimal::Decimal;
+
+  fn clock() -> FixedClock {
+    FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap())
+  }
+
+  fn jpy(value: i64) -> Money {
+    Money::new(Decimal::from(value), Currency::JPY).unwrap()
+  }
+
+  /// 返金を予約し、決済代行サービスで実行した結果を記録します
+  fn refund(payment: &mut Payment, amount: Money) -> Result<PaymentEvent, PaymentError> {
+    let refund_id = RefundId::generate(&UuidV4Generator);
+    payment.request_refund(refund_id.clone(), amount, &clock())?;
+    payment.complete_refund(&refund_id, &clock())
+  }
+
+  fn captured_payment() -> Payment {
+    let mut payment = Payment::new(PaymentId::generate(&UuidV4Generator), OrderId::generate(&UuidV4Generator), jpy(1000)).unwrap();
+    payment.authorize("ref-1", &clock()).unwrap();
+    payment.capture(&clock()).unwrap();
+    payment
+  }
+
+  #[test]
+  fn test_payment_authorize_and_capture_success() {
+    let mut payment = Payment::new(PaymentId::generate(&UuidV4Generator), OrderId::generate(&UuidV4Generator), jpy(1000)).unwrap();
+
+    let authorized = payment.authorize("ref-1", &clock()).unwrap();
+    let captured = payment.capture(&clock()).unwrap();
+
+    // assert
+    assert_eq!(PaymentStatus::Captured, payment.get_status());
+    assert_eq!(Some("ref-1"), payment.get_provider_reference());
+    assert!(matches!(authorized, PaymentEvent::PaymentAuthorized(event) if event.amount == jpy(1000)));
+    assert!(matches!(captured, PaymentEvent::PaymentCaptured(event) if &event.order_id == payment.get_order_id()));
+    assert_eq!(jpy(1000), payment.get_refundable_amount());
+  }
+
+  #[test]
+  fn test_payment_new_failed() {
+    // assert
+    assert_eq!(
+      Err(PaymentError::InvalidAmount(jpy(0))),
+      Payment::new(PaymentId::generate(&UuidV4Generator), OrderId::generate(&UuidV4Generator), jpy(0))
+    );
+  }
+
+  #[test]
+  fn test_payment_refund_success() {
+    let mut payment = captured_payment();
+
+    let partial = refund(&mut payment, jpy(300)).unwrap();
+    let status_after_partial = payment.get_status();
+    let full = refund(&mut payment, jpy(700)).unwrap();
+
+    // assert
+    assert_eq!(PaymentStatus::PartiallyRefunded, status_after_partial);
+    assert!(matches!(partial, PaymentEvent::PaymentRefunded(event) if !event.fully_refunded));
+    assert!(matches!(full, PaymentEvent::PaymentRefunded(event) if event.fully_refunded && event.refunded_total == jpy(1000)));
+    assert_eq!(PaymentStatus::Refunded, payment.get_status());
+    assert_eq!(jpy(0), payment.get_refundable_amount());
+  }
+
+  #[test]
+  fn test_payment_refund_failed() {
+    let mut payment = captured_payment();
+    refund(&mut payment, jpy(600)).unwrap();
+
+    // assert
+    assert_eq!(
+      Err(PaymentError::RefundExceedsCaptured { requested: jpy(401), refundable: jpy(400) }),
+      refund(&mut payment, jpy(401))
+    );
+    assert_eq!(Err(PaymentError::InvalidAmount(jpy(0))), refund(&mut payment, jpy(0)));
+    assert!(matches!(
+      refund(&mut payment, Money::new(Decimal::from(1), Currency::USD).unwrap()),
+      Err(PaymentError::InvalidMoney(MoneyError::CurrencyMismatch { .. }))
+    ));
+    assert_eq!(&jpy(600), payment.get_refunded_amount());
+  }
+
+  #[test]
+  fn test_payment_pending_refund() {
+    let mut payment = captured_payment();
+    let declined = RefundId::generate(&UuidV4Generator);
+    let completed = RefundId::generate(&UuidV4Generator);
+
+    payment.request_refund(declined.clone(), jpy(600), &clock()).unwrap();
+    payment.request_refund(completed.clone(), jpy(300), &clock()).unwrap();
+    let refundable_while_pending = payment.get_refundable_amount();
+    let exceeded = payment.request_refund(RefundId::generate(&UuidV4Generator), jpy(101), &clock());
+    let cancelled = payment.cancel_refund(&declined, "declined", &clock()).unwrap();
+    let refunded = payment.complete_refund(&completed, &clock()).unwrap();
+    let not_pending = payment.complete_refund(&declined, &clock());
+
+    // assert
+    assert_eq!(jpy(100), refundable_while_pending);
+    assert_eq!(Err(PaymentError::RefundExceedsCaptured { requested: jpy(101), refundable: jpy(100) }), exceeded);
+    assert!(matches!(cancelled, PaymentEvent::RefundCancelled(event) if event.refund_id == declined && event.amount == jpy(600)));
+    assert!(matches!(refunded, PaymentEvent::PaymentRefunded(event) if event.refund_id == completed && event.refunded_total == jpy(300)));
+    assert!(matches!(not_pending, Err(PaymentError::RefundNotPending { .. })));
+    assert!(payment.get_pending_refunds().is_empty());
+    assert_eq!(PaymentStatus::PartiallyRefunded, payment.get_status());
+    assert_eq!(jpy(700), payment.get_refundable_amount());
+  }
+
+  #[test]
+  fn test_payment_invalid_transition_failed() {
+    let mut pending = Payment::new(PaymentId::generate(&UuidV4Generator), OrderId::generate(&UuidV4Generator), jpy(1000)).unwrap();
+    let mut captured = captured_payment();
+
+    // assert
+    assert!(matches!(
+      pending.capture(&clock()),
+      Err(PaymentError::InvalidTransition { status: PaymentStatus::Pending, action: "capture", .. })
+    ));
+    assert!(matches!(
+      refund(&mut pending, jpy(100)),
+      Err(PaymentError::InvalidTransition { action: "refund", .. })
+    ));
+    assert!(matches!(
+      captured.fail("declined", &clock()),
+      Err(PaymentError::InvalidTransition { status: PaymentStatus::Captured, action: "fail", .. })
+    ));
+    pending.fail("declined", &clock()).unwrap();
+    assert!(matches!(pending.authorize("ref-1", &clock()), Err(PaymentError::InvalidTransition { .. })));
+  }
+
+  #[test]
+  fn test_payment_events_serde() {
+    let mut payment = Payment::new(PaymentId::generate(&UuidV4Generator), OrderId::generate(&UuidV4Generator), jpy(1000)).unwrap();
+    let mut failed = Payment::new(PaymentId::generate(&UuidV4Generator), OrderId::generate(&UuidV4Generator), jpy(1000)).unwrap();
+    let refund_id = RefundId::generate(&UuidV4Generator);
+    let cancelled_id = RefundId::generate(&UuidV4Generator);
+    let events = vec![
+      payment.authorize("ref-1", &clock()).unwrap(),
+      payment.capture(&clock()).unwrap(),
+      payment.request_refund(refund_id.clone(), jpy(300), &clock()).unwrap(),
+      payment.complete_refund(&refund_id, &clock()).unwrap(),
+      payment.request_refund(cancelled_id.clone(), jpy(100), &clock()).unwrap(),
+      payment.cancel_refund(&cancelled_id, "declined", &clock()).unwrap(),
+      failed.fail("declined", &clock()).unwrap(),
+    ];
+
+    let json = events.iter().map(|event| serde_json::to_value(event).unwrap()).collect::<Vec<_>>();
+    let deserialized = json.iter().map(|value| serde_json::from_value::<PaymentEvent>(value.clone()).unwrap()).collect::<Vec<_>>();
+
+    // assert
+    assert_eq!(
+      vec!["PaymentAuthorized", "PaymentCaptured", "RefundRequested", "PaymentRefunded", "RefundRequested", "RefundCancelled", "PaymentFailed"],
+      json.iter().map(|value| value["type"].as_str().unwrap()).collect::<Vec<_>>(),
+    );
+    assert_eq!(events, deserialized);
+  }
+}
@@ -0,0 +1,62 @@
+use crate::aggregate_id::AggregateIdError;
+use crate::error_code::{ErrorCode, FieldPath};
+use crate::payment::payment_provider::PaymentProviderError;
+use crate::payment::payment_status::PaymentStatus;
+use crate::value_object::money::{Money, MoneyError};
+use thiserror::Error;
+
+/// 支払いのエラーです
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+pub enum PaymentError {
+  #[error("payment {payment_id} cannot {action} in status {status}")]
+  InvalidTransition { payment_id: String, status: PaymentStatus, action: &'static str },
+
+  #[error("payment amount must be greater than 0: {0}")]
+  InvalidAmount(Money),
+
+  #[error("refund {requested} exceeds refundable amount {refundable}")]
+  RefundExceedsCaptured { requested: Money, refundable: Money },
+
+  #[error("refund {refund_id} is not pending on payment {payment_id}")]
+  RefundNotPending { payment_id: String, refund_id: String },
+
+  #[error("payment not found: {0}")]
+  PaymentNotFound(String),
+
+  #[error("captured payment not found for order: {0}")]
+  CapturedPaymentNotFound(String),
+
+  #[error("Invalid Payment ID: {0}")]
+  InvalidPaymentId(#[from] AggregateIdError),
+
+  #[error("payment provider error: {0}")]
+  Provider(#[from] PaymentProviderError),
+
+  #[error("Invalid Money: {0}")]
+  InvalidMoney(#[from] MoneyError),
+}
+
+impl ErrorCode for PaymentError {
+  fn code(&self) -> &'static str {
+    match self {
+      PaymentError::InvalidTransition { .. } => "payment.invalid_transition",
+      PaymentError::InvalidAmount(_) => "payment.invalid_amount",
+      PaymentError::RefundExceedsCaptured { .. } => "payment.refund_exceeds_captured",
+      PaymentError::RefundNotPending { .. } => "payment.refund_not_pending",
+      PaymentError::PaymentNotFound(_) => "payment.not_found",
+      PaymentError::CapturedPaymentNotFound(_) => "payment.captured_payment_not_found",
+      PaymentError::InvalidPaymentId(e) => e.code(),
+      PaymentError::Provider(e) => e.code(),
+      PaymentError::InvalidMoney(e) => e.code(),
+    }
+  }
+
+  fn field_path(&self) -> Option<FieldPath> {
+    match self {
+      PaymentError::InvalidAmount(_) | PaymentError::RefundExceedsCaptured { .. } | PaymentError::InvalidMoney(_) => {
+        Some(FieldPath::new("amount"))
+      }
+      _ => None,
+    }
+  }
+}
@@ -0,0 +1,121 @@
+use crate::order::order_id::OrderId;
+use crate::payment::payment_id::PaymentId;
+use crate::payment::refund_id::RefundId;
+use crate::value_object::money::Money;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 支払いのイベントです
+///
+/// シリアライズすると、`type`にイベント名を持つJSONオブジェクトになります
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum PaymentEvent {
+  PaymentAuthorized(PaymentAuthorized),
+  PaymentCaptured(PaymentCaptured),
+  RefundRequested(RefundRequested),
+  PaymentRefunded(PaymentRefunded),
+  RefundCancelled(RefundCancelled),
+  PaymentFailed(PaymentFailed),
+}
+
+impl PaymentEvent {
+  /// イベントが発生した支払いのIDを返します
+  pub fn payment_id(&self) -> &PaymentId {
+    match self {
+      PaymentEvent::PaymentAuthorized(event) => &event.payment_id,
+      PaymentEvent::PaymentCaptured(event) => &event.payment_id,
+      PaymentEvent::RefundRequested(event) => &event.payment_id,
+      PaymentEvent::PaymentRefunded(event) => &event.payment_id,
+      PaymentEvent::RefundCancelled(event) => &event.payment_id,
+      PaymentEvent::PaymentFailed(event) => &event.payment_id,
+    }
+  }
+
+  /// 支払い対象の注文のIDを返します
+  pub fn order_id(&self) -> &OrderId {
+    match self {
+      PaymentEvent::PaymentAuthorized(event) => &event.order_id,
+      PaymentEvent::PaymentCaptured(event) => &event.order_id,
+      PaymentEvent::RefundRequested(event) => &event.order_id,
+      PaymentEvent::PaymentRefunded(event) => &event.order_id,
+      PaymentEvent::RefundCancelled(event) => &event.order_id,
+      PaymentEvent::PaymentFailed(event) => &event.order_id,
+    }
+  }
+
+  /// イベントの発生日時を返します
+  pub fn occurred_at(&self) -> &DateTime<Utc> {
+    match self {
+      PaymentEvent::PaymentAuthorized(event) => &event.occurred_at,
+      PaymentEvent::PaymentCaptured(event) => &event.occurred_at,
+      PaymentEvent::RefundRequested(event) => &event.occurred_at,
+      PaymentEvent::PaymentRefunded(event) => &event.occurred_at,
+      PaymentEvent::RefundCancelled(event) => &event.occurred_at,
+      PaymentEvent::PaymentFailed(event) => &event.occurred_at,
+    }
+  }
+}
+
+/// 支払いが承認されたイベントです
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaymentAuthorized {
+  pub payment_id: PaymentId,
+  pub order_id: OrderId,
+  pub occurred_at: DateTime<Utc>,
+  pub amount: Money,
+  pub provider_reference: String,
+}
+
+/// 支払いの売上が確定されたイベントです
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaymentCaptured {
+  pub payment_id: PaymentId,
+  pub order_id: OrderId,
+  pub occurred_at: DateTime<Utc>,
+  pub amount: Money,
+}
+
+/// 決済代行サービスで返金する前に、返金を予約したイベントです
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RefundRequested {
+  pub payment_id: PaymentId,
+  pub order_id: OrderId,
+  pub occurred_at: DateTime<Utc>,
+  pub refund_id: RefundId,
+  pub amount: Money,
+}
+
+/// 支払いが返金されたイベントです
+///
+/// refunded_totalは返金後の返金額の累計、fully_refundedは全額返金された場合trueです
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaymentRefunded {
+  pub payment_id: PaymentId,
+  pub order_id: OrderId,
+  pub occurred_at: DateTime<Utc>,
+  pub refund_id: RefundId,
+  pub amount: Money,
+  pub refunded_total: Money,
+  pub fully_refunded: bool,
+}
+
+/// 決済代行サービスが返金を拒否したため、予約した返金を取り消したイベントです
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RefundCancelled {
+  pub payment_id: PaymentId,
+  pub order_id: OrderId,
+  pub occurred_at: DateTime<Utc>,
+  pub refund_id: RefundId,
+  pub amount: Money,
+  pub reason: String,
+}
+
+/// 支払いの承認・売上確定が拒否されたイベントです
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaymentFailed {
+  pub payment_id: PaymentId,
+  pub order_id: OrderId,
+  pub occurred_at: DateTime<Utc>,
+  pub reason: String,
+}
@@ -0,0 +1,13 @@
+use crate::aggregate_id::{IdPrefix, PrefixedId};
+
+/// 支払いIDのプレフィックスです
+pub struct PaymentIdPrefix;
+
+impl IdPrefix for PaymentIdPrefix {
+  const PREFIX: &'static str = "PAYMENT";
+}
+
+/// 支払いIDです
+///
+/// `Display`は`PAYMENT-<uuid>`形式、serdeはUUIDのみの形式で表現します
+pub type PaymentId = PrefixedId<PaymentIdPrefix>;
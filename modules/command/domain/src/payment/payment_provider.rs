@@ -0,0 +1,67 @@
+use crate::error_code::ErrorCode;
+use crate::payment::payment_id::PaymentId;
+use crate::payment::refund_id::RefundId;
+use crate::value_object::money::Money;
+use thiserror::Error;
+
+/// 決済代行サービスのエラーです
+///
+/// Declined: 決済代行サービスが取引を拒否した場合(支払いは失敗として記録します)
+///
+/// Unavailable: 通信エラーなどで結果が得られなかった場合(支払いの状態は変更しません)
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+pub enum PaymentProviderError {
+  #[error("declined: {0}")]
+  Declined(String),
+
+  #[error("unavailable: {0}")]
+  Unavailable(String),
+}
+
+impl ErrorCode for PaymentProviderError {
+  fn code(&self) -> &'static str {
+    match self {
+      PaymentProviderError::Declined(_) => "payment_provider.declined",
+      PaymentProviderError::Unavailable(_) => "payment_provider.unavailable",
+    }
+  }
+}
+
+/// 決済代行サービスを呼び出すポートのトレイトです
+///
+/// 実装はインフラストラクチャ層に置き、コマンドプロセッサーに注入します
+pub trait PaymentProvider: Send + Sync {
+  /// 支払い金額の与信を確保します
+  ///
+  /// # Arguments
+  /// * `payment_id`: 支払いID
+  /// * `amount`: 支払い金額
+  ///
+  /// # Return
+  /// * `Result<String, PaymentProviderError>`: 決済代行サービスでの取引の参照
+  fn authorize(&self, payment_id: &PaymentId, amount: &Money) -> Result<String, PaymentProviderError>;
+
+  /// 与信を確保した取引の売上を確定します
+  ///
+  /// # Arguments
+  /// * `provider_reference`: 取引の参照
+  /// * `amount`: 売上を確定する金額
+  ///
+  /// # Return
+  /// * `Result<(), PaymentProviderError>`
+  fn capture(&self, provider_reference: &str, amount: &Money) -> Result<(), PaymentProviderError>;
+
+  /// 売上を確定した取引を返金します
+  ///
+  /// 同じ返金IDでの呼び出しは冪等でなければなりません。
+  /// 結果が得られずに再実行した場合も、返金は1回だけ実行されます
+  ///
+  /// # Arguments
+  /// * `provider_reference`: 取引の参照
+  /// * `refund_id`: 返金ID(冪等キー)
+  /// * `amount`: 返金額
+  ///
+  /// # Return
+  /// * `Result<(), PaymentProviderError>`
+  fn refund(&self, provider_reference: &str, refund_id: &RefundId, amount: &Money) -> Result<(), PaymentProviderError>;
+}
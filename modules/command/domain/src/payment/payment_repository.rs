@@ -0,0 +1,51 @@
+use crate::order::order_id::OrderId;
+use crate::payment::payment_id::PaymentId;
+use crate::payment::Payment;
+use crate::repository::{RepositoryError, Versioned};
+
+/// 支払いの永続化を行うリポジトリのトレイトです
+///
+/// 同じ支払いへの売上確定や返金が同時に行われても二重に記録されないよう、
+/// 更新時はバージョンによる楽観的排他制御を行います
+pub trait PaymentRepository: Send + Sync {
+  /// 支払いIDに対応する支払いを取得します
+  ///
+  /// # Arguments
+  /// * `payment_id`: &PaymentId
+  ///
+  /// # Return
+  /// * `Result<Option<Versioned<Payment>>, RepositoryError>`
+  fn find_by_id(&self, payment_id: &PaymentId) -> Result<Option<Versioned<Payment>>, RepositoryError>;
+
+  /// 注文に対する支払いを作成順に取得します
+  ///
+  /// # Arguments
+  /// * `order_id`: &OrderId
+  ///
+  /// # Return
+  /// * `Result<Vec<Payment>, RepositoryError>`
+  fn find_by_order_id(&self, order_id: &OrderId) -> Result<Vec<Payment>, RepositoryError>;
+
+  /// 新しい支払いを保存します
+  ///
+  /// 同じIDの支払いがある場合は`AlreadyExists`を返します
+  ///
+  /// # Arguments
+  /// * `payment`: Payment
+  ///
+  /// # Return
+  /// * `Result<(), RepositoryError>`
+  fn insert(&self, payment: Payment) -> Result<(), RepositoryError>;
+
+  /// 支払いを更新します
+  ///
+  /// 保存済みのバージョンがexpected_versionと異なる場合は`VersionConflict`を返します
+  ///
+  /// # Arguments
+  /// * `payment`: Payment
+  /// * `expected_version`: 取得時のバージョン
+  ///
+  /// # Return
+  /// * `Result<(), RepositoryError>`
+  fn update(&self, payment: Payment, expected_version: u64) -> Result<(), RepositoryError>;
+}
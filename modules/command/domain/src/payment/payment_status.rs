@@ -0,0 +1,44 @@
+use std::fmt::{Display, Formatter};
+
+/// 支払いの状態です
+///
+/// Pending: 未承認
+///
+/// Authorized: 承認済み(与信確保)
+///
+/// Captured: 売上確定済み
+///
+/// PartiallyRefunded: 一部返金済み
+///
+/// Refunded: 全額返金済み
+///
+/// Failed: 承認・売上確定の失敗
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PaymentStatus {
+  Pending,
+  Authorized,
+  Captured,
+  PartiallyRefunded,
+  Refunded,
+  Failed,
+}
+
+impl PaymentStatus {
+  /// 状態を表す文字列を返します
+  pub fn code(&self) -> &'static str {
+    match self {
+      PaymentStatus::Pending => "pending",
+      PaymentStatus::Authorized => "authorized",
+      PaymentStatus::Captured => "captured",
+      PaymentStatus::PartiallyRefunded => "partially_refunded",
+      PaymentStatus::Refunded => "refunded",
+      PaymentStatus::Failed => "failed",
+    }
+  }
+}
+
+impl Display for PaymentStatus {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.code())
+  }
+}
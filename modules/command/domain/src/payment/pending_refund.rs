@@ -0,0 +1,16 @@
+use crate::payment::refund_id::RefundId;
+use crate::value_object::money::Money;
+
+/// 決済代行サービスでの実行を待っている返金です
+///
+/// 決済代行サービスを呼び出す前に支払いに記録し、返金できる金額から差し引きます。
+/// 決済代行サービスの結果が得られなかった場合も残るため、同じ返金IDで再実行できます
+///
+/// refund_id: 返金ID(決済代行サービスへの冪等キー)
+///
+/// amount: 返金額
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingRefund {
+  pub refund_id: RefundId,
+  pub amount: Money,
+}
@@ -0,0 +1,14 @@
+use crate::aggregate_id::{IdPrefix, PrefixedId};
+
+/// 返金IDのプレフィックスです
+pub struct RefundIdPrefix;
+
+impl IdPrefix for RefundIdPrefix {
+  const PREFIX: &'static str = "REFUND";
+}
+
+/// 返金IDです
+///
+/// 決済代行サービスへの返金の冪等キーとして使います。
+/// `Display`は`REFUND-<uuid>`形式、serdeはUUIDのみの形式で表現します
+pub type RefundId = PrefixedId<RefundIdPrefix>;
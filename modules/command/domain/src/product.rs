@@ -1 +1,3 @@
-pub mod product_name;
\ No newline at end of file
+pub mod product_name;
+pub mod product_category;
+pub mod product_quantity_limits;
@@ -0,0 +1,83 @@
+use crate::error_code::ErrorCode;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// 商品カテゴリーです
+///
+/// 税率の判定に使用します。前後の空白を除き、小文字に揃えて保持します
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ProductCategory(String);
+
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+pub enum ProductCategoryError {
+  #[error("product category is empty")]
+  CategoryEmpty
+}
+
+impl ErrorCode for ProductCategoryError {
+  fn code(&self) -> &'static str {
+    match self {
+      ProductCategoryError::CategoryEmpty => "product_category.empty",
+    }
+  }
+}
+
+impl ProductCategory {
+  pub fn new(value: &str) -> Result<Self, ProductCategoryError> {
+    let value = value.trim().to_lowercase();
+    if value.is_empty() { Err(ProductCategoryError::CategoryEmpty)? }
+    Ok(Self(value))
+  }
+
+  /// Getter
+  pub fn value(&self) -> &str { &self.0 }
+}
+
+impl FromStr for ProductCategory {
+  type Err = ProductCategoryError;
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Self::new(s)
+  }
+}
+
+impl Display for ProductCategory {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+impl Serialize for ProductCategory {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for ProductCategory {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    Self::from_str(&value).map_err(serde::de::Error::custom)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_product_category_new_success() {
+    let result = ProductCategory::new(" Food ");
+
+    // assert
+    assert_eq!("food", result.unwrap().value())
+  }
+
+  #[test]
+  fn test_product_category_new_failed() {
+    let result = ProductCategory::new("  ");
+
+    // assert
+    assert_eq!(Err(ProductCategoryError::CategoryEmpty), result)
+  }
+}
@@ -1,21 +1,83 @@
+use crate::error_code::ErrorCode;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 use thiserror::Error;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
 
+/// 商品名です
+///
+/// 前後の空白を除き、Unicode正規化(NFC)した値を保持します
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ProductName(String);
 
-#[derive(Debug, Error)]
+/// 商品名の最大文字数(書記素クラスタ数)です
+///
+/// 結合文字や異体字セレクタを含む文字も、見た目の1文字として数えます
+pub const MAX_PRODUCT_NAME_LEN: usize = 100;
+
+/// 商品名エラーのクラスです
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
 pub enum ProductNameError {
   #[error("product name is empty")]
-  NameEmpty
+  NameEmpty,
+
+  #[error("product name must be at most {max} characters: {actual}")]
+  NameTooLong { max: usize, actual: usize },
+
+  #[error("product name contains a forbidden character U+{:04X} at {position}", u32::from(*character))]
+  ForbiddenCharacter { character: char, position: usize },
+}
+
+impl ErrorCode for ProductNameError {
+  fn code(&self) -> &'static str {
+    match self {
+      ProductNameError::NameEmpty => "product_name.empty",
+      ProductNameError::NameTooLong { .. } => "product_name.too_long",
+      ProductNameError::ForbiddenCharacter { .. } => "product_name.forbidden_character",
+    }
+  }
 }
 
 impl ProductName {
+  /// コンストラクタです
+  ///
+  /// 前後の空白を除いてNFCに正規化した後、空文字・禁止文字・文字数を検証します
+  ///
+  /// # Arguments
+  /// * `value`: 商品名
+  ///
+  /// # Return
+  /// * `Result<ProductName, ProductNameError>`
   pub fn new(value: &str) -> Result<Self, ProductNameError> {
+    let value = value.trim().nfc().collect::<String>();
     if value.is_empty() { Err(ProductNameError::NameEmpty)? }
-    Ok(Self(value.to_string()))
+    if let Some((position, character)) = value.chars().enumerate().find(|(_, c)| is_forbidden(*c)) {
+      Err(ProductNameError::ForbiddenCharacter { character, position })?
+    }
+    let len = value.graphemes(true).count();
+    if len > MAX_PRODUCT_NAME_LEN {
+      Err(ProductNameError::NameTooLong { max: MAX_PRODUCT_NAME_LEN, actual: len })?
+    }
+    Ok(Self(value))
   }
+
+  /// Getter
+  pub fn value(&self) -> &str { &self.0 }
+}
+
+/// 商品名に使用できない文字かどうかを返します
+///
+/// 制御文字、ゼロ幅スペース、双方向テキストの制御文字、BOM、置換文字を禁止します。
+/// 絵文字の合成に使うゼロ幅接合子(U+200D)は許可します
+fn is_forbidden(c: char) -> bool {
+  c.is_control()
+    || matches!(
+      c,
+      '\u{200B}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2060}'..='\u{2064}'
+        | '\u{2066}'..='\u{2069}' | '\u{FEFF}' | '\u{FFFD}'
+    )
 }
 
 impl FromStr for ProductName {
@@ -29,4 +91,89 @@ impl Display for ProductName {
   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
     write!(f, "{}", self.0)
   }
-}
\ No newline at end of file
+}
+
+impl Serialize for ProductName {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for ProductName {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    Self::from_str(&value).map_err(serde::de::Error::custom)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use proptest::prelude::*;
+  use rstest::rstest;
+
+  #[rstest]
+  #[case("  りんごジュース  ", "りんごジュース")]
+  #[case("\u{3000}緑茶\t", "緑茶")]
+  #[case("カ\u{3099}ム", "ガム")]
+  #[case("Cafe\u{301}", "Café")]
+  #[case("👨\u{200D}👩\u{200D}👧 セット", "👨\u{200D}👩\u{200D}👧 セット")]
+  fn test_product_name_new_normalizes(#[case] value: &str, #[case] expected: &str) {
+    let result = ProductName::new(value);
+
+    // assert
+    assert_eq!(expected, result.unwrap().value());
+  }
+
+  #[rstest]
+  #[case("", ProductNameError::NameEmpty)]
+  #[case(" \u{3000}\n", ProductNameError::NameEmpty)]
+  #[case("お\u{0}茶", ProductNameError::ForbiddenCharacter { character: '\u{0}', position: 1 })]
+  #[case("緑\u{200B}茶", ProductNameError::ForbiddenCharacter { character: '\u{200B}', position: 1 })]
+  #[case("\u{202E}abc", ProductNameError::ForbiddenCharacter { character: '\u{202E}', position: 0 })]
+  #[case("ab\ncd", ProductNameError::ForbiddenCharacter { character: '\n', position: 2 })]
+  fn test_product_name_new_failed(#[case] value: &str, #[case] expected: ProductNameError) {
+    let result = ProductName::new(value);
+
+    // assert
+    assert_eq!(Err(expected), result);
+  }
+
+  #[test]
+  fn test_product_name_length_is_counted_in_graphemes() {
+    let combining = "ガ".repeat(MAX_PRODUCT_NAME_LEN).nfd().collect::<String>();
+    let too_long = "あ".repeat(MAX_PRODUCT_NAME_LEN + 1);
+
+    // assert
+    assert_eq!(MAX_PRODUCT_NAME_LEN * 2, combining.chars().count());
+    assert!(ProductName::new(&combining).is_ok());
+    assert_eq!(
+      Err(ProductNameError::NameTooLong { max: MAX_PRODUCT_NAME_LEN, actual: MAX_PRODUCT_NAME_LEN + 1 }),
+      ProductName::new(&too_long),
+    );
+  }
+
+  #[test]
+  fn test_product_name_serde() {
+    let name = ProductName::new(" hogehoge ").unwrap();
+
+    let json = serde_json::to_string(&name).unwrap();
+
+    // assert
+    assert_eq!("\"hogehoge\"", json);
+    assert_eq!(name, serde_json::from_str::<ProductName>(&json).unwrap());
+    assert!(serde_json::from_str::<ProductName>("\"  \"").is_err());
+    assert!(serde_json::from_str::<ProductName>("\"hoge\\u200Bhoge\"").is_err());
+  }
+
+  proptest! {
+    #[test]
+    fn prop_product_name_new_is_idempotent(value in "\\PC{1,120}") {
+      if let Ok(name) = ProductName::new(&value) {
+        prop_assert_eq!(&name, &ProductName::new(name.value()).unwrap());
+        prop_assert!(name.value().graphemes(true).count() <= MAX_PRODUCT_NAME_LEN);
+        prop_assert!(!name.value().chars().any(is_forbidden));
+      }
+    }
+  }
+}
@@ -0,0 +1,136 @@
+use crate::order::order_item::OrderItem;
+use crate::value_object::quantity::{Quantity, QuantityError};
+use std::collections::{BTreeMap, HashMap};
+
+/// 商品ごとの注文数量の上限です
+///
+/// 商品ごとの上限がない場合は`default_max`を使用し、どちらもない場合は上限なしとします
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProductQuantityLimits {
+  default_max: Option<Quantity>,
+  by_product: HashMap<i32, Quantity>,
+}
+
+impl ProductQuantityLimits {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `default_max`: 商品ごとの上限がない商品に適用する上限
+  ///
+  /// # Return
+  /// * `ProductQuantityLimits`
+  pub fn new(default_max: Option<Quantity>) -> Self {
+    Self { default_max, by_product: HashMap::new() }
+  }
+
+  /// 商品の上限を設定します
+  ///
+  /// # Arguments
+  /// * `product_id`: 商品ID
+  /// * `max`: 1回の注文で購入できる数量の上限
+  ///
+  /// # Return
+  /// * `ProductQuantityLimits`
+  pub fn with_limit(mut self, product_id: i32, max: Quantity) -> Self {
+    self.by_product.insert(product_id, max);
+    self
+  }
+
+  /// 商品に適用する上限を返します
+  pub fn max_for(&self, product_id: i32) -> Option<&Quantity> {
+    self.by_product.get(&product_id).or(self.default_max.as_ref())
+  }
+
+  /// 注文アイテムの数量が上限以内であることを検証します
+  ///
+  /// 同じ商品が複数の明細にある場合は、数量を合算して検証します
+  ///
+  /// # Arguments
+  /// * `order_items`: 注文アイテム
+  ///
+  /// # Return
+  /// * `Result<(), QuantityError>`
+  pub fn validate(&self, order_items: &[OrderItem]) -> Result<(), QuantityError> {
+    let mut quantities: BTreeMap<i32, Quantity> = BTreeMap::new();
+    for item in order_items {
+      let quantity = match quantities.get(&item.get_product_id()) {
+        Some(total) => total.add(item.get_quantity())?,
+        None => Quantity::try_from(item.get_quantity())?,
+      };
+      quantities.insert(item.get_product_id(), quantity);
+    }
+    for (product_id, quantity) in quantities {
+      if let Some(max) = self.max_for(product_id) {
+        if quantity.value() > max.value() {
+          Err(QuantityError::ExceedsMaximum { product_id, value: quantity.value(), max: max.value() })?
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::id_generator::UuidV4Generator;
+  use crate::order::order_item::OrderItemParams;
+  use crate::order::order_item_id::OrderItemId;
+  use crate::value_object::discount::Discount;
+  use proptest::prelude::*;
+  use rust_decimal::Decimal;
+
+  fn item(product_id: i32, quantity: i32) -> OrderItem {
+    OrderItem::place_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id,
+      product_name: "hogehoge",
+      product_category: "general",
+      unit_price: Decimal::from(100),
+      currency: "JPY",
+      discount: Discount::none(),
+      quantity,
+    }).unwrap()
+  }
+
+  fn limits() -> ProductQuantityLimits {
+    ProductQuantityLimits::new(Some(Quantity::try_from(10).unwrap())).with_limit(1, Quantity::try_from(3).unwrap())
+  }
+
+  #[test]
+  fn test_product_quantity_limits_validate_success() {
+    // assert
+    assert_eq!(Ok(()), limits().validate(&[item(1, 2), item(1, 1), item(2, 10)]));
+    assert_eq!(Ok(()), ProductQuantityLimits::default().validate(&[item(1, i32::MAX)]));
+  }
+
+  #[test]
+  fn test_product_quantity_limits_validate_failed() {
+    // assert
+    assert_eq!(
+      Err(QuantityError::ExceedsMaximum { product_id: 1, value: 4, max: 3 }),
+      limits().validate(&[item(1, 2), item(2, 1), item(1, 2)])
+    );
+    assert_eq!(
+      Err(QuantityError::ExceedsMaximum { product_id: 2, value: 11, max: 10 }),
+      limits().validate(&[item(2, 11)])
+    );
+    assert_eq!(
+      Err(QuantityError::Overflow(i32::MAX, 1)),
+      ProductQuantityLimits::default().validate(&[item(1, i32::MAX), item(1, 1)])
+    );
+  }
+
+  proptest! {
+    #[test]
+    fn prop_product_quantity_limits_validate_matches_total(
+      max in 1i32..100,
+      quantities in prop::collection::vec(1i32..50, 1..5),
+    ) {
+      let limits = ProductQuantityLimits::default().with_limit(1, Quantity::try_from(max).unwrap());
+      let items = quantities.iter().map(|quantity| item(1, *quantity)).collect::<Vec<OrderItem>>();
+
+      prop_assert_eq!(quantities.iter().sum::<i32>() <= max, limits.validate(&items).is_ok());
+    }
+  }
+}
@@ -0,0 +1,424 @@
+pub mod promotion_error;
+pub mod promotion_event;
+pub mod promotion_id;
+pub mod promotion_repository;
+pub mod promotion_terms;
+
+use crate::clock::Clock;
+use crate::customer::customer_id::CustomerId;
+use crate::order::order_discount::OrderDiscount;
+use crate::order::order_id::OrderId;
+use crate::order::order_item::OrderItem;
+use crate::promotion::promotion_error::PromotionError;
+use crate::promotion::promotion_event::{PromotionCreated, PromotionEvent, PromotionRedeemed, RedemptionCancelled};
+use crate::promotion::promotion_id::PromotionId;
+use crate::promotion::promotion_terms::{PromotionScope, PromotionTerms};
+use crate::value_object::coupon_code::CouponCode;
+use std::collections::HashMap;
+
+/// クーポンによるプロモーションの集約です
+///
+/// 使用回数は注文の確定と同時に`redeem`で記録し、
+/// リポジトリのバージョンで同時実行時の使用回数の超過を防ぎます
+#[derive(Debug, Clone, PartialEq)]
+pub struct Promotion {
+  /// プロモーションID
+  id: PromotionId,
+
+  /// プロモーションの条件
+  terms: PromotionTerms,
+
+  /// 全体の使用回数
+  redemption_count: u32,
+
+  /// 顧客ごとの使用回数
+  redemptions_by_customer: HashMap<CustomerId, u32>,
+}
+
+impl Promotion {
+  /// プロモーションを作成します
+  ///
+  /// # Arguments
+  /// * `id`: PromotionId
+  /// * `clock`: 作成日時の取得元
+  /// * `terms`: プロモーションの条件
+  ///
+  /// # Return
+  /// * `Result<(Promotion, Vec<PromotionEvent>), PromotionError>`
+  pub fn create(
+    id: PromotionId,
+    clock: &dyn Clock,
+    terms: PromotionTerms,
+  ) -> Result<(Self, Vec<PromotionEvent>), PromotionError> {
+    if let Some(valid_until) = terms.valid_until {
+      if valid_until <= terms.valid_from {
+        Err(PromotionError::InvalidPeriod { valid_from: terms.valid_from, valid_until })?
+      }
+    }
+    if terms.usage_limit == Some(0) || terms.per_customer_limit == Some(0) {
+      Err(PromotionError::InvalidUsageLimit)?
+    }
+
+    let event = PromotionEvent::PromotionCreated(PromotionCreated {
+      promotion_id: id.clone(),
+      occurred_at: clock.now(),
+      coupon_code: terms.coupon_code.clone(),
+    });
+    let promotion = Self { id, terms, redemption_count: 0, redemptions_by_customer: HashMap::new() };
+    Ok((promotion, vec![event]))
+  }
+
+  /// クーポンの使用を記録します
+  ///
+  /// 有効期間、全体の使用回数の上限、顧客ごとの使用回数の上限を検証します
+  ///
+  /// # Arguments
+  /// * `order_id`: クーポンを使用した注文のID
+  /// * `customer_id`: 注文した顧客のID
+  /// * `clock`: 使用日時の取得元
+  ///
+  /// # Return
+  /// * `Result<PromotionEvent, PromotionError>`
+  pub fn redeem(
+    &mut self,
+    order_id: &OrderId,
+    customer_id: &CustomerId,
+    clock: &dyn Clock,
+  ) -> Result<PromotionEvent, PromotionError> {
+    let now = clock.now();
+    let coupon_code = self.terms.coupon_code.to_string();
+    if now < self.terms.valid_from {
+      Err(PromotionError::NotStarted(coupon_code.clone()))?
+    }
+    if self.terms.valid_until.is_some_and(|valid_until| valid_until <= now) {
+      Err(PromotionError::Expired(coupon_code.clone()))?
+    }
+    if self.terms.usage_limit.is_some_and(|limit| self.redemption_count >= limit) {
+      Err(PromotionError::UsageLimitReached(coupon_code.clone()))?
+    }
+    if let Some(limit) = self.terms.per_customer_limit {
+      if self.get_customer_redemption_count(customer_id) >= limit {
+        Err(PromotionError::CustomerLimitReached { coupon_code, customer_id: customer_id.to_string() })?
+      }
+    }
+
+    self.redemption_count += 1;
+    *self.redemptions_by_customer.entry(customer_id.clone()).or_insert(0) += 1;
+    Ok(PromotionEvent::PromotionRedeemed(PromotionRedeemed {
+      promotion_id: self.id.clone(),
+      occurred_at: now,
+      coupon_code: self.terms.coupon_code.clone(),
+      order_id: order_id.clone(),
+      customer_id: customer_id.clone(),
+      redemption_count: self.redemption_count,
+    }))
+  }
+
+  /// クーポンの使用を取り消します
+  ///
+  /// 注文の確定に失敗した場合の補償に使用し、`redeem`で増やした使用回数を戻します
+  ///
+  /// # Arguments
+  /// * `order_id`: クーポンを使用した注文のID
+  /// * `customer_id`: 注文した顧客のID
+  /// * `clock`: 取り消し日時の取得元
+  ///
+  /// # Return
+  /// * `Result<PromotionEvent, PromotionError>`
+  pub fn cancel_redemption(
+    &mut self,
+    order_id: &OrderId,
+    customer_id: &CustomerId,
+    clock: &dyn Clock,
+  ) -> Result<PromotionEvent, PromotionError> {
+    let Some(count) = self.redemptions_by_customer.get_mut(customer_id).filter(|count| **count > 0) else {
+      Err(PromotionError::RedemptionNotFound {
+        coupon_code: self.terms.coupon_code.to_string(),
+        customer_id: customer_id.to_string(),
+      })?
+    };
+    *count -= 1;
+    if *count == 0 {
+      self.redemptions_by_customer.remove(customer_id);
+    }
+    self.redemption_count -= 1;
+    Ok(PromotionEvent::RedemptionCancelled(RedemptionCancelled {
+      promotion_id: self.id.clone(),
+      occurred_at: clock.now(),
+      coupon_code: self.terms.coupon_code.clone(),
+      order_id: order_id.clone(),
+      customer_id: customer_id.clone(),
+      redemption_count: self.redemption_count,
+    }))
+  }
+
+  /// プロモーションの割引を注文の明細に適用します
+  ///
+  /// 適用範囲が注文全体の場合は注文割引を返し、
+  /// カテゴリーの場合は該当する明細に明細割引を適用します
+  ///
+  /// # Arguments
+  /// * `order_items`: 注文の明細
+  ///
+  /// # Return
+  /// * `Result<(Vec<OrderItem>, Option<OrderDiscount>), PromotionError>`
+  pub fn apply(&self, order_items: Vec<OrderItem>) -> Result<(Vec<OrderItem>, Option<OrderDiscount>), PromotionError> {
+    match &self.terms.scope {
+      PromotionScope::Order => {
+        let order_discount = OrderDiscount::new(
+          self.terms.discount.clone(),
+          Some(self.terms.coupon_code.clone()),
+          self.terms.stackable,
+        );
+        Ok((order_items, Some(order_discount)))
+      }
+      PromotionScope::Category(category) => {
+        if !order_items.iter().any(|item| item.get_product_category() == category) {
+          Err(PromotionError::NotApplicable(self.terms.coupon_code.to_string()))?
+        }
+        let order_items = order_items
+          .into_iter()
+          .map(|item| match item.get_product_category() == category {
+            true => item.apply_discount(self.terms.discount.clone()),
+            false => Ok(item),
+          })
+          .collect::<Result<Vec<OrderItem>, _>>()?;
+        Ok((order_items, None))
+      }
+    }
+  }
+
+  /// プロモーションIDのゲッター
+  pub fn get_id(&self) -> &PromotionId { &self.id }
+
+  /// クーポンコードのゲッター
+  pub fn get_coupon_code(&self) -> &CouponCode { &self.terms.coupon_code }
+
+  /// プロモーションの条件のゲッター
+  pub fn get_terms(&self) -> &PromotionTerms { &self.terms }
+
+  /// 全体の使用回数のゲッター
+  pub fn get_redemption_count(&self) -> u32 { self.redemption_count }
+
+  /// 顧客ごとの使用回数を返します
+  pub fn get_customer_redemption_count(&self, customer_id: &CustomerId) -> u32 {
+    self.redemptions_by_customer.get(customer_id).copied().unwrap_or(0)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::clock::FixedClock;
+  use crate::id_generator::UuidV4Generator;
+  use crate::order::order_item::OrderItemParams;
+  use crate::order::order_item_id::OrderItemId;
+  use crate::product::product_category::ProductCategory;
+  use crate::value_object::discount::{Discount, DiscountError};
+  use chrono::{DateTime, Duration, TimeZone, Utc};
+  use rust_decimal::Decimal;
+
+  fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()
+  }
+
+  fn terms(scope: PromotionScope) -> PromotionTerms {
+    PromotionTerms {
+      coupon_code: CouponCode::new("AUTUMN").unwrap(),
+      discount: Discount::try_from(10).unwrap(),
+      scope,
+      stackable: false,
+      valid_from: now(),
+      valid_until: Some(now() + Duration::days(30)),
+      usage_limit: Some(2),
+      per_customer_limit: Some(1),
+    }
+  }
+
+  fn promotion(scope: PromotionScope) -> Promotion {
+    Promotion::create(PromotionId::generate(&UuidV4Generator), &FixedClock::new(now()), terms(scope)).unwrap().0
+  }
+
+  fn item(category: &str, discount: Discount) -> OrderItem {
+    OrderItem::place_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id: 1,
+      product_name: "hogehoge",
+      product_category: category,
+      unit_price: Decimal::from(1000),
+      currency: "JPY",
+      discount,
+      quantity: 1,
+    }).unwrap()
+  }
+
+  #[test]
+  fn test_promotion_create_success() {
+    let (promotion, events) = Promotion::create(
+      PromotionId::generate(&UuidV4Generator),
+      &FixedClock::new(now()),
+      terms(PromotionScope::Order),
+    ).unwrap();
+
+    // assert
+    assert_eq!(0, promotion.get_redemption_count());
+    assert!(matches!(&events[0], PromotionEvent::PromotionCreated(created) if &created.promotion_id == promotion.get_id()));
+  }
+
+  #[test]
+  fn test_promotion_create_failed() {
+    let mut invalid_period = terms(PromotionScope::Order);
+    invalid_period.valid_until = Some(now());
+    let mut invalid_limit = terms(PromotionScope::Order);
+    invalid_limit.usage_limit = Some(0);
+
+    // assert
+    assert!(matches!(
+      Promotion::create(PromotionId::generate(&UuidV4Generator), &FixedClock::new(now()), invalid_period),
+      Err(PromotionError::InvalidPeriod { .. })
+    ));
+    assert!(matches!(
+      Promotion::create(PromotionId::generate(&UuidV4Generator), &FixedClock::new(now()), invalid_limit),
+      Err(PromotionError::InvalidUsageLimit)
+    ));
+  }
+
+  #[test]
+  fn test_promotion_redeem_success() {
+    let mut promotion = promotion(PromotionScope::Order);
+    let order_id = OrderId::generate(&UuidV4Generator);
+    let customer_id = CustomerId::generate(&UuidV4Generator);
+
+    let event = promotion.redeem(&order_id, &customer_id, &FixedClock::new(now())).unwrap();
+
+    // assert
+    assert_eq!(1, promotion.get_redemption_count());
+    assert_eq!(1, promotion.get_customer_redemption_count(&customer_id));
+    assert!(matches!(
+      event,
+      PromotionEvent::PromotionRedeemed(redeemed) if redeemed.order_id == order_id && redeemed.redemption_count == 1
+    ));
+  }
+
+  #[test]
+  fn test_promotion_cancel_redemption() {
+    let mut promotion = promotion(PromotionScope::Order);
+    let clock = FixedClock::new(now());
+    let order_id = OrderId::generate(&UuidV4Generator);
+    let customer_id = CustomerId::generate(&UuidV4Generator);
+    promotion.redeem(&order_id, &customer_id, &clock).unwrap();
+
+    let event = promotion.cancel_redemption(&order_id, &customer_id, &clock).unwrap();
+    let again = promotion.cancel_redemption(&order_id, &customer_id, &clock);
+
+    // assert
+    assert_eq!(0, promotion.get_redemption_count());
+    assert_eq!(0, promotion.get_customer_redemption_count(&customer_id));
+    assert!(matches!(
+      event,
+      PromotionEvent::RedemptionCancelled(cancelled) if cancelled.order_id == order_id && cancelled.redemption_count == 0
+    ));
+    assert!(matches!(again, Err(PromotionError::RedemptionNotFound { .. })));
+    assert!(promotion.redeem(&order_id, &customer_id, &clock).is_ok());
+  }
+
+  #[test]
+  fn test_promotion_redeem_limit_failed() {
+    let mut promotion = promotion(PromotionScope::Order);
+    let clock = FixedClock::new(now());
+    let customer_id = CustomerId::generate(&UuidV4Generator);
+    promotion.redeem(&OrderId::generate(&UuidV4Generator), &customer_id, &clock).unwrap();
+
+    // assert
+    assert!(matches!(
+      promotion.redeem(&OrderId::generate(&UuidV4Generator), &customer_id, &clock),
+      Err(PromotionError::CustomerLimitReached { .. })
+    ));
+    promotion.redeem(&OrderId::generate(&UuidV4Generator), &CustomerId::generate(&UuidV4Generator), &clock).unwrap();
+    assert!(matches!(
+      promotion.redeem(&OrderId::generate(&UuidV4Generator), &CustomerId::generate(&UuidV4Generator), &clock),
+      Err(PromotionError::UsageLimitReached(_))
+    ));
+    assert_eq!(2, promotion.get_redemption_count());
+  }
+
+  #[test]
+  fn test_promotion_redeem_period_failed() {
+    let mut promotion = promotion(PromotionScope::Order);
+
+    // assert
+    assert!(matches!(
+      promotion.redeem(&OrderId::generate(&UuidV4Generator), &CustomerId::generate(&UuidV4Generator), &FixedClock::new(now() - Duration::seconds(1))),
+      Err(PromotionError::NotStarted(_))
+    ));
+    assert!(matches!(
+      promotion.redeem(&OrderId::generate(&UuidV4Generator), &CustomerId::generate(&UuidV4Generator), &FixedClock::new(now() + Duration::days(30))),
+      Err(PromotionError::Expired(_))
+    ));
+    assert_eq!(0, promotion.get_redemption_count());
+  }
+
+  #[test]
+  fn test_promotion_apply_order_scope_success() {
+    let promotion = promotion(PromotionScope::Order);
+
+    let (items, order_discount) = promotion.apply(vec![item("general", Discount::none())]).unwrap();
+
+    // assert
+    assert!(items[0].get_discount().is_zero());
+    let order_discount = order_discount.unwrap();
+    assert_eq!(Some(&CouponCode::new("AUTUMN").unwrap()), order_discount.get_coupon_code());
+  }
+
+  #[test]
+  fn test_promotion_apply_category_scope_success() {
+    let promotion = promotion(PromotionScope::Category(ProductCategory::new("food").unwrap()));
+
+    let (items, order_discount) = promotion.apply(vec![
+      item("food", Discount::none()),
+      item("general", Discount::none()),
+    ]).unwrap();
+
+    // assert
+    assert!(order_discount.is_none());
+    assert_eq!(&Decimal::from(100), items[0].get_discount_amount().amount());
+    assert!(items[1].get_discount().is_zero());
+  }
+
+  #[test]
+  fn test_promotion_apply_category_scope_failed() {
+    let promotion = promotion(PromotionScope::Category(ProductCategory::new("food").unwrap()));
+
+    // assert
+    assert!(matches!(
+      promotion.apply(vec![item("general", Discount::none())]),
+      Err(PromotionError::NotApplicable(_))
+    ));
+    assert_eq!(
+      Err(PromotionError::InvalidDiscount(DiscountError::NotStackable)),
+      promotion.apply(vec![item("food", Discount::try_from(5).unwrap())]).map(|_| ())
+    );
+  }
+
+  #[test]
+  fn test_promotion_events_serde() {
+    let clock = FixedClock::new(now());
+    let order_id = OrderId::generate(&UuidV4Generator);
+    let customer_id = CustomerId::generate(&UuidV4Generator);
+    let (mut promotion, mut events) = Promotion::create(
+      PromotionId::generate(&UuidV4Generator), &clock, terms(PromotionScope::Category(ProductCategory::new("food").unwrap())),
+    ).unwrap();
+    events.push(promotion.redeem(&order_id, &customer_id, &clock).unwrap());
+    events.push(promotion.cancel_redemption(&order_id, &customer_id, &clock).unwrap());
+
+    let json = events.iter().map(|event| serde_json::to_value(event).unwrap()).collect::<Vec<_>>();
+    let deserialized = json.iter().map(|value| serde_json::from_value::<PromotionEvent>(value.clone()).unwrap()).collect::<Vec<_>>();
+
+    // assert
+    assert_eq!(
+      vec!["PromotionCreated", "PromotionRedeemed", "RedemptionCancelled"],
+      json.iter().map(|value| value["type"].as_str().unwrap()).collect::<Vec<_>>(),
+    );
+    assert_eq!(events, deserialized);
+  }
+}
@@ -0,0 +1,64 @@
+use crate::error_code::{ErrorCode, FieldPath};
+use crate::value_object::discount::DiscountError;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+/// プロモーションのエラーです
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+pub enum PromotionError {
+  #[error("promotion period is invalid: {valid_from} - {valid_until}")]
+  InvalidPeriod { valid_from: DateTime<Utc>, valid_until: DateTime<Utc> },
+
+  #[error("usage limit must be greater than 0")]
+  InvalidUsageLimit,
+
+  #[error("promotion {0} has not started yet")]
+  NotStarted(String),
+
+  #[error("promotion {0} has expired")]
+  Expired(String),
+
+  #[error("promotion {0} has reached its usage limit")]
+  UsageLimitReached(String),
+
+  #[error("customer {customer_id} has reached the usage limit of promotion {coupon_code}")]
+  CustomerLimitReached { coupon_code: String, customer_id: String },
+
+  #[error("promotion {0} is not applicable to the order")]
+  NotApplicable(String),
+
+  #[error("coupon {0} is not found")]
+  CouponNotFound(String),
+
+  #[error("customer {customer_id} has not redeemed promotion {coupon_code}")]
+  RedemptionNotFound { coupon_code: String, customer_id: String },
+
+  #[error("Invalid Discount: {0}")]
+  InvalidDiscount(#[from] DiscountError),
+}
+
+impl ErrorCode for PromotionError {
+  fn code(&self) -> &'static str {
+    match self {
+      PromotionError::InvalidPeriod { .. } => "promotion.invalid_period",
+      PromotionError::InvalidUsageLimit => "promotion.invalid_usage_limit",
+      PromotionError::NotStarted(_) => "promotion.not_started",
+      PromotionError::Expired(_) => "promotion.expired",
+      PromotionError::UsageLimitReached(_) => "promotion.usage_limit_reached",
+      PromotionError::CustomerLimitReached { .. } => "promotion.customer_limit_reached",
+      PromotionError::NotApplicable(_) => "promotion.not_applicable",
+      PromotionError::CouponNotFound(_) => "promotion.coupon_not_found",
+      PromotionError::RedemptionNotFound { .. } => "promotion.redemption_not_found",
+      PromotionError::InvalidDiscount(e) => e.code(),
+    }
+  }
+
+  fn field_path(&self) -> Option<FieldPath> {
+    match self {
+      PromotionError::InvalidPeriod { .. } => Some(FieldPath::new("valid_until")),
+      PromotionError::InvalidUsageLimit => Some(FieldPath::new("usage_limit")),
+      PromotionError::InvalidDiscount(e) => e.field_path(),
+      _ => Some(FieldPath::new("coupon_code")),
+    }
+  }
+}
@@ -0,0 +1,71 @@
+use crate::customer::customer_id::CustomerId;
+use crate::order::order_id::OrderId;
+use crate::promotion::promotion_id::PromotionId;
+use crate::value_object::coupon_code::CouponCode;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// プロモーションのイベントです
+///
+/// シリアライズすると、`type`にイベント名を持つJSONオブジェクトになります
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum PromotionEvent {
+  PromotionCreated(PromotionCreated),
+  PromotionRedeemed(PromotionRedeemed),
+  RedemptionCancelled(RedemptionCancelled),
+}
+
+impl PromotionEvent {
+  /// イベントが発生したプロモーションのIDを返します
+  pub fn promotion_id(&self) -> &PromotionId {
+    match self {
+      PromotionEvent::PromotionCreated(event) => &event.promotion_id,
+      PromotionEvent::PromotionRedeemed(event) => &event.promotion_id,
+      PromotionEvent::RedemptionCancelled(event) => &event.promotion_id,
+    }
+  }
+
+  /// イベントの発生日時を返します
+  pub fn occurred_at(&self) -> &DateTime<Utc> {
+    match self {
+      PromotionEvent::PromotionCreated(event) => &event.occurred_at,
+      PromotionEvent::PromotionRedeemed(event) => &event.occurred_at,
+      PromotionEvent::RedemptionCancelled(event) => &event.occurred_at,
+    }
+  }
+}
+
+/// プロモーションが作成されたイベントです
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromotionCreated {
+  pub promotion_id: PromotionId,
+  pub occurred_at: DateTime<Utc>,
+  pub coupon_code: CouponCode,
+}
+
+/// クーポンが注文で使用されたイベントです
+///
+/// redemption_countは使用後の累計使用回数です
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromotionRedeemed {
+  pub promotion_id: PromotionId,
+  pub occurred_at: DateTime<Utc>,
+  pub coupon_code: CouponCode,
+  pub order_id: OrderId,
+  pub customer_id: CustomerId,
+  pub redemption_count: u32,
+}
+
+/// 注文の確定に失敗したため、クーポンの使用が取り消されたイベントです
+///
+/// redemption_countは取り消し後の累計使用回数です
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RedemptionCancelled {
+  pub promotion_id: PromotionId,
+  pub occurred_at: DateTime<Utc>,
+  pub coupon_code: CouponCode,
+  pub order_id: OrderId,
+  pub customer_id: CustomerId,
+  pub redemption_count: u32,
+}
@@ -0,0 +1,13 @@
+use crate::aggregate_id::{IdPrefix, PrefixedId};
+
+/// プロモーションIDのプレフィックスです
+pub struct PromotionIdPrefix;
+
+impl IdPrefix for PromotionIdPrefix {
+  const PREFIX: &'static str = "PROMOTION";
+}
+
+/// プロモーションIDです
+///
+/// `Display`は`PROMOTION-<uuid>`形式、serdeはUUIDのみの形式で表現します
+pub type PromotionId = PrefixedId<PromotionIdPrefix>;
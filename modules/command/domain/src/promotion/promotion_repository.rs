@@ -0,0 +1,41 @@
+use crate::promotion::Promotion;
+use crate::repository::{RepositoryError, Versioned};
+use crate::value_object::coupon_code::CouponCode;
+
+/// プロモーションの永続化を行うリポジトリのトレイトです
+///
+/// 同時に行われた注文でクーポンが使用回数を超えて使用されないよう、
+/// 更新時はバージョンによる楽観的排他制御を行います
+pub trait PromotionRepository: Send + Sync {
+  /// クーポンコードに対応するプロモーションを取得します
+  ///
+  /// # Arguments
+  /// * `coupon_code`: &CouponCode
+  ///
+  /// # Return
+  /// * `Result<Option<Versioned<Promotion>>, RepositoryError>`
+  fn find_by_coupon_code(&self, coupon_code: &CouponCode) -> Result<Option<Versioned<Promotion>>, RepositoryError>;
+
+  /// 新しいプロモーションを保存します
+  ///
+  /// 同じIDまたはクーポンコードのプロモーションがある場合は`AlreadyExists`を返します
+  ///
+  /// # Arguments
+  /// * `promotion`: Promotion
+  ///
+  /// # Return
+  /// * `Result<(), RepositoryError>`
+  fn insert(&self, promotion: Promotion) -> Result<(), RepositoryError>;
+
+  /// プロモーションを更新します
+  ///
+  /// 保存済みのバージョンがexpected_versionと異なる場合は`VersionConflict`を返します
+  ///
+  /// # Arguments
+  /// * `promotion`: Promotion
+  /// * `expected_version`: 取得時のバージョン
+  ///
+  /// # Return
+  /// * `Result<(), RepositoryError>`
+  fn update(&self, promotion: Promotion, expected_version: u64) -> Result<(), RepositoryError>;
+}
@@ -0,0 +1,45 @@
+use crate::product::product_category::ProductCategory;
+use crate::value_object::coupon_code::CouponCode;
+use crate::value_object::discount::Discount;
+use chrono::{DateTime, Utc};
+
+/// プロモーションの割引を適用する範囲です
+///
+/// Order: 注文全体に対する注文割引として適用します
+///
+/// Category: 指定したカテゴリーの明細に明細割引として適用します
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum PromotionScope {
+  Order,
+  Category(ProductCategory),
+}
+
+/// プロモーションの条件です
+///
+/// valid_untilを含まない期間(valid_from <= 日時 < valid_until)で有効です
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromotionTerms {
+  /// クーポンコード
+  pub coupon_code: CouponCode,
+
+  /// 割引
+  pub discount: Discount,
+
+  /// 割引の適用範囲
+  pub scope: PromotionScope,
+
+  /// 他の注文割引と併用できる場合true
+  pub stackable: bool,
+
+  /// 有効期間の開始日時
+  pub valid_from: DateTime<Utc>,
+
+  /// 有効期間の終了日時(無期限の場合はNone)
+  pub valid_until: Option<DateTime<Utc>>,
+
+  /// 全体の使用回数の上限(無制限の場合はNone)
+  pub usage_limit: Option<u32>,
+
+  /// 顧客ごとの使用回数の上限(無制限の場合はNone)
+  pub per_customer_limit: Option<u32>,
+}
@@ -0,0 +1,36 @@
+use crate::error_code::ErrorCode;
+use thiserror::Error;
+
+/// 楽観的排他制御のためのバージョンを付与した集約です
+///
+/// aggregate: 集約
+///
+/// version: 保存時のバージョン(保存のたびに1ずつ増えます)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Versioned<T> {
+  pub aggregate: T,
+  pub version: u64,
+}
+
+/// リポジトリのエラーです
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+pub enum RepositoryError {
+  #[error("aggregate not found: {0}")]
+  NotFound(String),
+
+  #[error("aggregate already exists: {0}")]
+  AlreadyExists(String),
+
+  #[error("version conflict on {id}: expected {expected}, but got {actual}")]
+  VersionConflict { id: String, expected: u64, actual: u64 },
+}
+
+impl ErrorCode for RepositoryError {
+  fn code(&self) -> &'static str {
+    match self {
+      RepositoryError::NotFound(_) => "repository.not_found",
+      RepositoryError::AlreadyExists(_) => "repository.already_exists",
+      RepositoryError::VersionConflict { .. } => "repository.version_conflict",
+    }
+  }
+}
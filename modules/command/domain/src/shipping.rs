@@ -0,0 +1,6 @@
+pub mod country_code;
+pub mod delivery_method;
+pub mod postal_code;
+pub mod shipping_address;
+pub mod shipping_details;
+pub mod shipping_error;
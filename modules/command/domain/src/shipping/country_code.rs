@@ -0,0 +1,76 @@
+use crate::shipping::shipping_error::ShippingError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// 配送先の国コードです
+///
+/// ISO-3166-1 alpha-2の2文字の英大文字で、英小文字は大文字に変換します
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct CountryCode(String);
+
+impl CountryCode {
+  pub fn new(value: &str) -> Result<Self, ShippingError> {
+    let code = value.trim().to_ascii_uppercase();
+    if code.len() != 2 || !code.chars().all(|c| c.is_ascii_uppercase()) {
+      Err(ShippingError::InvalidCountryCode(value.to_string()))?
+    }
+    Ok(Self(code))
+  }
+
+  /// Getter
+  pub fn value(&self) -> &str { &self.0 }
+}
+
+impl FromStr for CountryCode {
+  type Err = ShippingError;
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Self::new(s)
+  }
+}
+
+impl Display for CountryCode {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+impl Serialize for CountryCode {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for CountryCode {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    Self::from_str(&value).map_err(serde::de::Error::custom)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rstest::rstest;
+
+  #[rstest]
+  #[case("JP", "JP")]
+  #[case(" us ", "US")]
+  fn test_country_code_new_success(#[case] value: &str, #[case] expected: &str) {
+    let result = CountryCode::new(value);
+
+    // assert
+    assert_eq!(expected, result.unwrap().value())
+  }
+
+  #[rstest]
+  #[case("")]
+  #[case("JPN")]
+  #[case("J1")]
+  fn test_country_code_new_failed(#[case] value: &str) {
+    let result = CountryCode::new(value);
+
+    // assert
+    assert_eq!(Err(ShippingError::InvalidCountryCode(value.to_string())), result)
+  }
+}
@@ -0,0 +1,81 @@
+use crate::shipping::shipping_error::ShippingError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// 配送方法です
+///
+/// 文字列では`standard`、`express`、`scheduled`で表します
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum DeliveryMethod {
+  /// 通常配送
+  Standard,
+  /// 速達
+  Express,
+  /// 日時指定
+  Scheduled,
+}
+
+impl DeliveryMethod {
+  /// 配送方法を表す文字列を返します
+  pub fn code(&self) -> &'static str {
+    match self {
+      DeliveryMethod::Standard => "standard",
+      DeliveryMethod::Express => "express",
+      DeliveryMethod::Scheduled => "scheduled",
+    }
+  }
+}
+
+impl FromStr for DeliveryMethod {
+  type Err = ShippingError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.trim().to_ascii_lowercase().as_str() {
+      "standard" => Ok(DeliveryMethod::Standard),
+      "express" => Ok(DeliveryMethod::Express),
+      "scheduled" => Ok(DeliveryMethod::Scheduled),
+      _ => Err(ShippingError::UnknownDeliveryMethod(s.to_string())),
+    }
+  }
+}
+
+impl Display for DeliveryMethod {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.code())
+  }
+}
+
+impl Serialize for DeliveryMethod {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for DeliveryMethod {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    Self::from_str(&value).map_err(serde::de::Error::custom)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_delivery_method_from_str_success() {
+    // assert
+    assert_eq!(Ok(DeliveryMethod::Express), DeliveryMethod::from_str(" Express "));
+    assert_eq!("scheduled", DeliveryMethod::Scheduled.to_string());
+  }
+
+  #[test]
+  fn test_delivery_method_from_str_failed() {
+    // assert
+    assert_eq!(
+      Err(ShippingError::UnknownDeliveryMethod("drone".to_string())),
+      DeliveryMethod::from_str("drone")
+    );
+  }
+}
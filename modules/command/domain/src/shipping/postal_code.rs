@@ -0,0 +1,131 @@
+use crate::shipping::country_code::CountryCode;
+use crate::shipping::shipping_error::ShippingError;
+use serde::{Serialize, Serializer};
+use std::fmt::{Display, Formatter};
+
+/// 郵便番号です
+///
+/// 国ごとの形式で検証し、表記を揃えて保持します。
+///
+/// - JP: `123-4567`(ハイフンなしの7桁も受け付けます)
+/// - US: `12345`または`12345-6789`
+/// - CA: `A1A 1A1`(空白なしも受け付けます)
+/// - その他: 英数字・空白・ハイフンからなる10文字以内
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PostalCode(String);
+
+/// 形式を定めていない国の郵便番号の最大文字数です
+const MAX_POSTAL_CODE_LEN: usize = 10;
+
+impl PostalCode {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `country`: 配送先の国コード
+  /// * `value`: 郵便番号
+  ///
+  /// # Return
+  /// * `Result<PostalCode, ShippingError>`
+  pub fn new(country: &CountryCode, value: &str) -> Result<Self, ShippingError> {
+    let trimmed = value.trim().to_ascii_uppercase();
+    let normalized = match country.value() {
+      "JP" => Self::normalize_jp(&trimmed),
+      "US" => Self::normalize_us(&trimmed),
+      "CA" => Self::normalize_ca(&trimmed),
+      _ => Self::normalize_other(&trimmed),
+    };
+    let Some(normalized) = normalized else {
+      Err(ShippingError::InvalidPostalCode { country: country.to_string(), value: value.to_string() })?
+    };
+    Ok(Self(normalized))
+  }
+
+  /// Getter
+  pub fn value(&self) -> &str { &self.0 }
+
+  fn normalize_jp(value: &str) -> Option<String> {
+    let digits = match value.split_once('-') {
+      Some((head, tail)) if head.len() == 3 && tail.len() == 4 => format!("{}{}", head, tail),
+      Some(_) => return None,
+      None => value.to_string(),
+    };
+    if digits.len() != 7 || !all_digits(&digits) {
+      return None;
+    }
+    Some(format!("{}-{}", &digits[..3], &digits[3..]))
+  }
+
+  fn normalize_us(value: &str) -> Option<String> {
+    let valid = match value.split_once('-') {
+      Some((zip, plus4)) => zip.len() == 5 && all_digits(zip) && plus4.len() == 4 && all_digits(plus4),
+      None => value.len() == 5 && all_digits(value),
+    };
+    valid.then(|| value.to_string())
+  }
+
+  fn normalize_ca(value: &str) -> Option<String> {
+    let compact = value.replacen(' ', "", 1);
+    let valid = compact.len() == 6 && compact.chars().enumerate().all(|(i, c)| match i % 2 {
+      0 => c.is_ascii_uppercase(),
+      _ => c.is_ascii_digit(),
+    });
+    valid.then(|| format!("{} {}", &compact[..3], &compact[3..]))
+  }
+
+  fn normalize_other(value: &str) -> Option<String> {
+    let valid_len = (1..=MAX_POSTAL_CODE_LEN).contains(&value.len());
+    let valid_chars = value.chars().all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-');
+    (valid_len && valid_chars).then(|| value.to_string())
+  }
+}
+
+fn all_digits(value: &str) -> bool {
+  value.chars().all(|c| c.is_ascii_digit())
+}
+
+impl Display for PostalCode {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+impl Serialize for PostalCode {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rstest::rstest;
+
+  #[rstest]
+  #[case("JP", "100-0001", "100-0001")]
+  #[case("JP", "1000001", "100-0001")]
+  #[case("US", "94105", "94105")]
+  #[case("US", "94105-1234", "94105-1234")]
+  #[case("CA", "k1a0b1", "K1A 0B1")]
+  #[case("GB", "sw1a 1aa", "SW1A 1AA")]
+  fn test_postal_code_new_success(#[case] country: &str, #[case] value: &str, #[case] expected: &str) {
+    let result = PostalCode::new(&CountryCode::new(country).unwrap(), value);
+
+    // assert
+    assert_eq!(expected, result.unwrap().value())
+  }
+
+  #[rstest]
+  #[case("JP", "10-00001")]
+  #[case("JP", "100-000A")]
+  #[case("US", "9410")]
+  #[case("US", "94105-12")]
+  #[case("CA", "K1A 0B")]
+  #[case("GB", "")]
+  #[case("GB", "SW1A_1AA")]
+  fn test_postal_code_new_failed(#[case] country: &str, #[case] value: &str) {
+    let result = PostalCode::new(&CountryCode::new(country).unwrap(), value);
+
+    // assert
+    assert!(matches!(result, Err(ShippingError::InvalidPostalCode { .. })))
+  }
+}
@@ -0,0 +1,210 @@
+use crate::shipping::country_code::CountryCode;
+use crate::shipping::postal_code::PostalCode;
+use crate::shipping::shipping_error::ShippingError;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// 配送先の住所です
+///
+/// 受取人・市区町村・番地は必須で、JP・US・CAでは都道府県/州も必須です。
+/// 各項目は前後の空白を除いて保持します
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct ShippingAddress {
+  /// 受取人
+  recipient: String,
+
+  /// 国コード
+  country: CountryCode,
+
+  /// 郵便番号
+  postal_code: PostalCode,
+
+  /// 都道府県・州
+  subdivision: Option<String>,
+
+  /// 市区町村
+  city: String,
+
+  /// 番地
+  line1: String,
+
+  /// 建物名・部屋番号
+  line2: Option<String>,
+}
+
+/// 住所の各項目の最大文字数です
+const MAX_ADDRESS_FIELD_LEN: usize = 100;
+
+/// 都道府県・州を必須とする国です
+const SUBDIVISION_REQUIRED_COUNTRIES: [&str; 3] = ["JP", "US", "CA"];
+
+impl ShippingAddress {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `recipient`: 受取人
+  /// * `country`: ISO-3166-1 alpha-2の国コード
+  /// * `postal_code`: 郵便番号
+  /// * `subdivision`: 都道府県・州
+  /// * `city`: 市区町村
+  /// * `line1`: 番地
+  /// * `line2`: 建物名・部屋番号
+  ///
+  /// # Return
+  /// * `Result<ShippingAddress, ShippingError>`
+  pub fn new(
+    recipient: &str,
+    country: &str,
+    postal_code: &str,
+    subdivision: Option<&str>,
+    city: &str,
+    line1: &str,
+    line2: Option<&str>,
+  ) -> Result<Self, ShippingError> {
+    let country = CountryCode::new(country)?;
+    let postal_code = PostalCode::new(&country, postal_code)?;
+    let subdivision = optional_field("subdivision", subdivision)?;
+    if subdivision.is_none() && SUBDIVISION_REQUIRED_COUNTRIES.contains(&country.value()) {
+      Err(ShippingError::MissingField("subdivision"))?
+    }
+    Ok(Self {
+      recipient: required_field("recipient", recipient)?,
+      country,
+      postal_code,
+      subdivision,
+      city: required_field("city", city)?,
+      line1: required_field("line1", line1)?,
+      line2: optional_field("line2", line2)?,
+    })
+  }
+
+  /// 受取人のゲッター
+  pub fn get_recipient(&self) -> &str { &self.recipient }
+
+  /// 国コードのゲッター
+  pub fn get_country(&self) -> &CountryCode { &self.country }
+
+  /// 郵便番号のゲッター
+  pub fn get_postal_code(&self) -> &PostalCode { &self.postal_code }
+
+  /// 都道府県・州のゲッター
+  pub fn get_subdivision(&self) -> Option<&str> { self.subdivision.as_deref() }
+
+  /// 市区町村のゲッター
+  pub fn get_city(&self) -> &str { &self.city }
+
+  /// 番地のゲッター
+  pub fn get_line1(&self) -> &str { &self.line1 }
+
+  /// 建物名・部屋番号のゲッター
+  pub fn get_line2(&self) -> Option<&str> { self.line2.as_deref() }
+}
+
+/// 必須項目を検証します
+fn required_field(field: &'static str, value: &str) -> Result<String, ShippingError> {
+  optional_field(field, Some(value))?.ok_or(ShippingError::MissingField(field))
+}
+
+/// 任意項目を検証します
+///
+/// 空白のみの場合は未指定として扱います
+fn optional_field(field: &'static str, value: Option<&str>) -> Result<Option<String>, ShippingError> {
+  let Some(value) = value.map(str::trim).filter(|value| !value.is_empty()) else {
+    return Ok(None);
+  };
+  let len = value.chars().count();
+  if len > MAX_ADDRESS_FIELD_LEN {
+    Err(ShippingError::FieldTooLong { field, max: MAX_ADDRESS_FIELD_LEN, actual: len })?
+  }
+  Ok(Some(value.to_string()))
+}
+
+/// 配送先の住所のデシリアライズ用の値です
+#[derive(Deserialize)]
+struct ShippingAddressValue {
+  recipient: String,
+  country: String,
+  postal_code: String,
+  subdivision: Option<String>,
+  city: String,
+  line1: String,
+  line2: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for ShippingAddress {
+  /// 郵便番号の形式を国ごとに検証するため、`ShippingAddress::new`で組み立てます
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let value = ShippingAddressValue::deserialize(deserializer)?;
+    Self::new(
+      &value.recipient,
+      &value.country,
+      &value.postal_code,
+      value.subdivision.as_deref(),
+      &value.city,
+      &value.line1,
+      value.line2.as_deref(),
+    )
+      .map_err(serde::de::Error::custom)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_shipping_address_new_success() {
+    let result = ShippingAddress::new(
+      " 山田 太郎 ", "jp", "1000001", Some("東京都"), "千代田区", "千代田1-1", Some(" "),
+    );
+
+    // assert
+    let address = result.unwrap();
+    assert_eq!("山田 太郎", address.get_recipient());
+    assert_eq!("JP", address.get_country().value());
+    assert_eq!("100-0001", address.get_postal_code().value());
+    assert_eq!(None, address.get_line2());
+  }
+
+  #[test]
+  fn test_shipping_address_new_without_subdivision_success() {
+    let result = ShippingAddress::new("John Smith", "GB", "SW1A 1AA", None, "London", "10 Downing Street", None);
+
+    // assert
+    assert!(result.is_ok())
+  }
+
+  #[test]
+  fn test_shipping_address_new_failed() {
+    // assert
+    assert_eq!(
+      Err(ShippingError::MissingField("subdivision")),
+      ShippingAddress::new("John Smith", "US", "94105", None, "San Francisco", "1 Market St", None)
+    );
+    assert_eq!(
+      Err(ShippingError::MissingField("recipient")),
+      ShippingAddress::new(" ", "JP", "100-0001", Some("東京都"), "千代田区", "千代田1-1", None)
+    );
+    assert!(matches!(
+      ShippingAddress::new("山田 太郎", "JP", "94105", Some("東京都"), "千代田区", "千代田1-1", None),
+      Err(ShippingError::InvalidPostalCode { .. })
+    ));
+    assert!(matches!(
+      ShippingAddress::new("山田 太郎", "JP", "100-0001", Some("東京都"), "千代田区", &"a".repeat(101), None),
+      Err(ShippingError::FieldTooLong { field: "line1", max: 100, actual: 101 })
+    ));
+  }
+
+  #[test]
+  fn test_shipping_address_serde() {
+    let address = ShippingAddress::new("山田 太郎", "JP", "1000001", Some("東京都"), "千代田区", "千代田1-1", None).unwrap();
+
+    let json = serde_json::to_value(&address).unwrap();
+
+    // assert
+    assert_eq!("100-0001", json["postal_code"]);
+    assert_eq!(address, serde_json::from_value::<ShippingAddress>(json.clone()).unwrap());
+    let mut invalid = json;
+    invalid["country"] = serde_json::json!("US");
+    assert!(serde_json::from_value::<ShippingAddress>(invalid).is_err());
+  }
+}
@@ -0,0 +1,14 @@
+use crate::shipping::delivery_method::DeliveryMethod;
+use crate::shipping::shipping_address::ShippingAddress;
+use serde::{Deserialize, Serialize};
+
+/// 注文の配送情報です
+///
+/// address: 配送先の住所
+///
+/// delivery_method: 配送方法
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ShippingDetails {
+  pub address: ShippingAddress,
+  pub delivery_method: DeliveryMethod,
+}
@@ -0,0 +1,45 @@
+use crate::error_code::{ErrorCode, FieldPath};
+use thiserror::Error;
+
+/// 配送先・配送方法のエラーです
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+pub enum ShippingError {
+  #[error("invalid country code: {0}")]
+  InvalidCountryCode(String),
+
+  #[error("invalid postal code for {country}: {value}")]
+  InvalidPostalCode { country: String, value: String },
+
+  #[error("{0} is required")]
+  MissingField(&'static str),
+
+  #[error("{field} must be at most {max} characters: actual={actual}")]
+  FieldTooLong { field: &'static str, max: usize, actual: usize },
+
+  #[error("unknown delivery method: {0}")]
+  UnknownDeliveryMethod(String),
+}
+
+impl ErrorCode for ShippingError {
+  fn code(&self) -> &'static str {
+    match self {
+      ShippingError::InvalidCountryCode(_) => "shipping.invalid_country_code",
+      ShippingError::InvalidPostalCode { .. } => "shipping.invalid_postal_code",
+      ShippingError::MissingField(_) => "shipping.missing_field",
+      ShippingError::FieldTooLong { .. } => "shipping.field_too_long",
+      ShippingError::UnknownDeliveryMethod(_) => "shipping.unknown_delivery_method",
+    }
+  }
+
+  /// 配送先の住所の入力からの相対パスを返します
+  ///
+  /// 配送方法のエラーは住所のフィールドではないためNoneを返します
+  fn field_path(&self) -> Option<FieldPath> {
+    match self {
+      ShippingError::InvalidCountryCode(_) => Some(FieldPath::new("country")),
+      ShippingError::InvalidPostalCode { .. } => Some(FieldPath::new("postal_code")),
+      ShippingError::MissingField(field) | ShippingError::FieldTooLong { field, .. } => Some(FieldPath::new(field)),
+      ShippingError::UnknownDeliveryMethod(_) => None,
+    }
+  }
+}
@@ -0,0 +1,3 @@
+pub mod region;
+pub mod tax_breakdown;
+pub mod tax_rule;
@@ -0,0 +1,132 @@
+use crate::error_code::ErrorCode;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// 税率を判定する地域です
+///
+/// ISO-3166-1の国コードと、任意でISO-3166-2の地域コードを持ちます。
+/// 文字列では`JP`や`US-CA`の形式で表します
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Region {
+  country: String,
+  subdivision: Option<String>,
+}
+
+/// 地域エラーのクラスです
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+pub enum RegionError {
+  #[error("invalid region: {0}")]
+  InvalidRegion(String),
+}
+
+impl ErrorCode for RegionError {
+  fn code(&self) -> &'static str {
+    match self {
+      RegionError::InvalidRegion(_) => "region.invalid",
+    }
+  }
+}
+
+impl Region {
+  /// 国コードのゲッター
+  pub fn country(&self) -> &str { &self.country }
+
+  /// 地域コードのゲッター
+  pub fn subdivision(&self) -> Option<&str> { self.subdivision.as_deref() }
+
+  /// `other`と同じ地域、または`other`を含む地域の場合trueを返します
+  ///
+  /// 例: `US`は`US-CA`を含みます
+  pub fn contains(&self, other: &Region) -> bool {
+    self.country == other.country
+      && (self.subdivision.is_none() || self.subdivision == other.subdivision)
+  }
+}
+
+impl FromStr for Region {
+  type Err = RegionError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let value = s.trim().to_ascii_uppercase();
+    let (country, subdivision) = match value.split_once('-') {
+      Some((country, subdivision)) => (country, Some(subdivision)),
+      None => (value.as_str(), None),
+    };
+    let valid_country = country.len() == 2 && country.chars().all(|c| c.is_ascii_uppercase());
+    let valid_subdivision = subdivision.is_none_or(|subdivision| {
+      (1..=3).contains(&subdivision.len()) && subdivision.chars().all(|c| c.is_ascii_alphanumeric())
+    });
+    if !valid_country || !valid_subdivision {
+      Err(RegionError::InvalidRegion(s.to_string()))?
+    }
+    Ok(Self {
+      country: country.to_string(),
+      subdivision: subdivision.map(str::to_string),
+    })
+  }
+}
+
+impl Display for Region {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match &self.subdivision {
+      Some(subdivision) => write!(f, "{}-{}", self.country, subdivision),
+      None => write!(f, "{}", self.country),
+    }
+  }
+}
+
+impl Serialize for Region {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for Region {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    Self::from_str(&value).map_err(serde::de::Error::custom)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rstest::rstest;
+
+  #[rstest]
+  #[case("JP", "JP")]
+  #[case("us-ca", "US-CA")]
+  #[case("JP-13", "JP-13")]
+  fn test_region_from_str_success(#[case] value: &str, #[case] expected: &str) {
+    let result = Region::from_str(value);
+
+    // assert
+    assert_eq!(expected, result.unwrap().to_string())
+  }
+
+  #[rstest]
+  #[case("")]
+  #[case("JPN")]
+  #[case("US-")]
+  #[case("US-CALI")]
+  fn test_region_from_str_failed(#[case] value: &str) {
+    let result = Region::from_str(value);
+
+    // assert
+    assert_eq!(Err(RegionError::InvalidRegion(value.to_string())), result)
+  }
+
+  #[test]
+  fn test_region_contains_success() {
+    let us = Region::from_str("US").unwrap();
+    let ca = Region::from_str("US-CA").unwrap();
+
+    // assert
+    assert!(us.contains(&ca));
+    assert!(ca.contains(&ca));
+    assert!(!ca.contains(&us));
+    assert!(!us.contains(&Region::from_str("JP").unwrap()));
+  }
+}
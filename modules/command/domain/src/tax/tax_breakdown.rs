@@ -0,0 +1,267 @@
+use crate::order::order_item_id::OrderItemId;
+use crate::product::product_category::ProductCategory;
+use crate::tax::region::Region;
+use crate::tax::tax_rule::{TaxInclusion, TaxRate, TaxRule, TaxTreatment};
+use crate::value_object::currency::Currency;
+use crate::value_object::money::{Money, MoneyError};
+use crate::value_object::rounding_policy::RoundingPolicy;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// 課税対象の明細です
+///
+/// amountは明細割引と按分した注文割引を差し引いた金額です
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaxableLine {
+  pub order_item_id: OrderItemId,
+  pub product_category: ProductCategory,
+  pub amount: Money,
+}
+
+/// 明細ごとの税額です
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineTax {
+  pub order_item_id: OrderItemId,
+  pub product_category: ProductCategory,
+  pub treatment: TaxTreatment,
+  pub taxable_amount: Money,
+  pub tax_amount: Money,
+}
+
+/// 税率・税込区分ごとの税額の集計です
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaxSummary {
+  pub rate: TaxRate,
+  pub inclusion: TaxInclusion,
+  pub taxable_amount: Money,
+  pub tax_amount: Money,
+}
+
+/// 注文の税額の内訳です
+///
+/// 税額は明細ごとに丸めてから合計します
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaxBreakdown {
+  lines: Vec<LineTax>,
+  summaries: Vec<TaxSummary>,
+  total_tax: Money,
+  exclusive_tax: Money,
+}
+
+impl TaxBreakdown {
+  /// 税額の内訳を計算します
+  ///
+  /// 税率はtax_ruleから判定し、該当するルールがない明細は非課税とします
+  ///
+  /// # Arguments
+  /// * `currency`: 注文の通貨
+  /// * `rounding_policy`: 税額の丸めポリシー
+  /// * `region`: 注文の地域
+  /// * `tax_rule`: 税率のルール
+  /// * `lines`: 課税対象の明細
+  ///
+  /// # Return
+  /// * `Result<TaxBreakdown, MoneyError>`
+  pub fn calculate(
+    currency: Currency,
+    rounding_policy: RoundingPolicy,
+    region: &Region,
+    tax_rule: &dyn TaxRule,
+    lines: &[TaxableLine],
+  ) -> Result<Self, MoneyError> {
+    let mut line_taxes = Vec::with_capacity(lines.len());
+    for line in lines {
+      let treatment = tax_rule
+        .resolve(&line.product_category, region)
+        .unwrap_or(TaxTreatment::tax_free());
+      let rate = treatment.rate().value() / Decimal::ONE_HUNDRED;
+      let tax_amount = match treatment.inclusion() {
+        TaxInclusion::Exclusive => rounding_policy.round(&line.amount.multiply(rate)?),
+        TaxInclusion::Inclusive => rounding_policy.round(&line.amount.multiply(rate / (Decimal::ONE + rate))?),
+      };
+      line_taxes.push(LineTax {
+        order_item_id: line.order_item_id.clone(),
+        product_category: line.product_category.clone(),
+        treatment,
+        taxable_amount: line.amount,
+        tax_amount,
+      });
+    }
+    Self::from_lines(currency, line_taxes)
+  }
+
+  /// 明細ごとの税額から集計と合計を計算します
+  ///
+  /// # Arguments
+  /// * `currency`: 注文の通貨
+  /// * `lines`: 明細ごとの税額
+  ///
+  /// # Return
+  /// * `Result<TaxBreakdown, MoneyError>`
+  fn from_lines(currency: Currency, lines: Vec<LineTax>) -> Result<Self, MoneyError> {
+    let mut summaries: Vec<TaxSummary> = Vec::new();
+    let mut total_tax = Money::zero(currency);
+    let mut exclusive_tax = Money::zero(currency);
+
+    for line in &lines {
+      let treatment = line.treatment;
+      total_tax = total_tax.add(&line.tax_amount)?;
+      if treatment.inclusion() == TaxInclusion::Exclusive {
+        exclusive_tax = exclusive_tax.add(&line.tax_amount)?;
+      }
+      match summaries
+        .iter_mut()
+        .find(|summary| summary.rate == treatment.rate() && summary.inclusion == treatment.inclusion()) {
+        Some(summary) => {
+          summary.taxable_amount = summary.taxable_amount.add(&line.taxable_amount)?;
+          summary.tax_amount = summary.tax_amount.add(&line.tax_amount)?;
+        }
+        None => summaries.push(TaxSummary {
+          rate: treatment.rate(),
+          inclusion: treatment.inclusion(),
+          taxable_amount: line.taxable_amount,
+          tax_amount: line.tax_amount,
+        }),
+      }
+    }
+
+    Ok(Self { lines, summaries, total_tax, exclusive_tax })
+  }
+
+  /// 明細ごとの税額のゲッター
+  pub fn lines(&self) -> &[LineTax] { &self.lines }
+
+  /// 税率ごとの集計のゲッター
+  pub fn summaries(&self) -> &[TaxSummary] { &self.summaries }
+
+  /// 税額合計(内税・外税)のゲッター
+  pub fn total_tax(&self) -> &Money { &self.total_tax }
+
+  /// 外税の税額合計のゲッター
+  ///
+  /// 税込の総額は、税抜の合計金額にこの金額を加算したものです
+  pub fn exclusive_tax(&self) -> &Money { &self.exclusive_tax }
+}
+
+/// 税額の内訳のデシリアライズ用の値です
+#[derive(Deserialize)]
+struct TaxBreakdownValue {
+  lines: Vec<LineTax>,
+  summaries: Vec<TaxSummary>,
+  total_tax: Money,
+  exclusive_tax: Money,
+}
+
+impl<'de> Deserialize<'de> for TaxBreakdown {
+  /// 明細から集計と合計を計算し直し、一致しない場合はエラーにします
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let value = TaxBreakdownValue::deserialize(deserializer)?;
+    let breakdown = Self::from_lines(value.total_tax.currency(), value.lines).map_err(serde::de::Error::custom)?;
+    if breakdown.summaries != value.summaries
+      || breakdown.total_tax != value.total_tax
+      || breakdown.exclusive_tax != value.exclusive_tax {
+      Err(serde::de::Error::custom("tax breakdown does not match its lines"))?
+    }
+    Ok(breakdown)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::id_generator::UuidV4Generator;
+  use crate::tax::tax_rule::{RegionalTaxRule, TaxRules};
+  use std::str::FromStr;
+  use std::sync::Arc;
+
+  fn jpy(value: i64) -> Money {
+    Money::new(Decimal::from(value), Currency::JPY).unwrap()
+  }
+
+  fn line(category: &str, amount: i64) -> TaxableLine {
+    TaxableLine {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_category: ProductCategory::new(category).unwrap(),
+      amount: jpy(amount),
+    }
+  }
+
+  fn rules() -> TaxRules {
+    let rate = |value: i64| TaxRate::try_from(Decimal::from(value)).unwrap();
+    TaxRules::default()
+      .with_rule(Arc::new(RegionalTaxRule::new(
+        Region::from_str("JP").unwrap(),
+        Some(ProductCategory::new("food").unwrap()),
+        TaxTreatment::new(rate(8), TaxInclusion::Exclusive),
+      )))
+      .with_rule(Arc::new(RegionalTaxRule::new(
+        Region::from_str("JP").unwrap(),
+        Some(ProductCategory::new("book").unwrap()),
+        TaxTreatment::new(rate(10), TaxInclusion::Inclusive),
+      )))
+      .with_rule(Arc::new(RegionalTaxRule::new(
+        Region::from_str("JP").unwrap(),
+        None,
+        TaxTreatment::new(rate(10), TaxInclusion::Exclusive),
+      )))
+  }
+
+  #[test]
+  fn test_tax_breakdown_calculate_success() {
+    let lines = vec![line("food", 1000), line("food", 505), line("general", 2000), line("book", 1100)];
+    let result = TaxBreakdown::calculate(
+      Currency::JPY,
+      Currency::JPY.default_rounding_policy(),
+      &Region::from_str("JP").unwrap(),
+      &rules(),
+      &lines,
+    ).unwrap();
+
+    // assert
+    // 食品: 80 + 40.4→40、一般: 200、書籍(内税): 1100×10/110 = 100
+    assert_eq!(jpy(80), result.lines()[0].tax_amount);
+    assert_eq!(jpy(40), result.lines()[1].tax_amount);
+    assert_eq!(jpy(200), result.lines()[2].tax_amount);
+    assert_eq!(jpy(100), result.lines()[3].tax_amount);
+    assert_eq!(&jpy(420), result.total_tax());
+    assert_eq!(&jpy(320), result.exclusive_tax());
+    assert_eq!(3, result.summaries().len());
+    assert_eq!(jpy(1505), result.summaries()[0].taxable_amount);
+    assert_eq!(jpy(120), result.summaries()[0].tax_amount);
+  }
+
+  #[test]
+  fn test_tax_breakdown_calculate_without_rule_is_tax_free() {
+    let result = TaxBreakdown::calculate(
+      Currency::JPY,
+      Currency::JPY.default_rounding_policy(),
+      &Region::from_str("US-CA").unwrap(),
+      &rules(),
+      &[line("food", 1000)],
+    ).unwrap();
+
+    // assert
+    assert_eq!(&jpy(0), result.total_tax());
+    assert_eq!(TaxTreatment::tax_free(), result.lines()[0].treatment);
+  }
+
+  #[test]
+  fn test_tax_breakdown_serde() {
+    let breakdown = TaxBreakdown::calculate(
+      Currency::JPY,
+      Currency::JPY.default_rounding_policy(),
+      &Region::from_str("JP").unwrap(),
+      &rules(),
+      &[line("food", 1000), line("book", 1100)],
+    ).unwrap();
+
+    let json = serde_json::to_value(&breakdown).unwrap();
+
+    // assert
+    assert_eq!("exclusive", json["lines"][0]["treatment"]["inclusion"]);
+    assert_eq!(breakdown, serde_json::from_value::<TaxBreakdown>(json.clone()).unwrap());
+    let mut invalid = json;
+    invalid["total_tax"]["amount"] = serde_json::json!("0");
+    assert!(serde_json::from_value::<TaxBreakdown>(invalid).is_err());
+  }
+}
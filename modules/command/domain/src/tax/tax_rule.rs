@@ -0,0 +1,231 @@
+use crate::error_code::ErrorCode;
+use crate::product::product_category::ProductCategory;
+use crate::tax::region::Region;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// 税込・税抜の区分です
+///
+/// Inclusive: 金額に税が含まれている(内税)
+///
+/// Exclusive: 金額に税を加算する(外税)
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxInclusion {
+  Inclusive,
+  Exclusive,
+}
+
+/// 税率(%)です
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct TaxRate(Decimal);
+
+/// 税エラーのクラスです
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+pub enum TaxError {
+  #[error("tax rate must be between 0 and 100: {0}")]
+  InvalidRate(Decimal),
+}
+
+impl ErrorCode for TaxError {
+  fn code(&self) -> &'static str {
+    match self {
+      TaxError::InvalidRate(_) => "tax.invalid_rate",
+    }
+  }
+}
+
+impl TryFrom<Decimal> for TaxRate {
+  type Error = TaxError;
+
+  /// 実質的なコンストラクタです
+  fn try_from(value: Decimal) -> Result<Self, Self::Error> {
+    if value < Decimal::ZERO || Decimal::ONE_HUNDRED < value {
+      Err(TaxError::InvalidRate(value))?
+    }
+    Ok(Self(value))
+  }
+}
+
+impl TaxRate {
+  /// 非課税(0%)を返します
+  pub fn zero() -> Self {
+    Self(Decimal::ZERO)
+  }
+
+  /// Getter
+  pub fn value(&self) -> &Decimal { &self.0 }
+}
+
+impl Display for TaxRate {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}%", self.0)
+  }
+}
+
+impl Serialize for TaxRate {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    Serialize::serialize(&self.0, serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for TaxRate {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let value = <Decimal as Deserialize>::deserialize(deserializer)?;
+    Self::try_from(value).map_err(serde::de::Error::custom)
+  }
+}
+
+/// 明細に適用する税率と税込・税抜の区分です
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct TaxTreatment {
+  rate: TaxRate,
+  inclusion: TaxInclusion,
+}
+
+impl TaxTreatment {
+  /// コンストラクタです
+  pub fn new(rate: TaxRate, inclusion: TaxInclusion) -> Self {
+    Self { rate, inclusion }
+  }
+
+  /// 非課税を返します
+  pub fn tax_free() -> Self {
+    Self::new(TaxRate::zero(), TaxInclusion::Exclusive)
+  }
+
+  /// 税率のゲッター
+  pub fn rate(&self) -> TaxRate { self.rate }
+
+  /// 税込・税抜区分のゲッター
+  pub fn inclusion(&self) -> TaxInclusion { self.inclusion }
+}
+
+/// 税率を判定するルールのトレイトです
+///
+/// 商品カテゴリーと地域から、適用する税率を返します。
+/// 該当しない場合はNoneを返します
+pub trait TaxRule: Debug + Send + Sync {
+  fn resolve(&self, category: &ProductCategory, region: &Region) -> Option<TaxTreatment>;
+}
+
+/// 地域(と任意で商品カテゴリー)で税率を判定するルールです
+///
+/// categoryがNoneの場合は、地域内のすべての商品カテゴリーに適用します
+#[derive(Debug, Clone)]
+pub struct RegionalTaxRule {
+  region: Region,
+  category: Option<ProductCategory>,
+  treatment: TaxTreatment,
+}
+
+impl RegionalTaxRule {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `region`: 対象の地域
+  /// * `category`: 対象の商品カテゴリー
+  /// * `treatment`: 適用する税率
+  ///
+  /// # Return
+  /// * `RegionalTaxRule`
+  pub fn new(region: Region, category: Option<ProductCategory>, treatment: TaxTreatment) -> Self {
+    Self { region, category, treatment }
+  }
+}
+
+impl TaxRule for RegionalTaxRule {
+  fn resolve(&self, category: &ProductCategory, region: &Region) -> Option<TaxTreatment> {
+    let category_matches = self.category.as_ref().is_none_or(|c| c == category);
+    (self.region.contains(region) && category_matches).then_some(self.treatment)
+  }
+}
+
+/// 複数のルールをまとめたルールです
+///
+/// 登録順に判定し、最初に該当したルールの税率を返します。
+/// そのため、より限定的なルールを先に登録します。ルールが空の場合は常に非課税です
+#[derive(Debug, Clone, Default)]
+pub struct TaxRules {
+  rules: Vec<Arc<dyn TaxRule>>,
+}
+
+impl TaxRules {
+  /// コンストラクタです
+  pub fn new(rules: Vec<Arc<dyn TaxRule>>) -> Self {
+    Self { rules }
+  }
+
+  /// ルールを末尾に追加します
+  pub fn with_rule(mut self, rule: Arc<dyn TaxRule>) -> Self {
+    self.rules.push(rule);
+    self
+  }
+}
+
+impl TaxRule for TaxRules {
+  fn resolve(&self, category: &ProductCategory, region: &Region) -> Option<TaxTreatment> {
+    self.rules.iter().find_map(|rule| rule.resolve(category, region))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::str::FromStr;
+
+  fn treatment(rate: i64, inclusion: TaxInclusion) -> TaxTreatment {
+    TaxTreatment::new(TaxRate::try_from(Decimal::from(rate)).unwrap(), inclusion)
+  }
+
+  fn rules() -> TaxRules {
+    TaxRules::default()
+      .with_rule(Arc::new(RegionalTaxRule::new(
+        Region::from_str("JP").unwrap(),
+        Some(ProductCategory::new("food").unwrap()),
+        treatment(8, TaxInclusion::Inclusive),
+      )))
+      .with_rule(Arc::new(RegionalTaxRule::new(
+        Region::from_str("JP").unwrap(),
+        None,
+        treatment(10, TaxInclusion::Inclusive),
+      )))
+      .with_rule(Arc::new(RegionalTaxRule::new(
+        Region::from_str("US-CA").unwrap(),
+        None,
+        treatment(7, TaxInclusion::Exclusive),
+      )))
+  }
+
+  #[test]
+  fn test_tax_rate_try_from_failed() {
+    // assert
+    assert!(TaxRate::try_from(Decimal::from(-1)).is_err());
+    assert!(TaxRate::try_from(Decimal::from(101)).is_err());
+  }
+
+  #[test]
+  fn test_tax_rules_resolve_success() {
+    let rules = rules();
+    let food = ProductCategory::new("food").unwrap();
+    let book = ProductCategory::new("book").unwrap();
+
+    // assert
+    assert_eq!(
+      Some(treatment(8, TaxInclusion::Inclusive)),
+      rules.resolve(&food, &Region::from_str("JP-13").unwrap())
+    );
+    assert_eq!(
+      Some(treatment(10, TaxInclusion::Inclusive)),
+      rules.resolve(&book, &Region::from_str("JP").unwrap())
+    );
+    assert_eq!(
+      Some(treatment(7, TaxInclusion::Exclusive)),
+      rules.resolve(&food, &Region::from_str("US-CA").unwrap())
+    );
+    assert_eq!(None, rules.resolve(&food, &Region::from_str("US-NY").unwrap()));
+  }
+}
@@ -0,0 +1,105 @@
+/// 検証に失敗した値のエラーです
+///
+/// 複数の値の検証で、最初のエラーで止めずにすべてのエラーを集約するために使います。
+/// 必ず1件以上のエラーを持ちます
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ValidationErrors<E> {
+  first: E,
+  rest: Vec<E>,
+}
+
+/// 検証した値です
+///
+/// 検証に失敗した場合は`ValidationErrors`を返します
+pub type Validated<T, E> = Result<T, ValidationErrors<E>>;
+
+impl<E> ValidationErrors<E> {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `first`: エラー
+  ///
+  /// # Return
+  /// * `ValidationErrors<E>`
+  pub fn new(first: E) -> Self {
+    Self { first, rest: vec![] }
+  }
+
+  /// 別の検証のエラーを後ろに追加します
+  ///
+  /// # Arguments
+  /// * `other`: 追加するエラー
+  ///
+  /// # Return
+  /// * `ValidationErrors<E>`
+  pub fn append(mut self, other: ValidationErrors<E>) -> Self {
+    self.rest.push(other.first);
+    self.rest.extend(other.rest);
+    self
+  }
+
+  /// エラーを変換します
+  ///
+  /// # Arguments
+  /// * `f`: エラーの変換
+  ///
+  /// # Return
+  /// * `ValidationErrors<F>`
+  pub fn map<F>(self, mut f: impl FnMut(E) -> F) -> ValidationErrors<F> {
+    ValidationErrors { first: f(self.first), rest: self.rest.into_iter().map(f).collect() }
+  }
+
+  /// 最初のエラーを返します
+  pub fn into_first(self) -> E {
+    self.first
+  }
+
+  /// すべてのエラーを発生した順に返します
+  pub fn into_vec(self) -> Vec<E> {
+    let mut errors = Vec::with_capacity(self.rest.len() + 1);
+    errors.push(self.first);
+    errors.extend(self.rest);
+    errors
+  }
+}
+
+impl<E> From<E> for ValidationErrors<E> {
+  fn from(error: E) -> Self {
+    Self::new(error)
+  }
+}
+
+/// 2つの検証した値を組み合わせます
+///
+/// 両方の検証に失敗した場合は、両方のエラーを順に集約します
+///
+/// # Arguments
+/// * `a`: 検証した値
+/// * `b`: 検証した値
+///
+/// # Return
+/// * `Validated<(A, B), E>`
+pub fn zip<A, B, E>(a: Validated<A, E>, b: Validated<B, E>) -> Validated<(A, B), E> {
+  match (a, b) {
+    (Ok(a), Ok(b)) => Ok((a, b)),
+    (Err(errors), Ok(_)) | (Ok(_), Err(errors)) => Err(errors),
+    (Err(a), Err(b)) => Err(a.append(b)),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_zip() {
+    let ok = zip(Ok::<_, ValidationErrors<&str>>(1), Ok(2));
+    let left = zip(Err::<i32, _>(ValidationErrors::new("a")), Ok(2));
+    let both = zip(Err::<i32, _>(ValidationErrors::new("a").append(ValidationErrors::new("b"))), Err::<i32, _>(ValidationErrors::new("c")));
+
+    // assert
+    assert_eq!(Ok((1, 2)), ok);
+    assert_eq!(vec!["a"], left.unwrap_err().into_vec());
+    assert_eq!(vec!["A", "B", "C"], both.unwrap_err().map(str::to_uppercase).into_vec());
+  }
+}
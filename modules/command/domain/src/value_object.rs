@@ -1,3 +1,7 @@
 pub mod quantity;
 pub mod price;
-pub mod discount;
\ No newline at end of file
+pub mod discount;
+pub mod currency;
+pub mod money;
+pub mod rounding_policy;
+pub mod coupon_code;
@@ -0,0 +1,127 @@
+use crate::error_code::ErrorCode;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// クーポンコードのクラスです
+///
+/// 英大文字・数字・ハイフンからなる3〜32文字の文字列です。
+/// 英小文字は大文字に変換します
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct CouponCode(String);
+
+const COUPON_CODE_MIN_LEN: usize = 3;
+const COUPON_CODE_MAX_LEN: usize = 32;
+
+/// クーポンコードエラーのクラスです
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+pub enum CouponCodeError {
+  #[error("invalid coupon code: {0}")]
+  Invalid(String),
+}
+
+impl ErrorCode for CouponCodeError {
+  fn code(&self) -> &'static str {
+    match self {
+      CouponCodeError::Invalid(_) => "coupon_code.invalid",
+    }
+  }
+}
+
+impl CouponCode {
+  /// コンストラクタです
+  ///
+  /// 前後の空白を取り除き、英小文字は大文字に変換します
+  ///
+  /// # Arguments
+  /// * `value`: クーポンコード
+  ///
+  /// # Return
+  /// * `Result<CouponCode, CouponCodeError>`
+  pub fn new(value: &str) -> Result<Self, CouponCodeError> {
+    let code = value.trim().to_ascii_uppercase();
+    let valid_len = (COUPON_CODE_MIN_LEN..=COUPON_CODE_MAX_LEN).contains(&code.len());
+    let valid_chars = code.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '-');
+    if !valid_len || !valid_chars {
+      Err(CouponCodeError::Invalid(value.to_string()))?
+    }
+    Ok(Self(code))
+  }
+
+  /// Getter
+  pub fn value(&self) -> &str { &self.0 }
+}
+
+impl FromStr for CouponCode {
+  type Err = CouponCodeError;
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Self::new(s)
+  }
+}
+
+impl Display for CouponCode {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+impl Serialize for CouponCode {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for CouponCode {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    Self::from_str(&value).map_err(serde::de::Error::custom)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use proptest::prelude::*;
+  use rstest::rstest;
+
+  #[rstest]
+  #[case("SUMMER-2024", "SUMMER-2024")]
+  #[case(" welcome10 ", "WELCOME10")]
+  fn test_coupon_code_new_success(#[case] value: &str, #[case] expected: &str) {
+    let result = CouponCode::new(value);
+
+    // assert
+    assert_eq!(expected, result.unwrap().value())
+  }
+
+  #[rstest]
+  #[case("")]
+  #[case("AB")]
+  #[case("SUMMER 2024")]
+  #[case("クーポン")]
+  #[case("A123456789012345678901234567890123")]
+  fn test_coupon_code_new_failed(#[case] value: &str) {
+    let result = CouponCode::new(value);
+
+    // assert
+    assert_eq!(Err(CouponCodeError::Invalid(value.to_string())), result)
+  }
+
+  proptest! {
+    #[test]
+    fn prop_coupon_code_new_normalizes_valid_codes(value in "[A-Za-z0-9-]{3,32}") {
+      let code = CouponCode::new(&value).unwrap();
+
+      prop_assert_eq!(value.to_ascii_uppercase(), code.value());
+      prop_assert_eq!(&code, &CouponCode::new(code.value()).unwrap());
+    }
+
+    #[test]
+    fn prop_coupon_code_new_rejects_invalid_chars(prefix in "[A-Z0-9]{3}", invalid in "[^A-Za-z0-9\\s-]") {
+      let value = format!("{}{}", prefix, invalid);
+
+      prop_assert_eq!(Err(CouponCodeError::Invalid(value.clone())), CouponCode::new(&value));
+    }
+  }
+}
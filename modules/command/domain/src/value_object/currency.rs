@@ -0,0 +1,178 @@
+use crate::error_code::ErrorCode;
+use crate::value_object::rounding_policy::{RoundingMode, RoundingPolicy, RoundingScope};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// ISO-4217の通貨です
+///
+/// 取り扱う通貨のみを定義しています
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Currency {
+  JPY,
+  USD,
+  EUR,
+  GBP,
+  CNY,
+  KRW,
+  TWD,
+  HKD,
+  SGD,
+  AUD,
+  CAD,
+  CHF,
+  KWD,
+  BHD,
+}
+
+/// 通貨エラーのクラスです
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+pub enum CurrencyError {
+  #[error("unsupported currency: {0}")]
+  Unsupported(String),
+}
+
+impl ErrorCode for CurrencyError {
+  fn code(&self) -> &'static str {
+    match self {
+      CurrencyError::Unsupported(_) => "currency.unsupported",
+    }
+  }
+}
+
+impl Currency {
+  /// ISO-4217の通貨コードを返します
+  pub fn code(&self) -> &'static str {
+    match self {
+      Currency::JPY => "JPY",
+      Currency::USD => "USD",
+      Currency::EUR => "EUR",
+      Currency::GBP => "GBP",
+      Currency::CNY => "CNY",
+      Currency::KRW => "KRW",
+      Currency::TWD => "TWD",
+      Currency::HKD => "HKD",
+      Currency::SGD => "SGD",
+      Currency::AUD => "AUD",
+      Currency::CAD => "CAD",
+      Currency::CHF => "CHF",
+      Currency::KWD => "KWD",
+      Currency::BHD => "BHD",
+    }
+  }
+
+  /// 補助単位の桁数を返します
+  ///
+  /// 例: JPYは0桁、USDは2桁(セント)
+  pub fn minor_units(&self) -> u32 {
+    match self {
+      Currency::JPY | Currency::KRW => 0,
+      Currency::KWD | Currency::BHD => 3,
+      _ => 2,
+    }
+  }
+
+  /// 通貨のデフォルトの丸めポリシーを返します
+  ///
+  /// 補助単位のない通貨は四捨五入、それ以外は銀行型丸めで、いずれも明細ごとに丸めます
+  pub fn default_rounding_policy(&self) -> RoundingPolicy {
+    match self.minor_units() {
+      0 => RoundingPolicy::new(RoundingMode::HalfUp, RoundingScope::PerLine),
+      _ => RoundingPolicy::new(RoundingMode::Bankers, RoundingScope::PerLine),
+    }
+  }
+}
+
+impl Display for Currency {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.code())
+  }
+}
+
+impl FromStr for Currency {
+  type Err = CurrencyError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "JPY" => Ok(Currency::JPY),
+      "USD" => Ok(Currency::USD),
+      "EUR" => Ok(Currency::EUR),
+      "GBP" => Ok(Currency::GBP),
+      "CNY" => Ok(Currency::CNY),
+      "KRW" => Ok(Currency::KRW),
+      "TWD" => Ok(Currency::TWD),
+      "HKD" => Ok(Currency::HKD),
+      "SGD" => Ok(Currency::SGD),
+      "AUD" => Ok(Currency::AUD),
+      "CAD" => Ok(Currency::CAD),
+      "CHF" => Ok(Currency::CHF),
+      "KWD" => Ok(Currency::KWD),
+      "BHD" => Ok(Currency::BHD),
+      _ => Err(CurrencyError::Unsupported(s.to_string())),
+    }
+  }
+}
+
+impl Serialize for Currency {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    Self::from_str(&value).map_err(serde::de::Error::custom)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use proptest::prelude::*;
+  use rstest::rstest;
+
+  #[rstest]
+  #[case("JPY", 0)]
+  #[case("USD", 2)]
+  #[case("BHD", 3)]
+  fn test_currency_from_str_success(#[case] code: &str, #[case] minor_units: u32) {
+    let result = Currency::from_str(code).unwrap();
+
+    // assert
+    assert_eq!(code, result.code());
+    assert_eq!(minor_units, result.minor_units())
+  }
+
+  #[rstest]
+  #[case("jpy")]
+  #[case("XXX")]
+  #[case("")]
+  fn test_currency_from_str_failed(#[case] code: &str) {
+    let result = Currency::from_str(code);
+
+    // assert
+    assert_eq!(Err(CurrencyError::Unsupported(code.to_string())), result)
+  }
+
+  const CURRENCIES: [Currency; 14] = [
+    Currency::JPY, Currency::USD, Currency::EUR, Currency::GBP, Currency::CNY, Currency::KRW, Currency::TWD,
+    Currency::HKD, Currency::SGD, Currency::AUD, Currency::CAD, Currency::CHF, Currency::KWD, Currency::BHD,
+  ];
+
+  proptest! {
+    #[test]
+    fn prop_currency_code_round_trip(currency in prop::sample::select(CURRENCIES.to_vec())) {
+      prop_assert_eq!(currency, Currency::from_str(currency.code()).unwrap());
+      prop_assert!(currency.minor_units() <= 3);
+    }
+
+    #[test]
+    fn prop_currency_from_str_rejects_unknown_codes(code in "\\PC{0,5}") {
+      let result = Currency::from_str(&code);
+
+      prop_assert_eq!(CURRENCIES.iter().any(|currency| currency.code() == code), result.is_ok());
+    }
+  }
+}
@@ -1,47 +1,287 @@
+use crate::error_code::{ErrorCode, FieldPath};
+use crate::value_object::money::{Money, MoneyError};
 use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::{Display, Formatter};
 use thiserror::Error;
 
 /// 割引のクラスです
+///
+/// 割合(%)による割引と、固定金額による割引のどちらかを表します
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct Discount {
-  discount: Decimal,
+  discount: DiscountKind,
+}
+
+/// 割引の種類です
+///
+/// Percentage: 0〜100の割合(%)
+///
+/// FixedAmount: 0以上の固定金額
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum DiscountKind {
+  Percentage(Decimal),
+  FixedAmount(Money),
 }
 
 /// 割引エラーのクラスです
-#[derive(Debug, Clone, Error)]
-pub struct DiscountError;
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+pub enum DiscountError {
+  #[error("discount percentage must be between 0 and 100: {0}")]
+  InvalidPercentage(Decimal),
 
-impl Display for DiscountError {
-  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-    write!(f, "Discount failed to validate")
+  #[error("discount amount must not be negative: {0}")]
+  NegativeAmount(Money),
+
+  #[error("discount {discount} exceeds the amount {amount}")]
+  ExceedsAmount { discount: Money, amount: Money },
+
+  #[error("coupon code {0} is applied more than once")]
+  DuplicateCoupon(String),
+
+  #[error("a non-stackable discount cannot be combined with other order discounts")]
+  NotStackable,
+
+  #[error(transparent)]
+  InvalidMoney(#[from] MoneyError),
+}
+
+impl ErrorCode for DiscountError {
+  fn code(&self) -> &'static str {
+    match self {
+      DiscountError::InvalidPercentage(_) => "discount.invalid_percentage",
+      DiscountError::NegativeAmount(_) => "discount.negative_amount",
+      DiscountError::ExceedsAmount { .. } => "discount.exceeds_amount",
+      DiscountError::DuplicateCoupon(_) => "coupon_code.duplicated",
+      DiscountError::NotStackable => "discount.not_stackable",
+      DiscountError::InvalidMoney(e) => e.code(),
+    }
+  }
+
+  /// 割引を含む入力(注文の明細や割引、プロモーション)からの相対パスを返します
+  fn field_path(&self) -> Option<FieldPath> {
+    match self {
+      DiscountError::InvalidPercentage(_)
+      | DiscountError::NegativeAmount(_)
+      | DiscountError::InvalidMoney(MoneyError::InvalidScale { .. }) => Some(FieldPath::new("discount").field("value")),
+      DiscountError::DuplicateCoupon(_) => Some(FieldPath::new("coupon_code")),
+      _ => None,
+    }
   }
 }
 
 impl Display for Discount {
   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-    write!(f, "{}", self.discount)
+    match &self.discount {
+      DiscountKind::Percentage(rate) => write!(f, "{}%", rate),
+      DiscountKind::FixedAmount(amount) => write!(f, "{}", amount),
+    }
   }
 }
 
 impl TryFrom<i32> for Discount {
   type Error = DiscountError;
 
-  /// 実質的なコンストラクタです
+  /// 割合(%)で割引を生成します
   fn try_from(value: i32) -> Result<Self, Self::Error> {
-    if value < 0 && 101 < value {
-      Err(DiscountError)?
-    };
-    Ok(Self::new(value))
+    Self::percentage(Decimal::from(value))
   }
 }
 
 impl Discount {
   /// プライベートコンストラクタです
-  fn new(discount: i32) -> Self {
-    Self { discount: Decimal::from(discount) }
+  fn new(discount: DiscountKind) -> Self {
+    Self { discount }
+  }
+
+  /// 割引なしを返します
+  pub fn none() -> Self {
+    Self::new(DiscountKind::Percentage(Decimal::ZERO))
+  }
+
+  /// 割合(%)による割引を生成します
+  ///
+  /// # Arguments
+  /// * `rate`: 0〜100の割合
+  ///
+  /// # Return
+  /// * `Result<Discount, DiscountError>`
+  pub fn percentage(rate: Decimal) -> Result<Self, DiscountError> {
+    if rate < Decimal::ZERO || Decimal::ONE_HUNDRED < rate {
+      Err(DiscountError::InvalidPercentage(rate))?
+    };
+    Ok(Self::new(DiscountKind::Percentage(rate)))
+  }
+
+  /// 固定金額による割引を生成します
+  ///
+  /// # Arguments
+  /// * `amount`: 0以上の金額
+  ///
+  /// # Return
+  /// * `Result<Discount, DiscountError>`
+  pub fn fixed_amount(amount: Money) -> Result<Self, DiscountError> {
+    if amount.amount() < &Decimal::ZERO {
+      Err(DiscountError::NegativeAmount(amount))?
+    };
+    Ok(Self::new(DiscountKind::FixedAmount(amount)))
+  }
+
+  /// 割引額を計算します
+  ///
+  /// 割合の場合は丸めを行わないため、補助単位より細かい金額になる場合があります。
+  /// 固定金額が対象の金額を超える場合はエラーになります
+  ///
+  /// # Arguments
+  /// * `amount`: 割引対象の金額
+  ///
+  /// # Return
+  /// * `Result<Money, DiscountError>`
+  pub fn calc_amount(&self, amount: &Money) -> Result<Money, DiscountError> {
+    match &self.discount {
+      DiscountKind::Percentage(rate) => Ok(amount.multiply(rate / Decimal::ONE_HUNDRED)?),
+      DiscountKind::FixedAmount(discount) => {
+        if discount.currency() != amount.currency() {
+          Err(MoneyError::CurrencyMismatch { expected: amount.currency(), actual: discount.currency() })?
+        }
+        if discount.amount() > amount.amount() {
+          Err(DiscountError::ExceedsAmount { discount: *discount, amount: *amount })?
+        }
+        Ok(*discount)
+      }
+    }
+  }
+
+  /// 割引がない場合trueを返します
+  pub fn is_zero(&self) -> bool {
+    match &self.discount {
+      DiscountKind::Percentage(rate) => rate.is_zero(),
+      DiscountKind::FixedAmount(amount) => amount.amount().is_zero(),
+    }
   }
 
   /// Getter
-  pub fn value(&self) -> &Decimal { &self.discount }
-}
\ No newline at end of file
+  pub fn kind(&self) -> &DiscountKind { &self.discount }
+}
+
+impl Serialize for Discount {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.discount.serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for Discount {
+  /// 割合・金額の範囲を`Discount::percentage`、`Discount::fixed_amount`で検証します
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let discount = match DiscountKind::deserialize(deserializer)? {
+      DiscountKind::Percentage(rate) => Self::percentage(rate),
+      DiscountKind::FixedAmount(amount) => Self::fixed_amount(amount),
+    };
+    discount.map_err(serde::de::Error::custom)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::value_object::currency::Currency;
+  use proptest::prelude::*;
+  use rstest::rstest;
+
+  fn jpy(value: i64) -> Money {
+    Money::new(Decimal::from(value), Currency::JPY).unwrap()
+  }
+
+  #[rstest]
+  #[case(0)]
+  #[case(10)]
+  #[case(100)]
+  fn test_discount_try_from_success(#[case] value: i32) {
+    let result = Discount::try_from(value);
+
+    // assert
+    assert_eq!(&DiscountKind::Percentage(Decimal::from(value)), result.unwrap().kind())
+  }
+
+  #[rstest]
+  #[case(-1)]
+  #[case(101)]
+  fn test_discount_try_from_failed(#[case] value: i32) {
+    let result = Discount::try_from(value);
+
+    // assert
+    assert_eq!(Err(DiscountError::InvalidPercentage(Decimal::from(value))), result)
+  }
+
+  #[test]
+  fn test_discount_fixed_amount_failed() {
+    let result = Discount::fixed_amount(jpy(-1));
+
+    // assert
+    assert!(matches!(result, Err(DiscountError::NegativeAmount(_))))
+  }
+
+  #[rstest]
+  #[case(Discount::try_from(10).unwrap(), 100)]
+  #[case(Discount::fixed_amount(jpy(300)).unwrap(), 300)]
+  #[case(Discount::fixed_amount(jpy(1000)).unwrap(), 1000)]
+  #[case(Discount::none(), 0)]
+  fn test_discount_calc_amount_success(#[case] discount: Discount, #[case] expected: i64) {
+    let result = discount.calc_amount(&jpy(1000));
+
+    // assert
+    assert_eq!(jpy(expected).amount(), result.unwrap().amount())
+  }
+
+  #[test]
+  fn test_discount_calc_amount_failed() {
+    let discount = Discount::fixed_amount(jpy(1001)).unwrap();
+    let usd = Discount::fixed_amount(Money::new(Decimal::ONE, Currency::USD).unwrap()).unwrap();
+
+    // assert
+    assert!(matches!(discount.calc_amount(&jpy(1000)), Err(DiscountError::ExceedsAmount { .. })));
+    assert!(matches!(usd.calc_amount(&jpy(1000)), Err(DiscountError::InvalidMoney(_))));
+  }
+
+  #[test]
+  fn test_discount_serde() {
+    let percentage = Discount::try_from(10).unwrap();
+    let fixed_amount = Discount::fixed_amount(Money::new(Decimal::from(100), Currency::JPY).unwrap()).unwrap();
+
+    let json = serde_json::to_value(&percentage).unwrap();
+
+    // assert
+    assert_eq!(serde_json::json!({ "type": "percentage", "value": "10" }), json);
+    assert_eq!(percentage, serde_json::from_value::<Discount>(json).unwrap());
+    assert_eq!(fixed_amount, serde_json::from_value::<Discount>(serde_json::to_value(&fixed_amount).unwrap()).unwrap());
+    assert!(serde_json::from_str::<Discount>(r#"{"type":"percentage","value":"101"}"#).is_err());
+    assert!(serde_json::from_str::<Discount>(r#"{"type":"fixed_amount","value":{"amount":"-1","currency":"JPY"}}"#).is_err());
+  }
+
+  proptest! {
+    #[test]
+    fn prop_discount_percentage_validates_range(rate in -1_000i64..20_000) {
+      let rate = Decimal::new(rate, 2);
+
+      prop_assert_eq!(Decimal::ZERO <= rate && rate <= Decimal::ONE_HUNDRED, Discount::percentage(rate).is_ok());
+    }
+
+    #[test]
+    fn prop_discount_calc_amount_never_exceeds_amount(rate in 0i64..=10_000, amount in 0i64..10_000_000) {
+      let discount = Discount::percentage(Decimal::new(rate, 2)).unwrap();
+
+      let result = discount.calc_amount(&jpy(amount)).unwrap();
+
+      prop_assert!(result.amount() >= &Decimal::ZERO);
+      prop_assert!(result.amount() <= jpy(amount).amount());
+    }
+
+    #[test]
+    fn prop_discount_fixed_amount_is_capped_by_amount(discount in 0i64..10_000, amount in 0i64..10_000) {
+      let result = Discount::fixed_amount(jpy(discount)).unwrap().calc_amount(&jpy(amount));
+
+      prop_assert_eq!(discount <= amount, result.is_ok());
+    }
+  }
+}
@@ -0,0 +1,326 @@
+use crate::error_code::ErrorCode;
+use crate::value_object::currency::{Currency, CurrencyError};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// 通貨付きの金額を表すValueObjectです
+///
+/// 異なる通貨同士の演算と、桁あふれする演算はエラーになります
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
+pub struct Money {
+  amount: Decimal,
+  currency: Currency,
+}
+
+/// 金額エラーのクラスです
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+pub enum MoneyError {
+  #[error("currency mismatch: expected {expected}, but got {actual}")]
+  CurrencyMismatch { expected: Currency, actual: Currency },
+
+  #[error("{currency} allows at most {minor_units} decimal places: {amount}")]
+  InvalidScale { amount: Decimal, currency: Currency, minor_units: u32 },
+
+  #[error("amount overflowed: {amount} {currency}")]
+  Overflow { amount: Decimal, currency: Currency },
+
+  #[error(transparent)]
+  InvalidCurrency(#[from] CurrencyError),
+}
+
+impl ErrorCode for MoneyError {
+  fn code(&self) -> &'static str {
+    match self {
+      MoneyError::CurrencyMismatch { .. } => "money.currency_mismatch",
+      MoneyError::InvalidScale { .. } => "money.invalid_scale",
+      MoneyError::Overflow { .. } => "money.overflow",
+      MoneyError::InvalidCurrency(e) => e.code(),
+    }
+  }
+}
+
+impl Display for Money {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{} {}", self.amount, self.currency)
+  }
+}
+
+impl Money {
+  /// コンストラクタです
+  ///
+  /// 通貨の補助単位より細かい金額はエラーになります
+  ///
+  /// # Arguments
+  /// * `amount`: 金額
+  /// * `currency`: 通貨
+  ///
+  /// # Return
+  /// * `Result<Money, MoneyError>`
+  pub fn new(amount: Decimal, currency: Currency) -> Result<Self, MoneyError> {
+    if amount.normalize().scale() > currency.minor_units() {
+      Err(MoneyError::InvalidScale {
+        amount,
+        currency,
+        minor_units: currency.minor_units(),
+      })?
+    }
+    Ok(Self { amount, currency })
+  }
+
+  /// 通貨コードの文字列から生成します
+  ///
+  /// # Arguments
+  /// * `amount`: 金額
+  /// * `currency`: ISO-4217の通貨コード
+  ///
+  /// # Return
+  /// * `Result<Money, MoneyError>`
+  pub fn parse(amount: Decimal, currency: &str) -> Result<Self, MoneyError> {
+    Self::new(amount, Currency::from_str(currency)?)
+  }
+
+  /// 0円(0ドル等)を返します
+  pub fn zero(currency: Currency) -> Self {
+    Self { amount: Decimal::ZERO, currency }
+  }
+
+  /// 足し算します
+  ///
+  /// # Arguments
+  /// * `other`: 同じ通貨の金額
+  ///
+  /// # Return
+  /// * `Result<Money, MoneyError>`
+  pub fn add(&self, other: &Money) -> Result<Self, MoneyError> {
+    self.ensure_same_currency(other)?;
+    self.checked(self.amount.checked_add(other.amount))
+  }
+
+  /// 引き算します
+  ///
+  /// # Arguments
+  /// * `other`: 同じ通貨の金額
+  ///
+  /// # Return
+  /// * `Result<Money, MoneyError>`
+  pub fn sub(&self, other: &Money) -> Result<Self, MoneyError> {
+    self.ensure_same_currency(other)?;
+    self.checked(self.amount.checked_sub(other.amount))
+  }
+
+  /// 数量を掛けます
+  ///
+  /// # Arguments
+  /// * `quantity`: 数量
+  ///
+  /// # Return
+  /// * `Result<Money, MoneyError>`
+  pub fn times(&self, quantity: i32) -> Result<Self, MoneyError> {
+    self.checked(self.amount.checked_mul(Decimal::from(quantity)))
+  }
+
+  /// 比率を掛けます
+  ///
+  /// 結果は補助単位より細かくなる場合があります
+  ///
+  /// # Arguments
+  /// * `rate`: 比率
+  ///
+  /// # Return
+  /// * `Result<Money, MoneyError>`
+  pub fn multiply(&self, rate: Decimal) -> Result<Self, MoneyError> {
+    self.checked(self.amount.checked_mul(rate))
+  }
+
+  /// 通貨の補助単位の桁数に丸めます
+  ///
+  /// 丸めた結果の桁数は常に補助単位の桁数になります
+  ///
+  /// # Arguments
+  /// * `strategy`: RoundingStrategy
+  ///
+  /// # Return
+  /// * `Money`
+  pub fn round_dp_with_strategy(&self, strategy: RoundingStrategy) -> Self {
+    let minor_units = self.currency.minor_units();
+    let mut amount = self.amount.round_dp_with_strategy(minor_units, strategy);
+    amount.rescale(minor_units);
+    Self { amount, currency: self.currency }
+  }
+
+  /// 金額のゲッター
+  pub fn amount(&self) -> &Decimal { &self.amount }
+
+  /// 通貨のゲッター
+  pub fn currency(&self) -> Currency { self.currency }
+
+  /// 演算の結果を同じ通貨の金額にします。桁あふれした場合はエラーになります
+  fn checked(&self, amount: Option<Decimal>) -> Result<Self, MoneyError> {
+    match amount {
+      Some(amount) => Ok(Self { amount, currency: self.currency }),
+      None => Err(MoneyError::Overflow { amount: self.amount, currency: self.currency }),
+    }
+  }
+
+  fn ensure_same_currency(&self, other: &Money) -> Result<(), MoneyError> {
+    if self.currency != other.currency {
+      Err(MoneyError::CurrencyMismatch {
+        expected: self.currency,
+        actual: other.currency,
+      })?
+    }
+    Ok(())
+  }
+}
+
+/// 金額のデシリアライズ用の値です
+#[derive(Deserialize)]
+struct MoneyValue {
+  amount: Decimal,
+  currency: Currency,
+}
+
+impl<'de> Deserialize<'de> for Money {
+  /// 補助単位の桁数を`Money::new`で検証します
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let value = MoneyValue::deserialize(deserializer)?;
+    Self::new(value.amount, value.currency).map_err(serde::de::Error::custom)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use proptest::prelude::*;
+  use rstest::rstest;
+
+  #[rstest]
+  #[case(Decimal::new(100, 0), Currency::JPY)]
+  #[case(Decimal::new(1999, 2), Currency::USD)]
+  #[case(Decimal::new(1000, 3), Currency::JPY)]
+  #[case(Decimal::new(1234, 3), Currency::KWD)]
+  fn test_money_new_success(#[case] amount: Decimal, #[case] currency: Currency) {
+    let result = Money::new(amount, currency);
+
+    // assert
+    assert!(result.is_ok());
+    assert_eq!(&amount, result.unwrap().amount())
+  }
+
+  #[rstest]
+  #[case(Decimal::new(15, 1), Currency::JPY)]
+  #[case(Decimal::new(1999, 3), Currency::USD)]
+  fn test_money_new_failed(#[case] amount: Decimal, #[case] currency: Currency) {
+    let result = Money::new(amount, currency);
+
+    // assert
+    assert!(matches!(result, Err(MoneyError::InvalidScale { .. })))
+  }
+
+  #[test]
+  fn test_money_parse_failed() {
+    let result = Money::parse(Decimal::ONE, "XXX");
+
+    // assert
+    assert!(matches!(result, Err(MoneyError::InvalidCurrency(_))))
+  }
+
+  #[test]
+  fn test_money_add_success() {
+    let a = Money::new(Decimal::new(1050, 2), Currency::USD).unwrap();
+    let b = Money::new(Decimal::new(250, 2), Currency::USD).unwrap();
+    let result = a.add(&b).unwrap();
+
+    // assert
+    assert_eq!(&Decimal::new(1300, 2), result.amount());
+    assert_eq!(Currency::USD, result.currency())
+  }
+
+  #[test]
+  fn test_money_add_failed() {
+    let a = Money::new(Decimal::from(100), Currency::JPY).unwrap();
+    let b = Money::new(Decimal::from(1), Currency::USD).unwrap();
+
+    // assert
+    assert_eq!(
+      Err(MoneyError::CurrencyMismatch { expected: Currency::JPY, actual: Currency::USD }),
+      a.add(&b)
+    );
+    assert!(a.sub(&b).is_err())
+  }
+
+  #[test]
+  fn test_money_times_success() {
+    let money = Money::new(Decimal::from(500), Currency::JPY).unwrap();
+
+    // assert
+    assert_eq!(&Decimal::from(1500), money.times(3).unwrap().amount())
+  }
+
+  #[test]
+  fn test_money_overflow_failed() {
+    let max = Money::new(Decimal::MAX, Currency::JPY).unwrap();
+    let min = Money::new(Decimal::MIN, Currency::JPY).unwrap();
+    let one = Money::new(Decimal::ONE, Currency::JPY).unwrap();
+
+    // assert
+    assert!(matches!(max.add(&one), Err(MoneyError::Overflow { .. })));
+    assert!(matches!(min.sub(&one), Err(MoneyError::Overflow { .. })));
+    assert!(matches!(max.times(2), Err(MoneyError::Overflow { .. })));
+    assert!(matches!(max.multiply(Decimal::TWO), Err(MoneyError::Overflow { .. })));
+    assert_eq!("money.overflow", max.add(&one).unwrap_err().code());
+  }
+
+  fn usd(cents: i64) -> Money {
+    Money::new(Decimal::new(cents, 2), Currency::USD).unwrap()
+  }
+
+  #[test]
+  fn test_money_serde() {
+    let money = usd(1050);
+
+    let json = serde_json::to_value(money).unwrap();
+
+    // assert
+    assert_eq!(serde_json::json!({ "amount": "10.50", "currency": "USD" }), json);
+    assert_eq!(money, serde_json::from_value::<Money>(json).unwrap());
+    assert!(serde_json::from_str::<Money>(r#"{"amount":"10.505","currency":"USD"}"#).is_err());
+    assert!(serde_json::from_str::<Money>(r#"{"amount":"10","currency":"XXX"}"#).is_err());
+  }
+
+  proptest! {
+    #[test]
+    fn prop_money_add_sub_round_trip(a in -1_000_000_000i64..1_000_000_000, b in -1_000_000_000i64..1_000_000_000) {
+      let sum = usd(a).add(&usd(b)).unwrap();
+
+      prop_assert_eq!(sum, usd(b).add(&usd(a)).unwrap());
+      prop_assert_eq!(usd(a), sum.sub(&usd(b)).unwrap());
+    }
+
+    #[test]
+    fn prop_money_currency_mismatch_failed(a in any::<i32>(), b in any::<i32>()) {
+      let jpy = Money::new(Decimal::from(a), Currency::JPY).unwrap();
+      let usd = usd(b as i64);
+
+      prop_assert!(jpy.add(&usd).is_err());
+      prop_assert!(jpy.sub(&usd).is_err());
+    }
+
+    #[test]
+    fn prop_money_new_validates_scale(mantissa in any::<i32>(), scale in 0u32..6) {
+      let amount = Decimal::new(mantissa as i64, scale);
+
+      prop_assert_eq!(amount.normalize().scale() <= 2, Money::new(amount, Currency::USD).is_ok());
+    }
+
+    #[test]
+    fn prop_money_times_matches_repeated_add(cents in -1_000_000i64..1_000_000, quantity in 0i32..50) {
+      let repeated = (0..quantity).try_fold(Money::zero(Currency::USD), |acc, _| acc.add(&usd(cents))).unwrap();
+
+      prop_assert_eq!(repeated, usd(cents).times(quantity).unwrap());
+    }
+  }
+}
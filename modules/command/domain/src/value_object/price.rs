@@ -1,20 +1,34 @@
+use crate::error_code::ErrorCode;
+use crate::value_object::money::{Money, MoneyError};
 use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::{Display, Formatter};
 use thiserror::Error;
 
 /// 金額のクラスです
+///
+/// 通貨付きの正の金額を表します
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Price {
-  price: Decimal,
+  price: Money,
 }
 
 /// 金額エラーのクラスです
 #[derive(Debug, Clone, Error)]
-pub struct PriceError;
+pub enum PriceError {
+  #[error("Price failed to validate")]
+  NotPositive,
 
-impl Display for PriceError {
-  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-    write!(f, "Price failed to validate")
+  #[error(transparent)]
+  InvalidMoney(#[from] MoneyError),
+}
+
+impl ErrorCode for PriceError {
+  fn code(&self) -> &'static str {
+    match self {
+      PriceError::NotPositive => "price.not_positive",
+      PriceError::InvalidMoney(e) => e.code(),
+    }
   }
 }
 
@@ -24,13 +38,13 @@ impl Display for Price {
   }
 }
 
-impl TryFrom<Decimal> for Price {
+impl TryFrom<Money> for Price {
   type Error = PriceError;
 
   /// 実質的なコンストラクタです
-  fn try_from(value: Decimal) -> Result<Self, Self::Error> {
-    if value <= Decimal::from(0) {
-      Err(PriceError)?
+  fn try_from(value: Money) -> Result<Self, Self::Error> {
+    if value.amount() <= &Decimal::ZERO {
+      Err(PriceError::NotPositive)?
     };
     Ok(Self::new(value))
   }
@@ -38,38 +52,60 @@ impl TryFrom<Decimal> for Price {
 
 impl Price {
   /// プライベートコンストラクタです
-  fn new(price: Decimal) -> Self {
+  fn new(price: Money) -> Self {
     Self { price }
   }
 
-  /// 引数で受け取った値で足し算
-  pub fn add(self, value: Decimal, quantity: i32) -> Result<Self, PriceError> {
-    if value <= Decimal::from(0) {
-      Err(PriceError)?
+  /// 引数で受け取った金額×数量を足し算
+  pub fn add(self, value: &Price, quantity: i32) -> Result<Self, PriceError> {
+    if quantity <= 0 {
+      Err(PriceError::NotPositive)?
     }
-    Ok(Self::new(self.price + (value * Decimal::from(quantity))))
+    Ok(Self::new(self.price.add(&value.price.times(quantity)?)?))
   }
 
   /// Getter
-  pub fn value(&self) -> &Decimal { &self.price }
+  pub fn value(&self) -> &Decimal { self.price.amount() }
+
+  /// 通貨付きの金額を返します
+  pub fn money(&self) -> &Money { &self.price }
+}
+
+impl Serialize for Price {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.price.serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for Price {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let value = Money::deserialize(deserializer)?;
+    Self::try_from(value).map_err(serde::de::Error::custom)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::value_object::currency::Currency;
+  use proptest::prelude::*;
   use rstest::rstest;
 
+  fn jpy(value: i32) -> Money {
+    Money::new(Decimal::from(value), Currency::JPY).unwrap()
+  }
+
   #[rstest]
   #[case(1)]
   #[case(100)]
   fn test_price_new_success(#[case] value: i32) {
-    let result = Price::new(Decimal::from(value));
-    assert_eq!(Decimal::from(value), result.price)
+    let result = Price::new(jpy(value));
+    assert_eq!(Decimal::from(value), *result.price.amount())
   }
 
   #[test]
   fn test_price_try_from_success() {
-    let value = Decimal::from(1);
+    let value = jpy(1);
     let result = Price::try_from(value);
 
     // assert
@@ -79,7 +115,7 @@ mod tests {
 
   #[test]
   fn test_price_try_from_failed() {
-    let value = Decimal::ZERO;
+    let value = Money::zero(Currency::JPY);
     let result = Price::try_from(value);
 
     //assert
@@ -90,38 +126,63 @@ mod tests {
   #[case(100, 2)]
   #[case(300, 5)]
   fn test_price_add_success(#[case] value: i32, #[case] quantity: i32) {
-    let price = Price::try_from(Decimal::from(value));
+    let price = Price::try_from(jpy(value));
     let result = Price::add(
       price.unwrap(),
-      Decimal::from(value),
+      &Price::try_from(jpy(value)).unwrap(),
       quantity,
     );
 
     //assert
-    assert!(result.is_ok())
+    assert!(result.is_ok());
+    assert_eq!(&Decimal::from(value + value * quantity), result.unwrap().value())
   }
 
   #[test]
   fn test_price_add_failed() {
-    let price = Price::try_from(Decimal::from(1));
+    let price = Price::try_from(jpy(1));
     let result = Price::add(
       price.unwrap(),
-      Decimal::from(0),
-      1,
+      &Price::try_from(jpy(1)).unwrap(),
+      0,
     );
 
     //assert
     assert!(result.is_err())
   }
 
+  #[test]
+  fn test_price_add_currency_mismatch_failed() {
+    let price = Price::try_from(jpy(1)).unwrap();
+    let other = Price::try_from(Money::new(Decimal::ONE, Currency::USD).unwrap()).unwrap();
+    let result = Price::add(price, &other, 1);
+
+    //assert
+    assert!(matches!(result, Err(PriceError::InvalidMoney(_))))
+  }
+
   #[rstest]
   #[case(100)]
   #[case(50)]
   fn test_price_value_success(#[case] value: i32) {
-    let result = Price::try_from(Decimal::from(value));
+    let result = Price::try_from(jpy(value));
 
     // assert
     assert!(result.is_ok());
     assert_eq!(result.unwrap().value(), &Decimal::from(value))
   }
-}
\ No newline at end of file
+
+  proptest! {
+    #[test]
+    fn prop_price_try_from_accepts_only_positive(value in any::<i32>()) {
+      prop_assert_eq!(value > 0, Price::try_from(jpy(value)).is_ok());
+    }
+
+    #[test]
+    fn prop_price_add_equals_value_times_quantity(base in 1i32..1_000_000, value in 1i32..1_000_000, quantity in 1i32..1_000) {
+      let result = Price::try_from(jpy(base)).unwrap().add(&Price::try_from(jpy(value)).unwrap(), quantity).unwrap();
+
+      prop_assert_eq!(&(Decimal::from(base) + Decimal::from(value) * Decimal::from(quantity)), result.value());
+    }
+  }
+}
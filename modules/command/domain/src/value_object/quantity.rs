@@ -1,3 +1,5 @@
+use crate::error_code::ErrorCode;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::{Display, Formatter};
 use thiserror::Error;
 
@@ -7,12 +9,26 @@ pub struct Quantity {
   quantity: i32,
 }
 
-#[derive(Error, Debug, Clone)]
-pub struct QuantityError;
+/// 数量エラーのクラスです
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum QuantityError {
+  #[error("quantity must be greater than 0: {0}")]
+  NotPositive(i32),
 
-impl Display for QuantityError {
-  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-    write!(f, "Quantity failed to validate")
+  #[error("quantity overflowed: {0} + {1}")]
+  Overflow(i32, i32),
+
+  #[error("quantity {value} exceeds the maximum {max} for product {product_id}")]
+  ExceedsMaximum { product_id: i32, value: i32, max: i32 },
+}
+
+impl ErrorCode for QuantityError {
+  fn code(&self) -> &'static str {
+    match self {
+      QuantityError::NotPositive(_) => "quantity.not_positive",
+      QuantityError::Overflow(_, _) => "quantity.overflow",
+      QuantityError::ExceedsMaximum { .. } => "quantity.exceeds_maximum",
+    }
   }
 }
 
@@ -27,7 +43,7 @@ impl TryFrom<i32> for Quantity {
 
   fn try_from(value: i32) -> Result<Self, Self::Error> {
     if value <= 0 {
-      Err(QuantityError)?
+      Err(QuantityError::NotPositive(value))?
     };
     Ok(Quantity::new(value))
   }
@@ -49,33 +65,70 @@ impl Quantity {
 
   /// 数量をプラスします
   ///
+  /// `i32`の範囲を超える場合はエラーになります
+  ///
   /// # Arguments
   ///
-  /// * `quantity` 数量
+  /// * `value` 加算する数量(1以上)
   ///
   /// # Return
   ///
-  /// * `Quantity`
+  /// * `Result<Quantity, QuantityError>`
   pub fn add(&self, value: i32) -> Result<Self, QuantityError> {
     if value <= 0 {
-      Err(QuantityError)?
+      Err(QuantityError::NotPositive(value))?
     }
-    Ok(Quantity::new(self.quantity + value))
+    let quantity = self.quantity
+      .checked_add(value)
+      .ok_or(QuantityError::Overflow(self.quantity, value))?;
+    Ok(Quantity::new(quantity))
+  }
+
+  /// 数量をマイナスします
+  ///
+  /// 結果が0以下になる場合はエラーになります
+  ///
+  /// # Arguments
+  ///
+  /// * `value` 減算する数量(1以上)
+  ///
+  /// # Return
+  ///
+  /// * `Result<Quantity, QuantityError>`
+  pub fn subtract(&self, value: i32) -> Result<Self, QuantityError> {
+    if value <= 0 {
+      Err(QuantityError::NotPositive(value))?
+    }
+    Quantity::try_from(self.quantity - value)
   }
 
   /// `quantity`を返却します
   ///
   /// # Return
   ///
-  /// * `Decimal`
+  /// * `i32`
   pub fn value(&self) -> i32 {
     self.quantity
   }
 }
 
+impl Serialize for Quantity {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_i32(self.quantity)
+  }
+}
+
+impl<'de> Deserialize<'de> for Quantity {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let value = i32::deserialize(deserializer)?;
+    Self::try_from(value).map_err(serde::de::Error::custom)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
+  use proptest::prelude::*;
   use rstest::rstest;
 
   #[rstest]
@@ -101,6 +154,38 @@ mod tests {
     assert!(result.is_err())
   }
 
+  #[test]
+  fn test_quantity_add_overflow_failed() {
+    let quantity = Quantity::try_from(i32::MAX).unwrap();
+
+    let result = quantity.add(1);
+
+    // assert
+    assert_eq!(Err(QuantityError::Overflow(i32::MAX, 1)), result);
+  }
+
+  #[rstest]
+  #[case(10, 1, 9)]
+  #[case(10, 9, 1)]
+  fn test_quantity_subtract_success(#[case] base: i32, #[case] value: i32, #[case] expected: i32) {
+    let result = Quantity::try_from(base).unwrap().subtract(value).unwrap();
+
+    // assert
+    assert_eq!(expected, result.value())
+  }
+
+  #[rstest]
+  #[case(10, 10, QuantityError::NotPositive(0))]
+  #[case(10, 11, QuantityError::NotPositive(-1))]
+  #[case(10, 0, QuantityError::NotPositive(0))]
+  #[case(10, -1, QuantityError::NotPositive(-1))]
+  fn test_quantity_subtract_failed(#[case] base: i32, #[case] value: i32, #[case] expected: QuantityError) {
+    let result = Quantity::try_from(base).unwrap().subtract(value);
+
+    // assert
+    assert_eq!(Err(expected), result)
+  }
+
   #[rstest]
   #[case(10)]
   #[case(1)]
@@ -110,4 +195,43 @@ mod tests {
     // assert
     assert_eq!(value, result)
   }
+
+  #[rstest]
+  #[case("1", Some(1))]
+  #[case("0", None)]
+  #[case("-1", None)]
+  #[case("\"1\"", None)]
+  fn test_quantity_deserialize(#[case] json: &str, #[case] expected: Option<i32>) {
+    let result = serde_json::from_str::<Quantity>(json);
+
+    // assert
+    assert_eq!(expected, result.ok().map(|quantity| quantity.value()));
+  }
+
+  proptest! {
+    #[test]
+    fn prop_quantity_try_from_accepts_only_positive(value in any::<i32>()) {
+      prop_assert_eq!(value > 0, Quantity::try_from(value).is_ok());
+    }
+
+    #[test]
+    fn prop_quantity_add_is_checked(base in 1i32.., value in any::<i32>()) {
+      let result = Quantity::try_from(base).unwrap().add(value);
+
+      match base.checked_add(value) {
+        Some(expected) if value > 0 => prop_assert_eq!(expected, result.unwrap().value()),
+        _ => prop_assert!(result.is_err()),
+      }
+    }
+
+    #[test]
+    fn prop_quantity_subtract_never_reaches_zero(base in 1i32.., value in 1i32..) {
+      let result = Quantity::try_from(base).unwrap().subtract(value);
+
+      prop_assert_eq!(value < base, result.is_ok());
+      if let Ok(quantity) = result {
+        prop_assert_eq!(base, quantity.add(value).unwrap().value());
+      }
+    }
+  }
 }
@@ -0,0 +1,136 @@
+use crate::value_object::money::Money;
+use rust_decimal::RoundingStrategy;
+use serde::{Deserialize, Serialize};
+
+/// 端数の丸め方です
+///
+/// Bankers: 最近接偶数への丸め(銀行型丸め)
+///
+/// HalfUp: 四捨五入(0から遠い方への丸め)
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+  Bankers,
+  HalfUp,
+}
+
+/// 丸めを行う単位です
+///
+/// PerLine: 明細ごとに丸めてから合計します
+///
+/// PerOrder: 明細を丸めずに合計し、注文合計で一度だけ丸めます
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingScope {
+  PerLine,
+  PerOrder,
+}
+
+/// 金額の丸めポリシーです
+///
+/// 丸めの桁数は通貨の補助単位の桁数になります
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct RoundingPolicy {
+  mode: RoundingMode,
+  scope: RoundingScope,
+}
+
+impl RoundingPolicy {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `mode`: RoundingMode
+  /// * `scope`: RoundingScope
+  ///
+  /// # Return
+  /// * `RoundingPolicy`
+  pub fn new(mode: RoundingMode, scope: RoundingScope) -> Self {
+    Self { mode, scope }
+  }
+
+  /// 金額を通貨の補助単位の桁数に丸めます
+  ///
+  /// # Arguments
+  /// * `money`: &Money
+  ///
+  /// # Return
+  /// * `Money`
+  pub fn round(&self, money: &Money) -> Money {
+    let strategy = match self.mode {
+      RoundingMode::Bankers => RoundingStrategy::MidpointNearestEven,
+      RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+    };
+    money.round_dp_with_strategy(strategy)
+  }
+
+  /// 丸め方のゲッター
+  pub fn mode(&self) -> RoundingMode { self.mode }
+
+  /// 丸め単位のゲッター
+  pub fn scope(&self) -> RoundingScope { self.scope }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::value_object::currency::Currency;
+  use proptest::prelude::*;
+  use rstest::rstest;
+  use rust_decimal::Decimal;
+
+  #[rstest]
+  #[case(RoundingMode::Bankers, Decimal::new(1025, 3), Decimal::new(102, 2))]
+  #[case(RoundingMode::HalfUp, Decimal::new(1025, 3), Decimal::new(103, 2))]
+  #[case(RoundingMode::Bankers, Decimal::new(1035, 3), Decimal::new(104, 2))]
+  #[case(RoundingMode::HalfUp, Decimal::new(-1025, 3), Decimal::new(-103, 2))]
+  fn test_rounding_policy_round_success(
+    #[case] mode: RoundingMode,
+    #[case] amount: Decimal,
+    #[case] expected: Decimal,
+  ) {
+    let policy = RoundingPolicy::new(mode, RoundingScope::PerLine);
+    let money = Money::new(Decimal::ONE, Currency::USD).unwrap().multiply(amount).unwrap();
+    let result = policy.round(&money);
+
+    // assert
+    assert_eq!(&expected, result.amount());
+    assert_eq!(2, result.amount().scale())
+  }
+
+  #[test]
+  fn test_rounding_policy_round_by_minor_units_success() {
+    let policy = RoundingPolicy::new(RoundingMode::HalfUp, RoundingScope::PerLine);
+    let money = Money::new(Decimal::from(100), Currency::JPY).unwrap().multiply(Decimal::new(3333, 4)).unwrap();
+    let result = policy.round(&money);
+
+    // assert
+    assert_eq!(&Decimal::from(33), result.amount())
+  }
+
+  fn mode_strategy() -> impl Strategy<Value = RoundingMode> {
+    prop_oneof![Just(RoundingMode::Bankers), Just(RoundingMode::HalfUp)]
+  }
+
+  proptest! {
+    #[test]
+    fn prop_rounding_policy_round_is_idempotent_and_close(mode in mode_strategy(), mantissa in any::<i64>(), scale in 0u32..8) {
+      let policy = RoundingPolicy::new(mode, RoundingScope::PerLine);
+      let money = Money::new(Decimal::ONE, Currency::USD).unwrap().multiply(Decimal::new(mantissa, scale)).unwrap();
+
+      let rounded = policy.round(&money);
+
+      prop_assert_eq!(2, rounded.amount().scale());
+      prop_assert_eq!(rounded, policy.round(&rounded));
+      prop_assert!((rounded.amount() - money.amount()).abs() <= Decimal::new(5, 3));
+    }
+
+    #[test]
+    fn prop_rounding_policy_half_up_is_symmetric(mantissa in any::<i64>(), scale in 0u32..8) {
+      let policy = RoundingPolicy::new(RoundingMode::HalfUp, RoundingScope::PerLine);
+      let money = Money::new(Decimal::ONE, Currency::JPY).unwrap().multiply(Decimal::new(mantissa, scale)).unwrap();
+      let negated = Money::new(Decimal::ONE, Currency::JPY).unwrap().multiply(-Decimal::new(mantissa, scale)).unwrap();
+
+      prop_assert_eq!(-*policy.round(&money).amount(), *policy.round(&negated).amount());
+    }
+  }
+}
@@ -0,0 +1,114 @@
+use command_domain::payment::payment_id::PaymentId;
+use command_domain::payment::payment_provider::{PaymentProvider, PaymentProviderError};
+use command_domain::payment::refund_id::RefundId;
+use command_domain::value_object::money::Money;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// 外部の決済代行サービスを呼び出さないPaymentProviderの実装です
+///
+/// ローカル環境とテストで使用します。
+/// 通常はすべての取引を受け付け、`fail_next`で登録した結果を呼び出し順に返します。
+/// 返金は返金IDごとに1回だけ記録し、同じ返金IDでの再実行は成功として扱います
+#[derive(Debug, Default)]
+pub struct FakePaymentProvider {
+  next_reference: AtomicU64,
+  scripted_failures: Mutex<VecDeque<PaymentProviderError>>,
+  refunds: Mutex<BTreeMap<RefundId, Money>>,
+}
+
+impl FakePaymentProvider {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// 次の呼び出しで返すエラーを登録します
+  ///
+  /// # Arguments
+  /// * `error`: PaymentProviderError
+  ///
+  /// # Return
+  /// * `FakePaymentProvider`
+  pub fn fail_next(self, error: PaymentProviderError) -> Self {
+    self.scripted_failures.lock().unwrap().push_back(error);
+    self
+  }
+
+  /// 実行した返金を返金IDの順に返します
+  pub fn refunds(&self) -> Vec<(RefundId, Money)> {
+    self.refunds.lock().unwrap().iter().map(|(refund_id, amount)| (refund_id.clone(), *amount)).collect()
+  }
+
+  /// 登録したエラーがあれば取り出します
+  fn next_result(&self) -> Result<(), PaymentProviderError> {
+    match self.scripted_failures.lock().unwrap().pop_front() {
+      Some(error) => Err(error),
+      None => Ok(()),
+    }
+  }
+}
+
+impl PaymentProvider for FakePaymentProvider {
+  fn authorize(&self, _payment_id: &PaymentId, _amount: &Money) -> Result<String, PaymentProviderError> {
+    self.next_result()?;
+    let sequence = self.next_reference.fetch_add(1, Ordering::SeqCst) + 1;
+    Ok(format!("fake-{}", sequence))
+  }
+
+  fn capture(&self, _provider_reference: &str, _amount: &Money) -> Result<(), PaymentProviderError> {
+    self.next_result()
+  }
+
+  fn refund(&self, _provider_reference: &str, refund_id: &RefundId, amount: &Money) -> Result<(), PaymentProviderError> {
+    if self.refunds.lock().unwrap().contains_key(refund_id) {
+      return Ok(());
+    }
+    self.next_result()?;
+    self.refunds.lock().unwrap().insert(refund_id.clone(), *amount);
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use command_domain::id_generator::UuidV4Generator;
+  use command_domain::value_object::currency::Currency;
+  use rust_decimal::Decimal;
+
+  #[test]
+  fn test_fake_payment_provider_success() {
+    let provider = FakePaymentProvider::new()
+      .fail_next(PaymentProviderError::Declined("insufficient funds".to_string()));
+    let amount = Money::new(Decimal::from(1000), Currency::JPY).unwrap();
+
+    let declined = provider.authorize(&PaymentId::generate(&UuidV4Generator), &amount);
+    let first = provider.authorize(&PaymentId::generate(&UuidV4Generator), &amount);
+    let second = provider.authorize(&PaymentId::generate(&UuidV4Generator), &amount);
+
+    // assert
+    assert_eq!(Err(PaymentProviderError::Declined("insufficient funds".to_string())), declined);
+    assert_eq!(Ok("fake-1".to_string()), first);
+    assert_eq!(Ok("fake-2".to_string()), second);
+    assert_eq!(Ok(()), provider.capture("fake-1", &amount));
+  }
+
+  #[test]
+  fn test_fake_payment_provider_refund_is_idempotent() {
+    let provider = FakePaymentProvider::new()
+      .fail_next(PaymentProviderError::Unavailable("timeout".to_string()));
+    let amount = Money::new(Decimal::from(300), Currency::JPY).unwrap();
+    let refund_id = RefundId::generate(&UuidV4Generator);
+
+    let unavailable = provider.refund("fake-1", &refund_id, &amount);
+    let retried = provider.refund("fake-1", &refund_id, &amount);
+    let repeated = provider.refund("fake-1", &refund_id, &amount);
+
+    // assert
+    assert_eq!(Err(PaymentProviderError::Unavailable("timeout".to_string())), unavailable);
+    assert_eq!(Ok(()), retried);
+    assert_eq!(Ok(()), repeated);
+    assert_eq!(vec![(refund_id, amount)], provider.refunds());
+  }
+}
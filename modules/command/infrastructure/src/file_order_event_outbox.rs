@@ -0,0 +1,213 @@
+use command_domain::event_envelope::{EventEnvelope, EventMetadata};
+use command_domain::event_id::EventId;
+use command_domain::id_generator::UuidV4Generator;
+use command_domain::order::order_event::OrderEvent;
+use command_domain::order::order_event_publisher::OrderEventPublisher;
+use shared_telemetry::propagation;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+/// 注文のイベントをファイル(イベントログ)へ書き出すアウトボックスです
+///
+/// `publish`ではイベントをメモリ上に溜め、`flush`でまとめてファイルの末尾に追記します。
+/// イベントは配信時のスパンのトレースコンテキストとイベントIDを付けた`EventEnvelope`として、1行1件のJSON(JSON Lines)で書き出します
+#[derive(Debug)]
+pub struct FileOrderEventOutbox {
+  path: PathBuf,
+  pending: Mutex<Vec<EventEnvelope<OrderEvent>>>,
+  interrupted: AtomicBool,
+}
+
+impl FileOrderEventOutbox {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `path`: イベントログのファイル(存在しない場合は最初の書き出しで作成します)
+  ///
+  /// # Return
+  /// * `FileOrderEventOutbox`
+  pub fn new(path: impl Into<PathBuf>) -> Self {
+    Self { path: path.into(), pending: Mutex::new(vec![]), interrupted: AtomicBool::new(false) }
+  }
+
+  /// イベントログのファイルのゲッター
+  pub fn path(&self) -> &Path { &self.path }
+
+  /// 書き出していないイベントの件数を返します
+  pub fn pending_len(&self) -> usize {
+    self.pending.lock().unwrap_or_else(PoisonError::into_inner).len()
+  }
+
+  /// 溜めたイベントをイベントログの末尾に追記します
+  ///
+  /// 書き出しに失敗した場合はイベントを残すため、次の`flush`で再度書き出します。
+  /// 途中まで書き出したイベントも再度書き出しますが、同じイベントIDのため投影処理では重複して適用されません。
+  /// 失敗した後の書き出しは改行から始め、書きかけの行と次のイベントが同じ行にならないようにします
+  ///
+  /// # Return
+  /// * `std::io::Result<usize>`: 書き出したイベントの件数
+  pub fn flush(&self) -> std::io::Result<usize> {
+    let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+    if pending.is_empty() {
+      return Ok(0);
+    }
+    let mut lines = vec![];
+    if self.interrupted.load(Ordering::SeqCst) {
+      lines.push(b'\n');
+    }
+    for envelope in pending.iter() {
+      serde_json::to_writer(&mut lines, envelope)?;
+      lines.push(b'\n');
+    }
+    if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+      std::fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+    if let Err(e) = file.write_all(&lines) {
+      self.interrupted.store(true, Ordering::SeqCst);
+      Err(e)?
+    }
+    self.interrupted.store(false, Ordering::SeqCst);
+    let count = pending.len();
+    pending.clear();
+    Ok(count)
+  }
+}
+
+impl OrderEventPublisher for FileOrderEventOutbox {
+  /// コマンドのスパンの中で呼び出すため、そのトレースコンテキストをメタデータに格納します。
+  /// イベントIDはイベントごとに採番します
+  fn publish(&self, events: &[OrderEvent]) {
+    let metadata = EventMetadata::default().with_trace_context(propagation::current_trace_context());
+    self.pending
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner)
+      .extend(events.iter().map(|event| {
+        EventEnvelope::new(event.clone(), metadata.clone().with_event_id(EventId::generate(&UuidV4Generator)))
+      }));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::{TimeZone, Utc};
+  use command_domain::clock::FixedClock;
+  use command_domain::customer::customer_id::CustomerId;
+  use command_domain::id_generator::UuidV4Generator;
+  use command_domain::order::order_id::OrderId;
+  use command_domain::order::order_item::{OrderItem, OrderItemParams};
+  use command_domain::order::order_item_id::OrderItemId;
+  use command_domain::order::order_pricing::OrderPricing;
+  use command_domain::order::Order;
+  use command_domain::shipping::delivery_method::DeliveryMethod;
+  use command_domain::shipping::shipping_address::ShippingAddress;
+  use command_domain::shipping::shipping_details::ShippingDetails;
+  use command_domain::tax::region::Region;
+  use command_domain::tax::tax_rule::TaxRules;
+  use command_domain::value_object::currency::Currency;
+  use command_domain::value_object::discount::Discount;
+  use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+  use rust_decimal::Decimal;
+  use shared_telemetry::tracer;
+  use std::str::FromStr;
+  use tracing_subscriber::layer::SubscriberExt;
+
+  fn order_events() -> Vec<OrderEvent> {
+    let clock = FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap());
+    let item = OrderItem::place_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id: 1,
+      product_name: "hogehoge",
+      product_category: "general",
+      unit_price: Decimal::from(500),
+      currency: "JPY",
+      discount: Discount::try_from(10).unwrap(),
+      quantity: 2,
+    }).unwrap();
+    Order::place_order(
+      OrderId::generate(&UuidV4Generator),
+      CustomerId::generate(&UuidV4Generator),
+      &clock,
+      OrderPricing {
+        currency: Currency::JPY,
+        rounding_policy: Currency::JPY.default_rounding_policy(),
+        region: Region::from_str("JP").unwrap(),
+        tax_rule: &TaxRules::default(),
+      },
+      vec![item],
+      vec![],
+      ShippingDetails {
+        address: ShippingAddress::new("山田 太郎", "JP", "100-0001", Some("東京都"), "千代田区", "千代田1-1", None).unwrap(),
+        delivery_method: DeliveryMethod::Standard,
+      },
+    ).unwrap().1
+  }
+
+  fn event_log(test_name: &str) -> PathBuf {
+    let path = std::env::temp_dir()
+      .join(format!("command-infrastructure-{}-{}", test_name, std::process::id()))
+      .join("order-events.jsonl");
+    let _ = std::fs::remove_file(&path);
+    path
+  }
+
+  #[test]
+  fn test_flush_writes_envelopes_with_trace_context() {
+    let exporter = InMemorySpanExporter::default();
+    let tracer_provider = SdkTracerProvider::builder().with_simple_exporter(exporter.clone()).build();
+    let subscriber = tracing_subscriber::registry().with(tracer::layer(&tracer_provider, "test-service"));
+    let outbox = FileOrderEventOutbox::new(event_log("flush"));
+    let events = order_events();
+
+    tracing::subscriber::with_default(subscriber, || {
+      tracing::info_span!("place_order").in_scope(|| outbox.publish(&events));
+    });
+    let pending = outbox.pending_len();
+    let flushed = outbox.flush().unwrap();
+    let envelopes = std::fs::read_to_string(outbox.path())
+      .unwrap()
+      .lines()
+      .map(|line| serde_json::from_str::<EventEnvelope<OrderEvent>>(line).unwrap())
+      .collect::<Vec<_>>();
+    let spans = exporter.get_finished_spans().unwrap();
+    let place_order = spans.iter().find(|span| span.name == "place_order").unwrap();
+
+    // assert
+    assert_eq!(events.len(), pending);
+    assert_eq!(events.len(), flushed);
+    assert_eq!(0, outbox.pending_len());
+    assert_eq!(events, envelopes.iter().map(|envelope| envelope.event.clone()).collect::<Vec<_>>());
+    assert!(envelopes
+      .iter()
+      .all(|envelope| envelope.metadata.trace_context["traceparent"].contains(&place_order.span_context.trace_id().to_string())));
+    assert_eq!(
+      events.len(),
+      envelopes.iter().filter_map(|envelope| envelope.metadata.event_id.clone()).collect::<std::collections::HashSet<_>>().len(),
+    );
+  }
+
+  #[test]
+  fn test_flush_appends_and_keeps_failed_events() {
+    let path = event_log("append");
+    let outbox = FileOrderEventOutbox::new(&path);
+    let blocked = FileOrderEventOutbox::new(path.join("order-events.jsonl"));
+
+    outbox.publish(&order_events());
+    outbox.flush().unwrap();
+    outbox.publish(&order_events());
+    outbox.flush().unwrap();
+    let empty = outbox.flush().unwrap();
+    blocked.publish(&order_events());
+    let failed = blocked.flush();
+
+    // assert
+    assert_eq!(4, std::fs::read_to_string(&path).unwrap().lines().count());
+    assert_eq!(0, empty);
+    assert!(failed.is_err());
+    assert_eq!(2, blocked.pending_len());
+  }
+}
@@ -0,0 +1,97 @@
+use command_domain::customer::customer_id::CustomerId;
+use command_domain::customer::customer_repository::CustomerRepository;
+use command_domain::customer::Customer;
+use command_domain::repository::{RepositoryError, Versioned};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// メモリ上に顧客を保持するリポジトリです
+///
+/// 更新はMutexの中でバージョンを比較してから行うため、
+/// 同時に更新された場合は後から更新した側が`VersionConflict`になります
+#[derive(Debug, Default)]
+pub struct InMemoryCustomerRepository {
+  customers: Mutex<HashMap<CustomerId, Versioned<Customer>>>,
+}
+
+impl InMemoryCustomerRepository {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// リポジトリを利用できる場合trueを返します
+  ///
+  /// 更新中のスレッドがパニックしてロックが壊れた場合、以降の操作はすべて失敗するためfalseになります
+  pub fn is_available(&self) -> bool {
+    !self.customers.is_poisoned()
+  }
+}
+
+impl CustomerRepository for InMemoryCustomerRepository {
+  fn find_by_id(&self, customer_id: &CustomerId) -> Result<Option<Versioned<Customer>>, RepositoryError> {
+    Ok(self.customers.lock().unwrap().get(customer_id).cloned())
+  }
+
+  fn insert(&self, customer: Customer) -> Result<(), RepositoryError> {
+    let mut customers = self.customers.lock().unwrap();
+    if customers.contains_key(customer.get_id()) {
+      Err(RepositoryError::AlreadyExists(customer.get_id().to_string()))?
+    }
+    customers.insert(customer.get_id().clone(), Versioned { aggregate: customer, version: 1 });
+    Ok(())
+  }
+
+  fn update(&self, customer: Customer, expected_version: u64) -> Result<(), RepositoryError> {
+    let mut customers = self.customers.lock().unwrap();
+    let Some(current) = customers.get_mut(customer.get_id()) else {
+      Err(RepositoryError::NotFound(customer.get_id().to_string()))?
+    };
+    if current.version != expected_version {
+      Err(RepositoryError::VersionConflict {
+        id: customer.get_id().to_string(),
+        expected: expected_version,
+        actual: current.version,
+      })?
+    }
+    *current = Versioned { aggregate: customer, version: expected_version + 1 };
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::{TimeZone, Utc};
+  use command_domain::clock::FixedClock;
+  use command_domain::customer::customer_limits::CustomerLimits;
+  use command_domain::id_generator::UuidV4Generator;
+
+  fn customer() -> Customer {
+    let clock = FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap());
+    Customer::register(CustomerId::generate(&UuidV4Generator), &clock, "山田 太郎", CustomerLimits::unlimited()).unwrap().0
+  }
+
+  #[test]
+  fn test_insert_and_update_success() {
+    let repository = InMemoryCustomerRepository::new();
+    let customer = customer();
+    repository.insert(customer.clone()).unwrap();
+
+    repository.update(customer.clone(), 1).unwrap();
+
+    // assert
+    assert_eq!(2, repository.find_by_id(customer.get_id()).unwrap().unwrap().version);
+    assert!(matches!(repository.insert(customer.clone()), Err(RepositoryError::AlreadyExists(_))));
+    assert!(matches!(repository.update(customer, 1), Err(RepositoryError::VersionConflict { expected: 1, actual: 2, .. })));
+  }
+
+  #[test]
+  fn test_update_not_found_failed() {
+    let repository = InMemoryCustomerRepository::new();
+
+    let result = repository.update(customer(), 1);
+
+    // assert
+    assert!(matches!(result, Err(RepositoryError::NotFound(_))))
+  }
+}
@@ -0,0 +1,149 @@
+use command_domain::order::order_id::OrderId;
+use command_domain::order::order_repository::OrderRepository;
+use command_domain::order::Order;
+use command_domain::repository::{RepositoryError, Versioned};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// メモリ上に注文を保持するリポジトリです
+///
+/// 更新はMutexの中でバージョンを比較してから行うため、
+/// 同時に更新された場合は後から更新した側が`VersionConflict`になります
+#[derive(Debug, Default)]
+pub struct InMemoryOrderRepository {
+  orders: Mutex<HashMap<OrderId, Versioned<Order>>>,
+}
+
+impl InMemoryOrderRepository {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// リポジトリを利用できる場合trueを返します
+  ///
+  /// 更新中のスレッドがパニックしてロックが壊れた場合、以降の操作はすべて失敗するためfalseになります
+  pub fn is_available(&self) -> bool {
+    !self.orders.is_poisoned()
+  }
+}
+
+impl OrderRepository for InMemoryOrderRepository {
+  fn find_by_id(&self, order_id: &OrderId) -> Result<Option<Versioned<Order>>, RepositoryError> {
+    Ok(self.orders.lock().unwrap().get(order_id).cloned())
+  }
+
+  fn insert(&self, order: Order) -> Result<(), RepositoryError> {
+    let mut orders = self.orders.lock().unwrap();
+    if orders.contains_key(order.get_id()) {
+      Err(RepositoryError::AlreadyExists(order.get_id().to_string()))?
+    }
+    orders.insert(order.get_id().clone(), Versioned { aggregate: order, version: 1 });
+    Ok(())
+  }
+
+  fn update(&self, order: Order, expected_version: u64) -> Result<(), RepositoryError> {
+    let mut orders = self.orders.lock().unwrap();
+    let Some(current) = orders.get_mut(order.get_id()) else {
+      Err(RepositoryError::NotFound(order.get_id().to_string()))?
+    };
+    if current.version != expected_version {
+      Err(RepositoryError::VersionConflict {
+        id: order.get_id().to_string(),
+        expected: expected_version,
+        actual: current.version,
+      })?
+    }
+    *current = Versioned { aggregate: order, version: expected_version + 1 };
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::{TimeZone, Utc};
+  use command_domain::clock::FixedClock;
+  use command_domain::customer::customer_id::CustomerId;
+  use command_domain::id_generator::UuidV4Generator;
+  use command_domain::order::order_item::{OrderItem, OrderItemParams};
+  use command_domain::order::order_item_id::OrderItemId;
+  use command_domain::order::order_pricing::OrderPricing;
+  use command_domain::shipping::delivery_method::DeliveryMethod;
+  use command_domain::shipping::shipping_address::ShippingAddress;
+  use command_domain::shipping::shipping_details::ShippingDetails;
+  use command_domain::tax::region::Region;
+  use command_domain::tax::tax_rule::TaxRules;
+  use command_domain::value_object::currency::Currency;
+  use command_domain::value_object::discount::Discount;
+  use rust_decimal::Decimal;
+  use std::str::FromStr;
+
+  fn order() -> Order {
+    let item = OrderItem::place_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id: 1,
+      product_name: "hogehoge",
+      product_category: "general",
+      unit_price: Decimal::from(500),
+      currency: "JPY",
+      discount: Discount::none(),
+      quantity: 2,
+    }).unwrap();
+    let address = ShippingAddress::new(
+      "山田 太郎", "JP", "100-0001", Some("東京都"), "千代田区", "千代田1-1", None,
+    ).unwrap();
+    Order::place_order(
+      OrderId::generate(&UuidV4Generator),
+      CustomerId::generate(&UuidV4Generator),
+      &FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()),
+      OrderPricing {
+        currency: Currency::JPY,
+        rounding_policy: Currency::JPY.default_rounding_policy(),
+        region: Region::from_str("JP").unwrap(),
+        tax_rule: &TaxRules::default(),
+      },
+      vec![item],
+      vec![],
+      ShippingDetails { address, delivery_method: DeliveryMethod::Standard },
+    ).unwrap().0
+  }
+
+  #[test]
+  fn test_insert_and_update_success() {
+    let repository = InMemoryOrderRepository::new();
+    let order = order();
+    repository.insert(order.clone()).unwrap();
+
+    repository.update(order.clone(), 1).unwrap();
+
+    // assert
+    assert_eq!(2, repository.find_by_id(order.get_id()).unwrap().unwrap().version);
+    assert!(matches!(repository.insert(order.clone()), Err(RepositoryError::AlreadyExists(_))));
+    assert!(matches!(repository.update(order, 1), Err(RepositoryError::VersionConflict { expected: 1, actual: 2, .. })));
+  }
+
+  #[test]
+  fn test_update_not_found_failed() {
+    let repository = InMemoryOrderRepository::new();
+
+    let result = repository.update(order(), 1);
+
+    // assert
+    assert!(matches!(result, Err(RepositoryError::NotFound(_))))
+  }
+
+  #[test]
+  fn test_is_available() {
+    let repository = InMemoryOrderRepository::new();
+    let available = repository.is_available();
+
+    let _ = std::panic::catch_unwind(|| {
+      let _orders = repository.orders.lock().unwrap();
+      panic!("panic while holding the lock");
+    });
+
+    // assert
+    assert!(available);
+    assert!(!repository.is_available());
+  }
+}
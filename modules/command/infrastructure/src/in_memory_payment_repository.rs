@@ -0,0 +1,124 @@
+use command_domain::order::order_id::OrderId;
+use command_domain::payment::payment_id::PaymentId;
+use command_domain::payment::payment_repository::PaymentRepository;
+use command_domain::payment::Payment;
+use command_domain::repository::{RepositoryError, Versioned};
+use std::sync::Mutex;
+
+/// メモリ上に支払いを保持するリポジトリです
+///
+/// 注文ごとの支払いを作成順に返すため、保存順のVecで保持します。
+/// 更新はMutexの中でバージョンを比較してから行うため、
+/// 同時に更新された場合は後から更新した側が`VersionConflict`になります
+#[derive(Debug, Default)]
+pub struct InMemoryPaymentRepository {
+  payments: Mutex<Vec<Versioned<Payment>>>,
+}
+
+impl InMemoryPaymentRepository {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// リポジトリを利用できる場合trueを返します
+  ///
+  /// 更新中のスレッドがパニックしてロックが壊れた場合、以降の操作はすべて失敗するためfalseになります
+  pub fn is_available(&self) -> bool {
+    !self.payments.is_poisoned()
+  }
+}
+
+impl PaymentRepository for InMemoryPaymentRepository {
+  fn find_by_id(&self, payment_id: &PaymentId) -> Result<Option<Versioned<Payment>>, RepositoryError> {
+    let payments = self.payments.lock().unwrap();
+    Ok(payments.iter().find(|payment| payment.aggregate.get_id() == payment_id).cloned())
+  }
+
+  fn find_by_order_id(&self, order_id: &OrderId) -> Result<Vec<Payment>, RepositoryError> {
+    let payments = self.payments.lock().unwrap();
+    Ok(payments
+      .iter()
+      .filter(|payment| payment.aggregate.get_order_id() == order_id)
+      .map(|payment| payment.aggregate.clone())
+      .collect())
+  }
+
+  fn insert(&self, payment: Payment) -> Result<(), RepositoryError> {
+    let mut payments = self.payments.lock().unwrap();
+    if payments.iter().any(|current| current.aggregate.get_id() == payment.get_id()) {
+      Err(RepositoryError::AlreadyExists(payment.get_id().to_string()))?
+    }
+    payments.push(Versioned { aggregate: payment, version: 1 });
+    Ok(())
+  }
+
+  fn update(&self, payment: Payment, expected_version: u64) -> Result<(), RepositoryError> {
+    let mut payments = self.payments.lock().unwrap();
+    let Some(current) = payments.iter_mut().find(|current| current.aggregate.get_id() == payment.get_id()) else {
+      Err(RepositoryError::NotFound(payment.get_id().to_string()))?
+    };
+    if current.version != expected_version {
+      Err(RepositoryError::VersionConflict {
+        id: payment.get_id().to_string(),
+        expected: expected_version,
+        actual: current.version,
+      })?
+    }
+    *current = Versioned { aggregate: payment, version: expected_version + 1 };
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use command_domain::id_generator::UuidV4Generator;
+  use command_domain::value_object::currency::Currency;
+  use command_domain::value_object::money::Money;
+  use rust_decimal::Decimal;
+
+  fn payment(order_id: &OrderId) -> Payment {
+    let amount = Money::new(Decimal::from(1000), Currency::JPY).unwrap();
+    Payment::new(PaymentId::generate(&UuidV4Generator), order_id.clone(), amount).unwrap()
+  }
+
+  #[test]
+  fn test_insert_and_update_success() {
+    let repository = InMemoryPaymentRepository::new();
+    let payment = payment(&OrderId::generate(&UuidV4Generator));
+    repository.insert(payment.clone()).unwrap();
+
+    repository.update(payment.clone(), 1).unwrap();
+
+    // assert
+    assert_eq!(2, repository.find_by_id(payment.get_id()).unwrap().unwrap().version);
+    assert!(matches!(repository.insert(payment.clone()), Err(RepositoryError::AlreadyExists(_))));
+    assert!(matches!(repository.update(payment, 1), Err(RepositoryError::VersionConflict { expected: 1, actual: 2, .. })));
+  }
+
+  #[test]
+  fn test_find_by_order_id_success() {
+    let repository = InMemoryPaymentRepository::new();
+    let order_id = OrderId::generate(&UuidV4Generator);
+    let first = payment(&order_id);
+    let second = payment(&order_id);
+    repository.insert(first.clone()).unwrap();
+    repository.insert(payment(&OrderId::generate(&UuidV4Generator))).unwrap();
+    repository.insert(second.clone()).unwrap();
+
+    let result = repository.find_by_order_id(&order_id).unwrap();
+
+    // assert
+    assert_eq!(vec![first, second], result);
+  }
+
+  #[test]
+  fn test_update_not_found_failed() {
+    let repository = InMemoryPaymentRepository::new();
+
+    let result = repository.update(payment(&OrderId::generate(&UuidV4Generator)), 1);
+
+    // assert
+    assert!(matches!(result, Err(RepositoryError::NotFound(_))))
+  }
+}
@@ -0,0 +1,145 @@
+use command_domain::promotion::promotion_id::PromotionId;
+use command_domain::promotion::promotion_repository::PromotionRepository;
+use command_domain::promotion::Promotion;
+use command_domain::repository::{RepositoryError, Versioned};
+use command_domain::value_object::coupon_code::CouponCode;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// メモリ上にプロモーションを保持するリポジトリです
+///
+/// 更新はMutexの中でバージョンを比較してから行うため、
+/// 同時に更新された場合は後から更新した側が`VersionConflict`になります
+#[derive(Debug, Default)]
+pub struct InMemoryPromotionRepository {
+  promotions: Mutex<HashMap<PromotionId, Versioned<Promotion>>>,
+}
+
+impl InMemoryPromotionRepository {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// リポジトリを利用できる場合trueを返します
+  ///
+  /// 更新中のスレッドがパニックしてロックが壊れた場合、以降の操作はすべて失敗するためfalseになります
+  pub fn is_available(&self) -> bool {
+    !self.promotions.is_poisoned()
+  }
+}
+
+impl PromotionRepository for InMemoryPromotionRepository {
+  fn find_by_coupon_code(&self, coupon_code: &CouponCode) -> Result<Option<Versioned<Promotion>>, RepositoryError> {
+    let promotions = self.promotions.lock().unwrap();
+    Ok(
+      promotions
+        .values()
+        .find(|versioned| versioned.aggregate.get_coupon_code() == coupon_code)
+        .cloned()
+    )
+  }
+
+  fn insert(&self, promotion: Promotion) -> Result<(), RepositoryError> {
+    let mut promotions = self.promotions.lock().unwrap();
+    let exists = promotions.values().any(|versioned| {
+      versioned.aggregate.get_id() == promotion.get_id()
+        || versioned.aggregate.get_coupon_code() == promotion.get_coupon_code()
+    });
+    if exists {
+      Err(RepositoryError::AlreadyExists(promotion.get_coupon_code().to_string()))?
+    }
+    promotions.insert(promotion.get_id().clone(), Versioned { aggregate: promotion, version: 1 });
+    Ok(())
+  }
+
+  fn update(&self, promotion: Promotion, expected_version: u64) -> Result<(), RepositoryError> {
+    let mut promotions = self.promotions.lock().unwrap();
+    let Some(current) = promotions.get_mut(promotion.get_id()) else {
+      Err(RepositoryError::NotFound(promotion.get_id().to_string()))?
+    };
+    if current.version != expected_version {
+      Err(RepositoryError::VersionConflict {
+        id: promotion.get_id().to_string(),
+        expected: expected_version,
+        actual: current.version,
+      })?
+    }
+    *current = Versioned { aggregate: promotion, version: expected_version + 1 };
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::{TimeZone, Utc};
+  use command_domain::clock::{Clock, FixedClock};
+  use command_domain::customer::customer_id::CustomerId;
+  use command_domain::id_generator::UuidV4Generator;
+  use command_domain::order::order_id::OrderId;
+  use command_domain::promotion::promotion_terms::{PromotionScope, PromotionTerms};
+  use command_domain::value_object::discount::Discount;
+
+  fn promotion(coupon_code: &str) -> Promotion {
+    let clock = FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap());
+    let terms = PromotionTerms {
+      coupon_code: CouponCode::new(coupon_code).unwrap(),
+      discount: Discount::try_from(10).unwrap(),
+      scope: PromotionScope::Order,
+      stackable: false,
+      valid_from: clock.now(),
+      valid_until: None,
+      usage_limit: None,
+      per_customer_limit: None,
+    };
+    Promotion::create(PromotionId::generate(&UuidV4Generator), &clock, terms).unwrap().0
+  }
+
+  #[test]
+  fn test_insert_and_find_success() {
+    let repository = InMemoryPromotionRepository::new();
+    repository.insert(promotion("AUTUMN")).unwrap();
+
+    let found = repository.find_by_coupon_code(&CouponCode::new("autumn").unwrap()).unwrap();
+
+    // assert
+    assert_eq!(1, found.unwrap().version);
+    assert!(repository.find_by_coupon_code(&CouponCode::new("WINTER").unwrap()).unwrap().is_none());
+  }
+
+  #[test]
+  fn test_insert_duplicate_coupon_failed() {
+    let repository = InMemoryPromotionRepository::new();
+    repository.insert(promotion("AUTUMN")).unwrap();
+
+    let result = repository.insert(promotion("AUTUMN"));
+
+    // assert
+    assert!(matches!(result, Err(RepositoryError::AlreadyExists(_))))
+  }
+
+  #[test]
+  fn test_update_version_conflict_failed() {
+    let repository = InMemoryPromotionRepository::new();
+    repository.insert(promotion("AUTUMN")).unwrap();
+    let clock = FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 2, 9, 0, 0).unwrap());
+    let coupon_code = CouponCode::new("AUTUMN").unwrap();
+    let mut first = repository.find_by_coupon_code(&coupon_code).unwrap().unwrap();
+    let mut second = repository.find_by_coupon_code(&coupon_code).unwrap().unwrap();
+    let id = first.aggregate.get_id().to_string();
+    first.aggregate.redeem(&OrderId::generate(&UuidV4Generator), &CustomerId::generate(&UuidV4Generator), &clock).unwrap();
+    second.aggregate.redeem(&OrderId::generate(&UuidV4Generator), &CustomerId::generate(&UuidV4Generator), &clock).unwrap();
+
+    repository.update(first.aggregate, first.version).unwrap();
+    let result = repository.update(second.aggregate, second.version);
+
+    // assert
+    assert_eq!(
+      Err(RepositoryError::VersionConflict { id, expected: 1, actual: 2 }),
+      result
+    );
+    let stored = repository.find_by_coupon_code(&coupon_code).unwrap().unwrap();
+    assert_eq!(1, stored.aggregate.get_redemption_count());
+    assert_eq!(2, stored.version);
+  }
+}
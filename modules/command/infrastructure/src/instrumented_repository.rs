@@ -0,0 +1,195 @@
+use command_domain::customer::customer_id::CustomerId;
+use command_domain::customer::customer_repository::CustomerRepository;
+use command_domain::customer::Customer;
+use command_domain::order::order_id::OrderId;
+use command_domain::order::order_repository::OrderRepository;
+use command_domain::order::Order;
+use command_domain::payment::payment_id::PaymentId;
+use command_domain::payment::payment_repository::PaymentRepository;
+use command_domain::payment::Payment;
+use command_domain::promotion::promotion_repository::PromotionRepository;
+use command_domain::promotion::Promotion;
+use command_domain::repository::{RepositoryError, Versioned};
+use command_domain::value_object::coupon_code::CouponCode;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::info_span;
+
+/// ストアの操作を計測するトレイトです
+pub trait StoreMetrics: Send + Sync {
+  /// ストアを操作したことを記録します
+  ///
+  /// # Arguments
+  /// * `aggregate_type`: 集約の種類(例: `order`)
+  /// * `operation`: 操作の種類(例: `find_by_id`)
+  /// * `elapsed`: 処理時間
+  fn operation_completed(&self, aggregate_type: &'static str, operation: &'static str, elapsed: Duration);
+
+  /// 楽観的排他制御でバージョンが競合したことを記録します
+  ///
+  /// # Arguments
+  /// * `aggregate_type`: 集約の種類
+  fn version_conflicted(&self, aggregate_type: &'static str);
+}
+
+/// リポジトリの操作の処理時間とバージョンの競合を記録するデコレーターです
+///
+/// 操作ごとにスパンを作成します。取得は`aggregate.load`、保存は`event.append`です
+///
+/// 集約の種類ごとのリポジトリのトレイトを実装するため、処理クラスにはそのまま注入できます
+pub struct InstrumentedRepository<R: ?Sized> {
+  inner: Arc<R>,
+  aggregate_type: &'static str,
+  metrics: Arc<dyn StoreMetrics>,
+}
+
+impl<R: ?Sized> InstrumentedRepository<R> {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `inner`: 計測するリポジトリ
+  /// * `aggregate_type`: 集約の種類
+  /// * `metrics`: Arc<dyn StoreMetrics>
+  ///
+  /// # Return
+  /// * `InstrumentedRepository<R>`
+  pub fn new(inner: Arc<R>, aggregate_type: &'static str, metrics: Arc<dyn StoreMetrics>) -> Self {
+    Self { inner, aggregate_type, metrics }
+  }
+
+  /// リポジトリの操作を計測します
+  ///
+  /// `insert`と`update`は集約のイベントの保存、それ以外は集約の取得として扱います
+  fn observe<T>(
+    &self,
+    operation: &'static str,
+    f: impl FnOnce(&R) -> Result<T, RepositoryError>,
+  ) -> Result<T, RepositoryError> {
+    let span = match operation {
+      "insert" | "update" => info_span!("event.append", aggregate_type = self.aggregate_type, operation),
+      _ => info_span!("aggregate.load", aggregate_type = self.aggregate_type, operation),
+    };
+    let _entered = span.enter();
+    let started_at = Instant::now();
+    let result = f(self.inner.as_ref());
+    self.metrics.operation_completed(self.aggregate_type, operation, started_at.elapsed());
+    if let Err(RepositoryError::VersionConflict { .. }) = result {
+      self.metrics.version_conflicted(self.aggregate_type);
+    }
+    result
+  }
+}
+
+impl<R: OrderRepository + ?Sized> OrderRepository for InstrumentedRepository<R> {
+  fn find_by_id(&self, order_id: &OrderId) -> Result<Option<Versioned<Order>>, RepositoryError> {
+    self.observe("find_by_id", |inner| inner.find_by_id(order_id))
+  }
+
+  fn insert(&self, order: Order) -> Result<(), RepositoryError> {
+    self.observe("insert", |inner| inner.insert(order))
+  }
+
+  fn update(&self, order: Order, expected_version: u64) -> Result<(), RepositoryError> {
+    self.observe("update", |inner| inner.update(order, expected_version))
+  }
+}
+
+impl<R: CustomerRepository + ?Sized> CustomerRepository for InstrumentedRepository<R> {
+  fn find_by_id(&self, customer_id: &CustomerId) -> Result<Option<Versioned<Customer>>, RepositoryError> {
+    self.observe("find_by_id", |inner| inner.find_by_id(customer_id))
+  }
+
+  fn insert(&self, customer: Customer) -> Result<(), RepositoryError> {
+    self.observe("insert", |inner| inner.insert(customer))
+  }
+
+  fn update(&self, customer: Customer, expected_version: u64) -> Result<(), RepositoryError> {
+    self.observe("update", |inner| inner.update(customer, expected_version))
+  }
+}
+
+impl<R: PaymentRepository + ?Sized> PaymentRepository for InstrumentedRepository<R> {
+  fn find_by_id(&self, payment_id: &PaymentId) -> Result<Option<Versioned<Payment>>, RepositoryError> {
+    self.observe("find_by_id", |inner| inner.find_by_id(payment_id))
+  }
+
+  fn find_by_order_id(&self, order_id: &OrderId) -> Result<Vec<Payment>, RepositoryError> {
+    self.observe("find_by_order_id", |inner| inner.find_by_order_id(order_id))
+  }
+
+  fn insert(&self, payment: Payment) -> Result<(), RepositoryError> {
+    self.observe("insert", |inner| inner.insert(payment))
+  }
+
+  fn update(&self, payment: Payment, expected_version: u64) -> Result<(), RepositoryError> {
+    self.observe("update", |inner| inner.update(payment, expected_version))
+  }
+}
+
+impl<R: PromotionRepository + ?Sized> PromotionRepository for InstrumentedRepository<R> {
+  fn find_by_coupon_code(&self, coupon_code: &CouponCode) -> Result<Option<Versioned<Promotion>>, RepositoryError> {
+    self.observe("find_by_coupon_code", |inner| inner.find_by_coupon_code(coupon_code))
+  }
+
+  fn insert(&self, promotion: Promotion) -> Result<(), RepositoryError> {
+    self.observe("insert", |inner| inner.insert(promotion))
+  }
+
+  fn update(&self, promotion: Promotion, expected_version: u64) -> Result<(), RepositoryError> {
+    self.observe("update", |inner| inner.update(promotion, expected_version))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::in_memory_customer_repository::InMemoryCustomerRepository;
+  use chrono::{TimeZone, Utc};
+  use command_domain::clock::FixedClock;
+  use command_domain::customer::customer_limits::CustomerLimits;
+  use std::str::FromStr;
+  use std::sync::Mutex;
+
+  /// 記録した内容を保持するStoreMetricsです
+  #[derive(Default)]
+  struct RecordingMetrics {
+    operations: Mutex<Vec<(&'static str, &'static str)>>,
+    conflicts: Mutex<Vec<&'static str>>,
+  }
+
+  impl StoreMetrics for RecordingMetrics {
+    fn operation_completed(&self, aggregate_type: &'static str, operation: &'static str, _elapsed: Duration) {
+      self.operations.lock().unwrap().push((aggregate_type, operation));
+    }
+
+    fn version_conflicted(&self, aggregate_type: &'static str) {
+      self.conflicts.lock().unwrap().push(aggregate_type);
+    }
+  }
+
+  #[test]
+  fn test_instrumented_repository() {
+    let metrics = Arc::new(RecordingMetrics::default());
+    let repository = InstrumentedRepository::new(Arc::new(InMemoryCustomerRepository::new()), "customer", metrics.clone());
+    let clock = FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap());
+    let (customer, _) = Customer::register(
+      CustomerId::from_str("CUSTOMER-00000000-0000-0000-0000-000000000001").unwrap(),
+      &clock,
+      "山田 太郎",
+      CustomerLimits::unlimited(),
+    ).unwrap();
+
+    repository.insert(customer.clone()).unwrap();
+    let found = repository.find_by_id(customer.get_id()).unwrap();
+    let result = repository.update(customer, 2);
+
+    // assert
+    assert_eq!(Some(1), found.map(|versioned| versioned.version));
+    assert!(matches!(result, Err(RepositoryError::VersionConflict { .. })));
+    assert_eq!(
+      vec![("customer", "insert"), ("customer", "find_by_id"), ("customer", "update")],
+      *metrics.operations.lock().unwrap(),
+    );
+    assert_eq!(vec!["customer"], *metrics.conflicts.lock().unwrap());
+  }
+}
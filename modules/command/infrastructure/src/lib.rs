@@ -0,0 +1,7 @@
+pub mod fake_payment_provider;
+pub mod file_order_event_outbox;
+pub mod in_memory_customer_repository;
+pub mod in_memory_order_repository;
+pub mod in_memory_payment_repository;
+pub mod in_memory_promotion_repository;
+pub mod instrumented_repository;
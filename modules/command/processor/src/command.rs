@@ -0,0 +1,282 @@
+use chrono::{DateTime, Utc};
+use command_domain::order::order_error::OrderError;
+use command_domain::shipping::shipping_address::ShippingAddress;
+use command_domain::value_object::currency::Currency;
+use command_domain::value_object::discount::Discount;
+use command_domain::value_object::money::Money;
+use rust_decimal::Decimal;
+
+/// 注文確定コマンドです
+///
+/// currency: ISO-4217の通貨コード
+///
+/// region: 課税地域(例: `JP`、`US-CA`)
+///
+/// items: 注文する商品の一覧
+///
+/// discounts: 注文全体に対する割引の一覧
+///
+/// coupon_code: プロモーションのクーポンコード(使用しない場合はNone)
+///
+/// customer_id: 注文する顧客のID
+///
+/// shipping_address: 配送先の住所
+///
+/// delivery_method: 配送方法(`standard`、`express`、`scheduled`)
+#[derive(Debug, Clone)]
+pub struct PlaceOrder {
+  pub customer_id: String,
+  pub currency: String,
+  pub region: String,
+  pub items: Vec<PlaceOrderItem>,
+  pub discounts: Vec<PlaceOrderDiscount>,
+  pub coupon_code: Option<String>,
+  pub shipping_address: ShippingAddressValue,
+  pub delivery_method: String,
+}
+
+/// 注文確定コマンドの明細です
+///
+/// product_id: 商品ID
+///
+/// product_name: 商品名
+///
+/// product_category: 商品カテゴリ(税ルールの判定に使用します)
+///
+/// unit_price: 単価
+///
+/// discount: 明細に対する割引(割引なしの場合はNone)
+///
+/// quantity: 数量
+#[derive(Debug, Clone)]
+pub struct PlaceOrderItem {
+  pub product_id: i32,
+  pub product_name: String,
+  pub product_category: String,
+  pub unit_price: Decimal,
+  pub discount: Option<DiscountValue>,
+  pub quantity: i32,
+}
+
+/// 注文確定コマンドの注文割引です
+///
+/// discount: 割引の値
+///
+/// coupon_code: クーポンによる割引の場合はクーポンコード
+///
+/// stackable: 他の注文割引と併用できる場合true
+#[derive(Debug, Clone)]
+pub struct PlaceOrderDiscount {
+  pub discount: DiscountValue,
+  pub coupon_code: Option<String>,
+  pub stackable: bool,
+}
+
+/// 割引の値です
+///
+/// Percentage: 割合(%)
+///
+/// FixedAmount: 注文の通貨での固定金額
+#[derive(Debug, Clone)]
+pub enum DiscountValue {
+  Percentage(Decimal),
+  FixedAmount(Decimal),
+}
+
+impl DiscountValue {
+  /// 割引の値をDiscountに変換します
+  ///
+  /// # Arguments
+  /// * `currency`: 固定金額の通貨
+  ///
+  /// # Return
+  /// * `Result<Discount, OrderError>`
+  pub fn to_discount(self, currency: Currency) -> Result<Discount, OrderError> {
+    let discount = match self {
+      DiscountValue::Percentage(rate) => Discount::percentage(rate)?,
+      DiscountValue::FixedAmount(amount) => Discount::fixed_amount(Money::new(amount, currency)?)?,
+    };
+    Ok(discount)
+  }
+}
+
+/// 配送先の住所の値です
+///
+/// country: ISO-3166-1 alpha-2の国コード
+///
+/// subdivision: 都道府県・州(JP・US・CAでは必須)
+///
+/// line2: 建物名・部屋番号
+#[derive(Debug, Clone)]
+pub struct ShippingAddressValue {
+  pub recipient: String,
+  pub country: String,
+  pub postal_code: String,
+  pub subdivision: Option<String>,
+  pub city: String,
+  pub line1: String,
+  pub line2: Option<String>,
+}
+
+impl ShippingAddressValue {
+  /// 住所の値をShippingAddressに変換します
+  ///
+  /// # Return
+  /// * `Result<ShippingAddress, OrderError>`
+  pub fn to_address(&self) -> Result<ShippingAddress, OrderError> {
+    let address = ShippingAddress::new(
+      &self.recipient,
+      &self.country,
+      &self.postal_code,
+      self.subdivision.as_deref(),
+      &self.city,
+      &self.line1,
+      self.line2.as_deref(),
+    )?;
+    Ok(address)
+  }
+}
+
+/// 明細追加コマンドです
+///
+/// 確定後、支払い前の注文に明細を追加します
+///
+/// order_id: 明細を追加する注文のID
+///
+/// items: 追加する商品の一覧(注文の通貨で指定します)
+#[derive(Debug, Clone)]
+pub struct AddOrderItems {
+  pub order_id: String,
+  pub items: Vec<PlaceOrderItem>,
+}
+
+/// 配送先変更コマンドです
+///
+/// order_id: 変更する注文のID
+///
+/// shipping_address: 変更後の住所
+#[derive(Debug, Clone)]
+pub struct ChangeShippingAddress {
+  pub order_id: String,
+  pub shipping_address: ShippingAddressValue,
+}
+
+/// 出荷コマンドです
+///
+/// order_id: 出荷する注文のID
+#[derive(Debug, Clone)]
+pub struct ShipOrder {
+  pub order_id: String,
+}
+
+/// 支払い承認コマンドです
+///
+/// 注文の支払総額で決済代行サービスに与信を確保します
+///
+/// order_id: 支払い対象の注文のID
+#[derive(Debug, Clone)]
+pub struct AuthorizePayment {
+  pub order_id: String,
+}
+
+/// 支払いの売上確定コマンドです
+///
+/// payment_id: 売上を確定する支払いのID
+#[derive(Debug, Clone)]
+pub struct CapturePayment {
+  pub payment_id: String,
+}
+
+/// 返金コマンドです
+///
+/// payment_id: 返金する支払いのID
+///
+/// amount: 返金額(全額を返金する場合はNone)
+#[derive(Debug, Clone)]
+pub struct RefundPayment {
+  pub payment_id: String,
+  pub amount: Option<Decimal>,
+}
+
+/// 予約中の返金の再実行コマンドです
+///
+/// 決済代行サービスの結果が得られずに残った返金を、同じ返金IDで再実行します
+///
+/// payment_id: 返金を再実行する支払いのID
+#[derive(Debug, Clone)]
+pub struct RetryRefunds {
+  pub payment_id: String,
+}
+
+/// 返品コマンドです
+///
+/// 返品した明細の返金額を、注文の売上確定済みの支払いから返金します
+///
+/// order_id: 返品する注文のID
+///
+/// items: 返品する明細と数量
+#[derive(Debug, Clone)]
+pub struct ReturnItems {
+  pub order_id: String,
+  pub items: Vec<ReturnItemValue>,
+}
+
+/// 返品する明細です
+///
+/// order_item_id: `ORDER_ITEM-<uuid>`形式の注文アイテムID
+///
+/// quantity: 返品する数量
+#[derive(Debug, Clone)]
+pub struct ReturnItemValue {
+  pub order_item_id: String,
+  pub quantity: i32,
+}
+
+/// プロモーション作成コマンドです
+///
+/// currency: 固定金額の割引の通貨
+///
+/// category: 割引を適用する商品カテゴリー(注文全体に適用する場合はNone)
+///
+/// valid_until: 有効期間の終了日時(無期限の場合はNone)
+///
+/// usage_limit: 全体の使用回数の上限(無制限の場合はNone)
+///
+/// per_customer_limit: 顧客ごとの使用回数の上限(無制限の場合はNone)
+#[derive(Debug, Clone)]
+pub struct CreatePromotion {
+  pub coupon_code: String,
+  pub discount: DiscountValue,
+  pub currency: String,
+  pub category: Option<String>,
+  pub stackable: bool,
+  pub valid_from: DateTime<Utc>,
+  pub valid_until: Option<DateTime<Utc>>,
+  pub usage_limit: Option<u32>,
+  pub per_customer_limit: Option<u32>,
+}
+
+/// 顧客登録コマンドです
+///
+/// name: 顧客名
+///
+/// max_open_orders: 未完了の注文数の上限(無制限の場合はNone)
+///
+/// credit_limit: 与信枠(無制限の場合はNone)
+#[derive(Debug, Clone)]
+pub struct RegisterCustomer {
+  pub name: String,
+  pub max_open_orders: Option<u32>,
+  pub credit_limit: Option<CreditLimit>,
+}
+
+/// 顧客登録コマンドの与信枠です
+///
+/// amount: 金額
+///
+/// currency: ISO-4217の通貨コード
+#[derive(Debug, Clone)]
+pub struct CreditLimit {
+  pub amount: Decimal,
+  pub currency: String,
+}
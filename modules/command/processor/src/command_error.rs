@@ -0,0 +1,140 @@
+use command_domain::customer::customer_error::CustomerError;
+use command_domain::error_code::{ErrorCode, FieldError, FieldPath};
+use command_domain::order::order_error::OrderError;
+use command_domain::payment::payment_error::PaymentError;
+use command_domain::payment::payment_provider::PaymentProviderError;
+use command_domain::promotion::promotion_error::PromotionError;
+use command_domain::repository::RepositoryError;
+use thiserror::Error;
+
+/// コマンド処理のエラーの分類です
+///
+/// HTTPのステータスコードとメトリクスの処理結果の区分は、この分類から決めます
+///
+/// NotFound: 対象の集約やクーポンが見つからない
+///
+/// InvalidState: 集約の状態により処理できない
+///
+/// Conflict: 同時更新や一意性による競合
+///
+/// Unavailable: 外部サービスが利用できない
+///
+/// Invalid: 入力の誤り
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ErrorKind {
+  NotFound,
+  InvalidState,
+  Conflict,
+  Unavailable,
+  Invalid,
+}
+
+/// コマンド処理のエラーです
+#[derive(Debug, Error)]
+pub enum CommandError {
+  #[error(transparent)]
+  InvalidOrder(#[from] OrderError),
+
+  #[error(transparent)]
+  InvalidCustomer(#[from] CustomerError),
+
+  #[error(transparent)]
+  InvalidPromotion(#[from] PromotionError),
+
+  #[error(transparent)]
+  InvalidPayment(#[from] PaymentError),
+
+  #[error(transparent)]
+  Repository(#[from] RepositoryError),
+
+  #[error("{0} could not be processed due to concurrent updates")]
+  ConcurrencyConflict(String),
+
+  #[error("command has {} validation errors", .0.len())]
+  Validation(Vec<CommandError>),
+}
+
+impl CommandError {
+  /// エラーの分類を返します
+  ///
+  /// # Return
+  /// * `ErrorKind`
+  pub fn kind(&self) -> ErrorKind {
+    match self {
+      CommandError::InvalidOrder(OrderError::OrderNotFound(_))
+      | CommandError::InvalidCustomer(CustomerError::CustomerNotFound(_))
+      | CommandError::InvalidPromotion(PromotionError::CouponNotFound(_) | PromotionError::RedemptionNotFound { .. })
+      | CommandError::InvalidPayment(PaymentError::PaymentNotFound(_))
+      | CommandError::Repository(RepositoryError::NotFound(_)) => ErrorKind::NotFound,
+      CommandError::InvalidOrder(OrderError::AlreadyShipped(_) | OrderError::InvalidStatus { .. })
+      | CommandError::InvalidPayment(
+        PaymentError::InvalidTransition { .. } | PaymentError::CapturedPaymentNotFound(_) | PaymentError::RefundNotPending { .. },
+      ) => {
+        ErrorKind::InvalidState
+      }
+      CommandError::Repository(RepositoryError::AlreadyExists(_) | RepositoryError::VersionConflict { .. })
+      | CommandError::ConcurrencyConflict(_) => ErrorKind::Conflict,
+      CommandError::InvalidPayment(PaymentError::Provider(PaymentProviderError::Unavailable(_))) => ErrorKind::Unavailable,
+      _ => ErrorKind::Invalid,
+    }
+  }
+}
+
+impl ErrorCode for CommandError {
+  fn code(&self) -> &'static str {
+    match self {
+      CommandError::InvalidOrder(e) => e.code(),
+      CommandError::InvalidCustomer(e) => e.code(),
+      CommandError::InvalidPromotion(e) => e.code(),
+      CommandError::InvalidPayment(e) => e.code(),
+      CommandError::Repository(e) => e.code(),
+      CommandError::ConcurrencyConflict(_) => "command.concurrency_conflict",
+      CommandError::Validation(_) => "command.validation_failed",
+    }
+  }
+
+  fn field_path(&self) -> Option<FieldPath> {
+    match self {
+      CommandError::InvalidOrder(e) => e.field_path(),
+      CommandError::InvalidCustomer(e) => e.field_path(),
+      CommandError::InvalidPromotion(e) => e.field_path(),
+      CommandError::InvalidPayment(e) => e.field_path(),
+      CommandError::Repository(e) => e.field_path(),
+      CommandError::ConcurrencyConflict(_) | CommandError::Validation(_) => None,
+    }
+  }
+
+  fn field_errors(&self) -> Vec<FieldError> {
+    match self {
+      CommandError::InvalidOrder(e) => e.field_errors(),
+      CommandError::InvalidCustomer(e) => e.field_errors(),
+      CommandError::InvalidPromotion(e) => e.field_errors(),
+      CommandError::InvalidPayment(e) => e.field_errors(),
+      CommandError::Repository(e) => e.field_errors(),
+      CommandError::ConcurrencyConflict(_) => vec![],
+      CommandError::Validation(errors) => errors.iter().flat_map(ErrorCode::field_errors).collect(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use command_domain::id_generator::UuidV4Generator;
+  use command_domain::order::order_id::OrderId;
+
+  #[test]
+  fn test_command_error_kind() {
+    let kind = |e: CommandError| e.kind();
+
+    // assert
+    assert_eq!(ErrorKind::NotFound, kind(OrderError::OrderNotFound("order-1".to_string()).into()));
+    assert_eq!(ErrorKind::NotFound, kind(CustomerError::CustomerNotFound("customer-1".to_string()).into()));
+    assert_eq!(ErrorKind::NotFound, kind(PromotionError::CouponNotFound("SAVE100".to_string()).into()));
+    assert_eq!(ErrorKind::NotFound, kind(RepositoryError::NotFound("order-1".to_string()).into()));
+    assert_eq!(ErrorKind::InvalidState, kind(OrderError::AlreadyShipped(OrderId::generate(&UuidV4Generator)).into()));
+    assert_eq!(ErrorKind::Conflict, kind(CommandError::ConcurrencyConflict("order-1".to_string())));
+    assert_eq!(ErrorKind::Unavailable, kind(PaymentError::from(PaymentProviderError::Unavailable("down".to_string())).into()));
+    assert_eq!(ErrorKind::Invalid, kind(OrderError::EmptyOrderItems.into()));
+  }
+}
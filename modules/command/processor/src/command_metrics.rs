@@ -0,0 +1,151 @@
+use crate::command_error::{CommandError, ErrorKind};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// コマンドの処理結果の区分です
+///
+/// Success: 成功
+///
+/// Rejected: 入力や集約の状態による拒否(集約が見つからない場合を含みます)
+///
+/// Conflict: 同時更新による競合
+///
+/// Error: 外部サービスの障害
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CommandOutcome {
+  Success,
+  Rejected,
+  Conflict,
+  Error,
+}
+
+impl CommandOutcome {
+  /// メトリクスのラベルに使う文字列を返します
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      CommandOutcome::Success => "success",
+      CommandOutcome::Rejected => "rejected",
+      CommandOutcome::Conflict => "conflict",
+      CommandOutcome::Error => "error",
+    }
+  }
+}
+
+impl From<&CommandError> for CommandOutcome {
+  fn from(e: &CommandError) -> Self {
+    match e.kind() {
+      ErrorKind::Conflict => CommandOutcome::Conflict,
+      ErrorKind::Unavailable => CommandOutcome::Error,
+      ErrorKind::NotFound | ErrorKind::InvalidState | ErrorKind::Invalid => CommandOutcome::Rejected,
+    }
+  }
+}
+
+/// コマンドの処理を計測するトレイトです
+pub trait CommandMetrics: Send + Sync {
+  /// コマンドを処理したことを記録します
+  ///
+  /// # Arguments
+  /// * `command`: コマンドの種類(例: `place_order`)
+  /// * `outcome`: 処理結果の区分
+  /// * `elapsed`: 処理時間
+  fn command_handled(&self, command: &'static str, outcome: CommandOutcome, elapsed: Duration);
+
+  /// コマンドの処理で発生したイベントを記録します
+  ///
+  /// # Arguments
+  /// * `aggregate_type`: 集約の種類(例: `order`)
+  /// * `count`: イベント数
+  fn events_appended(&self, aggregate_type: &'static str, count: usize);
+}
+
+/// 何も記録しないCommandMetricsです
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopCommandMetrics;
+
+impl CommandMetrics for NoopCommandMetrics {
+  fn command_handled(&self, _command: &'static str, _outcome: CommandOutcome, _elapsed: Duration) {}
+
+  fn events_appended(&self, _aggregate_type: &'static str, _count: usize) {}
+}
+
+/// コマンドを処理し、処理結果と発生したイベントを記録します
+///
+/// 処理結果はログにも出力します。コマンドのスパンの中で呼び出すため、ログにはスパンの注文IDなどのフィールドが付きます
+///
+/// # Arguments
+/// * `metrics`: 記録先
+/// * `command`: コマンドの種類
+/// * `aggregate_type`: イベントを発生させる集約の種類
+/// * `handle`: コマンドの処理
+///
+/// # Return
+/// * `handle`の結果
+pub(crate) fn observe<A, E>(
+  metrics: &dyn CommandMetrics,
+  command: &'static str,
+  aggregate_type: &'static str,
+  handle: impl FnOnce() -> Result<(A, Vec<E>), CommandError>,
+) -> Result<(A, Vec<E>), CommandError> {
+  let started_at = Instant::now();
+  let result = handle();
+  let elapsed = started_at.elapsed();
+  let outcome = match &result {
+    Ok((_, events)) => {
+      metrics.events_appended(aggregate_type, events.len());
+      info!(command, outcome = "success", events = events.len(), elapsed_ms = elapsed.as_millis() as u64, "command handled");
+      CommandOutcome::Success
+    }
+    Err(e) => {
+      let outcome = CommandOutcome::from(e);
+      match outcome {
+        CommandOutcome::Rejected => info!(command, outcome = outcome.as_str(), error = %e, "command rejected"),
+        _ => warn!(command, outcome = outcome.as_str(), error = %e, "command failed"),
+      }
+      outcome
+    }
+  };
+  metrics.command_handled(command, outcome, elapsed);
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use command_domain::order::order_error::OrderError;
+  use std::sync::Mutex;
+
+  /// 記録した内容を保持するCommandMetricsです
+  #[derive(Default)]
+  struct RecordingMetrics {
+    commands: Mutex<Vec<(&'static str, CommandOutcome)>>,
+    events: Mutex<Vec<(&'static str, usize)>>,
+  }
+
+  impl CommandMetrics for RecordingMetrics {
+    fn command_handled(&self, command: &'static str, outcome: CommandOutcome, _elapsed: Duration) {
+      self.commands.lock().unwrap().push((command, outcome));
+    }
+
+    fn events_appended(&self, aggregate_type: &'static str, count: usize) {
+      self.events.lock().unwrap().push((aggregate_type, count));
+    }
+  }
+
+  #[test]
+  fn test_observe() {
+    let metrics = RecordingMetrics::default();
+
+    let success = observe(&metrics, "place_order", "order", || Ok(((), vec![1, 2])));
+    let rejected = observe::<(), i32>(&metrics, "place_order", "order", || Err(OrderError::EmptyOrderItems.into()));
+    let conflict = observe::<(), i32>(&metrics, "ship_order", "order", || Err(CommandError::ConcurrencyConflict("1".to_string())));
+
+    // assert
+    assert!(success.is_ok() && rejected.is_err() && conflict.is_err());
+    assert_eq!(
+      vec![("place_order", CommandOutcome::Success), ("place_order", CommandOutcome::Rejected), ("ship_order", CommandOutcome::Conflict)],
+      *metrics.commands.lock().unwrap(),
+    );
+    assert_eq!(vec![("order", 2)], *metrics.events.lock().unwrap());
+  }
+}
@@ -0,0 +1,131 @@
+use crate::command::RegisterCustomer;
+use crate::command_error::CommandError;
+use crate::command_metrics::{observe, CommandMetrics, NoopCommandMetrics};
+use command_domain::clock::Clock;
+use command_domain::customer::customer_error::CustomerError;
+use command_domain::customer::customer_event::CustomerEvent;
+use command_domain::customer::customer_id::CustomerId;
+use command_domain::customer::customer_limits::CustomerLimits;
+use command_domain::customer::customer_repository::CustomerRepository;
+use command_domain::customer::Customer;
+use command_domain::id_generator::IdGenerator;
+use command_domain::value_object::money::Money;
+use std::sync::Arc;
+use tracing::instrument;
+
+/// 顧客のコマンドを処理するクラスです
+pub struct CustomerCommandProcessor {
+  clock: Arc<dyn Clock>,
+  id_generator: Arc<dyn IdGenerator>,
+  customer_repository: Arc<dyn CustomerRepository>,
+  metrics: Arc<dyn CommandMetrics>,
+}
+
+impl CustomerCommandProcessor {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `clock`: Arc<dyn Clock>
+  /// * `id_generator`: Arc<dyn IdGenerator>
+  /// * `customer_repository`: Arc<dyn CustomerRepository>
+  ///
+  /// # Return
+  /// * `CustomerCommandProcessor`
+  pub fn new(
+    clock: Arc<dyn Clock>,
+    id_generator: Arc<dyn IdGenerator>,
+    customer_repository: Arc<dyn CustomerRepository>,
+  ) -> Self {
+    Self { clock, id_generator, customer_repository, metrics: Arc::new(NoopCommandMetrics) }
+  }
+
+  /// コマンドの処理結果を記録するメトリクスを設定します
+  ///
+  /// 指定がない場合は何も記録しません
+  ///
+  /// # Arguments
+  /// * `metrics`: Arc<dyn CommandMetrics>
+  ///
+  /// # Return
+  /// * `CustomerCommandProcessor`
+  pub fn with_metrics(mut self, metrics: Arc<dyn CommandMetrics>) -> Self {
+    self.metrics = metrics;
+    self
+  }
+
+  /// 顧客を登録します
+  ///
+  /// # Arguments
+  /// * `command`: RegisterCustomer
+  ///
+  /// # Return
+  /// * `Result<(Customer, Vec<CustomerEvent>), CommandError>`
+  #[instrument(skip_all, fields(command = "register_customer"))]
+  pub fn register_customer(&self, command: RegisterCustomer) -> Result<(Customer, Vec<CustomerEvent>), CommandError> {
+    observe(self.metrics.as_ref(), "register_customer", "customer", || self.handle_register_customer(command))
+  }
+
+  fn handle_register_customer(&self, command: RegisterCustomer) -> Result<(Customer, Vec<CustomerEvent>), CommandError> {
+    let credit_limit = command.credit_limit
+      .map(|credit_limit| Money::parse(credit_limit.amount, &credit_limit.currency))
+      .transpose()
+      .map_err(CustomerError::from)?;
+    let limits = CustomerLimits::new(command.max_open_orders, credit_limit)?;
+    let (customer, events) = Customer::register(
+      CustomerId::generate(self.id_generator.as_ref()),
+      self.clock.as_ref(),
+      &command.name,
+      limits,
+    )?;
+    self.customer_repository.insert(customer.clone())?;
+    Ok((customer, events))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::command::CreditLimit;
+  use chrono::{TimeZone, Utc};
+  use command_domain::clock::FixedClock;
+  use command_domain::id_generator::SequentialIdGenerator;
+  use command_infrastructure::in_memory_customer_repository::InMemoryCustomerRepository;
+  use rust_decimal::Decimal;
+
+  fn processor(repository: Arc<InMemoryCustomerRepository>) -> CustomerCommandProcessor {
+    let clock = FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap());
+    CustomerCommandProcessor::new(Arc::new(clock), Arc::new(SequentialIdGenerator::new(1)), repository)
+  }
+
+  #[test]
+  fn test_register_customer_success() {
+    let repository = Arc::new(InMemoryCustomerRepository::new());
+    let command = RegisterCustomer {
+      name: "山田 太郎".to_string(),
+      max_open_orders: Some(3),
+      credit_limit: Some(CreditLimit { amount: Decimal::from(50000), currency: "JPY".to_string() }),
+    };
+
+    let (customer, events) = processor(repository.clone()).register_customer(command).unwrap();
+
+    // assert
+    assert_eq!("CUSTOMER-00000000-0000-0001-0000-000000000001", customer.get_id().to_string());
+    assert_eq!(Some(3), customer.get_limits().max_open_orders());
+    assert_eq!(1, events.len());
+    assert!(repository.find_by_id(customer.get_id()).unwrap().is_some());
+  }
+
+  #[test]
+  fn test_register_customer_failed() {
+    let command = RegisterCustomer {
+      name: "山田 太郎".to_string(),
+      max_open_orders: None,
+      credit_limit: Some(CreditLimit { amount: Decimal::from(50000), currency: "XXX".to_string() }),
+    };
+
+    let result = processor(Arc::new(InMemoryCustomerRepository::new())).register_customer(command);
+
+    // assert
+    assert!(matches!(result, Err(CommandError::InvalidCustomer(CustomerError::InvalidMoney(_)))))
+  }
+}
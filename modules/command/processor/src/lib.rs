@@ -0,0 +1,8 @@
+pub mod command;
+pub mod command_metrics;
+pub mod command_error;
+pub mod customer_command_processor;
+pub mod order_command_processor;
+pub mod payment_command_processor;
+pub mod promotion_command_processor;
+pub mod validation;
@@ -0,0 +1,1292 @@
+use crate::command::{AddOrderItems, ChangeShippingAddress, PlaceOrder, PlaceOrderDiscount, PlaceOrderItem, ShipOrder};
+use crate::command_error::CommandError;
+use crate::command_metrics::{observe, CommandMetrics, NoopCommandMetrics};
+use crate::validation::{finish, validate, validate_all, ValidationMode};
+use command_domain::clock::Clock;
+use command_domain::customer::customer_error::CustomerError;
+use command_domain::customer::customer_id::CustomerId;
+use command_domain::customer::customer_repository::CustomerRepository;
+use command_domain::customer::Customer;
+use command_domain::id_generator::IdGenerator;
+use command_domain::order::order_discount::OrderDiscount;
+use command_domain::order::order_error::OrderError;
+use command_domain::order::order_event::OrderEvent;
+use command_domain::order::order_event_publisher::{NoopOrderEventPublisher, OrderEventPublisher};
+use command_domain::order::order_id::OrderId;
+use command_domain::order::order_item::{OrderItem, OrderItemParams};
+use command_domain::order::order_item_id::OrderItemId;
+use command_domain::order::order_pricing::OrderPricing;
+use command_domain::order::order_repository::OrderRepository;
+use command_domain::order::Order;
+use command_domain::product::product_quantity_limits::ProductQuantityLimits;
+use command_domain::promotion::promotion_error::PromotionError;
+use command_domain::promotion::promotion_repository::PromotionRepository;
+use command_domain::promotion::Promotion;
+use command_domain::repository::{RepositoryError, Versioned};
+use command_domain::shipping::delivery_method::DeliveryMethod;
+use command_domain::shipping::shipping_details::ShippingDetails;
+use command_domain::tax::region::Region;
+use command_domain::tax::tax_rule::{TaxRule, TaxRules};
+use command_domain::validation::{zip, Validated, ValidationErrors};
+use command_domain::value_object::coupon_code::CouponCode;
+use command_domain::value_object::currency::Currency;
+use command_domain::value_object::discount::Discount;
+use command_domain::value_object::money::{Money, MoneyError};
+use command_domain::value_object::rounding_policy::RoundingPolicy;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{error, field, instrument, Span};
+
+/// 同時更新による競合時の再試行回数の上限です
+const MAX_PLACEMENT_ATTEMPTS: usize = 10;
+
+/// 検証済みの注文確定コマンドの入力です
+struct PlaceOrderInput {
+  customer_id: CustomerId,
+  currency: Currency,
+  region: Region,
+  shipping: ShippingDetails,
+  order_items: Vec<OrderItem>,
+  order_discounts: Vec<OrderDiscount>,
+  coupon_code: Option<CouponCode>,
+}
+
+/// 注文のコマンドを処理するクラスです
+///
+/// 日時は`Clock`から、IDは`IdGenerator`から取得するため、
+/// テストでは`FixedClock`と`SequentialIdGenerator`を注入します。
+/// 注文を保存したコマンドのイベントは、`OrderEventPublisher`で読み取り側へ配信します
+pub struct OrderCommandProcessor {
+  clock: Arc<dyn Clock>,
+  id_generator: Arc<dyn IdGenerator>,
+  rounding_policies: HashMap<Currency, RoundingPolicy>,
+  tax_rule: Arc<dyn TaxRule>,
+  quantity_limits: ProductQuantityLimits,
+  validation_mode: ValidationMode,
+  customer_repository: Arc<dyn CustomerRepository>,
+  order_repository: Arc<dyn OrderRepository>,
+  promotion_repository: Option<Arc<dyn PromotionRepository>>,
+  metrics: Arc<dyn CommandMetrics>,
+  event_publisher: Arc<dyn OrderEventPublisher>,
+}
+
+impl OrderCommandProcessor {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `clock`: Arc<dyn Clock>
+  /// * `id_generator`: Arc<dyn IdGenerator>
+  /// * `customer_repository`: Arc<dyn CustomerRepository>
+  /// * `order_repository`: Arc<dyn OrderRepository>
+  ///
+  /// # Return
+  /// * `OrderCommandProcessor`
+  pub fn new(
+    clock: Arc<dyn Clock>,
+    id_generator: Arc<dyn IdGenerator>,
+    customer_repository: Arc<dyn CustomerRepository>,
+    order_repository: Arc<dyn OrderRepository>,
+  ) -> Self {
+    Self {
+      clock,
+      id_generator,
+      rounding_policies: HashMap::new(),
+      tax_rule: Arc::new(TaxRules::default()),
+      quantity_limits: ProductQuantityLimits::default(),
+      validation_mode: ValidationMode::default(),
+      customer_repository,
+      order_repository,
+      promotion_repository: None,
+      metrics: Arc::new(NoopCommandMetrics),
+      event_publisher: Arc::new(NoopOrderEventPublisher),
+    }
+  }
+
+  /// コマンドの処理結果を記録するメトリクスを設定します
+  ///
+  /// 指定がない場合は何も記録しません
+  ///
+  /// # Arguments
+  /// * `metrics`: Arc<dyn CommandMetrics>
+  ///
+  /// # Return
+  /// * `OrderCommandProcessor`
+  pub fn with_metrics(mut self, metrics: Arc<dyn CommandMetrics>) -> Self {
+    self.metrics = metrics;
+    self
+  }
+
+  /// 保存した注文のイベントの配信先を設定します
+  ///
+  /// 指定がない場合は配信しません
+  ///
+  /// # Arguments
+  /// * `event_publisher`: Arc<dyn OrderEventPublisher>
+  ///
+  /// # Return
+  /// * `OrderCommandProcessor`
+  pub fn with_event_publisher(mut self, event_publisher: Arc<dyn OrderEventPublisher>) -> Self {
+    self.event_publisher = event_publisher;
+    self
+  }
+
+  /// クーポンの取得に使用するプロモーションのリポジトリを設定します
+  ///
+  /// 指定がない場合はクーポンコードを指定した注文を`CouponNotFound`とします
+  ///
+  /// # Arguments
+  /// * `promotion_repository`: Arc<dyn PromotionRepository>
+  ///
+  /// # Return
+  /// * `OrderCommandProcessor`
+  pub fn with_promotion_repository(mut self, promotion_repository: Arc<dyn PromotionRepository>) -> Self {
+    self.promotion_repository = Some(promotion_repository);
+    self
+  }
+
+  /// 税ルールを設定します
+  ///
+  /// 指定がない場合はすべての明細を非課税として扱います
+  ///
+  /// # Arguments
+  /// * `tax_rule`: Arc<dyn TaxRule>
+  ///
+  /// # Return
+  /// * `OrderCommandProcessor`
+  pub fn with_tax_rule(mut self, tax_rule: Arc<dyn TaxRule>) -> Self {
+    self.tax_rule = tax_rule;
+    self
+  }
+
+  /// 商品ごとの注文数量の上限を設定します
+  ///
+  /// 指定がない場合は数量の上限なしとして扱います
+  ///
+  /// # Arguments
+  /// * `quantity_limits`: ProductQuantityLimits
+  ///
+  /// # Return
+  /// * `OrderCommandProcessor`
+  pub fn with_quantity_limits(mut self, quantity_limits: ProductQuantityLimits) -> Self {
+    self.quantity_limits = quantity_limits;
+    self
+  }
+
+  /// 入力の検証方式を設定します
+  ///
+  /// 指定がない場合は最初のエラーのみを返します
+  ///
+  /// # Arguments
+  /// * `validation_mode`: ValidationMode
+  ///
+  /// # Return
+  /// * `OrderCommandProcessor`
+  pub fn with_validation_mode(mut self, validation_mode: ValidationMode) -> Self {
+    self.validation_mode = validation_mode;
+    self
+  }
+
+  /// 通貨の丸めポリシーを上書きします
+  ///
+  /// 指定がない通貨は`Currency::default_rounding_policy`を使用します
+  ///
+  /// # Arguments
+  /// * `currency`: Currency
+  /// * `rounding_policy`: RoundingPolicy
+  ///
+  /// # Return
+  /// * `OrderCommandProcessor`
+  pub fn with_rounding_policy(mut self, currency: Currency, rounding_policy: RoundingPolicy) -> Self {
+    self.rounding_policies.insert(currency, rounding_policy);
+    self
+  }
+
+  /// 通貨に対応する丸めポリシーを返します
+  fn rounding_policy(&self, currency: Currency) -> RoundingPolicy {
+    self.rounding_policies
+      .get(&currency)
+      .copied()
+      .unwrap_or(currency.default_rounding_policy())
+  }
+
+  /// 注文を確定します
+  ///
+  /// 顧客の未完了の注文数と与信枠を検証し、注文を顧客の未完了の注文として受け付けます。
+  /// クーポンコードが指定された場合は、プロモーションの使用も同時に行います。
+  /// 他の注文と同時に更新してバージョンが競合した場合は、顧客とプロモーションを再取得して再試行します
+  ///
+  /// # Arguments
+  /// * `command`: PlaceOrder
+  ///
+  /// # Return
+  /// * `Result<(Order, Vec<OrderEvent>), CommandError>`
+  #[instrument(skip_all, fields(command = "place_order", order_id))]
+  pub fn place_order(&self, command: PlaceOrder) -> Result<(Order, Vec<OrderEvent>), CommandError> {
+    observe(self.metrics.as_ref(), "place_order", "order", || self.handle_place_order(command))
+      .inspect(|(_, events)| self.event_publisher.publish(events))
+  }
+
+  fn handle_place_order(&self, command: PlaceOrder) -> Result<(Order, Vec<OrderEvent>), CommandError> {
+    let PlaceOrderInput { customer_id, currency, region, shipping, order_items, order_discounts, coupon_code } =
+      self.validate_place_order(command)?;
+    let promotion_repository = match &coupon_code {
+      Some(coupon_code) => Some(
+        self.promotion_repository
+          .as_deref()
+          .ok_or_else(|| PromotionError::CouponNotFound(coupon_code.to_string()))?
+      ),
+      None => None,
+    };
+    let order_id = OrderId::generate(self.id_generator.as_ref());
+    Span::current().record("order_id", field::display(&order_id));
+    let pricing = OrderPricing {
+      currency,
+      rounding_policy: self.rounding_policy(currency),
+      region,
+      tax_rule: self.tax_rule.as_ref(),
+    };
+
+    for _ in 0..MAX_PLACEMENT_ATTEMPTS {
+      let Versioned { aggregate: mut customer, version: customer_version } = self.find_customer(&customer_id)?;
+      let mut promotion = coupon_code
+        .as_ref()
+        .zip(promotion_repository)
+        .map(|(coupon_code, repository)| find_promotion(repository, coupon_code))
+        .transpose()?;
+      let (order_items, order_discounts) = match &mut promotion {
+        Some(Versioned { aggregate: promotion, .. }) => {
+          promotion.redeem(&order_id, &customer_id, self.clock.as_ref())?;
+          let (order_items, promotion_discount) = promotion.apply(order_items.clone())?;
+          (order_items, order_discounts.iter().cloned().chain(promotion_discount).collect())
+        }
+        None => (order_items.clone(), order_discounts.clone()),
+      };
+      let (order, events) = Order::place_order(
+        order_id.clone(),
+        customer_id.clone(),
+        self.clock.as_ref(),
+        pricing.clone(),
+        order_items,
+        order_discounts,
+        shipping.clone(),
+      )?;
+      customer.accept_order(&order_id, *order.get_grand_total(), self.clock.as_ref())?;
+
+      match self.customer_repository.update(customer, customer_version) {
+        Ok(()) => {}
+        Err(RepositoryError::VersionConflict { .. }) => continue,
+        Err(e) => Err(e)?,
+      }
+      let (Some(repository), Some(Versioned { aggregate: promotion, version: promotion_version })) = (promotion_repository, promotion) else {
+        return self.save_order(order, events, None);
+      };
+      // プロモーションの更新に失敗した場合は、受け付けた注文を顧客から外してから再試行します
+      match repository.update(promotion, promotion_version) {
+        Ok(()) => return self.save_order(order, events, coupon_code.as_ref().map(|coupon_code| (repository, coupon_code))),
+        Err(RepositoryError::VersionConflict { .. }) => self.release_order(&customer_id, &order_id)?,
+        Err(e) => {
+          self.release_order(&customer_id, &order_id)?;
+          Err(e)?
+        }
+      }
+    }
+    Err(CommandError::ConcurrencyConflict(order_id.to_string()))
+  }
+
+  /// 確定後、支払い前の注文に明細を追加します
+  ///
+  /// 明細は注文の通貨で検証するため、注文を取得してから検証します。
+  /// 追加後の支払総額で顧客の与信枠を検証し、顧客の未完了の注文の支払総額を更新します。
+  /// 注文の更新でバージョンが競合した場合は、顧客の支払総額を戻してから再試行します
+  ///
+  /// # Arguments
+  /// * `command`: AddOrderItems
+  ///
+  /// # Return
+  /// * `Result<(Order, Vec<OrderEvent>), CommandError>`
+  #[instrument(skip_all, fields(command = "add_order_items", order_id = %command.order_id))]
+  pub fn add_order_items(&self, command: AddOrderItems) -> Result<(Order, Vec<OrderEvent>), CommandError> {
+    observe(self.metrics.as_ref(), "add_order_items", "order", || self.handle_add_order_items(command))
+      .inspect(|(_, events)| self.event_publisher.publish(events))
+  }
+
+  fn handle_add_order_items(&self, command: AddOrderItems) -> Result<(Order, Vec<OrderEvent>), CommandError> {
+    let order_id = OrderId::from_str(&command.order_id).map_err(OrderError::from)?;
+    let order = self.find_order(&order_id)?.aggregate;
+    let currency = order.get_currency();
+    let order_items = validate_all(self.validate_order_items(command.items, &currency.to_string(), currency))
+      .and_then(|order_items| {
+        let all_items = order.get_order_items().iter().cloned().chain(order_items.iter().cloned()).collect::<Vec<_>>();
+        validate(self.quantity_limits.validate(&all_items).map_err(OrderError::from)).map(|()| order_items)
+      });
+    let order_items = finish(self.validation_mode, order_items)?;
+
+    for _ in 0..MAX_PLACEMENT_ATTEMPTS {
+      let Versioned { aggregate: mut order, version } = self.find_order(&order_id)?;
+      let previous_total = *order.get_grand_total();
+      let events = order.add_items(order_items.clone(), self.tax_rule.as_ref(), self.clock.as_ref())?;
+      self.change_order_amount(order.get_customer_id(), &order_id, *order.get_grand_total())?;
+      // 注文の更新に失敗した場合は、顧客の支払総額を戻してから再試行します
+      match self.order_repository.update(order.clone(), version) {
+        Ok(()) => return Ok((order, events)),
+        Err(RepositoryError::VersionConflict { .. }) => {
+          self.change_order_amount(order.get_customer_id(), &order_id, previous_total)?
+        }
+        Err(e) => {
+          self.change_order_amount(order.get_customer_id(), &order_id, previous_total)?;
+          Err(e)?
+        }
+      }
+    }
+    Err(CommandError::ConcurrencyConflict(order_id.to_string()))
+  }
+
+  /// 出荷前の注文の配送先の住所を変更します
+  ///
+  /// # Arguments
+  /// * `command`: ChangeShippingAddress
+  ///
+  /// # Return
+  /// * `Result<(Order, Vec<OrderEvent>), CommandError>`
+  #[instrument(skip_all, fields(command = "change_shipping_address", order_id = %command.order_id))]
+  pub fn change_shipping_address(&self, command: ChangeShippingAddress) -> Result<(Order, Vec<OrderEvent>), CommandError> {
+    observe(self.metrics.as_ref(), "change_shipping_address", "order", || self.handle_change_shipping_address(command))
+      .inspect(|(_, events)| self.event_publisher.publish(events))
+  }
+
+  fn handle_change_shipping_address(&self, command: ChangeShippingAddress) -> Result<(Order, Vec<OrderEvent>), CommandError> {
+    let address = command.shipping_address.to_address()?;
+    self.update_order(&command.order_id, |order, clock| order.change_shipping_address(address.clone(), clock))
+  }
+
+  /// 注文を出荷済みにします
+  ///
+  /// 出荷後は配送先を変更できません
+  ///
+  /// # Arguments
+  /// * `command`: ShipOrder
+  ///
+  /// # Return
+  /// * `Result<(Order, Vec<OrderEvent>), CommandError>`
+  #[instrument(skip_all, fields(command = "ship_order", order_id = %command.order_id))]
+  pub fn ship_order(&self, command: ShipOrder) -> Result<(Order, Vec<OrderEvent>), CommandError> {
+    observe(self.metrics.as_ref(), "ship_order", "order", || self.handle_ship_order(command))
+      .inspect(|(_, events)| self.event_publisher.publish(events))
+  }
+
+  fn handle_ship_order(&self, command: ShipOrder) -> Result<(Order, Vec<OrderEvent>), CommandError> {
+    self.update_order(&command.order_id, |order, clock| order.ship(clock))
+  }
+
+  /// 注文確定コマンドの入力を値オブジェクトに変換します
+  ///
+  /// 検証方式がAccumulateの場合は、すべての明細と割引のエラーをまとめて返します。
+  /// 明細と割引は金額の検証に通貨を使用するため、通貨が正しい場合のみ検証します
+  fn validate_place_order(&self, command: PlaceOrder) -> Result<PlaceOrderInput, CommandError> {
+    let customer_id = validate(CustomerId::from_str(&command.customer_id).map_err(CustomerError::from));
+    let currency = validate(Currency::from_str(&command.currency).map_err(MoneyError::from).map_err(OrderError::from));
+    let region = validate(Region::from_str(&command.region).map_err(OrderError::from));
+    let address = validate(command.shipping_address.to_address());
+    let delivery_method = validate(DeliveryMethod::from_str(&command.delivery_method).map_err(OrderError::from));
+    // 通貨が不正な場合は通貨のエラーで全体が失敗するため、明細と割引は空として扱います
+    let order_items = currency.as_ref().map_or(Ok(Vec::new()), |currency| {
+      validate_all(self.validate_order_items(command.items, &command.currency, *currency)).and_then(|order_items| {
+        validate(self.quantity_limits.validate(&order_items).map_err(OrderError::from)).map(|()| order_items)
+      })
+    });
+    let order_discounts = currency.as_ref().map_or(Ok(Vec::new()), |currency| {
+      validate_all(validate_order_discounts(command.discounts, *currency))
+    });
+    let coupon_code = validate(command.coupon_code.as_deref().map(CouponCode::new).transpose().map_err(OrderError::from));
+
+    let shipping = zip(address, delivery_method).map(|(address, delivery_method)| ShippingDetails { address, delivery_method });
+    let input = zip(zip(zip(customer_id, currency), zip(region, shipping)), zip(zip(order_items, order_discounts), coupon_code))
+      .map(|(((customer_id, currency), (region, shipping)), ((order_items, order_discounts), coupon_code))| PlaceOrderInput {
+        customer_id,
+        currency,
+        region,
+        shipping,
+        order_items,
+        order_discounts,
+        coupon_code,
+      });
+    finish(self.validation_mode, input)
+  }
+
+  /// 注文確定コマンドの明細を注文明細に変換します
+  ///
+  /// エラーには明細の添字を付けます
+  fn validate_order_items(
+    &self,
+    items: Vec<PlaceOrderItem>,
+    currency_code: &str,
+    currency: Currency,
+  ) -> Validated<Vec<OrderItem>, OrderError> {
+    let mut order_items = Ok(Vec::new());
+    for (index, item) in items.into_iter().enumerate() {
+      let discount = item.discount.map_or(Ok(Discount::none()), |discount| discount.to_discount(currency));
+      let order_item = OrderItem::validate_order_item(OrderItemParams {
+        order_item_id: OrderItemId::generate(self.id_generator.as_ref()),
+        product_id: item.product_id,
+        product_name: &item.product_name,
+        product_category: &item.product_category,
+        unit_price: item.unit_price,
+        currency: currency_code,
+        discount: discount.as_ref().ok().cloned().unwrap_or_else(Discount::none),
+        quantity: item.quantity,
+      });
+      let order_item = zip(discount.map_err(ValidationErrors::from), order_item)
+        .map(|(_, order_item)| order_item)
+        .map_err(|errors| errors.map(|e| e.at_item(index)));
+      order_items = zip(order_items, order_item).map(|(mut order_items, order_item)| {
+        order_items.push(order_item);
+        order_items
+      });
+    }
+    order_items
+  }
+
+  /// 注文を取得して変更し、保存します
+  ///
+  /// 他の変更と同時に更新してバージョンが競合した場合は、注文を再取得して再試行します
+  fn update_order<F>(&self, order_id: &str, mut change: F) -> Result<(Order, Vec<OrderEvent>), CommandError>
+  where
+    F: FnMut(&mut Order, &dyn Clock) -> Result<OrderEvent, OrderError>,
+  {
+    let order_id = OrderId::from_str(order_id).map_err(OrderError::from)?;
+    for _ in 0..MAX_PLACEMENT_ATTEMPTS {
+      let Versioned { aggregate: mut order, version } = self.find_order(&order_id)?;
+      let event = change(&mut order, self.clock.as_ref())?;
+      match self.order_repository.update(order.clone(), version) {
+        Ok(()) => return Ok((order, vec![event])),
+        Err(RepositoryError::VersionConflict { .. }) => continue,
+        Err(e) => Err(e)?,
+      }
+    }
+    Err(CommandError::ConcurrencyConflict(order_id.to_string()))
+  }
+
+  /// 確定した注文を保存します
+  ///
+  /// 注文は顧客とプロモーションの更新の後に保存するため、保存に失敗した場合は
+  /// 顧客が受け付けた注文とクーポンの使用を取り消してから、保存のエラーを返します
+  ///
+  /// # Arguments
+  /// * `order`: 確定した注文
+  /// * `events`: 注文確定のイベント
+  /// * `redemption`: クーポンを使用した場合は、プロモーションのリポジトリとクーポンコード
+  fn save_order(
+    &self,
+    order: Order,
+    events: Vec<OrderEvent>,
+    redemption: Option<(&dyn PromotionRepository, &CouponCode)>,
+  ) -> Result<(Order, Vec<OrderEvent>), CommandError> {
+    let Err(e) = self.order_repository.insert(order.clone()) else {
+      return Ok((order, events));
+    };
+    let customer_id = order.get_customer_id();
+    let order_id = order.get_id();
+    if let Err(release_error) = self.release_order(customer_id, order_id) {
+      error!(%customer_id, %order_id, "failed to release the order from the customer: {}", release_error);
+    }
+    if let Some((repository, coupon_code)) = redemption {
+      if let Err(cancel_error) = self.cancel_redemption(repository, coupon_code, order_id, customer_id) {
+        error!(%coupon_code, %order_id, "failed to cancel the coupon redemption: {}", cancel_error);
+      }
+    }
+    Err(e)?
+  }
+
+  /// 注文を取得します
+  fn find_order(&self, order_id: &OrderId) -> Result<Versioned<Order>, CommandError> {
+    let order = self.order_repository
+      .find_by_id(order_id)?
+      .ok_or_else(|| OrderError::OrderNotFound(order_id.to_string()))?;
+    Ok(order)
+  }
+
+  /// 顧客を取得します
+  fn find_customer(&self, customer_id: &CustomerId) -> Result<Versioned<Customer>, CommandError> {
+    let customer = self.customer_repository
+      .find_by_id(customer_id)?
+      .ok_or_else(|| CustomerError::CustomerNotFound(customer_id.to_string()))?;
+    Ok(customer)
+  }
+
+  /// 受け付けた注文を顧客の未完了の注文から外します
+  fn release_order(&self, customer_id: &CustomerId, order_id: &OrderId) -> Result<(), CommandError> {
+    for _ in 0..MAX_PLACEMENT_ATTEMPTS {
+      let Versioned { aggregate: mut customer, version } = self.find_customer(customer_id)?;
+      customer.release_order(order_id, self.clock.as_ref())?;
+      match self.customer_repository.update(customer, version) {
+        Ok(()) => return Ok(()),
+        Err(RepositoryError::VersionConflict { .. }) => continue,
+        Err(e) => Err(e)?,
+      }
+    }
+    Err(CommandError::ConcurrencyConflict(order_id.to_string()))
+  }
+
+  /// 顧客の未完了の注文の支払総額を変更します
+  fn change_order_amount(&self, customer_id: &CustomerId, order_id: &OrderId, amount: Money) -> Result<(), CommandError> {
+    for _ in 0..MAX_PLACEMENT_ATTEMPTS {
+      let Versioned { aggregate: mut customer, version } = self.find_customer(customer_id)?;
+      customer.change_order_amount(order_id, amount, self.clock.as_ref())?;
+      match self.customer_repository.update(customer, version) {
+        Ok(()) => return Ok(()),
+        Err(RepositoryError::VersionConflict { .. }) => continue,
+        Err(e) => Err(e)?,
+      }
+    }
+    Err(CommandError::ConcurrencyConflict(order_id.to_string()))
+  }
+
+  /// 注文で使用したクーポンの使用を取り消します
+  fn cancel_redemption(
+    &self,
+    repository: &dyn PromotionRepository,
+    coupon_code: &CouponCode,
+    order_id: &OrderId,
+    customer_id: &CustomerId,
+  ) -> Result<(), CommandError> {
+    for _ in 0..MAX_PLACEMENT_ATTEMPTS {
+      let Versioned { aggregate: mut promotion, version } = find_promotion(repository, coupon_code)?;
+      promotion.cancel_redemption(order_id, customer_id, self.clock.as_ref())?;
+      match repository.update(promotion, version) {
+        Ok(()) => return Ok(()),
+        Err(RepositoryError::VersionConflict { .. }) => continue,
+        Err(e) => Err(e)?,
+      }
+    }
+    Err(CommandError::ConcurrencyConflict(order_id.to_string()))
+  }
+}
+
+/// 注文確定コマンドの割引を注文全体への割引に変換します
+///
+/// エラーには割引の添字を付けます
+fn validate_order_discounts(
+  discounts: Vec<PlaceOrderDiscount>,
+  currency: Currency,
+) -> Validated<Vec<OrderDiscount>, OrderError> {
+  let mut order_discounts = Ok(Vec::new());
+  for (index, discount) in discounts.into_iter().enumerate() {
+    let value = discount.discount.to_discount(currency).map_err(ValidationErrors::from);
+    let coupon_code = discount.coupon_code.as_deref().map(CouponCode::new).transpose().map_err(|e| ValidationErrors::from(OrderError::from(e)));
+    let order_discount = zip(value, coupon_code)
+      .map(|(value, coupon_code)| OrderDiscount::new(value, coupon_code, discount.stackable))
+      .map_err(|errors| errors.map(|e| e.at_discount(index)));
+    order_discounts = zip(order_discounts, order_discount).map(|(mut order_discounts, order_discount)| {
+      order_discounts.push(order_discount);
+      order_discounts
+    });
+  }
+  order_discounts
+}
+
+/// クーポンコードに対応するプロモーションを取得します
+fn find_promotion(
+  repository: &dyn PromotionRepository,
+  coupon_code: &CouponCode,
+) -> Result<Versioned<Promotion>, CommandError> {
+  let promotion = repository
+    .find_by_coupon_code(coupon_code)?
+    .ok_or_else(|| PromotionError::CouponNotFound(coupon_code.to_string()))?;
+  Ok(promotion)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::command::{AuthorizePayment, CapturePayment, DiscountValue, PlaceOrderDiscount, PlaceOrderItem, ShippingAddressValue};
+  use crate::payment_command_processor::PaymentCommandProcessor;
+  use chrono::{Duration, TimeZone, Utc};
+  use command_domain::clock::FixedClock;
+  use command_domain::customer::customer_limits::CustomerLimits;
+  use command_domain::error_code::ErrorCode;
+  use command_domain::id_generator::{SequentialIdGenerator, UuidV4Generator};
+  use command_domain::promotion::promotion_id::PromotionId;
+  use command_domain::promotion::promotion_terms::{PromotionScope, PromotionTerms};
+  use command_domain::promotion::Promotion;
+  use command_domain::tax::tax_rule::{RegionalTaxRule, TaxInclusion, TaxRate, TaxTreatment};
+  use command_domain::value_object::coupon_code::CouponCodeError;
+  use command_domain::value_object::money::Money;
+  use command_domain::value_object::quantity::{Quantity, QuantityError};
+  use command_domain::value_object::rounding_policy::{RoundingMode, RoundingScope};
+  use command_domain::order::order_status::OrderStatus;
+  use command_domain::shipping::shipping_error::ShippingError;
+  use command_infrastructure::fake_payment_provider::FakePaymentProvider;
+  use command_infrastructure::in_memory_customer_repository::InMemoryCustomerRepository;
+  use command_infrastructure::in_memory_order_repository::InMemoryOrderRepository;
+  use command_infrastructure::in_memory_payment_repository::InMemoryPaymentRepository;
+  use command_infrastructure::in_memory_promotion_repository::InMemoryPromotionRepository;
+  use rust_decimal::Decimal;
+  use std::sync::Mutex;
+  use std::thread;
+
+  const CUSTOMER_ID: &str = "CUSTOMER-00000000-0000-0000-0000-000000000001";
+
+  fn customer_repository(customer_ids: &[String], limits: CustomerLimits) -> Arc<InMemoryCustomerRepository> {
+    let clock = FixedClock::new(Utc.with_ymd_and_hms(2024, 9, 1, 0, 0, 0).unwrap());
+    let repository = Arc::new(InMemoryCustomerRepository::new());
+    for customer_id in customer_ids {
+      let customer_id = CustomerId::from_str(customer_id).unwrap();
+      repository.insert(Customer::register(customer_id, &clock, "山田 太郎", limits).unwrap().0).unwrap();
+    }
+    repository
+  }
+
+  fn processor_with_customers(clock: Arc<FixedClock>, customer_repository: Arc<InMemoryCustomerRepository>) -> OrderCommandProcessor {
+    OrderCommandProcessor::new(
+      clock,
+      Arc::new(SequentialIdGenerator::new(1)),
+      customer_repository,
+      Arc::new(InMemoryOrderRepository::new()),
+    )
+  }
+
+  fn processor(clock: Arc<FixedClock>) -> OrderCommandProcessor {
+    processor_with_customers(clock, customer_repository(&[CUSTOMER_ID.to_string()], CustomerLimits::unlimited()))
+  }
+
+  fn place_order_command() -> PlaceOrder {
+    PlaceOrder {
+      customer_id: CUSTOMER_ID.to_string(),
+      currency: "JPY".to_string(),
+      region: "JP".to_string(),
+      items: vec![PlaceOrderItem {
+        product_id: 1,
+        product_name: "hogehoge".to_string(),
+        product_category: "general".to_string(),
+        unit_price: Decimal::from(500),
+        discount: Some(DiscountValue::Percentage(Decimal::from(10))),
+        quantity: 2,
+      }],
+      discounts: vec![],
+      coupon_code: None,
+      shipping_address: shipping_address("100-0001"),
+      delivery_method: "standard".to_string(),
+    }
+  }
+
+  fn shipping_address(postal_code: &str) -> ShippingAddressValue {
+    ShippingAddressValue {
+      recipient: "山田 太郎".to_string(),
+      country: "JP".to_string(),
+      postal_code: postal_code.to_string(),
+      subdivision: Some("東京都".to_string()),
+      city: "千代田区".to_string(),
+      line1: "千代田1-1".to_string(),
+      line2: None,
+    }
+  }
+
+  /// ログの出力先です
+  #[derive(Clone, Default)]
+  struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+  impl std::io::Write for LogBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+      self.0.lock().unwrap().extend_from_slice(buf);
+      Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn test_place_order_logs_command_and_order_id() {
+    let logs = LogBuffer::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt().json().with_current_span(true).with_writer(move || writer.clone()).finish();
+    let processor = processor(Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap())));
+
+    let (order, _) = tracing::subscriber::with_default(subscriber, || processor.place_order(place_order_command())).unwrap();
+    let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+    let log = serde_json::from_str::<serde_json::Value>(logs.lines().last().unwrap()).unwrap();
+
+    // assert
+    assert_eq!("command handled", log["fields"]["message"]);
+    assert_eq!("place_order", log["span"]["command"]);
+    assert_eq!(order.get_id().to_string(), log["span"]["order_id"]);
+  }
+
+  #[test]
+  fn test_place_order_uses_clock_success() {
+    let now = Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap();
+    let clock = Arc::new(FixedClock::new(now));
+    let processor = processor(clock.clone());
+
+    let (first, first_event) = processor.place_order(place_order_command()).unwrap();
+    clock.advance(Duration::hours(1));
+    let (second, second_event) = processor.place_order(place_order_command()).unwrap();
+
+    // assert
+    assert_eq!(&now, first.get_ordered_at());
+    assert_eq!(&now, first_event[0].occurred_at());
+    assert_eq!(&(now + Duration::hours(1)), second.get_ordered_at());
+    assert_eq!(&(now + Duration::hours(1)), second_event[0].occurred_at());
+  }
+
+  #[test]
+  fn test_place_order_uses_id_generator_success() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let processor = processor(clock);
+
+    let (order, _) = processor.place_order(place_order_command()).unwrap();
+
+    // assert
+    // 明細のIDが先に採番され、その後に注文IDが採番されます
+    assert_eq!(
+      "ORDER_ITEM-00000000-0000-0001-0000-000000000001",
+      order.get_order_items()[0].get_order_item_id().to_string()
+    );
+    assert_eq!(
+      "ORDER-00000000-0000-0001-0000-000000000002",
+      order.get_id().to_string()
+    );
+  }
+
+  #[test]
+  fn test_place_order_with_rounding_policy_success() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let policy = RoundingPolicy::new(RoundingMode::Bankers, RoundingScope::PerOrder);
+    let processor = processor(clock).with_rounding_policy(Currency::JPY, policy);
+
+    let (order, events) = processor.place_order(place_order_command()).unwrap();
+    let OrderEvent::OrderPlaced(placed) = &events[0] else { panic!("OrderPlaced expected") };
+
+    // assert
+    assert_eq!(policy, order.get_rounding_policy());
+    assert_eq!(policy, placed.rounding_policy);
+  }
+
+  #[test]
+  fn test_place_order_with_order_discount_success() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let processor = processor(clock);
+    let mut command = place_order_command();
+    command.discounts.push(PlaceOrderDiscount {
+      discount: DiscountValue::FixedAmount(Decimal::from(100)),
+      coupon_code: Some("welcome".to_string()),
+      stackable: false,
+    });
+
+    let (order, events) = processor.place_order(command).unwrap();
+
+    // assert
+    assert_eq!(&Decimal::from(800), order.get_total_price().amount());
+    assert!(matches!(
+      &events[2],
+      OrderEvent::OrderDiscountApplied(applied) if applied.coupon_code == Some(CouponCode::new("WELCOME").unwrap())
+    ));
+  }
+
+  #[test]
+  fn test_place_order_with_tax_rule_success() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let tax_rule = RegionalTaxRule::new(
+      Region::from_str("JP").unwrap(),
+      None,
+      TaxTreatment::new(TaxRate::try_from(Decimal::from(10)).unwrap(), TaxInclusion::Exclusive),
+    );
+    let processor = processor(clock).with_tax_rule(Arc::new(tax_rule));
+
+    let (order, _) = processor.place_order(place_order_command()).unwrap();
+
+    // assert
+    assert_eq!(&Decimal::from(900), order.get_total_price().amount());
+    assert_eq!(&Decimal::from(90), order.get_tax_breakdown().total_tax().amount());
+    assert_eq!(&Decimal::from(990), order.get_grand_total().amount());
+  }
+
+  #[test]
+  fn test_place_order_invalid_region_failed() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let processor = processor(clock);
+    let mut command = place_order_command();
+    command.region = "japan".to_string();
+
+    let result = processor.place_order(command);
+
+    // assert
+    assert!(matches!(result, Err(CommandError::InvalidOrder(OrderError::InvalidRegion(_)))))
+  }
+
+  #[test]
+  fn test_place_order_quantity_limit_failed() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let processor = processor(clock).with_quantity_limits(
+      ProductQuantityLimits::default().with_limit(1, Quantity::try_from(1).unwrap()),
+    );
+
+    let result = processor.place_order(place_order_command());
+
+    // assert
+    assert!(matches!(
+      result,
+      Err(CommandError::InvalidOrder(OrderError::InvalidQuantity(QuantityError::ExceedsMaximum { max: 1, value: 2, .. })))
+    ));
+  }
+
+  #[test]
+  fn test_place_order_accumulate_validation_failed() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let processor = processor(clock).with_validation_mode(ValidationMode::Accumulate);
+    let mut command = place_order_command();
+    let item = command.items[0].clone();
+    command.region = "japan".to_string();
+    command.items = vec![
+      PlaceOrderItem { quantity: 0, product_name: "".to_string(), ..item.clone() },
+      item.clone(),
+      PlaceOrderItem { unit_price: Decimal::from(-1), discount: Some(DiscountValue::Percentage(Decimal::from(101))), ..item },
+    ];
+    command.discounts.push(PlaceOrderDiscount {
+      discount: DiscountValue::FixedAmount(Decimal::from(-100)),
+      coupon_code: Some("!".to_string()),
+      stackable: true,
+    });
+
+    let result = processor.place_order(command);
+
+    // assert
+    let Err(error @ CommandError::Validation(_)) = result else { panic!("unexpected result: {:?}", result) };
+    let fields = error.field_errors().into_iter().map(|e| (e.field.to_string(), e.code)).collect::<Vec<_>>();
+    assert_eq!(
+      vec![
+        ("region".to_string(), "region.invalid"),
+        ("items[0].quantity".to_string(), "quantity.not_positive"),
+        ("items[0].product_name".to_string(), "product_name.empty"),
+        ("items[2].discount.value".to_string(), "discount.invalid_percentage"),
+        ("items[2].unit_price".to_string(), "price.not_positive"),
+        ("discounts[0].discount.value".to_string(), "discount.negative_amount"),
+        ("discounts[0].coupon_code".to_string(), "coupon_code.invalid"),
+      ],
+      fields,
+    );
+  }
+
+  #[test]
+  fn test_place_order_fail_fast_validation_failed() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let processor = processor(clock);
+    let mut command = place_order_command();
+    command.region = "japan".to_string();
+    command.items[0].quantity = 0;
+
+    let result = processor.place_order(command);
+
+    // assert
+    assert!(matches!(result, Err(CommandError::InvalidOrder(OrderError::InvalidRegion(_)))));
+  }
+
+  fn promotion_repository(usage_limit: Option<u32>, per_customer_limit: Option<u32>) -> Arc<InMemoryPromotionRepository> {
+    let clock = FixedClock::new(Utc.with_ymd_and_hms(2024, 9, 1, 0, 0, 0).unwrap());
+    let terms = PromotionTerms {
+      coupon_code: CouponCode::new("AUTUMN").unwrap(),
+      discount: Discount::fixed_amount(Money::new(Decimal::from(100), Currency::JPY).unwrap()).unwrap(),
+      scope: PromotionScope::Order,
+      stackable: false,
+      valid_from: clock.now(),
+      valid_until: None,
+      usage_limit,
+      per_customer_limit,
+    };
+    let repository = Arc::new(InMemoryPromotionRepository::new());
+    repository.insert(Promotion::create(PromotionId::generate(&UuidV4Generator), &clock, terms).unwrap().0).unwrap();
+    repository
+  }
+
+  #[test]
+  fn test_place_order_with_coupon_success() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let repository = promotion_repository(None, Some(1));
+    let processor = processor(clock).with_promotion_repository(repository.clone());
+    let mut command = place_order_command();
+    command.coupon_code = Some("autumn".to_string());
+
+    let (order, _) = processor.place_order(command.clone()).unwrap();
+    let result = processor.place_order(command);
+
+    // assert
+    assert_eq!(&Decimal::from(800), order.get_total_price().amount());
+    assert!(matches!(result, Err(CommandError::InvalidPromotion(PromotionError::CustomerLimitReached { .. }))));
+    let promotion = repository.find_by_coupon_code(&CouponCode::new("AUTUMN").unwrap()).unwrap().unwrap();
+    let customer_id = CustomerId::from_str(CUSTOMER_ID).unwrap();
+    assert_eq!(1, promotion.aggregate.get_customer_redemption_count(&customer_id));
+  }
+
+  /// 注文の保存に常に失敗するリポジトリです
+  struct FailingOrderRepository;
+
+  impl OrderRepository for FailingOrderRepository {
+    fn find_by_id(&self, _order_id: &OrderId) -> Result<Option<Versioned<Order>>, RepositoryError> {
+      Ok(None)
+    }
+
+    fn insert(&self, order: Order) -> Result<(), RepositoryError> {
+      Err(RepositoryError::AlreadyExists(order.get_id().to_string()))
+    }
+
+    fn update(&self, order: Order, _expected_version: u64) -> Result<(), RepositoryError> {
+      Err(RepositoryError::NotFound(order.get_id().to_string()))
+    }
+  }
+
+  #[test]
+  fn test_place_order_save_failed_compensates() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let customers = customer_repository(&[CUSTOMER_ID.to_string()], CustomerLimits::unlimited());
+    let promotions = promotion_repository(Some(1), None);
+    let processor = OrderCommandProcessor::new(
+      clock,
+      Arc::new(SequentialIdGenerator::new(1)),
+      customers.clone(),
+      Arc::new(FailingOrderRepository),
+    )
+      .with_promotion_repository(promotions.clone());
+    let mut command = place_order_command();
+    command.coupon_code = Some("AUTUMN".to_string());
+
+    let result = processor.place_order(command);
+
+    // assert
+    assert!(matches!(result, Err(CommandError::Repository(RepositoryError::AlreadyExists(_)))));
+    let customer = customers.find_by_id(&CustomerId::from_str(CUSTOMER_ID).unwrap()).unwrap().unwrap();
+    assert!(customer.aggregate.get_open_orders().is_empty());
+    let promotion = promotions.find_by_coupon_code(&CouponCode::new("AUTUMN").unwrap()).unwrap().unwrap();
+    assert_eq!(0, promotion.aggregate.get_redemption_count());
+  }
+
+  #[test]
+  fn test_place_order_with_coupon_concurrently_success() {
+    // 上限5回のクーポンを20件の注文で同時に使用しても、成功するのは5件のみです
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let repository = promotion_repository(Some(5), None);
+    let customer_ids = (1..=20)
+      .map(|i| format!("CUSTOMER-00000000-0000-0000-0000-{:012}", i))
+      .collect::<Vec<String>>();
+    let customers = customer_repository(&customer_ids, CustomerLimits::unlimited());
+    let processor = Arc::new(
+      processor_with_customers(clock, customers.clone()).with_promotion_repository(repository.clone())
+    );
+
+    let handles = customer_ids
+      .iter()
+      .map(|customer_id| {
+        let processor = processor.clone();
+        let mut command = place_order_command();
+        command.coupon_code = Some("AUTUMN".to_string());
+        command.customer_id = customer_id.clone();
+        thread::spawn(move || processor.place_order(command))
+      })
+      .collect::<Vec<_>>();
+    let results = handles.into_iter().map(|handle| handle.join().unwrap()).collect::<Vec<_>>();
+
+    // assert
+    assert_eq!(5, results.iter().filter(|result| result.is_ok()).count());
+    assert!(results
+      .iter()
+      .filter_map(|result| result.as_ref().err())
+      .all(|e| matches!(e, CommandError::InvalidPromotion(PromotionError::UsageLimitReached(_)))));
+    let promotion = repository.find_by_coupon_code(&CouponCode::new("AUTUMN").unwrap()).unwrap().unwrap();
+    assert_eq!(5, promotion.aggregate.get_redemption_count());
+    // クーポンを使用できなかった注文は顧客の未完了の注文に残りません
+    let open_orders = customer_ids
+      .iter()
+      .map(|customer_id| {
+        let customer_id = CustomerId::from_str(customer_id).unwrap();
+        customers.find_by_id(&customer_id).unwrap().unwrap().aggregate.get_open_orders().len()
+      })
+      .sum::<usize>();
+    assert_eq!(5, open_orders);
+  }
+
+  #[test]
+  fn test_place_order_open_order_limit_failed() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let limits = CustomerLimits::new(Some(1), None).unwrap();
+    let processor = processor_with_customers(clock, customer_repository(&[CUSTOMER_ID.to_string()], limits));
+
+    processor.place_order(place_order_command()).unwrap();
+    let result = processor.place_order(place_order_command());
+
+    // assert
+    assert!(matches!(
+      result,
+      Err(CommandError::InvalidCustomer(CustomerError::OpenOrderLimitReached { limit: 1, .. }))
+    ))
+  }
+
+  #[test]
+  fn test_place_order_credit_limit_failed() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let credit_limit = Money::new(Decimal::from(1500), Currency::JPY).unwrap();
+    let limits = CustomerLimits::new(None, Some(credit_limit)).unwrap();
+    let customers = customer_repository(&[CUSTOMER_ID.to_string()], limits);
+    let processor = processor_with_customers(clock, customers.clone());
+
+    processor.place_order(place_order_command()).unwrap();
+    let result = processor.place_order(place_order_command());
+
+    // assert
+    assert!(matches!(
+      result,
+      Err(CommandError::InvalidCustomer(CustomerError::CreditLimitExceeded { available, .. }))
+        if available == Money::new(Decimal::from(600), Currency::JPY).unwrap()
+    ));
+    let customer = customers.find_by_id(&CustomerId::from_str(CUSTOMER_ID).unwrap()).unwrap().unwrap();
+    assert_eq!(1, customer.aggregate.get_open_orders().len());
+  }
+
+  #[test]
+  fn test_place_order_customer_not_found_failed() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let processor = processor(clock);
+    let mut command = place_order_command();
+    command.customer_id = "CUSTOMER-00000000-0000-0000-0000-000000000099".to_string();
+
+    let result = processor.place_order(command);
+
+    // assert
+    assert!(matches!(result, Err(CommandError::InvalidCustomer(CustomerError::CustomerNotFound(_)))))
+  }
+
+  #[test]
+  fn test_place_order_coupon_not_found_failed() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let processor = processor(clock).with_promotion_repository(promotion_repository(None, None));
+    let mut command = place_order_command();
+    command.coupon_code = Some("WINTER".to_string());
+
+    let result = processor.place_order(command);
+
+    // assert
+    assert!(matches!(result, Err(CommandError::InvalidPromotion(PromotionError::CouponNotFound(_)))))
+  }
+
+  #[test]
+  fn test_place_order_invalid_coupon_code_failed() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let processor = processor(clock);
+    let mut command = place_order_command();
+    command.discounts.push(PlaceOrderDiscount {
+      discount: DiscountValue::Percentage(Decimal::from(10)),
+      coupon_code: Some("!".to_string()),
+      stackable: true,
+    });
+
+    let result = processor.place_order(command);
+
+    // assert
+    let Err(CommandError::InvalidOrder(OrderError::InvalidOrderDiscount { index: 0, error })) = result else {
+      panic!("unexpected result: {:?}", result)
+    };
+    assert!(matches!(*error, OrderError::InvalidCouponCode(CouponCodeError::Invalid(_))));
+  }
+
+  #[test]
+  fn test_place_order_invalid_item_reports_field_path() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let processor = processor(clock);
+    let mut command = place_order_command();
+    let item = command.items[0].clone();
+    command.items.push(PlaceOrderItem { quantity: 0, ..item.clone() });
+    command.items.push(PlaceOrderItem { discount: Some(DiscountValue::Percentage(Decimal::from(101))), ..item });
+
+    let quantity = processor.place_order(command.clone());
+    command.items.remove(1);
+    let discount = processor.place_order(command);
+
+    // assert
+    let quantity = quantity.unwrap_err();
+    let discount = discount.unwrap_err();
+    assert_eq!("quantity.not_positive", quantity.code());
+    assert_eq!(Some("items[1].quantity".to_string()), quantity.field_path().map(|path| path.to_string()));
+    assert_eq!("discount.invalid_percentage", discount.code());
+    assert_eq!(Some("items[1].discount.value".to_string()), discount.field_path().map(|path| path.to_string()));
+  }
+
+  #[test]
+  fn test_place_order_unsupported_currency_failed() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let processor = processor(clock);
+    let mut command = place_order_command();
+    command.currency = "XXX".to_string();
+
+    let result = processor.place_order(command);
+
+    // assert
+    assert!(matches!(result, Err(CommandError::InvalidOrder(OrderError::InvalidMoney(MoneyError::InvalidCurrency(_))))))
+  }
+
+  #[test]
+  fn test_place_order_failed() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let processor = processor(clock);
+    let mut command = place_order_command();
+    command.items[0].quantity = 0;
+
+    let result = processor.place_order(command);
+
+    // assert
+    assert!(result.is_err())
+  }
+
+  #[test]
+  fn test_place_order_invalid_shipping_failed() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let processor = processor(clock);
+    let mut invalid_postal_code = place_order_command();
+    invalid_postal_code.shipping_address.postal_code = "94105".to_string();
+    let mut unknown_method = place_order_command();
+    unknown_method.delivery_method = "drone".to_string();
+
+    // assert
+    assert!(matches!(
+      processor.place_order(invalid_postal_code),
+      Err(CommandError::InvalidOrder(OrderError::InvalidShipping(ShippingError::InvalidPostalCode { .. })))
+    ));
+    assert!(matches!(
+      processor.place_order(unknown_method),
+      Err(CommandError::InvalidOrder(OrderError::InvalidShipping(ShippingError::UnknownDeliveryMethod(_))))
+    ));
+  }
+
+  fn add_order_items_command(order: &Order, items: Vec<PlaceOrderItem>) -> AddOrderItems {
+    AddOrderItems { order_id: order.get_id().to_string(), items }
+  }
+
+  #[test]
+  fn test_add_order_items_success() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let credit_limit = Money::new(Decimal::from(1500), Currency::JPY).unwrap();
+    let customers = customer_repository(&[CUSTOMER_ID.to_string()], CustomerLimits::new(None, Some(credit_limit)).unwrap());
+    let processor = processor_with_customers(clock, customers.clone());
+    let (order, _) = processor.place_order(place_order_command()).unwrap();
+    let item = PlaceOrderItem { discount: None, quantity: 1, ..place_order_command().items[0].clone() };
+
+    let (added, events) = processor.add_order_items(add_order_items_command(&order, vec![item.clone()])).unwrap();
+    let exceeded = processor.add_order_items(add_order_items_command(&order, vec![item]));
+
+    // assert
+    assert_eq!(2, added.get_order_items().len());
+    assert_eq!(&Money::new(Decimal::from(1400), Currency::JPY).unwrap(), added.get_grand_total());
+    assert!(matches!(&events[0], OrderEvent::OrderItemsAdded(event) if event.order_items.len() == 1));
+    assert!(matches!(exceeded, Err(CommandError::InvalidCustomer(CustomerError::CreditLimitExceeded { .. }))));
+    let customer = customers.find_by_id(&CustomerId::from_str(CUSTOMER_ID).unwrap()).unwrap().unwrap();
+    assert_eq!(Some(added.get_grand_total()), customer.aggregate.get_open_orders().get(order.get_id()));
+    let stored = processor.find_order(order.get_id()).unwrap().aggregate;
+    assert_eq!(2, stored.get_order_items().len());
+  }
+
+  #[test]
+  fn test_add_order_items_accumulate_validation_failed() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let processor = processor(clock).with_validation_mode(ValidationMode::Accumulate);
+    let (order, _) = processor.place_order(place_order_command()).unwrap();
+    let item = place_order_command().items[0].clone();
+
+    let result = processor.add_order_items(add_order_items_command(&order, vec![
+      PlaceOrderItem { quantity: 0, product_name: "".to_string(), ..item.clone() },
+      item.clone(),
+      PlaceOrderItem { unit_price: Decimal::from(-1), ..item },
+    ]));
+
+    // assert
+    let Err(error @ CommandError::Validation(_)) = result else { panic!("unexpected result: {:?}", result) };
+    let fields = error.field_errors().into_iter().map(|e| (e.field.to_string(), e.code)).collect::<Vec<_>>();
+    assert_eq!(
+      vec![
+        ("items[0].quantity".to_string(), "quantity.not_positive"),
+        ("items[0].product_name".to_string(), "product_name.empty"),
+        ("items[2].unit_price".to_string(), "price.not_positive"),
+      ],
+      fields,
+    );
+    assert_eq!(1, processor.find_order(order.get_id()).unwrap().aggregate.get_order_items().len());
+  }
+
+  #[test]
+  fn test_add_order_items_failed() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let processor = processor(clock);
+    let item = place_order_command().items[0].clone();
+
+    let not_found = processor.add_order_items(AddOrderItems {
+      order_id: "ORDER-00000000-0000-0000-0000-000000000099".to_string(),
+      items: vec![item.clone()],
+    });
+    let (order, _) = processor.place_order(place_order_command()).unwrap();
+    let empty = processor.add_order_items(add_order_items_command(&order, vec![]));
+
+    // assert
+    assert!(matches!(not_found, Err(CommandError::InvalidOrder(OrderError::OrderNotFound(_)))));
+    assert!(matches!(empty, Err(CommandError::InvalidOrder(OrderError::EmptyOrderItems))));
+  }
+
+  #[test]
+  fn test_change_shipping_address_success() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let processor = processor(clock);
+    let (order, _) = processor.place_order(place_order_command()).unwrap();
+
+    let (changed, events) = processor.change_shipping_address(ChangeShippingAddress {
+      order_id: order.get_id().to_string(),
+      shipping_address: shipping_address("1500001"),
+    }).unwrap();
+
+    // assert
+    assert_eq!("150-0001", changed.get_shipping().address.get_postal_code().value());
+    assert!(matches!(&events[0], OrderEvent::ShippingAddressChanged(event) if event.previous_address == order.get_shipping().address));
+  }
+
+  #[test]
+  fn test_change_shipping_address_after_shipment_failed() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let order_repository = Arc::new(InMemoryOrderRepository::new());
+    let processor = OrderCommandProcessor::new(
+      clock.clone(),
+      Arc::new(SequentialIdGenerator::new(1)),
+      customer_repository(&[CUSTOMER_ID.to_string()], CustomerLimits::unlimited()),
+      order_repository.clone(),
+    );
+    let payment_processor = PaymentCommandProcessor::new(
+      clock,
+      Arc::new(SequentialIdGenerator::new(100)),
+      Arc::new(InMemoryPaymentRepository::new()),
+      order_repository,
+      Arc::new(FakePaymentProvider::new()),
+    );
+    let (order, _) = processor.place_order(place_order_command()).unwrap();
+    let (payment, _) = payment_processor.authorize_payment(AuthorizePayment { order_id: order.get_id().to_string() }).unwrap();
+    payment_processor.capture_payment(CapturePayment { payment_id: payment.get_id().to_string() }).unwrap();
+    let (shipped, _) = processor.ship_order(ShipOrder { order_id: order.get_id().to_string() }).unwrap();
+
+    let result = processor.change_shipping_address(ChangeShippingAddress {
+      order_id: order.get_id().to_string(),
+      shipping_address: shipping_address("150-0001"),
+    });
+
+    // assert
+    assert_eq!(OrderStatus::Shipped, shipped.get_status());
+    assert!(matches!(result, Err(CommandError::InvalidOrder(OrderError::AlreadyShipped(_)))));
+  }
+
+  #[test]
+  fn test_change_shipping_address_order_not_found_failed() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let processor = processor(clock);
+
+    let result = processor.change_shipping_address(ChangeShippingAddress {
+      order_id: OrderId::generate(&UuidV4Generator).to_string(),
+      shipping_address: shipping_address("150-0001"),
+    });
+
+    // assert
+    assert!(matches!(result, Err(CommandError::InvalidOrder(OrderError::OrderNotFound(_)))));
+  }
+}
@@ -0,0 +1,887 @@
+use crate::command::{AuthorizePayment, CapturePayment, RefundPayment, RetryRefunds, ReturnItems};
+use crate::command_error::CommandError;
+use crate::command_metrics::{observe, CommandMetrics, NoopCommandMetrics};
+use command_domain::clock::Clock;
+use command_domain::id_generator::IdGenerator;
+use command_domain::order::order_error::OrderError;
+use command_domain::order::order_event::OrderEvent;
+use command_domain::order::order_event_publisher::{NoopOrderEventPublisher, OrderEventPublisher};
+use command_domain::order::order_id::OrderId;
+use command_domain::order::order_item_id::OrderItemId;
+use command_domain::order::order_repository::OrderRepository;
+use command_domain::order::order_return::ReturnItem;
+use command_domain::order::Order;
+use command_domain::payment::payment_error::PaymentError;
+use command_domain::payment::payment_event::PaymentEvent;
+use command_domain::payment::payment_id::PaymentId;
+use command_domain::payment::payment_provider::{PaymentProvider, PaymentProviderError};
+use command_domain::payment::payment_repository::PaymentRepository;
+use command_domain::payment::payment_status::PaymentStatus;
+use command_domain::payment::pending_refund::PendingRefund;
+use command_domain::payment::refund_id::RefundId;
+use command_domain::payment::Payment;
+use command_domain::repository::{RepositoryError, Versioned};
+use command_domain::value_object::money::Money;
+use command_domain::value_object::quantity::Quantity;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{field, instrument, Span};
+
+/// 同時更新による競合時の再試行回数の上限です
+const MAX_UPDATE_ATTEMPTS: usize = 10;
+
+/// 支払いのコマンドを処理するクラスです
+///
+/// 決済代行サービスの呼び出しは`PaymentProvider`に委譲し、
+/// 支払いのイベントに応じて注文の状態を更新します。
+/// 注文を更新したイベントは、`OrderEventPublisher`で読み取り側へ配信します
+pub struct PaymentCommandProcessor {
+  clock: Arc<dyn Clock>,
+  id_generator: Arc<dyn IdGenerator>,
+  payment_repository: Arc<dyn PaymentRepository>,
+  order_repository: Arc<dyn OrderRepository>,
+  payment_provider: Arc<dyn PaymentProvider>,
+  metrics: Arc<dyn CommandMetrics>,
+  event_publisher: Arc<dyn OrderEventPublisher>,
+}
+
+impl PaymentCommandProcessor {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `clock`: Arc<dyn Clock>
+  /// * `id_generator`: Arc<dyn IdGenerator>
+  /// * `payment_repository`: Arc<dyn PaymentRepository>
+  /// * `order_repository`: Arc<dyn OrderRepository>
+  /// * `payment_provider`: Arc<dyn PaymentProvider>
+  ///
+  /// # Return
+  /// * `PaymentCommandProcessor`
+  pub fn new(
+    clock: Arc<dyn Clock>,
+    id_generator: Arc<dyn IdGenerator>,
+    payment_repository: Arc<dyn PaymentRepository>,
+    order_repository: Arc<dyn OrderRepository>,
+    payment_provider: Arc<dyn PaymentProvider>,
+  ) -> Self {
+    Self {
+      clock,
+      id_generator,
+      payment_repository,
+      order_repository,
+      payment_provider,
+      metrics: Arc::new(NoopCommandMetrics),
+      event_publisher: Arc::new(NoopOrderEventPublisher),
+    }
+  }
+
+  /// コマンドの処理結果を記録するメトリクスを設定します
+  ///
+  /// 指定がない場合は何も記録しません
+  ///
+  /// # Arguments
+  /// * `metrics`: Arc<dyn CommandMetrics>
+  ///
+  /// # Return
+  /// * `PaymentCommandProcessor`
+  pub fn with_metrics(mut self, metrics: Arc<dyn CommandMetrics>) -> Self {
+    self.metrics = metrics;
+    self
+  }
+
+  /// 更新した注文のイベントの配信先を設定します
+  ///
+  /// 指定がない場合は配信しません
+  ///
+  /// # Arguments
+  /// * `event_publisher`: Arc<dyn OrderEventPublisher>
+  ///
+  /// # Return
+  /// * `PaymentCommandProcessor`
+  pub fn with_event_publisher(mut self, event_publisher: Arc<dyn OrderEventPublisher>) -> Self {
+    self.event_publisher = event_publisher;
+    self
+  }
+
+  /// 注文の支払総額で支払いを承認します
+  ///
+  /// 決済代行サービスが拒否した場合は失敗した支払いとして記録し、注文は支払い失敗になります
+  ///
+  /// # Arguments
+  /// * `command`: AuthorizePayment
+  ///
+  /// # Return
+  /// * `Result<(Payment, Vec<PaymentEvent>), CommandError>`
+  #[instrument(skip_all, fields(command = "authorize_payment", order_id = %command.order_id))]
+  pub fn authorize_payment(&self, command: AuthorizePayment) -> Result<(Payment, Vec<PaymentEvent>), CommandError> {
+    observe(self.metrics.as_ref(), "authorize_payment", "payment", || self.handle_authorize_payment(command))
+  }
+
+  fn handle_authorize_payment(&self, command: AuthorizePayment) -> Result<(Payment, Vec<PaymentEvent>), CommandError> {
+    let order_id = OrderId::from_str(&command.order_id).map_err(OrderError::from)?;
+    let order = self.find_order(&order_id)?.aggregate;
+    if !order.get_status().is_awaiting_payment() {
+      Err(OrderError::InvalidStatus { order_id: order_id.clone(), status: order.get_status(), action: "authorize payment" })?
+    }
+    let mut payment = Payment::new(PaymentId::generate(self.id_generator.as_ref()), order_id, *order.get_grand_total())?;
+    let event = match self.payment_provider.authorize(payment.get_id(), payment.get_amount()) {
+      Ok(provider_reference) => payment.authorize(&provider_reference, self.clock.as_ref())?,
+      Err(PaymentProviderError::Declined(reason)) => payment.fail(&reason, self.clock.as_ref())?,
+      Err(e) => Err(PaymentError::from(e))?,
+    };
+    self.payment_repository.insert(payment.clone())?;
+    self.apply_to_order(&event)?;
+    Ok((payment, vec![event]))
+  }
+
+  /// 承認済みの支払いの売上を確定します
+  ///
+  /// 決済代行サービスが拒否した場合は失敗した支払いとして記録し、注文は支払い失敗になります
+  ///
+  /// # Arguments
+  /// * `command`: CapturePayment
+  ///
+  /// # Return
+  /// * `Result<(Payment, Vec<PaymentEvent>), CommandError>`
+  #[instrument(skip_all, fields(command = "capture_payment", payment_id = %command.payment_id, order_id))]
+  pub fn capture_payment(&self, command: CapturePayment) -> Result<(Payment, Vec<PaymentEvent>), CommandError> {
+    observe(self.metrics.as_ref(), "capture_payment", "payment", || self.handle_capture_payment(command))
+  }
+
+  fn handle_capture_payment(&self, command: CapturePayment) -> Result<(Payment, Vec<PaymentEvent>), CommandError> {
+    let Versioned { aggregate: payment, version } = self.find_payment(&command.payment_id)?;
+    Span::current().record("order_id", field::display(payment.get_order_id()));
+    // 決済代行サービスを呼び出す前に、売上を確定できる状態であることを検証します
+    let mut captured = payment.clone();
+    let captured_event = captured.capture(self.clock.as_ref())?;
+    let provider_reference = payment.get_provider_reference().unwrap_or_default();
+    let (payment, event) = match self.payment_provider.capture(provider_reference, payment.get_amount()) {
+      Ok(()) => (captured, captured_event),
+      Err(PaymentProviderError::Declined(reason)) => {
+        let mut failed = payment;
+        let event = failed.fail(&reason, self.clock.as_ref())?;
+        (failed, event)
+      }
+      Err(e) => Err(PaymentError::from(e))?,
+    };
+    self.payment_repository.update(payment.clone(), version)?;
+    self.apply_to_order(&event)?;
+    Ok((payment, vec![event]))
+  }
+
+  /// 売上を確定した支払いを返金します
+  ///
+  /// 返金額を省略した場合は返金できる残りの金額をすべて返金します。
+  /// 同時に返金しても返金額の累計が支払い金額を超えないよう、決済代行サービスを呼び出す前に返金を予約した支払いを保存します。
+  /// 決済代行サービスが拒否した場合は予約を取り消してエラーを返します。
+  /// 結果が得られなかった場合は予約を残してエラーを返すため、`retry_refunds`で再実行できます
+  ///
+  /// # Arguments
+  /// * `command`: RefundPayment
+  ///
+  /// # Return
+  /// * `Result<(Payment, Vec<PaymentEvent>), CommandError>`: RefundRequestedとPaymentRefunded
+  #[instrument(skip_all, fields(command = "refund_payment", payment_id = %command.payment_id, order_id))]
+  pub fn refund_payment(&self, command: RefundPayment) -> Result<(Payment, Vec<PaymentEvent>), CommandError> {
+    observe(self.metrics.as_ref(), "refund_payment", "payment", || self.handle_refund_payment(command))
+  }
+
+  fn handle_refund_payment(&self, command: RefundPayment) -> Result<(Payment, Vec<PaymentEvent>), CommandError> {
+    let payment_id = PaymentId::from_str(&command.payment_id).map_err(PaymentError::from)?;
+    let refund_id = RefundId::generate(self.id_generator.as_ref());
+    let (payment, requested) = self.update_payment(&payment_id, |payment| {
+      let amount = match command.amount {
+        Some(amount) => Money::new(amount, payment.get_amount().currency())?,
+        None => payment.get_refundable_amount(),
+      };
+      payment.request_refund(refund_id.clone(), amount, self.clock.as_ref())
+    })?;
+    Span::current().record("order_id", field::display(payment.get_order_id()));
+    let PaymentEvent::RefundRequested(requested_refund) = &requested else {
+      unreachable!("request_refund returns RefundRequested")
+    };
+    let pending = PendingRefund { refund_id, amount: requested_refund.amount };
+    let (payment, refunded) = self.settle_refund(&payment, &pending)?;
+    self.apply_to_order(&refunded)?;
+    Ok((payment, vec![requested, refunded]))
+  }
+
+  /// 決済代行サービスの結果が得られずに残った返金を、同じ返金IDで再実行します
+  ///
+  /// 決済代行サービスへの返金は返金IDで冪等なため、前回の呼び出しで返金されていた場合も二重には返金されません
+  ///
+  /// # Arguments
+  /// * `command`: RetryRefunds
+  ///
+  /// # Return
+  /// * `Result<(Payment, Vec<PaymentEvent>), CommandError>`: 再実行した返金のPaymentRefunded
+  #[instrument(skip_all, fields(command = "retry_refunds", payment_id = %command.payment_id, order_id))]
+  pub fn retry_refunds(&self, command: RetryRefunds) -> Result<(Payment, Vec<PaymentEvent>), CommandError> {
+    observe(self.metrics.as_ref(), "retry_refunds", "payment", || self.handle_retry_refunds(command))
+  }
+
+  fn handle_retry_refunds(&self, command: RetryRefunds) -> Result<(Payment, Vec<PaymentEvent>), CommandError> {
+    let Versioned { aggregate: mut payment, .. } = self.find_payment(&command.payment_id)?;
+    Span::current().record("order_id", field::display(payment.get_order_id()));
+    let mut events = vec![];
+    for pending in payment.get_pending_refunds().to_vec() {
+      let (settled, event) = self.settle_refund(&payment, &pending)?;
+      self.apply_to_order(&event)?;
+      payment = settled;
+      events.push(event);
+    }
+    Ok((payment, events))
+  }
+
+  /// 出荷済みの注文の明細を返品し、返金額を売上確定済みの支払いから返金します
+  ///
+  /// 返金額は注文の明細ごとの支払額から計算します。
+  /// 決済代行サービスを呼び出す前に、支払いに返金を予約してから返品を記録した注文を保存します。
+  /// 注文を保存できなかった場合は予約を取り消します。
+  /// 決済代行サービスの結果が得られなかった場合は予約を残してエラーを返すため、`retry_refunds`で再実行できます。
+  /// 返金額が0の場合は決済代行サービスを呼び出しません
+  ///
+  /// # Arguments
+  /// * `command`: ReturnItems
+  ///
+  /// # Return
+  /// * `Result<(Order, Vec<OrderEvent>), CommandError>`: 全額を返金した場合はOrderStatusChangedも返します
+  #[instrument(skip_all, fields(command = "return_items", order_id = %command.order_id))]
+  pub fn return_items(&self, command: ReturnItems) -> Result<(Order, Vec<OrderEvent>), CommandError> {
+    observe(self.metrics.as_ref(), "return_items", "order", || self.handle_return_items(command))
+  }
+
+  fn handle_return_items(&self, command: ReturnItems) -> Result<(Order, Vec<OrderEvent>), CommandError> {
+    let order_id = OrderId::from_str(&command.order_id).map_err(OrderError::from)?;
+    let items = command.items
+      .into_iter()
+      .map(|item| -> Result<ReturnItem, OrderError> {
+        Ok(ReturnItem {
+          order_item_id: OrderItemId::from_str(&item.order_item_id)?,
+          quantity: Quantity::try_from(item.quantity)?,
+        })
+      })
+      .collect::<Result<Vec<ReturnItem>, OrderError>>()?;
+    let Versioned { aggregate: mut order, version: order_version } = self.find_order(&order_id)?;
+    let returned = order.return_items(items, self.clock.as_ref())?;
+    let refund_amount = returned.refund_amount;
+    let mut events = vec![OrderEvent::ItemsReturned(returned)];
+    if refund_amount.amount().is_zero() {
+      self.order_repository.update(order, order_version)?;
+      self.event_publisher.publish(&events);
+      let order = self.find_order(&order_id)?.aggregate;
+      return Ok((order, events));
+    }
+
+    let captured = self.payment_repository
+      .find_by_order_id(&order_id)?
+      .into_iter()
+      .find(|payment| matches!(payment.get_status(), PaymentStatus::Captured | PaymentStatus::PartiallyRefunded))
+      .ok_or_else(|| PaymentError::CapturedPaymentNotFound(order_id.to_string()))?;
+    let refund_id = RefundId::generate(self.id_generator.as_ref());
+    let (payment, _) = self.update_payment(captured.get_id(), |payment| {
+      payment.request_refund(refund_id.clone(), refund_amount, self.clock.as_ref())
+    })?;
+    if let Err(e) = self.order_repository.update(order, order_version) {
+      self.update_payment(payment.get_id(), |payment| {
+        payment.cancel_refund(&refund_id, "order was not updated", self.clock.as_ref())
+      })?;
+      Err(e)?
+    }
+    self.event_publisher.publish(&events);
+    let pending = PendingRefund { refund_id, amount: refund_amount };
+    let (_, payment_event) = self.settle_refund(&payment, &pending)?;
+    events.extend(self.apply_to_order(&payment_event)?);
+    let order = self.find_order(&order_id)?.aggregate;
+    Ok((order, events))
+  }
+
+  /// 注文を取得します
+  fn find_order(&self, order_id: &OrderId) -> Result<Versioned<Order>, CommandError> {
+    let order = self.order_repository
+      .find_by_id(order_id)?
+      .ok_or_else(|| OrderError::OrderNotFound(order_id.to_string()))?;
+    Ok(order)
+  }
+
+  /// 支払いを取得します
+  fn find_payment(&self, payment_id: &str) -> Result<Versioned<Payment>, CommandError> {
+    let payment_id = PaymentId::from_str(payment_id).map_err(PaymentError::from)?;
+    let payment = self.payment_repository
+      .find_by_id(&payment_id)?
+      .ok_or_else(|| PaymentError::PaymentNotFound(payment_id.to_string()))?;
+    Ok(payment)
+  }
+
+  /// 予約した返金を決済代行サービスで実行し、結果を支払いに記録します
+  ///
+  /// 決済代行サービスが拒否した場合は予約を取り消し、結果が得られなかった場合は予約を残してエラーを返します
+  ///
+  /// # Arguments
+  /// * `payment`: 返金を予約した支払い
+  /// * `pending`: 予約した返金
+  ///
+  /// # Return
+  /// * `Result<(Payment, PaymentEvent), CommandError>`: 返金を記録した支払いとPaymentRefunded
+  fn settle_refund(&self, payment: &Payment, pending: &PendingRefund) -> Result<(Payment, PaymentEvent), CommandError> {
+    let provider_reference = payment.get_provider_reference().unwrap_or_default();
+    match self.payment_provider.refund(provider_reference, &pending.refund_id, &pending.amount) {
+      Ok(()) => {
+        let (payment, event) = self.update_payment(payment.get_id(), |payment| {
+          payment.complete_refund(&pending.refund_id, self.clock.as_ref())
+        })?;
+        Ok((payment, event))
+      }
+      Err(PaymentProviderError::Declined(reason)) => {
+        self.update_payment(payment.get_id(), |payment| {
+          payment.cancel_refund(&pending.refund_id, &reason, self.clock.as_ref())
+        })?;
+        Err(PaymentError::from(PaymentProviderError::Declined(reason)))?
+      }
+      Err(e) => Err(PaymentError::from(e))?,
+    }
+  }
+
+  /// 支払いを取得して変更し、保存します
+  ///
+  /// 他の変更と同時に更新してバージョンが競合した場合は、支払いを再取得して変更からやり直します
+  ///
+  /// # Arguments
+  /// * `payment_id`: 変更する支払いのID
+  /// * `change`: 支払いを変更してイベントを返す処理
+  ///
+  /// # Return
+  /// * `Result<(Payment, PaymentEvent), CommandError>`: 保存した支払いと変更のイベント
+  fn update_payment(
+    &self,
+    payment_id: &PaymentId,
+    change: impl Fn(&mut Payment) -> Result<PaymentEvent, PaymentError>,
+  ) -> Result<(Payment, PaymentEvent), CommandError> {
+    for _ in 0..MAX_UPDATE_ATTEMPTS {
+      let Versioned { aggregate: mut payment, version } = self.find_payment(&payment_id.to_string())?;
+      let event = change(&mut payment)?;
+      match self.payment_repository.update(payment.clone(), version) {
+        Ok(()) => return Ok((payment, event)),
+        Err(RepositoryError::VersionConflict { .. }) => continue,
+        Err(e) => Err(e)?,
+      }
+    }
+    Err(CommandError::ConcurrencyConflict(payment_id.to_string()))
+  }
+
+  /// 支払いのイベントを注文に適用します
+  ///
+  /// 他の変更と同時に更新してバージョンが競合した場合は、注文を再取得して再試行します
+  fn apply_to_order(&self, event: &PaymentEvent) -> Result<Option<OrderEvent>, CommandError> {
+    for _ in 0..MAX_UPDATE_ATTEMPTS {
+      let Versioned { aggregate: mut order, version } = self.find_order(event.order_id())?;
+      let Some(order_event) = order.apply_payment_event(event, self.clock.as_ref())? else {
+        return Ok(None);
+      };
+      match self.order_repository.update(order, version) {
+        Ok(()) => {
+          self.event_publisher.publish(std::slice::from_ref(&order_event));
+          return Ok(Some(order_event));
+        }
+        Err(RepositoryError::VersionConflict { .. }) => continue,
+        Err(e) => Err(e)?,
+      }
+    }
+    Err(CommandError::ConcurrencyConflict(event.order_id().to_string()))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::command::{PlaceOrder, PlaceOrderItem, ReturnItemValue, ShipOrder, ShippingAddressValue};
+  use crate::order_command_processor::OrderCommandProcessor;
+  use chrono::{TimeZone, Utc};
+  use command_domain::clock::FixedClock;
+  use command_domain::customer::customer_id::CustomerId;
+  use command_domain::customer::customer_limits::CustomerLimits;
+  use command_domain::customer::customer_repository::CustomerRepository;
+  use command_domain::customer::Customer;
+  use command_domain::id_generator::{SequentialIdGenerator, UuidV4Generator};
+  use command_domain::order::order_status::OrderStatus;
+  use command_domain::payment::payment_status::PaymentStatus;
+  use command_infrastructure::fake_payment_provider::FakePaymentProvider;
+  use command_infrastructure::in_memory_customer_repository::InMemoryCustomerRepository;
+  use command_infrastructure::in_memory_order_repository::InMemoryOrderRepository;
+  use command_infrastructure::in_memory_payment_repository::InMemoryPaymentRepository;
+  use rust_decimal::Decimal;
+
+  const CUSTOMER_ID: &str = "CUSTOMER-00000000-0000-0000-0000-000000000001";
+
+  struct Fixture {
+    orders: OrderCommandProcessor,
+    payments: PaymentCommandProcessor,
+    order_repository: Arc<InMemoryOrderRepository>,
+    payment_provider: Arc<FakePaymentProvider>,
+    published: Arc<RecordingPublisher>,
+  }
+
+  /// 配信した注文のイベントを記録するOrderEventPublisherです
+  #[derive(Default)]
+  struct RecordingPublisher(std::sync::Mutex<Vec<OrderEvent>>);
+
+  impl OrderEventPublisher for RecordingPublisher {
+    fn publish(&self, events: &[OrderEvent]) {
+      self.0.lock().unwrap().extend_from_slice(events);
+    }
+  }
+
+  impl RecordingPublisher {
+    /// 配信したイベントの種類を配信順に返します
+    fn types(&self) -> Vec<String> {
+      self.0
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|event| serde_json::to_value(event).unwrap()["type"].as_str().unwrap().to_string())
+        .collect()
+    }
+  }
+
+  fn fixture(payment_provider: FakePaymentProvider) -> Fixture {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()));
+    let id_generator = Arc::new(SequentialIdGenerator::new(1));
+    let customer_repository = Arc::new(InMemoryCustomerRepository::new());
+    let (customer, _) = Customer::register(
+      CustomerId::from_str(CUSTOMER_ID).unwrap(), clock.as_ref(), "山田 太郎", CustomerLimits::unlimited(),
+    ).unwrap();
+    customer_repository.insert(customer).unwrap();
+    let order_repository = Arc::new(InMemoryOrderRepository::new());
+    let published = Arc::new(RecordingPublisher::default());
+    let payment_provider = Arc::new(payment_provider);
+    Fixture {
+      orders: OrderCommandProcessor::new(clock.clone(), id_generator.clone(), customer_repository, order_repository.clone())
+        .with_event_publisher(published.clone()),
+      payments: PaymentCommandProcessor::new(
+        clock,
+        id_generator,
+        Arc::new(InMemoryPaymentRepository::new()),
+        order_repository.clone(),
+        payment_provider.clone(),
+      )
+        .with_event_publisher(published.clone()),
+      order_repository,
+      payment_provider,
+      published,
+    }
+  }
+
+  impl Fixture {
+    fn place_order(&self) -> String {
+      let (order, _) = self.orders.place_order(PlaceOrder {
+        customer_id: CUSTOMER_ID.to_string(),
+        currency: "JPY".to_string(),
+        region: "JP".to_string(),
+        items: vec![PlaceOrderItem {
+          product_id: 1,
+          product_name: "hogehoge".to_string(),
+          product_category: "general".to_string(),
+          unit_price: Decimal::from(500),
+          discount: None,
+          quantity: 2,
+        }],
+        discounts: vec![],
+        coupon_code: None,
+        shipping_address: ShippingAddressValue {
+          recipient: "山田 太郎".to_string(),
+          country: "JP".to_string(),
+          postal_code: "100-0001".to_string(),
+          subdivision: Some("東京都".to_string()),
+          city: "千代田区".to_string(),
+          line1: "千代田1-1".to_string(),
+          line2: None,
+        },
+        delivery_method: "standard".to_string(),
+      }).unwrap();
+      order.get_id().to_string()
+    }
+
+    fn ship_order(&self) -> Order {
+      let order_id = self.place_order();
+      let (payment, _) = self.payments.authorize_payment(AuthorizePayment { order_id: order_id.clone() }).unwrap();
+      self.payments.capture_payment(CapturePayment { payment_id: payment.get_id().to_string() }).unwrap();
+      self.orders.ship_order(ShipOrder { order_id }).unwrap().0
+    }
+
+    fn order_status(&self, order_id: &str) -> OrderStatus {
+      let order_id = OrderId::from_str(order_id).unwrap();
+      self.order_repository.find_by_id(&order_id).unwrap().unwrap().aggregate.get_status()
+    }
+  }
+
+  #[test]
+  fn test_authorize_capture_and_refund_success() {
+    let fixture = fixture(FakePaymentProvider::new());
+    let order_id = fixture.place_order();
+
+    let (authorized, _) = fixture.payments.authorize_payment(AuthorizePayment { order_id: order_id.clone() }).unwrap();
+    let status_after_authorize = fixture.order_status(&order_id);
+    let payment_id = authorized.get_id().to_string();
+    let (captured, _) = fixture.payments.capture_payment(CapturePayment { payment_id: payment_id.clone() }).unwrap();
+    let status_after_capture = fixture.order_status(&order_id);
+    let (partial, _) = fixture.payments.refund_payment(RefundPayment {
+      payment_id: payment_id.clone(),
+      amount: Some(Decimal::from(300)),
+    }).unwrap();
+    let status_after_partial = fixture.order_status(&order_id);
+    let (refunded, events) = fixture.payments.refund_payment(RefundPayment { payment_id, amount: None }).unwrap();
+
+    // assert
+    assert_eq!(Some("fake-1"), authorized.get_provider_reference());
+    assert_eq!(&Decimal::from(1000), authorized.get_amount().amount());
+    assert_eq!(OrderStatus::PaymentAuthorized, status_after_authorize);
+    assert_eq!(PaymentStatus::Captured, captured.get_status());
+    assert_eq!(OrderStatus::Paid, status_after_capture);
+    assert_eq!(PaymentStatus::PartiallyRefunded, partial.get_status());
+    assert_eq!(OrderStatus::Paid, status_after_partial);
+    assert_eq!(PaymentStatus::Refunded, refunded.get_status());
+    assert!(matches!(&events[0], PaymentEvent::RefundRequested(event) if event.amount.amount() == &Decimal::from(700)));
+    assert!(matches!(&events[1], PaymentEvent::PaymentRefunded(event) if event.amount.amount() == &Decimal::from(700)));
+    assert!(refunded.get_pending_refunds().is_empty());
+    assert_eq!(OrderStatus::Refunded, fixture.order_status(&order_id));
+  }
+
+  #[test]
+  fn test_authorize_payment_declined_success() {
+    let fixture = fixture(FakePaymentProvider::new().fail_next(PaymentProviderError::Declined("card declined".to_string())));
+    let order_id = fixture.place_order();
+
+    let (declined, events) = fixture.payments.authorize_payment(AuthorizePayment { order_id: order_id.clone() }).unwrap();
+    let status_after_decline = fixture.order_status(&order_id);
+    let (retried, _) = fixture.payments.authorize_payment(AuthorizePayment { order_id: order_id.clone() }).unwrap();
+
+    // assert
+    assert_eq!(PaymentStatus::Failed, declined.get_status());
+    assert!(matches!(&events[0], PaymentEvent::PaymentFailed(event) if event.reason == "card declined"));
+    assert_eq!(OrderStatus::PaymentFailed, status_after_decline);
+    assert_eq!(PaymentStatus::Authorized, retried.get_status());
+    assert_eq!(OrderStatus::PaymentAuthorized, fixture.order_status(&order_id));
+  }
+
+  #[test]
+  fn test_authorize_payment_failed() {
+    let fixture = fixture(FakePaymentProvider::new().fail_next(PaymentProviderError::Unavailable("timeout".to_string())));
+    let order_id = fixture.place_order();
+
+    let unavailable = fixture.payments.authorize_payment(AuthorizePayment { order_id: order_id.clone() });
+    fixture.payments.authorize_payment(AuthorizePayment { order_id: order_id.clone() }).unwrap();
+    let duplicated = fixture.payments.authorize_payment(AuthorizePayment { order_id: order_id.clone() });
+    let not_found = fixture.payments.authorize_payment(AuthorizePayment { order_id: OrderId::generate(&UuidV4Generator).to_string() });
+
+    // assert
+    assert!(matches!(
+      unavailable,
+      Err(CommandError::InvalidPayment(PaymentError::Provider(PaymentProviderError::Unavailable(_))))
+    ));
+    assert!(matches!(
+      duplicated,
+      Err(CommandError::InvalidOrder(OrderError::InvalidStatus { status: OrderStatus::PaymentAuthorized, .. }))
+    ));
+    assert!(matches!(not_found, Err(CommandError::InvalidOrder(OrderError::OrderNotFound(_)))));
+  }
+
+  #[test]
+  fn test_capture_payment_declined_success() {
+    let fixture = fixture(FakePaymentProvider::new());
+    let order_id = fixture.place_order();
+    let (authorized, _) = fixture.payments.authorize_payment(AuthorizePayment { order_id: order_id.clone() }).unwrap();
+    let fixture = Fixture {
+      payments: PaymentCommandProcessor {
+        payment_provider: Arc::new(FakePaymentProvider::new().fail_next(PaymentProviderError::Declined("expired".to_string()))),
+        ..fixture.payments
+      },
+      ..fixture
+    };
+
+    let (failed, _) = fixture.payments.capture_payment(CapturePayment { payment_id: authorized.get_id().to_string() }).unwrap();
+
+    // assert
+    assert_eq!(PaymentStatus::Failed, failed.get_status());
+    assert_eq!(OrderStatus::PaymentFailed, fixture.order_status(&order_id));
+  }
+
+  #[test]
+  fn test_refund_payment_failed() {
+    let fixture = fixture(FakePaymentProvider::new());
+    let order_id = fixture.place_order();
+    let (authorized, _) = fixture.payments.authorize_payment(AuthorizePayment { order_id }).unwrap();
+    let payment_id = authorized.get_id().to_string();
+
+    let before_capture = fixture.payments.refund_payment(RefundPayment { payment_id: payment_id.clone(), amount: None });
+    fixture.payments.capture_payment(CapturePayment { payment_id: payment_id.clone() }).unwrap();
+    let exceeded = fixture.payments.refund_payment(RefundPayment {
+      payment_id: payment_id.clone(),
+      amount: Some(Decimal::from(1001)),
+    });
+    let not_found = fixture.payments.refund_payment(RefundPayment { payment_id: PaymentId::generate(&UuidV4Generator).to_string(), amount: None });
+
+    // assert
+    assert!(matches!(before_capture, Err(CommandError::InvalidPayment(PaymentError::InvalidTransition { .. }))));
+    assert!(matches!(exceeded, Err(CommandError::InvalidPayment(PaymentError::RefundExceedsCaptured { .. }))));
+    assert!(matches!(not_found, Err(CommandError::InvalidPayment(PaymentError::PaymentNotFound(_)))));
+  }
+
+  /// 最初の取得だけ、登録した時点の支払いを返すPaymentRepositoryです
+  ///
+  /// 同じバージョンの支払いを読み込んだ2つの返金が、同時に処理される状況を再現します
+  struct StalePaymentRepository {
+    inner: Arc<dyn PaymentRepository>,
+    stale: std::sync::Mutex<Option<Versioned<Payment>>>,
+  }
+
+  impl PaymentRepository for StalePaymentRepository {
+    fn find_by_id(&self, payment_id: &PaymentId) -> Result<Option<Versioned<Payment>>, RepositoryError> {
+      match self.stale.lock().unwrap().take() {
+        Some(stale) => Ok(Some(stale)),
+        None => self.inner.find_by_id(payment_id),
+      }
+    }
+
+    fn find_by_order_id(&self, order_id: &OrderId) -> Result<Vec<Payment>, RepositoryError> {
+      self.inner.find_by_order_id(order_id)
+    }
+
+    fn insert(&self, payment: Payment) -> Result<(), RepositoryError> {
+      self.inner.insert(payment)
+    }
+
+    fn update(&self, payment: Payment, expected_version: u64) -> Result<(), RepositoryError> {
+      self.inner.update(payment, expected_version)
+    }
+  }
+
+  #[test]
+  fn test_refund_payment_same_version_success() {
+    let fixture = fixture(FakePaymentProvider::new());
+    let order_id = fixture.place_order();
+    let (authorized, _) = fixture.payments.authorize_payment(AuthorizePayment { order_id }).unwrap();
+    let payment_id = authorized.get_id().to_string();
+    fixture.payments.capture_payment(CapturePayment { payment_id: payment_id.clone() }).unwrap();
+    let stale = fixture.payments.find_payment(&payment_id).unwrap();
+    let refund = || RefundPayment { payment_id: payment_id.clone(), amount: Some(Decimal::from(700)) };
+
+    let (first, _) = fixture.payments.refund_payment(refund()).unwrap();
+    let repository = StalePaymentRepository {
+      inner: fixture.payments.payment_repository.clone(),
+      stale: std::sync::Mutex::new(Some(stale)),
+    };
+    let payments = PaymentCommandProcessor { payment_repository: Arc::new(repository), ..fixture.payments };
+    let second = payments.refund_payment(refund());
+
+    // assert
+    assert_eq!(&Decimal::from(700), first.get_refunded_amount().amount());
+    assert!(matches!(second, Err(CommandError::InvalidPayment(PaymentError::RefundExceedsCaptured { .. }))));
+    assert_eq!(1, fixture.payment_provider.refunds().len());
+    assert_eq!(&Decimal::from(700), payments.find_payment(&payment_id).unwrap().aggregate.get_refunded_amount().amount());
+  }
+
+  #[test]
+  fn test_retry_refunds_success() {
+    let fixture = fixture(FakePaymentProvider::new());
+    let order_id = fixture.place_order();
+    let (authorized, _) = fixture.payments.authorize_payment(AuthorizePayment { order_id: order_id.clone() }).unwrap();
+    let payment_id = authorized.get_id().to_string();
+    fixture.payments.capture_payment(CapturePayment { payment_id: payment_id.clone() }).unwrap();
+    let payment_provider = Arc::new(FakePaymentProvider::new().fail_next(PaymentProviderError::Unavailable("timeout".to_string())));
+    let fixture = Fixture {
+      payments: PaymentCommandProcessor { payment_provider: payment_provider.clone(), ..fixture.payments },
+      ..fixture
+    };
+    let payments = &fixture.payments;
+
+    let unavailable = payments.refund_payment(RefundPayment { payment_id: payment_id.clone(), amount: None });
+    let pending = payments.find_payment(&payment_id).unwrap().aggregate;
+    let exceeded = payments.refund_payment(RefundPayment { payment_id: payment_id.clone(), amount: Some(Decimal::from(1)) });
+    let (retried, events) = payments.retry_refunds(RetryRefunds { payment_id: payment_id.clone() }).unwrap();
+    let (repeated, repeated_events) = payments.retry_refunds(RetryRefunds { payment_id }).unwrap();
+
+    // assert
+    assert!(matches!(
+      unavailable,
+      Err(CommandError::InvalidPayment(PaymentError::Provider(PaymentProviderError::Unavailable(_))))
+    ));
+    assert_eq!(PaymentStatus::Captured, pending.get_status());
+    assert_eq!(&Decimal::from(1000), pending.get_pending_refund_amount().amount());
+    assert!(matches!(exceeded, Err(CommandError::InvalidPayment(PaymentError::RefundExceedsCaptured { .. }))));
+    assert_eq!(PaymentStatus::Refunded, retried.get_status());
+    assert!(retried.get_pending_refunds().is_empty());
+    assert!(matches!(&events[0], PaymentEvent::PaymentRefunded(event) if event.amount.amount() == &Decimal::from(1000)));
+    assert_eq!(1, payment_provider.refunds().len());
+    assert_eq!(PaymentStatus::Refunded, repeated.get_status());
+    assert!(repeated_events.is_empty());
+    assert_eq!(OrderStatus::Refunded, fixture.order_status(&order_id));
+  }
+
+  #[test]
+  fn test_refund_payment_declined_failed() {
+    let fixture = fixture(FakePaymentProvider::new());
+    let order_id = fixture.place_order();
+    let (authorized, _) = fixture.payments.authorize_payment(AuthorizePayment { order_id: order_id.clone() }).unwrap();
+    let payment_id = authorized.get_id().to_string();
+    fixture.payments.capture_payment(CapturePayment { payment_id: payment_id.clone() }).unwrap();
+    let payment_provider = Arc::new(FakePaymentProvider::new().fail_next(PaymentProviderError::Declined("closed".to_string())));
+    let fixture = Fixture {
+      payments: PaymentCommandProcessor { payment_provider: payment_provider.clone(), ..fixture.payments },
+      ..fixture
+    };
+    let payments = &fixture.payments;
+
+    let declined = payments.refund_payment(RefundPayment { payment_id: payment_id.clone(), amount: None });
+    let cancelled = payments.find_payment(&payment_id).unwrap().aggregate;
+
+    // assert
+    assert!(matches!(
+      declined,
+      Err(CommandError::InvalidPayment(PaymentError::Provider(PaymentProviderError::Declined(_))))
+    ));
+    assert!(cancelled.get_pending_refunds().is_empty());
+    assert_eq!(&Decimal::from(1000), cancelled.get_refundable_amount().amount());
+    assert!(payment_provider.refunds().is_empty());
+    assert_eq!(OrderStatus::Paid, fixture.order_status(&order_id));
+  }
+
+  fn return_items(order: &Order, quantity: i32) -> ReturnItems {
+    ReturnItems {
+      order_id: order.get_id().to_string(),
+      items: vec![ReturnItemValue {
+        order_item_id: order.get_order_items()[0].get_order_item_id().to_string(),
+        quantity,
+      }],
+    }
+  }
+
+  #[test]
+  fn test_return_items_success() {
+    let fixture = fixture(FakePaymentProvider::new());
+    let order = fixture.ship_order();
+
+    let (partial, partial_events) = fixture.payments.return_items(return_items(&order, 1)).unwrap();
+    let (returned, events) = fixture.payments.return_items(return_items(&order, 1)).unwrap();
+
+    // assert
+    assert_eq!(OrderStatus::Shipped, partial.get_status());
+    assert_eq!(1, partial_events.len());
+    assert!(matches!(&partial_events[0], OrderEvent::ItemsReturned(event) if event.refund_amount.amount() == &Decimal::from(500)));
+    assert_eq!(OrderStatus::Refunded, returned.get_status());
+    assert_eq!(2, returned.get_returned_quantity(order.get_order_items()[0].get_order_item_id()));
+    assert!(matches!(&events[0], OrderEvent::ItemsReturned(event) if event.refund_amount.amount() == &Decimal::from(500)));
+    assert!(matches!(&events[1], OrderEvent::OrderStatusChanged(event) if event.status == OrderStatus::Refunded));
+  }
+
+  #[test]
+  fn test_return_items_failed() {
+    let fixture = fixture(FakePaymentProvider::new());
+    let order = fixture.ship_order();
+    let placed = fixture.place_order();
+
+    let exceeded = fixture.payments.return_items(return_items(&order, 3));
+    let zero = fixture.payments.return_items(return_items(&order, 0));
+    let not_shipped = fixture.payments.return_items(ReturnItems { order_id: placed, ..return_items(&order, 1) });
+
+    // assert
+    assert!(matches!(exceeded, Err(CommandError::InvalidOrder(OrderError::ReturnQuantityExceeded { .. }))));
+    assert!(matches!(zero, Err(CommandError::InvalidOrder(OrderError::InvalidQuantity(_)))));
+    assert!(matches!(not_shipped, Err(CommandError::InvalidOrder(OrderError::InvalidStatus { status: OrderStatus::Placed, .. }))));
+    assert_eq!(OrderStatus::Shipped, fixture.order_status(&order.get_id().to_string()));
+  }
+
+  /// 注文の保存が常にバージョンの競合になるOrderRepositoryです
+  struct ConflictingOrderRepository(Arc<dyn OrderRepository>);
+
+  impl OrderRepository for ConflictingOrderRepository {
+    fn find_by_id(&self, order_id: &OrderId) -> Result<Option<Versioned<Order>>, RepositoryError> {
+      self.0.find_by_id(order_id)
+    }
+
+    fn insert(&self, order: Order) -> Result<(), RepositoryError> {
+      self.0.insert(order)
+    }
+
+    fn update(&self, order: Order, expected_version: u64) -> Result<(), RepositoryError> {
+      Err(RepositoryError::VersionConflict { id: order.get_id().to_string(), expected: expected_version, actual: expected_version + 1 })
+    }
+  }
+
+  #[test]
+  fn test_return_items_unavailable_and_retry_success() {
+    let fixture = fixture(FakePaymentProvider::new());
+    let order = fixture.ship_order();
+    let order_id = order.get_id().to_string();
+    let payment_provider = Arc::new(FakePaymentProvider::new().fail_next(PaymentProviderError::Unavailable("timeout".to_string())));
+    let fixture = Fixture {
+      payments: PaymentCommandProcessor { payment_provider: payment_provider.clone(), ..fixture.payments },
+      ..fixture
+    };
+    let payment_id = fixture.payments.payment_repository
+      .find_by_order_id(order.get_id())
+      .unwrap()[0]
+      .get_id()
+      .to_string();
+
+    let unavailable = fixture.payments.return_items(return_items(&order, 2));
+    let status_after_unavailable = fixture.order_status(&order_id);
+    let pending = fixture.payments.find_payment(&payment_id).unwrap().aggregate;
+    let (retried, _) = fixture.payments.retry_refunds(RetryRefunds { payment_id }).unwrap();
+
+    // assert
+    assert!(matches!(
+      unavailable,
+      Err(CommandError::InvalidPayment(PaymentError::Provider(PaymentProviderError::Unavailable(_))))
+    ));
+    assert_eq!(OrderStatus::Shipped, status_after_unavailable);
+    assert_eq!(&Decimal::from(1000), pending.get_pending_refund_amount().amount());
+    assert_eq!(PaymentStatus::Refunded, retried.get_status());
+    assert_eq!(1, payment_provider.refunds().len());
+    assert_eq!(OrderStatus::Refunded, fixture.order_status(&order_id));
+    assert_eq!(vec!["ItemsReturned", "OrderStatusChanged"], fixture.published.types()[4..].to_vec());
+  }
+
+  #[test]
+  fn test_return_items_order_conflict_failed() {
+    let fixture = fixture(FakePaymentProvider::new());
+    let order = fixture.ship_order();
+    let payments = PaymentCommandProcessor {
+      order_repository: Arc::new(ConflictingOrderRepository(fixture.order_repository.clone())),
+      ..fixture.payments
+    };
+    let payment_id = payments.payment_repository.find_by_order_id(order.get_id()).unwrap()[0].get_id().to_string();
+
+    let conflict = payments.return_items(return_items(&order, 1));
+    let payment = payments.find_payment(&payment_id).unwrap().aggregate;
+
+    // assert
+    assert!(matches!(conflict, Err(CommandError::Repository(RepositoryError::VersionConflict { .. }))));
+    assert!(payment.get_pending_refunds().is_empty());
+    assert_eq!(&Decimal::from(1000), payment.get_refundable_amount().amount());
+    assert!(fixture.payment_provider.refunds().is_empty());
+  }
+
+  #[test]
+  fn test_saved_order_events_published() {
+    let fixture = fixture(FakePaymentProvider::new());
+    let order = fixture.ship_order();
+    let placed = fixture.place_order();
+
+    fixture.payments.return_items(return_items(&order, 2)).unwrap();
+    let published = fixture.published.types();
+    let exceeded = fixture.payments.return_items(return_items(&order, 1));
+    let not_paid = fixture.orders.ship_order(ShipOrder { order_id: placed });
+
+    // assert
+    assert_eq!(
+      vec![
+        "OrderPlaced", "OrderStatusChanged", "OrderStatusChanged", "OrderShipped",
+        "OrderPlaced", "ItemsReturned", "OrderStatusChanged",
+      ],
+      published,
+    );
+    assert!(exceeded.is_err());
+    assert!(not_paid.is_err());
+    assert_eq!(published, fixture.published.types());
+  }
+}
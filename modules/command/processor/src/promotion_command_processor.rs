@@ -0,0 +1,165 @@
+use crate::command::CreatePromotion;
+use crate::command_error::CommandError;
+use crate::command_metrics::{observe, CommandMetrics, NoopCommandMetrics};
+use command_domain::clock::Clock;
+use command_domain::id_generator::IdGenerator;
+use command_domain::order::order_error::OrderError;
+use command_domain::product::product_category::ProductCategory;
+use command_domain::promotion::promotion_event::PromotionEvent;
+use command_domain::promotion::promotion_id::PromotionId;
+use command_domain::promotion::promotion_repository::PromotionRepository;
+use command_domain::promotion::promotion_terms::{PromotionScope, PromotionTerms};
+use command_domain::promotion::Promotion;
+use command_domain::value_object::coupon_code::CouponCode;
+use command_domain::value_object::currency::Currency;
+use command_domain::value_object::money::MoneyError;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::instrument;
+
+/// プロモーションのコマンドを処理するクラスです
+pub struct PromotionCommandProcessor {
+  clock: Arc<dyn Clock>,
+  id_generator: Arc<dyn IdGenerator>,
+  promotion_repository: Arc<dyn PromotionRepository>,
+  metrics: Arc<dyn CommandMetrics>,
+}
+
+impl PromotionCommandProcessor {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `clock`: Arc<dyn Clock>
+  /// * `id_generator`: Arc<dyn IdGenerator>
+  /// * `promotion_repository`: Arc<dyn PromotionRepository>
+  ///
+  /// # Return
+  /// * `PromotionCommandProcessor`
+  pub fn new(
+    clock: Arc<dyn Clock>,
+    id_generator: Arc<dyn IdGenerator>,
+    promotion_repository: Arc<dyn PromotionRepository>,
+  ) -> Self {
+    Self { clock, id_generator, promotion_repository, metrics: Arc::new(NoopCommandMetrics) }
+  }
+
+  /// コマンドの処理結果を記録するメトリクスを設定します
+  ///
+  /// 指定がない場合は何も記録しません
+  ///
+  /// # Arguments
+  /// * `metrics`: Arc<dyn CommandMetrics>
+  ///
+  /// # Return
+  /// * `PromotionCommandProcessor`
+  pub fn with_metrics(mut self, metrics: Arc<dyn CommandMetrics>) -> Self {
+    self.metrics = metrics;
+    self
+  }
+
+  /// プロモーションを作成します
+  ///
+  /// # Arguments
+  /// * `command`: CreatePromotion
+  ///
+  /// # Return
+  /// * `Result<(Promotion, Vec<PromotionEvent>), CommandError>`
+  #[instrument(skip_all, fields(command = "create_promotion"))]
+  pub fn create_promotion(&self, command: CreatePromotion) -> Result<(Promotion, Vec<PromotionEvent>), CommandError> {
+    observe(self.metrics.as_ref(), "create_promotion", "promotion", || self.handle_create_promotion(command))
+  }
+
+  fn handle_create_promotion(&self, command: CreatePromotion) -> Result<(Promotion, Vec<PromotionEvent>), CommandError> {
+    let currency = Currency::from_str(&command.currency).map_err(MoneyError::from).map_err(OrderError::from)?;
+    let scope = match command.category {
+      Some(category) => PromotionScope::Category(ProductCategory::new(&category).map_err(OrderError::from)?),
+      None => PromotionScope::Order,
+    };
+    let terms = PromotionTerms {
+      coupon_code: CouponCode::new(&command.coupon_code).map_err(OrderError::from)?,
+      discount: command.discount.to_discount(currency)?,
+      scope,
+      stackable: command.stackable,
+      valid_from: command.valid_from,
+      valid_until: command.valid_until,
+      usage_limit: command.usage_limit,
+      per_customer_limit: command.per_customer_limit,
+    };
+    let (promotion, events) = Promotion::create(
+      PromotionId::generate(self.id_generator.as_ref()),
+      self.clock.as_ref(),
+      terms,
+    )?;
+    self.promotion_repository.insert(promotion.clone())?;
+    Ok((promotion, events))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::command::DiscountValue;
+  use chrono::{Duration, TimeZone, Utc};
+  use command_domain::clock::FixedClock;
+  use command_domain::id_generator::SequentialIdGenerator;
+  use command_domain::promotion::promotion_error::PromotionError;
+  use command_domain::repository::RepositoryError;
+  use command_infrastructure::in_memory_promotion_repository::InMemoryPromotionRepository;
+  use rust_decimal::Decimal;
+
+  fn processor() -> PromotionCommandProcessor {
+    let clock = FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap());
+    PromotionCommandProcessor::new(
+      Arc::new(clock),
+      Arc::new(SequentialIdGenerator::new(1)),
+      Arc::new(InMemoryPromotionRepository::new()),
+    )
+  }
+
+  fn create_promotion_command() -> CreatePromotion {
+    CreatePromotion {
+      coupon_code: "food10".to_string(),
+      discount: DiscountValue::Percentage(Decimal::from(10)),
+      currency: "JPY".to_string(),
+      category: Some("Food".to_string()),
+      stackable: false,
+      valid_from: Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+      valid_until: None,
+      usage_limit: Some(100),
+      per_customer_limit: Some(1),
+    }
+  }
+
+  #[test]
+  fn test_create_promotion_success() {
+    let (promotion, events) = processor().create_promotion(create_promotion_command()).unwrap();
+
+    // assert
+    assert_eq!("PROMOTION-00000000-0000-0001-0000-000000000001", promotion.get_id().to_string());
+    assert_eq!("FOOD10", promotion.get_coupon_code().value());
+    assert_eq!(
+      PromotionScope::Category(ProductCategory::new("food").unwrap()),
+      promotion.get_terms().scope
+    );
+    assert_eq!(1, events.len());
+  }
+
+  #[test]
+  fn test_create_promotion_failed() {
+    let processor = processor();
+    processor.create_promotion(create_promotion_command()).unwrap();
+    let mut invalid_period = create_promotion_command();
+    invalid_period.coupon_code = "WINTER".to_string();
+    invalid_period.valid_until = Some(invalid_period.valid_from - Duration::days(1));
+
+    // assert
+    assert!(matches!(
+      processor.create_promotion(create_promotion_command()),
+      Err(CommandError::Repository(RepositoryError::AlreadyExists(_)))
+    ));
+    assert!(matches!(
+      processor.create_promotion(invalid_period),
+      Err(CommandError::InvalidPromotion(PromotionError::InvalidPeriod { .. }))
+    ));
+  }
+}
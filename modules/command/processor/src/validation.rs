@@ -0,0 +1,77 @@
+use crate::command_error::CommandError;
+use command_domain::validation::{Validated, ValidationErrors};
+
+/// コマンドの入力の検証方式です
+///
+/// FailFast: 最初のエラーのみを返します
+///
+/// Accumulate: すべての値オブジェクトのエラーをまとめて返します
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum ValidationMode {
+  #[default]
+  FailFast,
+  Accumulate,
+}
+
+/// 検証結果をコマンドのエラーの検証結果に変換します
+///
+/// # Arguments
+/// * `result`: 検証結果
+///
+/// # Return
+/// * `Validated<T, CommandError>`
+pub(crate) fn validate<T, E: Into<CommandError>>(result: Result<T, E>) -> Validated<T, CommandError> {
+  result.map_err(|e| ValidationErrors::new(e.into()))
+}
+
+/// 複数のエラーを返す検証結果をコマンドのエラーの検証結果に変換します
+pub(crate) fn validate_all<T, E: Into<CommandError>>(result: Validated<T, E>) -> Validated<T, CommandError> {
+  result.map_err(|errors| errors.map(Into::into))
+}
+
+/// 検証を終了し、検証方式に応じた結果を返します
+///
+/// # Arguments
+/// * `mode`: 検証方式
+/// * `result`: 検証した値から組み立てた値
+///
+/// # Return
+/// * FailFastの場合は最初のエラー、Accumulateの場合は`CommandError::Validation`(エラーが1件の場合はそのエラー)
+pub(crate) fn finish<T>(mode: ValidationMode, result: Validated<T, CommandError>) -> Result<T, CommandError> {
+  result.map_err(|errors| match mode {
+    ValidationMode::FailFast => errors.into_first(),
+    ValidationMode::Accumulate => {
+      let mut errors = errors.into_vec();
+      if errors.len() == 1 { errors.remove(0) } else { CommandError::Validation(errors) }
+    }
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use command_domain::order::order_error::OrderError;
+  use command_domain::validation::zip;
+
+  #[test]
+  fn test_finish() {
+    let result = || {
+      let order_items = validate::<(), _>(Err(OrderError::EmptyOrderItems));
+      let return_items = validate_all::<(), _>(Err(
+        ValidationErrors::new(OrderError::EmptyReturnItems).append(ValidationErrors::new(OrderError::OrderNotFound("1".to_string())))
+      ));
+      zip(zip(order_items, return_items), validate(Ok::<i32, OrderError>(1)))
+    };
+
+    let fail_fast = finish(ValidationMode::FailFast, result());
+    let accumulate = finish(ValidationMode::Accumulate, result());
+    let single = finish(ValidationMode::Accumulate, validate::<(), _>(Err(OrderError::EmptyOrderItems)));
+    let success = finish(ValidationMode::Accumulate, validate(Ok::<i32, OrderError>(1)));
+
+    // assert
+    assert!(matches!(fail_fast, Err(CommandError::InvalidOrder(OrderError::EmptyOrderItems))));
+    assert!(matches!(accumulate, Err(CommandError::Validation(errors)) if errors.len() == 3));
+    assert!(matches!(single, Err(CommandError::InvalidOrder(OrderError::EmptyOrderItems))));
+    assert_eq!(1, success.unwrap());
+  }
+}
@@ -0,0 +1,4 @@
+pub mod order_event_log;
+pub mod order_summary;
+pub mod order_summary_projection;
+pub mod order_summary_store;
@@ -0,0 +1,128 @@
+use command_domain::event_envelope::EventEnvelope;
+use command_domain::order::order_event::OrderEvent;
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// 注文のイベントを追記された順に読み込むイベントログです
+///
+/// 投影処理は読み込んだ位置を覚えておき、一定の間隔で`read_from`を呼び出して追記されたイベントを取得します
+pub trait OrderEventLog: Debug + Send + Sync {
+  /// ログ出力に使うイベントログの場所を返します
+  fn location(&self) -> String;
+
+  /// `position`以降に追記されたイベントを読み込みます
+  ///
+  /// # Arguments
+  /// * `position`: 前回の読み込みで返した位置(最初は0)
+  ///
+  /// # Return
+  /// * 読み込んだイベントと、次に読み込む位置
+  fn read_from(&self, position: u64) -> std::io::Result<(Vec<EventEnvelope<OrderEvent>>, u64)>;
+}
+
+/// ファイルのイベントログです
+///
+/// 書き込み用サーバーが追記するJSON Lines(1行1件の`EventEnvelope<OrderEvent>`)のファイルを読み込みます。
+/// 位置はファイルの先頭からのバイト数です。複数の関数で共有する場合は、EFSなどの共有ストレージに置きます
+#[derive(Debug)]
+pub struct FileOrderEventLog {
+  path: PathBuf,
+}
+
+impl FileOrderEventLog {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `path`: イベントログのファイル
+  ///
+  /// # Return
+  /// * `FileOrderEventLog`
+  pub fn new(path: impl Into<PathBuf>) -> Self {
+    Self { path: path.into() }
+  }
+
+  /// イベントログのファイルのゲッター
+  pub fn path(&self) -> &Path { &self.path }
+}
+
+impl OrderEventLog for FileOrderEventLog {
+  fn location(&self) -> String {
+    self.path.display().to_string()
+  }
+
+  /// ファイルがまだない場合は何も読み込みません。書き込み途中の最後の行は、次の読み込みまで読み込みません。
+  /// 読み込めない行は、ログに出力して読み飛ばします
+  fn read_from(&self, position: u64) -> std::io::Result<(Vec<EventEnvelope<OrderEvent>>, u64)> {
+    let mut file = match File::open(&self.path) {
+      Ok(file) => file,
+      Err(e) if e.kind() == ErrorKind::NotFound => return Ok((vec![], position)),
+      Err(e) => Err(e)?,
+    };
+    file.seek(SeekFrom::Start(position))?;
+    let mut buffer = vec![];
+    file.read_to_end(&mut buffer)?;
+    let Some(end) = buffer.iter().rposition(|byte| *byte == b'\n') else {
+      return Ok((vec![], position));
+    };
+    let envelopes = buffer[..end]
+      .split(|byte| *byte == b'\n')
+      .filter(|line| !line.is_empty())
+      .filter_map(|line| match serde_json::from_slice::<EventEnvelope<OrderEvent>>(line) {
+        Ok(envelope) => Some(envelope),
+        Err(e) => {
+          warn!("skipped an unreadable line in {}: {}", self.path.display(), e);
+          None
+        }
+      })
+      .collect();
+    Ok((envelopes, position + end as u64 + 1))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::{TimeZone, Utc};
+  use command_domain::event_envelope::EventMetadata;
+  use command_domain::id_generator::UuidV4Generator;
+  use command_domain::order::order_event::OrderShipped;
+  use command_domain::order::order_id::OrderId;
+  use std::io::Write;
+
+  fn line() -> String {
+    let event = OrderEvent::OrderShipped(OrderShipped {
+      order_id: OrderId::generate(&UuidV4Generator),
+      occurred_at: Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap(),
+    });
+    serde_json::to_string(&EventEnvelope::new(event, EventMetadata::default())).unwrap() + "\n"
+  }
+
+  #[test]
+  fn test_read_from_returns_next_position() {
+    let dir = std::env::temp_dir().join(format!("query-read-model-event-log-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("order-events.jsonl");
+    let _ = std::fs::remove_file(&path);
+    let event_log = FileOrderEventLog::new(&path);
+    let first = line();
+    let second = line();
+
+    let missing = event_log.read_from(0).unwrap();
+    std::fs::write(&path, first.clone() + &second[..10]).unwrap();
+    let (read, position) = event_log.read_from(0).unwrap();
+    std::fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(&second.as_bytes()[10..]).unwrap();
+    let (rest, end) = event_log.read_from(position).unwrap();
+    let nothing = event_log.read_from(end).unwrap();
+
+    // assert
+    assert_eq!((0, 0), (missing.0.len(), missing.1));
+    assert_eq!(1, read.len());
+    assert_eq!(first.len() as u64, position);
+    assert_eq!(1, rest.len());
+    assert_eq!((first.len() + second.len()) as u64, end);
+    assert_eq!((0, end), (nothing.0.len(), nothing.1));
+  }
+}
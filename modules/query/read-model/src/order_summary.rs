@@ -0,0 +1,509 @@
+use chrono::{DateTime, Utc};
+use command_domain::order::order_event::{OrderEvent, OrderItemPlaced, OrderItemsAdded, OrderPlaced};
+use command_domain::order::order_status::OrderStatus;
+use command_domain::shipping::shipping_address::ShippingAddress;
+use command_domain::tax::tax_breakdown::TaxBreakdown;
+use command_domain::tax::tax_rule::TaxInclusion;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use thiserror::Error;
+use utoipa::ToSchema;
+
+/// 注文サマリーの投影時のエラーです
+#[derive(Debug, Error, PartialEq)]
+pub enum OrderSummaryError {
+  #[error("OrderPlaced event not found")]
+  NotPlaced,
+  #[error("Order ID mismatch: expected={expected}, actual={actual}")]
+  OrderIdMismatch { expected: String, actual: String },
+  #[error("Order summary store is unavailable: {0}")]
+  Unavailable(String),
+}
+
+/// 注文の金額を小計・割引・税・総額に分けて表示するための読み取りモデルです
+///
+/// subtotal: 割引前の小計(単価×数量の合計)
+///
+/// discount_total: 明細割引と注文割引の合計
+///
+/// total_price: 税抜の合計金額(subtotal - discount_total)
+///
+/// tax_total: 税額の合計(内税分を含みます)
+///
+/// grand_total: 支払総額(total_priceに外税を加えた金額)
+///
+/// refunded_total: 返品による返金額の合計
+///
+/// status: 注文の状態(`placed`、`shipped`など)
+#[derive(Serialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct OrderSummary {
+  pub order_id: String,
+  pub customer_id: String,
+  pub ordered_at: DateTime<Utc>,
+  pub status: String,
+  pub currency: String,
+  pub region: String,
+  pub lines: Vec<OrderSummaryLine>,
+  pub discounts: Vec<OrderSummaryDiscount>,
+  pub subtotal: Decimal,
+  pub discount_total: Decimal,
+  pub total_price: Decimal,
+  pub tax_total: Decimal,
+  pub grand_total: Decimal,
+  pub refunded_total: Decimal,
+  pub shipping_address: OrderSummaryAddress,
+  pub delivery_method: String,
+}
+
+/// 注文サマリーの明細です
+///
+/// line_totalは明細割引のみ適用した金額で、注文割引は含みません。
+/// returned_quantityは返品済みの数量です
+#[derive(Serialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct OrderSummaryLine {
+  pub order_item_id: String,
+  pub product_id: i32,
+  pub product_name: String,
+  pub product_category: String,
+  pub unit_price: Decimal,
+  pub quantity: i32,
+  pub returned_quantity: i32,
+  pub line_total: Decimal,
+  pub tax_rate: Decimal,
+  pub tax_inclusive: bool,
+  pub tax_amount: Decimal,
+}
+
+/// 注文サマリーに適用された割引です
+///
+/// order_item_id: 明細割引の場合は明細ID、注文割引の場合はNone
+///
+/// coupon_code: クーポンによる割引の場合はクーポンコード
+///
+/// amount: 割引額
+#[derive(Serialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct OrderSummaryDiscount {
+  pub order_item_id: Option<String>,
+  pub coupon_code: Option<String>,
+  pub amount: Decimal,
+}
+
+/// 注文サマリーの配送先の住所です
+#[derive(Serialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct OrderSummaryAddress {
+  pub recipient: String,
+  pub country: String,
+  pub postal_code: String,
+  pub subdivision: Option<String>,
+  pub city: String,
+  pub line1: String,
+  pub line2: Option<String>,
+}
+
+impl From<&ShippingAddress> for OrderSummaryAddress {
+  fn from(address: &ShippingAddress) -> Self {
+    Self {
+      recipient: address.get_recipient().to_string(),
+      country: address.get_country().to_string(),
+      postal_code: address.get_postal_code().to_string(),
+      subdivision: address.get_subdivision().map(str::to_string),
+      city: address.get_city().to_string(),
+      line1: address.get_line1().to_string(),
+      line2: address.get_line2().map(str::to_string),
+    }
+  }
+}
+
+impl OrderSummary {
+  /// 注文のイベント列から注文サマリーを投影します
+  ///
+  /// # Arguments
+  /// * `events`: 注文のイベント列(先頭はOrderPlaced)
+  ///
+  /// # Return
+  /// * `Result<OrderSummary, OrderSummaryError>`
+  pub fn project(events: &[OrderEvent]) -> Result<Self, OrderSummaryError> {
+    let Some((OrderEvent::OrderPlaced(placed), rest)) = events.split_first() else {
+      Err(OrderSummaryError::NotPlaced)?
+    };
+    let mut summary = Self::from(placed);
+    for event in rest {
+      summary.apply(event)?;
+    }
+    Ok(summary)
+  }
+
+  /// イベントを注文サマリーに適用します
+  ///
+  /// # Arguments
+  /// * `event`: OrderEvent
+  ///
+  /// # Return
+  /// * `Result<(), OrderSummaryError>`
+  pub fn apply(&mut self, event: &OrderEvent) -> Result<(), OrderSummaryError> {
+    let order_id = event.order_id().to_string();
+    if order_id != self.order_id {
+      Err(OrderSummaryError::OrderIdMismatch { expected: self.order_id.clone(), actual: order_id })?
+    }
+    match event {
+      OrderEvent::OrderPlaced(placed) => *self = Self::from(placed),
+      OrderEvent::OrderItemsAdded(added) => self.add_items(added),
+      OrderEvent::LineDiscountApplied(applied) => self.discounts.push(OrderSummaryDiscount {
+        order_item_id: Some(applied.order_item_id.to_string()),
+        coupon_code: None,
+        amount: *applied.amount.amount(),
+      }),
+      OrderEvent::OrderDiscountApplied(applied) => self.discounts.push(OrderSummaryDiscount {
+        order_item_id: None,
+        coupon_code: applied.coupon_code.as_ref().map(|code| code.value().to_string()),
+        amount: *applied.amount.amount(),
+      }),
+      OrderEvent::ShippingAddressChanged(changed) => self.shipping_address = (&changed.address).into(),
+      OrderEvent::OrderShipped(_) => self.status = OrderStatus::Shipped.to_string(),
+      OrderEvent::OrderStatusChanged(changed) => self.status = changed.status.to_string(),
+      OrderEvent::ItemsReturned(returned) => {
+        for item in &returned.items {
+          let order_item_id = item.order_item_id.to_string();
+          if let Some(line) = self.lines.iter_mut().find(|line| line.order_item_id == order_item_id) {
+            line.returned_quantity += item.quantity;
+          }
+        }
+        self.refunded_total += returned.refund_amount.amount();
+      }
+    }
+    Ok(())
+  }
+
+  /// 追加した明細と、追加後の金額を反映します
+  ///
+  /// 注文割引は追加後の小計で再計算されるため、イベントの注文割引で置き換えます。
+  /// 税額は注文割引の按分が変わるため、既存の明細も含めて置き換えます
+  fn add_items(&mut self, added: &OrderItemsAdded) {
+    self.lines.extend(added.order_items.iter().map(|item| OrderSummaryLine::new(item, &added.tax)));
+    for line in &mut self.lines {
+      line.apply_tax(&added.tax);
+    }
+    self.discounts.retain(|discount| discount.order_item_id.is_some());
+    self.discounts.extend(added.order_discounts.iter().map(|applied| OrderSummaryDiscount {
+      order_item_id: None,
+      coupon_code: applied.coupon_code.as_ref().map(|code| code.value().to_string()),
+      amount: *applied.amount.amount(),
+    }));
+    self.subtotal += gross_total(&added.order_items);
+    self.total_price = *added.total_price.amount();
+    self.discount_total = self.subtotal - self.total_price;
+    self.tax_total = *added.tax.total_tax().amount();
+    self.grand_total = *added.grand_total.amount();
+  }
+}
+
+impl OrderSummaryLine {
+  /// 明細と税額の内訳から注文サマリーの明細を作成します
+  fn new(item: &OrderItemPlaced, tax: &TaxBreakdown) -> Self {
+    let mut line = Self {
+      order_item_id: item.order_item_id.to_string(),
+      product_id: item.product_id,
+      product_name: item.product_name.clone(),
+      product_category: item.product_category.to_string(),
+      unit_price: *item.unit_price.amount(),
+      quantity: item.quantity,
+      returned_quantity: 0,
+      line_total: *item.line_total.amount(),
+      tax_rate: Decimal::ZERO,
+      tax_inclusive: false,
+      tax_amount: Decimal::ZERO,
+    };
+    line.apply_tax(tax);
+    line
+  }
+
+  /// 税額の内訳から明細の税率と税額を設定します
+  fn apply_tax(&mut self, tax: &TaxBreakdown) {
+    let line_tax = tax.lines().iter().find(|line| line.order_item_id.to_string() == self.order_item_id);
+    self.tax_rate = line_tax.map_or(Decimal::ZERO, |line| *line.treatment.rate().value());
+    self.tax_inclusive = line_tax.is_some_and(|line| line.treatment.inclusion() == TaxInclusion::Inclusive);
+    self.tax_amount = line_tax.map_or(Decimal::ZERO, |line| *line.tax_amount.amount());
+  }
+}
+
+/// 割引前の明細金額(単価×数量)の合計を計算します
+fn gross_total(order_items: &[OrderItemPlaced]) -> Decimal {
+  order_items
+    .iter()
+    .map(|item| item.unit_price.amount() * Decimal::from(item.quantity))
+    .sum::<Decimal>()
+}
+
+impl From<&OrderPlaced> for OrderSummary {
+  fn from(placed: &OrderPlaced) -> Self {
+    let lines = placed.order_items
+      .iter()
+      .map(|item| OrderSummaryLine::new(item, &placed.tax))
+      .collect::<Vec<OrderSummaryLine>>();
+    let subtotal = gross_total(&placed.order_items);
+    let total_price = *placed.total_price.amount();
+
+    Self {
+      order_id: placed.order_id.to_string(),
+      customer_id: placed.customer_id.to_string(),
+      ordered_at: placed.occurred_at,
+      status: OrderStatus::Placed.to_string(),
+      currency: placed.currency.to_string(),
+      region: placed.region.to_string(),
+      lines,
+      discounts: vec![],
+      subtotal,
+      discount_total: subtotal - total_price,
+      total_price,
+      tax_total: *placed.tax.total_tax().amount(),
+      grand_total: *placed.grand_total.amount(),
+      refunded_total: Decimal::ZERO,
+      shipping_address: (&placed.shipping_address).into(),
+      delivery_method: placed.delivery_method.to_string(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::TimeZone;
+  use command_domain::clock::FixedClock;
+  use command_domain::customer::customer_id::CustomerId;
+  use command_domain::id_generator::UuidV4Generator;
+  use command_domain::order::order_discount::OrderDiscount;
+  use command_domain::order::order_id::OrderId;
+  use command_domain::order::order_item::{OrderItem, OrderItemParams};
+  use command_domain::order::order_item_id::OrderItemId;
+  use command_domain::order::order_pricing::OrderPricing;
+  use command_domain::order::Order;
+  use command_domain::order::order_event::{ItemsReturned, OrderShipped, OrderStatusChanged, ReturnedItem, ShippingAddressChanged};
+  use command_domain::product::product_category::ProductCategory;
+  use command_domain::shipping::delivery_method::DeliveryMethod;
+  use command_domain::shipping::shipping_details::ShippingDetails;
+  use command_domain::tax::region::Region;
+  use command_domain::tax::tax_rule::{RegionalTaxRule, TaxRate, TaxRules, TaxTreatment};
+  use command_domain::value_object::coupon_code::CouponCode;
+  use command_domain::value_object::currency::Currency;
+  use command_domain::value_object::discount::Discount;
+  use command_domain::value_object::money::Money;
+  use std::str::FromStr;
+  use std::sync::Arc;
+
+  const CUSTOMER_ID: &str = "CUSTOMER-00000000-0000-0000-0000-000000000001";
+
+  fn address(postal_code: &str) -> ShippingAddress {
+    ShippingAddress::new("山田 太郎", "JP", postal_code, Some("東京都"), "千代田区", "千代田1-1", None).unwrap()
+  }
+
+  fn tax_rules() -> TaxRules {
+    let rate = |value: i64| TaxRate::try_from(Decimal::from(value)).unwrap();
+    TaxRules::default()
+      .with_rule(Arc::new(RegionalTaxRule::new(
+        Region::from_str("JP").unwrap(),
+        Some(ProductCategory::new("food").unwrap()),
+        TaxTreatment::new(rate(8), TaxInclusion::Exclusive),
+      )))
+      .with_rule(Arc::new(RegionalTaxRule::new(
+        Region::from_str("JP").unwrap(),
+        None,
+        TaxTreatment::new(rate(10), TaxInclusion::Exclusive),
+      )))
+  }
+
+  fn placed_order() -> (Order, Vec<OrderEvent>) {
+    let items = vec![
+      OrderItem::place_order_item(OrderItemParams {
+        order_item_id: OrderItemId::generate(&UuidV4Generator),
+        product_id: 1,
+        product_name: "おにぎり",
+        product_category: "food",
+        unit_price: Decimal::from(500),
+        currency: "JPY",
+        discount: Discount::none(),
+        quantity: 2,
+      }).unwrap(),
+      OrderItem::place_order_item(OrderItemParams {
+        order_item_id: OrderItemId::generate(&UuidV4Generator),
+        product_id: 2,
+        product_name: "タオル",
+        product_category: "general",
+        unit_price: Decimal::from(1000),
+        currency: "JPY",
+        discount: Discount::try_from(10).unwrap(),
+        quantity: 2,
+      }).unwrap(),
+    ];
+    let order_discounts = vec![OrderDiscount::new(
+      Discount::fixed_amount(Money::new(Decimal::from(280), Currency::JPY).unwrap()).unwrap(),
+      Some(CouponCode::new("WELCOME").unwrap()),
+      false,
+    )];
+    Order::place_order(
+      OrderId::generate(&UuidV4Generator),
+      CustomerId::from_str(CUSTOMER_ID).unwrap(),
+      &FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap()),
+      OrderPricing {
+        currency: Currency::JPY,
+        rounding_policy: Currency::JPY.default_rounding_policy(),
+        region: Region::from_str("JP").unwrap(),
+        tax_rule: &tax_rules(),
+      },
+      items,
+      order_discounts,
+      ShippingDetails { address: address("100-0001"), delivery_method: DeliveryMethod::Express },
+    ).unwrap()
+  }
+
+  fn order_events() -> Vec<OrderEvent> {
+    placed_order().1
+  }
+
+  #[test]
+  fn test_order_summary_project_success() {
+    let summary = OrderSummary::project(&order_events()).unwrap();
+
+    // assert
+    // 小計3000円、明細割引200円、注文割引280円を1000:1800で按分(100円、180円)
+    // 食品: 900円 × 8% = 72円、一般: 1620円 × 10% = 162円
+    assert_eq!(Decimal::from(3000), summary.subtotal);
+    assert_eq!(Decimal::from(480), summary.discount_total);
+    assert_eq!(Decimal::from(2520), summary.total_price);
+    assert_eq!(Decimal::from(234), summary.tax_total);
+    assert_eq!(Decimal::from(2754), summary.grand_total);
+    assert_eq!(Decimal::from(8), summary.lines[0].tax_rate);
+    assert_eq!(Decimal::from(72), summary.lines[0].tax_amount);
+    assert_eq!(Decimal::from(1800), summary.lines[1].line_total);
+    assert_eq!(Decimal::from(162), summary.lines[1].tax_amount);
+    assert_eq!(
+      summary.discount_total,
+      summary.discounts.iter().map(|discount| discount.amount).sum::<Decimal>()
+    );
+    assert_eq!(Some("WELCOME".to_string()), summary.discounts[1].coupon_code);
+  }
+
+  #[test]
+  fn test_order_summary_apply_items_added_success() {
+    let (mut order, mut events) = placed_order();
+    let item = OrderItem::place_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id: 3,
+      product_name: "お茶",
+      product_category: "food",
+      unit_price: Decimal::from(200),
+      currency: "JPY",
+      discount: Discount::try_from(10).unwrap(),
+      quantity: 5,
+    }).unwrap();
+    events.extend(order.add_items(vec![item], &tax_rules(), &FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 10, 0, 0).unwrap())).unwrap());
+
+    let summary = OrderSummary::project(&events).unwrap();
+
+    // assert
+    assert_eq!(3, summary.lines.len());
+    assert_eq!(Decimal::from(4000), summary.subtotal);
+    assert_eq!(order.get_total_price().amount(), &summary.total_price);
+    assert_eq!(order.get_grand_total().amount(), &summary.grand_total);
+    assert_eq!(order.get_tax_breakdown().total_tax().amount(), &summary.tax_total);
+    assert_eq!(summary.tax_total, summary.lines.iter().map(|line| line.tax_amount).sum::<Decimal>());
+    assert_eq!(
+      summary.discount_total,
+      summary.discounts.iter().map(|discount| discount.amount).sum::<Decimal>()
+    );
+    assert_eq!(3, summary.discounts.len());
+  }
+
+  #[test]
+  fn test_order_summary_serialize_success() {
+    let summary = OrderSummary::project(&order_events()).unwrap();
+
+    let json = serde_json::to_value(&summary).unwrap();
+
+    // assert
+    assert_eq!("JPY", json["currency"]);
+    assert_eq!(CUSTOMER_ID, json["customer_id"]);
+    assert_eq!("JP", json["region"]);
+    assert_eq!("3000", json["subtotal"]);
+    assert_eq!("2754", json["grand_total"]);
+    assert_eq!("placed", json["status"]);
+    assert_eq!("express", json["delivery_method"]);
+    assert_eq!("100-0001", json["shipping_address"]["postal_code"]);
+    assert_eq!(serde_json::Value::Null, json["shipping_address"]["line2"]);
+  }
+
+  #[test]
+  fn test_order_summary_apply_shipping_events_success() {
+    let mut summary = OrderSummary::project(&order_events()).unwrap();
+    let order_id = OrderId::from_str(&summary.order_id).unwrap();
+    let occurred_at = Utc.with_ymd_and_hms(2024, 10, 2, 9, 0, 0).unwrap();
+
+    summary.apply(&OrderEvent::ShippingAddressChanged(ShippingAddressChanged {
+      order_id: order_id.clone(),
+      occurred_at,
+      previous_address: address("100-0001"),
+      address: address("150-0001"),
+    })).unwrap();
+    summary.apply(&OrderEvent::OrderShipped(OrderShipped { order_id, occurred_at })).unwrap();
+
+    // assert
+    assert_eq!("150-0001", summary.shipping_address.postal_code);
+    assert_eq!("shipped", summary.status);
+  }
+
+  #[test]
+  fn test_order_summary_apply_status_changed_success() {
+    let mut summary = OrderSummary::project(&order_events()).unwrap();
+
+    summary.apply(&OrderEvent::OrderStatusChanged(OrderStatusChanged {
+      order_id: OrderId::from_str(&summary.order_id).unwrap(),
+      occurred_at: Utc.with_ymd_and_hms(2024, 10, 2, 9, 0, 0).unwrap(),
+      previous_status: OrderStatus::Placed,
+      status: OrderStatus::PaymentAuthorized,
+    })).unwrap();
+
+    // assert
+    assert_eq!("payment_authorized", summary.status);
+  }
+
+  #[test]
+  fn test_order_summary_apply_items_returned_success() {
+    let mut summary = OrderSummary::project(&order_events()).unwrap();
+    let refund_amount = Money::new(Decimal::from(540), Currency::JPY).unwrap();
+
+    summary.apply(&OrderEvent::ItemsReturned(ItemsReturned {
+      order_id: OrderId::from_str(&summary.order_id).unwrap(),
+      occurred_at: Utc.with_ymd_and_hms(2024, 10, 5, 9, 0, 0).unwrap(),
+      items: vec![ReturnedItem {
+        order_item_id: OrderItemId::from_str(&summary.lines[0].order_item_id).unwrap(),
+        quantity: 1,
+        refund_amount,
+      }],
+      refund_amount,
+    })).unwrap();
+
+    // assert
+    assert_eq!(1, summary.lines[0].returned_quantity);
+    assert_eq!(0, summary.lines[1].returned_quantity);
+    assert_eq!(Decimal::from(540), summary.refunded_total);
+  }
+
+  #[test]
+  fn test_order_summary_project_failed() {
+    let events = order_events();
+
+    // assert
+    assert_eq!(Err(OrderSummaryError::NotPlaced), OrderSummary::project(&[]));
+    assert_eq!(Err(OrderSummaryError::NotPlaced), OrderSummary::project(&events[1..]));
+  }
+
+  #[test]
+  fn test_order_summary_apply_other_order_failed() {
+    let mut summary = OrderSummary::project(&order_events()).unwrap();
+    let other = order_events();
+
+    let result = summary.apply(&other[1]);
+
+    // assert
+    assert!(matches!(result, Err(OrderSummaryError::OrderIdMismatch { .. })))
+  }
+}
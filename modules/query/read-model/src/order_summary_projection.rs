@@ -0,0 +1,265 @@
+use crate::order_event_log::OrderEventLog;
+use crate::order_summary_store::InMemoryOrderSummaryStore;
+use command_domain::event_id::EventId;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// イベントログの注文のイベントを注文サマリーのストアに投影します
+///
+/// 読み込んだ位置を覚えておき、`catch_up`のたびにイベントログに追記されたイベントのみを投影します。
+/// 書き出しの失敗で再送されたイベントを重複して適用しないよう、適用済みのイベントIDを覚えておき読み飛ばします
+///
+/// 最後にイベントログの末尾まで投影した時刻を記録し、投影の遅れ(`lag`)として返します
+#[derive(Debug)]
+pub struct OrderSummaryProjection {
+  store: Arc<InMemoryOrderSummaryStore>,
+  event_log: Arc<dyn OrderEventLog>,
+  position: Mutex<u64>,
+  applied_event_ids: Mutex<HashSet<EventId>>,
+  caught_up_at: Mutex<Instant>,
+}
+
+impl OrderSummaryProjection {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `store`: 投影先のストア
+  /// * `event_log`: 投影元のイベントログ
+  ///
+  /// # Return
+  /// * `OrderSummaryProjection`
+  pub fn new(store: Arc<InMemoryOrderSummaryStore>, event_log: Arc<dyn OrderEventLog>) -> Self {
+    Self {
+      store,
+      event_log,
+      position: Mutex::new(0),
+      applied_event_ids: Mutex::new(HashSet::new()),
+      caught_up_at: Mutex::new(Instant::now()),
+    }
+  }
+
+  /// ログ出力に使うイベントログの場所を返します
+  pub fn location(&self) -> String {
+    self.event_log.location()
+  }
+
+  /// 投影の遅れを返します
+  ///
+  /// 最後にイベントログの末尾まで投影してからの経過時間です。
+  /// イベントログを読み込めない間や`catch_up`が呼ばれない間は増え続けます
+  ///
+  /// # Return
+  /// * `Duration`
+  pub fn lag(&self) -> Duration {
+    self.caught_up_at.lock().unwrap_or_else(PoisonError::into_inner).elapsed()
+  }
+
+  /// 前回の読み込み以降にイベントログに追記されたイベントを投影します
+  ///
+  /// 投影できないイベント(投影前に確定した注文のイベントなど)は、ログに出力して読み飛ばします。
+  /// 適用済みのイベントIDのイベントは、投影せずに読み飛ばします
+  ///
+  /// # Return
+  /// * `std::io::Result<usize>`: 投影したイベントの件数
+  pub fn catch_up(&self) -> std::io::Result<usize> {
+    let mut position = self.position.lock().unwrap_or_else(PoisonError::into_inner);
+    let read_at = Instant::now();
+    let (envelopes, next) = self.event_log.read_from(*position)?;
+    *position = next;
+    let mut applied_event_ids = self.applied_event_ids.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut applied = 0;
+    for envelope in envelopes {
+      if let Some(event_id) = &envelope.metadata.event_id {
+        if !applied_event_ids.insert(event_id.clone()) {
+          continue;
+        }
+      }
+      match self.store.apply_envelope(&envelope) {
+        Ok(()) => applied += 1,
+        Err(e) => warn!(order_id = %envelope.event.order_id(), "skipped an order event that cannot be projected: {}", e),
+      }
+    }
+    *self.caught_up_at.lock().unwrap_or_else(PoisonError::into_inner) = read_at;
+    Ok(applied)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::order_event_log::FileOrderEventLog;
+  use crate::order_summary_store::OrderSummaryQuery;
+  use chrono::{TimeZone, Utc};
+  use command_domain::clock::FixedClock;
+  use command_domain::customer::customer_id::CustomerId;
+  use command_domain::event_envelope::{EventEnvelope, EventMetadata};
+  use command_domain::id_generator::UuidV4Generator;
+  use command_domain::order::order_event::OrderEvent;
+  use command_domain::order::order_id::OrderId;
+  use command_domain::order::order_item::{OrderItem, OrderItemParams};
+  use command_domain::order::order_item_id::OrderItemId;
+  use command_domain::order::order_pricing::OrderPricing;
+  use command_domain::order::Order;
+  use command_domain::shipping::delivery_method::DeliveryMethod;
+  use command_domain::shipping::shipping_address::ShippingAddress;
+  use command_domain::shipping::shipping_details::ShippingDetails;
+  use command_domain::tax::region::Region;
+  use command_domain::tax::tax_rule::TaxRules;
+  use command_domain::value_object::currency::Currency;
+  use command_domain::value_object::discount::Discount;
+  use rust_decimal::Decimal;
+  use std::io::Write;
+  use std::path::{Path, PathBuf};
+  use std::str::FromStr;
+
+  const ALICE: &str = "CUSTOMER-00000000-0000-0000-0000-000000000001";
+
+  fn order_events() -> Vec<OrderEvent> {
+    let clock = FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap());
+    let item = OrderItem::place_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id: 1,
+      product_name: "hogehoge",
+      product_category: "general",
+      unit_price: Decimal::from(500),
+      currency: "JPY",
+      discount: Discount::try_from(10).unwrap(),
+      quantity: 2,
+    }).unwrap();
+    Order::place_order(
+      OrderId::generate(&UuidV4Generator),
+      CustomerId::from_str(ALICE).unwrap(),
+      &clock,
+      OrderPricing {
+        currency: Currency::JPY,
+        rounding_policy: Currency::JPY.default_rounding_policy(),
+        region: Region::from_str("JP").unwrap(),
+        tax_rule: &TaxRules::default(),
+      },
+      vec![item],
+      vec![],
+      ShippingDetails {
+        address: ShippingAddress::new("山田 太郎", "JP", "100-0001", Some("東京都"), "千代田区", "千代田1-1", None).unwrap(),
+        delivery_method: DeliveryMethod::Standard,
+      },
+    ).unwrap().1
+  }
+
+  /// イベントをJSON Linesの行にします
+  fn lines(events: &[OrderEvent]) -> String {
+    events
+      .iter()
+      .map(|event| serde_json::to_string(&EventEnvelope::new(event.clone(), EventMetadata::default())).unwrap() + "\n")
+      .collect()
+  }
+
+  /// イベントIDを付けたイベントをJSON Linesの行にします
+  fn lines_with_ids(events: &[OrderEvent], event_ids: &[EventId]) -> String {
+    events
+      .iter()
+      .zip(event_ids)
+      .map(|(event, event_id)| {
+        let metadata = EventMetadata::default().with_event_id(event_id.clone());
+        serde_json::to_string(&EventEnvelope::new(event.clone(), metadata)).unwrap() + "\n"
+      })
+      .collect()
+  }
+
+  fn event_log(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("query-read-model-{}-{}", test_name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("order-events.jsonl");
+    let _ = std::fs::remove_file(&path);
+    path
+  }
+
+  fn append(path: &Path, content: &[u8]) {
+    std::fs::OpenOptions::new().create(true).append(true).open(path).unwrap().write_all(content).unwrap();
+  }
+
+  #[test]
+  fn test_catch_up_projects_appended_events() {
+    let path = event_log("catch-up");
+    let store = Arc::new(InMemoryOrderSummaryStore::new());
+    let projection = OrderSummaryProjection::new(store.clone(), Arc::new(FileOrderEventLog::new(&path)));
+    let first = order_events();
+    let second = order_events();
+    let second_lines = lines(&second);
+    let (written, partial) = second_lines.as_bytes().split_at(second_lines.len() / 2);
+
+    let missing = projection.catch_up().unwrap();
+    append(&path, lines(&first).as_bytes());
+    append(&path, written);
+    let first_applied = projection.catch_up().unwrap();
+    let after_first = store.find_by_customer_id(ALICE).unwrap().len();
+    append(&path, partial);
+    let second_applied = projection.catch_up().unwrap();
+    let nothing = projection.catch_up().unwrap();
+
+    // assert
+    assert_eq!(0, missing);
+    assert_eq!(first.len(), first_applied);
+    assert_eq!(1, after_first);
+    assert_eq!(second.len(), second_applied);
+    assert_eq!(0, nothing);
+    assert_eq!(2, store.find_by_customer_id(ALICE).unwrap().len());
+    assert_eq!(1, store.find_by_order_id(&first[0].order_id().to_string()).unwrap().unwrap().discounts.len());
+  }
+
+  #[test]
+  fn test_catch_up_skips_unreadable_lines_and_unplaced_orders() {
+    let path = event_log("skip");
+    let store = Arc::new(InMemoryOrderSummaryStore::new());
+    let projection = OrderSummaryProjection::new(store.clone(), Arc::new(FileOrderEventLog::new(&path)));
+    let unplaced = order_events();
+    let placed = order_events();
+
+    append(&path, (lines(&unplaced[1..]) + "not json\n" + &lines(&placed)).as_bytes());
+    let applied = projection.catch_up().unwrap();
+
+    // assert
+    assert_eq!(placed.len(), applied);
+    assert!(store.find_by_order_id(&unplaced[0].order_id().to_string()).unwrap().is_none());
+    assert!(store.find_by_order_id(&placed[0].order_id().to_string()).unwrap().is_some());
+  }
+
+  #[test]
+  fn test_catch_up_skips_applied_event_ids() {
+    let path = event_log("dedupe");
+    let store = Arc::new(InMemoryOrderSummaryStore::new());
+    let projection = OrderSummaryProjection::new(store.clone(), Arc::new(FileOrderEventLog::new(&path)));
+    let events = order_events();
+    let event_ids = events.iter().map(|_| EventId::generate(&UuidV4Generator)).collect::<Vec<_>>();
+    let all = lines_with_ids(&events, &event_ids);
+    let interrupted = &all[..lines_with_ids(&events[..1], &event_ids[..1]).len() + 10];
+
+    append(&path, interrupted.as_bytes());
+    append(&path, ("\n".to_string() + &all).as_bytes());
+    let applied = projection.catch_up().unwrap();
+
+    // assert
+    assert_eq!(events.len(), applied);
+    assert_eq!(1, store.find_by_order_id(&events[0].order_id().to_string()).unwrap().unwrap().discounts.len());
+  }
+
+  #[test]
+  fn test_lag() {
+    let path = event_log("lag");
+    let projection = OrderSummaryProjection::new(Arc::new(InMemoryOrderSummaryStore::new()), Arc::new(FileOrderEventLog::new(&path)));
+    let unreadable = OrderSummaryProjection::new(Arc::new(InMemoryOrderSummaryStore::new()), Arc::new(FileOrderEventLog::new(path.parent().unwrap())));
+
+    std::thread::sleep(Duration::from_millis(50));
+    let before = projection.lag();
+    projection.catch_up().unwrap();
+    let after = projection.lag();
+    let failed = unreadable.catch_up();
+
+    // assert
+    assert!(before >= Duration::from_millis(50));
+    assert!(after < before);
+    assert!(failed.is_err());
+    assert!(unreadable.lag() >= Duration::from_millis(50));
+  }
+}
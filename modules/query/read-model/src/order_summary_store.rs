@@ -0,0 +1,288 @@
+use crate::order_summary::{OrderSummary, OrderSummaryError};
+use command_domain::event_envelope::EventEnvelope;
+use command_domain::order::order_event::OrderEvent;
+use shared_telemetry::propagation;
+use std::collections::HashMap;
+use std::sync::{PoisonError, RwLock};
+use tracing::info_span;
+
+/// 注文サマリーを参照するクエリのトレイトです
+pub trait OrderSummaryQuery: Send + Sync {
+  /// 注文IDに対応する注文サマリーを取得します
+  ///
+  /// # Arguments
+  /// * `order_id`: `ORDER-<uuid>`形式の注文ID
+  ///
+  /// # Return
+  /// * `Result<Option<OrderSummary>, OrderSummaryError>`
+  fn find_by_order_id(&self, order_id: &str) -> Result<Option<OrderSummary>, OrderSummaryError>;
+
+  /// 顧客の注文サマリーを注文日時の新しい順に取得します
+  ///
+  /// # Arguments
+  /// * `customer_id`: `CUSTOMER-<uuid>`形式の顧客ID
+  ///
+  /// # Return
+  /// * `Result<Vec<OrderSummary>, OrderSummaryError>`
+  fn find_by_customer_id(&self, customer_id: &str) -> Result<Vec<OrderSummary>, OrderSummaryError>;
+
+  /// クエリを利用できる場合trueを返します
+  ///
+  /// readinessの判定に使います
+  fn is_available(&self) -> bool;
+}
+
+/// メモリ上に注文サマリーを保持するストアです
+///
+/// 注文のイベントを`apply`で投影して更新します
+#[derive(Debug, Default)]
+pub struct InMemoryOrderSummaryStore {
+  summaries: RwLock<HashMap<String, OrderSummary>>,
+}
+
+impl InMemoryOrderSummaryStore {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// 注文のイベントを注文サマリーに投影します
+  ///
+  /// OrderPlacedは新しい注文サマリーを作成し、それ以外のイベントは既存の注文サマリーに適用します
+  ///
+  /// # Arguments
+  /// * `event`: OrderEvent
+  ///
+  /// # Return
+  /// * `Result<(), OrderSummaryError>`
+  pub fn apply(&self, event: &OrderEvent) -> Result<(), OrderSummaryError> {
+    let _entered = info_span!("projection.apply", order_id = %event.order_id()).entered();
+    let mut summaries = self.summaries.write().map_err(unavailable)?;
+    let order_id = event.order_id().to_string();
+    match event {
+      OrderEvent::OrderPlaced(placed) => {
+        summaries.insert(order_id, OrderSummary::from(placed));
+      }
+      _ => summaries
+        .get_mut(&order_id)
+        .ok_or(OrderSummaryError::NotPlaced)?
+        .apply(event)?,
+    }
+    Ok(())
+  }
+
+  /// メタデータ付きの注文のイベントを注文サマリーに投影します
+  ///
+  /// 投影のスパンの親を、メタデータのトレースコンテキスト(イベントを発生させたリクエストのスパン)にします
+  ///
+  /// # Arguments
+  /// * `envelope`: EventEnvelope<OrderEvent>
+  ///
+  /// # Return
+  /// * `Result<(), OrderSummaryError>`
+  pub fn apply_envelope(&self, envelope: &EventEnvelope<OrderEvent>) -> Result<(), OrderSummaryError> {
+    let span = info_span!("projection", order_id = %envelope.event.order_id());
+    propagation::set_parent(&span, &envelope.metadata.trace_context);
+    span.in_scope(|| self.apply(&envelope.event))
+  }
+}
+
+impl OrderSummaryQuery for InMemoryOrderSummaryStore {
+  fn find_by_order_id(&self, order_id: &str) -> Result<Option<OrderSummary>, OrderSummaryError> {
+    Ok(self.summaries.read().map_err(unavailable)?.get(order_id).cloned())
+  }
+
+  fn find_by_customer_id(&self, customer_id: &str) -> Result<Vec<OrderSummary>, OrderSummaryError> {
+    let mut summaries = self.summaries
+      .read()
+      .map_err(unavailable)?
+      .values()
+      .filter(|summary| summary.customer_id == customer_id)
+      .cloned()
+      .collect::<Vec<OrderSummary>>();
+    summaries.sort_by(|a, b| b.ordered_at.cmp(&a.ordered_at).then_with(|| a.order_id.cmp(&b.order_id)));
+    Ok(summaries)
+  }
+
+  /// 投影中のスレッドがパニックしてロックが壊れた場合、以降の操作はすべて失敗するためfalseになります
+  fn is_available(&self) -> bool {
+    !self.summaries.is_poisoned()
+  }
+}
+
+/// 投影中のパニックで壊れたロックをエラーにします
+///
+/// 投影が途中で止まった注文サマリーを返さないように、ロックを回復せずに失敗させます
+fn unavailable<T>(e: PoisonError<T>) -> OrderSummaryError {
+  OrderSummaryError::Unavailable(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::{Duration, TimeZone, Utc};
+  use command_domain::clock::FixedClock;
+  use command_domain::customer::customer_id::CustomerId;
+  use command_domain::event_envelope::EventMetadata;
+  use command_domain::id_generator::UuidV4Generator;
+  use command_domain::order::order_id::OrderId;
+  use command_domain::order::order_item::{OrderItem, OrderItemParams};
+  use command_domain::order::order_item_id::OrderItemId;
+  use command_domain::order::order_pricing::OrderPricing;
+  use command_domain::order::Order;
+  use command_domain::tax::region::Region;
+  use command_domain::shipping::delivery_method::DeliveryMethod;
+  use command_domain::shipping::shipping_address::ShippingAddress;
+  use command_domain::shipping::shipping_details::ShippingDetails;
+  use command_domain::tax::tax_rule::TaxRules;
+  use command_domain::value_object::currency::Currency;
+  use command_domain::value_object::discount::Discount;
+  use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+  use rust_decimal::Decimal;
+  use shared_telemetry::tracer;
+  use std::str::FromStr;
+  use tracing_subscriber::layer::SubscriberExt;
+
+  const ALICE: &str = "CUSTOMER-00000000-0000-0000-0000-000000000001";
+  const BOB: &str = "CUSTOMER-00000000-0000-0000-0000-000000000002";
+
+  fn order_events(customer_id: &str, hours: i64) -> Vec<OrderEvent> {
+    let clock = FixedClock::new(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap() + Duration::hours(hours));
+    let item = OrderItem::place_order_item(OrderItemParams {
+      order_item_id: OrderItemId::generate(&UuidV4Generator),
+      product_id: 1,
+      product_name: "hogehoge",
+      product_category: "general",
+      unit_price: Decimal::from(500),
+      currency: "JPY",
+      discount: Discount::try_from(10).unwrap(),
+      quantity: 2,
+    }).unwrap();
+    Order::place_order(
+      OrderId::generate(&UuidV4Generator),
+      CustomerId::from_str(customer_id).unwrap(),
+      &clock,
+      OrderPricing {
+        currency: Currency::JPY,
+        rounding_policy: Currency::JPY.default_rounding_policy(),
+        region: Region::from_str("JP").unwrap(),
+        tax_rule: &TaxRules::default(),
+      },
+      vec![item],
+      vec![],
+      ShippingDetails {
+        address: ShippingAddress::new(
+          "山田 太郎", "JP", "100-0001", Some("東京都"), "千代田区", "千代田1-1", None,
+        ).unwrap(),
+        delivery_method: DeliveryMethod::Standard,
+      },
+    ).unwrap().1
+  }
+
+  fn store(events: &[Vec<OrderEvent>]) -> InMemoryOrderSummaryStore {
+    let store = InMemoryOrderSummaryStore::new();
+    events.iter().flatten().for_each(|event| store.apply(event).unwrap());
+    store
+  }
+
+  #[test]
+  fn test_find_by_customer_id_success() {
+    let first = order_events(ALICE, 0);
+    let second = order_events(ALICE, 1);
+    let store = store(&[first.clone(), second.clone(), order_events(BOB, 2)]);
+
+    let summaries = store.find_by_customer_id(ALICE).unwrap();
+
+    // assert
+    assert_eq!(
+      vec![second[0].order_id().to_string(), first[0].order_id().to_string()],
+      summaries.iter().map(|summary| summary.order_id.clone()).collect::<Vec<String>>()
+    );
+    assert_eq!(1, summaries[0].discounts.len());
+    assert!(store.find_by_customer_id("CUSTOMER-00000000-0000-0000-0000-000000000003").unwrap().is_empty());
+  }
+
+  #[test]
+  fn test_find_by_order_id_success() {
+    let events = order_events(BOB, 0);
+    let store = store(std::slice::from_ref(&events));
+
+    let summary = store.find_by_order_id(&events[0].order_id().to_string()).unwrap();
+
+    // assert
+    assert_eq!(BOB, summary.unwrap().customer_id);
+  }
+
+  #[test]
+  fn test_apply_without_order_placed_failed() {
+    let events = order_events(ALICE, 0);
+    let store = InMemoryOrderSummaryStore::new();
+
+    let result = store.apply(&events[1]);
+
+    // assert
+    assert_eq!(Err(OrderSummaryError::NotPlaced), result);
+  }
+
+  #[test]
+  fn test_apply_envelope_links_to_write_span() {
+    let exporter = InMemorySpanExporter::default();
+    let tracer_provider = SdkTracerProvider::builder().with_simple_exporter(exporter.clone()).build();
+    let subscriber = tracing_subscriber::registry().with(tracer::layer(&tracer_provider, "test-service"));
+    let store = InMemoryOrderSummaryStore::new();
+
+    tracing::subscriber::with_default(subscriber, || {
+      let envelopes = tracing::info_span!("place_order").in_scope(|| {
+        let metadata = EventMetadata::default().with_trace_context(propagation::current_trace_context());
+        order_events(ALICE, 0)
+          .into_iter()
+          .map(|event| EventEnvelope::new(event, metadata.clone()))
+          .collect::<Vec<_>>()
+      });
+      envelopes.iter().for_each(|envelope| store.apply_envelope(envelope).unwrap());
+    });
+    let spans = exporter.get_finished_spans().unwrap();
+    let write = spans.iter().find(|span| span.name == "place_order").unwrap();
+    let projections = spans.iter().filter(|span| span.name == "projection").collect::<Vec<_>>();
+
+    // assert
+    assert_eq!(1, store.find_by_customer_id(ALICE).unwrap().len());
+    assert!(!projections.is_empty());
+    assert!(projections.iter().all(|span| {
+      span.span_context.trace_id() == write.span_context.trace_id() && span.parent_span_id == write.span_context.span_id()
+    }));
+  }
+
+  #[test]
+  fn test_is_available() {
+    let store = InMemoryOrderSummaryStore::new();
+    let available = store.is_available();
+
+    let _ = std::panic::catch_unwind(|| {
+      let _summaries = store.summaries.write().unwrap();
+      panic!("panic while holding the lock");
+    });
+
+    // assert
+    assert!(available);
+    assert!(!store.is_available());
+  }
+
+  #[test]
+  fn test_poisoned_store_failed() {
+    let events = order_events(ALICE, 0);
+    let store = store(std::slice::from_ref(&events));
+
+    let _ = std::panic::catch_unwind(|| {
+      let _summaries = store.summaries.write().unwrap();
+      panic!("panic while holding the lock");
+    });
+
+    // assert
+    assert!(matches!(store.find_by_customer_id(ALICE), Err(OrderSummaryError::Unavailable(_))));
+    assert!(matches!(
+      store.find_by_order_id(&events[0].order_id().to_string()),
+      Err(OrderSummaryError::Unavailable(_))
+    ));
+    assert!(matches!(store.apply(&events[0]), Err(OrderSummaryError::Unavailable(_))));
+  }
+}
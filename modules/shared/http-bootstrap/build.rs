@@ -0,0 +1,25 @@
+use std::process::Command;
+
+/// `/version`で返すgitのコミットハッシュを`GIT_SHA`に設定します
+///
+/// 環境変数`GIT_SHA`が指定されている場合はその値を、gitが使えない場合は`unknown`を設定します
+fn main() {
+  println!("cargo:rerun-if-env-changed=GIT_SHA");
+  for path in ["HEAD", &git(&["rev-parse", "--symbolic-full-name", "HEAD"]).unwrap_or_default()] {
+    if let Some(path) = git(&["rev-parse", "--git-path", path]) {
+      println!("cargo:rerun-if-changed={}", path);
+    }
+  }
+  let git_sha = std::env::var("GIT_SHA")
+    .ok()
+    .or_else(|| git(&["rev-parse", "--short=12", "HEAD"]))
+    .unwrap_or_else(|| "unknown".to_string());
+  println!("cargo:rustc-env=GIT_SHA={}", git_sha);
+}
+
+/// gitコマンドを実行し、標準出力を返します
+fn git(args: &[&str]) -> Option<String> {
+  let output = Command::new("git").args(args).output().ok()?;
+  let stdout = String::from_utf8(output.stdout).ok()?;
+  Some(stdout.trim().to_string()).filter(|stdout| output.status.success() && !stdout.is_empty())
+}
@@ -0,0 +1,168 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::sync::Arc;
+
+/// ビルド時のgitのコミットハッシュです
+pub const GIT_SHA: &str = env!("GIT_SHA");
+
+/// 依存先の確認処理です
+///
+/// 利用できない場合は理由を返します
+pub type ReadinessCheck = Arc<dyn Fn() -> Result<(), String> + Send + Sync>;
+
+/// リクエストを受け付けられるかを判定する、依存先の確認処理の一覧です
+#[derive(Clone, Default)]
+pub struct Readiness {
+  checks: Vec<(&'static str, ReadinessCheck)>,
+}
+
+impl Readiness {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// 依存先の確認処理を追加します
+  ///
+  /// # Arguments
+  /// * `name`: レスポンスに出力する依存先の名前
+  /// * `check`: 確認処理
+  pub fn with_check<F>(mut self, name: &'static str, check: F) -> Self
+  where
+    F: Fn() -> Result<(), String> + Send + Sync + 'static,
+  {
+    self.checks.push((name, Arc::new(check)));
+    self
+  }
+
+  /// すべての確認処理を実行します
+  ///
+  /// # Return
+  /// * すべて利用できる場合true、依存先ごとの結果(`ok`または理由)
+  fn check(&self) -> (bool, Map<String, Value>) {
+    let mut ready = true;
+    let mut results = Map::new();
+    for (name, check) in &self.checks {
+      let result = match check() {
+        Ok(()) => "ok".to_string(),
+        Err(reason) => {
+          ready = false;
+          reason
+        }
+      };
+      results.insert(name.to_string(), Value::String(result));
+    }
+    (ready, results)
+  }
+}
+
+/// `/version`で返すバージョン情報です
+///
+/// name: クレート名
+///
+/// version: クレートのバージョン
+///
+/// git_sha: ビルド時のgitのコミットハッシュ
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct VersionInfo {
+  pub name: &'static str,
+  pub version: &'static str,
+  pub git_sha: &'static str,
+}
+
+/// 呼び出したクレートの`VersionInfo`を返します
+#[macro_export]
+macro_rules! version_info {
+  () => {
+    $crate::health::VersionInfo {
+      name: env!("CARGO_PKG_NAME"),
+      version: env!("CARGO_PKG_VERSION"),
+      git_sha: $crate::health::GIT_SHA,
+    }
+  };
+}
+
+/// ヘルスチェックのハンドラー間で共有する状態です
+#[derive(Clone)]
+struct HealthState {
+  readiness: Readiness,
+  version: VersionInfo,
+}
+
+/// ヘルスチェックとバージョンのルーティングを返します
+///
+/// # Arguments
+/// * `readiness`: 依存先の確認処理の一覧
+/// * `version`: バージョン情報
+///
+/// # Return
+/// * `Router`
+pub fn routes(readiness: Readiness, version: VersionInfo) -> Router {
+  Router::new()
+    .route("/health/live", get(live))
+    .route("/health/ready", get(ready))
+    .route("/version", get(version_handler))
+    .with_state(HealthState { readiness, version })
+}
+
+/// プロセスが応答できる場合、200を返します
+async fn live() -> Json<Value> {
+  Json(json!({ "status": "ok" }))
+}
+
+/// すべての依存先が利用できる場合は200、いずれかが利用できない場合は503を返します
+async fn ready(State(state): State<HealthState>) -> Response {
+  let (ready, checks) = state.readiness.check();
+  if ready {
+    (StatusCode::OK, Json(json!({ "status": "ready", "checks": checks }))).into_response()
+  } else {
+    (StatusCode::SERVICE_UNAVAILABLE, Json(json!({ "status": "not_ready", "checks": checks }))).into_response()
+  }
+}
+
+/// バージョン情報を返します
+async fn version_handler(State(state): State<HealthState>) -> Json<VersionInfo> {
+  Json(state.version)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use axum_test::TestServer;
+  use std::sync::atomic::{AtomicBool, Ordering};
+
+  #[tokio::test]
+  async fn test_health_routes() {
+    let available = Arc::new(AtomicBool::new(true));
+    let readiness = Readiness::new()
+      .with_check("event_store", || Ok(()))
+      .with_check("read_model_store", {
+        let available = available.clone();
+        move || if available.load(Ordering::SeqCst) { Ok(()) } else { Err("lock poisoned".to_string()) }
+      });
+    let server = TestServer::new(routes(readiness, crate::version_info!())).unwrap();
+
+    let live = server.get("/health/live").await;
+    let ready = server.get("/health/ready").await;
+    available.store(false, Ordering::SeqCst);
+    let not_ready = server.get("/health/ready").await;
+    let version = server.get("/version").await.json::<Value>();
+
+    // assert
+    live.assert_status_ok();
+    ready.assert_status_ok();
+    assert_eq!(json!({ "status": "ready", "checks": { "event_store": "ok", "read_model_store": "ok" } }), ready.json::<Value>());
+    not_ready.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(
+      json!({ "status": "not_ready", "checks": { "event_store": "ok", "read_model_store": "lock poisoned" } }),
+      not_ready.json::<Value>(),
+    );
+    assert_eq!("shared-http-bootstrap", version["name"]);
+    assert_eq!(env!("CARGO_PKG_VERSION"), version["version"]);
+    assert_eq!(GIT_SHA, version["git_sha"]);
+  }
+}
@@ -0,0 +1,8 @@
+pub mod health;
+pub mod logging;
+pub mod metrics;
+pub mod middleware;
+pub mod openapi;
+pub mod server;
+pub mod settings;
+pub mod shutdown;
@@ -0,0 +1,108 @@
+use crate::settings::{LogFormat, LoggingSettings};
+use crate::shutdown::Shutdown;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use shared_telemetry::tracer::{self, TelemetrySettings};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+/// 出力するログの条件を指定する環境変数です
+const RUST_LOG_ENV: &str = "RUST_LOG";
+
+/// トレースの送信の状態です
+///
+/// tracer_provider: スパンの送信先(送信しない場合はNone)
+pub struct Telemetry {
+  tracer_provider: Option<SdkTracerProvider>,
+}
+
+impl Telemetry {
+  /// 終了時に送信していないスパンを書き出す処理を追加します
+  ///
+  /// # Arguments
+  /// * `shutdown`: Shutdown
+  ///
+  /// # Return
+  /// * `Shutdown`
+  pub fn flush_on(self, shutdown: Shutdown) -> Shutdown {
+    let Some(tracer_provider) = self.tracer_provider else {
+      return shutdown;
+    };
+    shutdown.with_hook("telemetry", move || async move {
+      // スパンの送信はブロッキングのため、ランタイムのスレッドを止めないようにします
+      tokio::task::spawn_blocking(move || tracer_provider.shutdown()).await??;
+      Ok(())
+    })
+  }
+}
+
+/// ログ出力とトレースの送信を設定します
+///
+/// 両方のサーバーで同じ形式・同じ条件で出力します。条件は`RUST_LOG`、未指定の場合は`logging.level`です。
+/// JSON形式では、リクエストIDやコマンドの種類などのスパンのフィールドもログに出力します。
+/// `telemetry.otlp_endpoint`を指定した場合は、スパンをOTLPで送信します
+///
+/// # Arguments
+/// * `service_name`: スパンに付けるサービス名
+/// * `logging`: LoggingSettings
+/// * `telemetry`: TelemetrySettings
+///
+/// # Return
+/// * `anyhow::Result<Telemetry>`
+pub fn init(service_name: &'static str, logging: &LoggingSettings, telemetry: &TelemetrySettings) -> anyhow::Result<Telemetry> {
+  let filter = env_filter(std::env::var(RUST_LOG_ENV).ok(), &logging.level)?;
+  let tracer_provider = tracer::tracer_provider(service_name, telemetry)?;
+  let text = (logging.format == LogFormat::Text)
+    .then(|| tracing_subscriber::fmt::layer().with_ansi(false).with_target(false));
+  let json = (logging.format == LogFormat::Json)
+    .then(|| tracing_subscriber::fmt::layer().json().with_current_span(true).with_span_list(true));
+  tracing_subscriber::registry()
+    .with(filter)
+    .with(text)
+    .with(json)
+    .with(tracer_provider.as_ref().map(|tracer_provider| tracer::layer(tracer_provider, service_name)))
+    .init();
+  Ok(Telemetry { tracer_provider })
+}
+
+/// 出力するログの条件を返します
+///
+/// # Arguments
+/// * `rust_log`: 環境変数`RUST_LOG`の値
+/// * `level`: 設定ファイルの`logging.level`
+///
+/// # Return
+/// * `anyhow::Result<EnvFilter>`: `RUST_LOG`が不正な場合はエラー
+fn env_filter(rust_log: Option<String>, level: &str) -> anyhow::Result<EnvFilter> {
+  match rust_log.filter(|rust_log| !rust_log.trim().is_empty()) {
+    Some(rust_log) => EnvFilter::try_new(&rust_log)
+      .map_err(|e| anyhow::anyhow!("invalid {} {:?}: {}", RUST_LOG_ENV, rust_log, e)),
+    None => EnvFilter::try_new(level)
+      .map_err(|e| anyhow::anyhow!("invalid logging.level {:?}: {}", level, e)),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rstest::rstest;
+
+  #[rstest]
+  #[case(Some("info"), "debug", "info")]
+  #[case(Some(""), "debug", "debug")]
+  #[case(None, "warn,command_processor=debug", "warn,command_processor=debug")]
+  fn test_env_filter(#[case] rust_log: Option<&str>, #[case] level: &str, #[case] expected: &str) {
+    let result = env_filter(rust_log.map(str::to_string), level).unwrap();
+
+    // assert
+    assert_eq!(EnvFilter::try_new(expected).unwrap().to_string(), result.to_string());
+  }
+
+  #[test]
+  fn test_env_filter_failed() {
+    let result = env_filter(Some("info,[".to_string()), "debug");
+
+    // assert
+    assert!(result.unwrap_err().to_string().contains("invalid RUST_LOG"));
+  }
+}
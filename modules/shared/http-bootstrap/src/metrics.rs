@@ -0,0 +1,144 @@
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use prometheus::{Encoder, HistogramOpts, HistogramVec, Registry, TextEncoder};
+use std::time::Instant;
+
+/// Prometheusのメトリクスです
+///
+/// registry: メトリクスの登録先(アプリケーション固有のメトリクスもここに登録します)
+///
+/// http_request_duration: HTTPリクエストの処理時間(method、route、status別)
+#[derive(Clone)]
+pub struct Metrics {
+  registry: Registry,
+  http_request_duration: HistogramVec,
+}
+
+impl Default for Metrics {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Metrics {
+  /// コンストラクタです
+  ///
+  /// HTTPリクエストのメトリクスを登録した新しいレジストリを作成します
+  pub fn new() -> Self {
+    let registry = Registry::new();
+    let http_request_duration = register(&registry, HistogramVec::new(
+      HistogramOpts::new("http_request_duration_seconds", "HTTP request latency in seconds"),
+      &["method", "route", "status"],
+    ));
+    Self { registry, http_request_duration }
+  }
+
+  /// メトリクスの登録先のゲッター
+  pub fn registry(&self) -> &Registry { &self.registry }
+
+  /// 登録したすべてのメトリクスをPrometheusのテキスト形式で返します
+  pub fn render(&self) -> String {
+    let mut buffer = vec![];
+    TextEncoder::new()
+      .encode(&self.registry.gather(), &mut buffer)
+      .expect("text encoding of gathered metrics must not fail");
+    String::from_utf8(buffer).expect("prometheus text format must be utf-8")
+  }
+
+  /// HTTPリクエストの処理時間を記録するミドルウェアを適用します
+  ///
+  /// routeはパスパラメーターを含まないルーティングのパス(`/orders/:order_id/ship`)です。
+  /// ミドルウェアはルーティングにのみ適用されるため、ルーティングにないパスへのリクエストは記録しません
+  ///
+  /// # Arguments
+  /// * `router`: ルーティング
+  ///
+  /// # Return
+  /// * `Router`
+  pub fn layer(&self, router: Router) -> Router {
+    router.layer(axum::middleware::from_fn_with_state(self.clone(), record_http_request))
+  }
+
+  /// `/metrics`のルーティングを返します
+  pub fn routes(&self) -> Router {
+    Router::new()
+      .route("/metrics", get(render_metrics))
+      .with_state(self.clone())
+  }
+}
+
+/// メトリクスをレジストリに登録します
+///
+/// メトリクスの名前とラベルは固定のため、登録の失敗はプログラムの誤りとして扱います
+///
+/// # Arguments
+/// * `registry`: 登録先
+/// * `collector`: メトリクス(`HistogramVec::new`などの結果)
+///
+/// # Return
+/// * 登録したメトリクス
+pub fn register<C>(registry: &Registry, collector: prometheus::Result<C>) -> C
+where
+  C: prometheus::core::Collector + Clone + 'static,
+{
+  let collector = collector.expect("metric definition must be valid");
+  registry
+    .register(Box::new(collector.clone()))
+    .expect("metric must be registered only once");
+  collector
+}
+
+/// HTTPリクエストの処理時間を記録します
+async fn record_http_request(State(metrics): State<Metrics>, request: Request, next: Next) -> Response {
+  let method = request.method().to_string();
+  let route = request
+    .extensions()
+    .get::<MatchedPath>()
+    .map(|path| path.as_str().to_string())
+    .unwrap_or_else(|| "unmatched".to_string());
+  let started_at = Instant::now();
+  let response = next.run(request).await;
+  metrics.http_request_duration
+    .with_label_values(&[&method, &route, response.status().as_str()])
+    .observe(started_at.elapsed().as_secs_f64());
+  response
+}
+
+/// メトリクスをPrometheusのテキスト形式で返します
+async fn render_metrics(State(metrics): State<Metrics>) -> impl IntoResponse {
+  (StatusCode::OK, [(CONTENT_TYPE, prometheus::TEXT_FORMAT)], metrics.render())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use axum_test::TestServer;
+  use prometheus::{IntCounterVec, Opts};
+
+  #[tokio::test]
+  async fn test_metrics_records_http_requests() {
+    let metrics = Metrics::new();
+    let orders = register(metrics.registry(), IntCounterVec::new(Opts::new("orders_total", "orders"), &["status"]));
+    orders.with_label_values(&["placed"]).inc();
+    let router = Router::new().route("/orders/:order_id", get(|| async { "order" }));
+    let server = TestServer::new(metrics.layer(router).merge(metrics.routes())).unwrap();
+
+    server.get("/orders/ORDER-1").await;
+    server.get("/orders/ORDER-2").await;
+    server.get("/unknown").await;
+    let response = server.get("/metrics").await;
+
+    // assert
+    response.assert_status_ok();
+    assert_eq!(prometheus::TEXT_FORMAT, response.header(CONTENT_TYPE));
+    let body = response.text();
+    assert!(body.contains(r#"http_request_duration_seconds_count{method="GET",route="/orders/:order_id",status="200"} 2"#));
+    assert!(!body.contains("/unknown"));
+    assert!(body.contains(r#"orders_total{status="placed"} 1"#));
+  }
+}
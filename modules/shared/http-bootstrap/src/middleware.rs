@@ -0,0 +1,77 @@
+use axum::extract::Request;
+use axum::Router;
+use shared_telemetry::propagation;
+use std::collections::BTreeMap;
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
+use tower_http::trace::TraceLayer;
+use tracing::{info_span, Span};
+
+/// リクエストIDのヘッダーです
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// トレースコンテキストを受け取るリクエストヘッダーです
+const TRACE_CONTEXT_HEADERS: [&str; 2] = ["traceparent", "tracestate"];
+
+/// すべてのルートに共通のミドルウェアを適用します
+///
+/// リクエストに`X-Request-Id`がない場合はUUIDで採番し、レスポンスにも同じ値を返します。
+/// リクエストとレスポンスは、リクエストIDを付けてトレースログに出力します
+///
+/// # Arguments
+/// * `router`: ルーティング
+///
+/// # Return
+/// * `Router`
+pub fn layer(router: Router) -> Router {
+  // 後から追加したレイヤーが外側になるため、採番→トレース→レスポンスへの設定の順に処理します
+  router
+    .layer(PropagateRequestIdLayer::x_request_id())
+    .layer(TraceLayer::new_for_http().make_span_with(request_span))
+    .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+}
+
+/// リクエストのスパンを作成します
+///
+/// リクエストにトレースコンテキスト(W3C Trace Context)がある場合は、呼び出し元のスパンを親にします
+fn request_span(request: &Request) -> Span {
+  let request_id = request
+    .headers()
+    .get(REQUEST_ID_HEADER)
+    .and_then(|value| value.to_str().ok())
+    .unwrap_or_default();
+  let span = info_span!(
+    "request",
+    request_id,
+    method = %request.method(),
+    uri = %request.uri(),
+    version = ?request.version(),
+  );
+  let trace_context = TRACE_CONTEXT_HEADERS
+    .iter()
+    .filter_map(|name| {
+      let value = request.headers().get(*name)?.to_str().ok()?;
+      Some((name.to_string(), value.to_string()))
+    })
+    .collect::<BTreeMap<String, String>>();
+  propagation::set_parent(&span, &trace_context);
+  span
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use axum::routing::get;
+  use axum_test::TestServer;
+
+  #[tokio::test]
+  async fn test_layer_sets_request_id() {
+    let server = TestServer::new(layer(Router::new().route("/", get(|| async { "ok" })))).unwrap();
+
+    let generated = server.get("/").await;
+    let propagated = server.get("/").add_header(REQUEST_ID_HEADER, "request-1").await;
+
+    // assert
+    assert_eq!(36, generated.header(REQUEST_ID_HEADER).len());
+    assert_eq!("request-1", propagated.header(REQUEST_ID_HEADER));
+  }
+}
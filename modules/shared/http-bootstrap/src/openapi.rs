@@ -0,0 +1,287 @@
+use axum::handler::Handler;
+use axum::http::header::CONTENT_TYPE;
+use axum::http::Method;
+use axum::response::{Html, IntoResponse};
+use axum::routing::{get, on, MethodFilter, MethodRouter};
+use axum::Router;
+use std::collections::BTreeSet;
+use std::path::Path;
+use utoipa::openapi::path::PathItem;
+use utoipa::openapi::OpenApi;
+use utoipa::Modify;
+use utoipa_scalar::Scalar;
+
+/// チェックインしたOpenAPIのドキュメントを書き換える環境変数です
+pub const UPDATE_OPENAPI: &str = "UPDATE_OPENAPI";
+
+/// `/openapi.json`と`/docs`のルーティングを返します
+///
+/// `/openapi.json`はOpenAPIのドキュメント、`/docs`はドキュメントを埋め込んだビューアー(Scalar)のHTMLです
+///
+/// # Arguments
+/// * `openapi`: OpenAPIのドキュメント
+///
+/// # Return
+/// * `Router`
+pub fn routes(openapi: OpenApi) -> Router {
+  let json = to_json(&openapi);
+  let html = Scalar::new(openapi).to_html();
+  Router::new()
+    .route("/openapi.json", get(move || async move { ([(CONTENT_TYPE, "application/json")], json).into_response() }))
+    .route("/docs", get(move || async move { Html(html) }))
+}
+
+/// OpenAPIのドキュメントに記載するルーティングの一覧です
+///
+/// ルーティングはこの一覧から作成するため、`check_routes`で一覧とドキュメントを照合すると、
+/// ドキュメントにないルーティングとルーティングのないドキュメントを検出できます
+pub struct ApiRoutes<S> {
+  routes: Vec<(Method, &'static str, MethodRouter<S>)>,
+}
+
+impl<S: Clone + Send + Sync + 'static> Default for ApiRoutes<S> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<S: Clone + Send + Sync + 'static> ApiRoutes<S> {
+  pub fn new() -> Self {
+    Self { routes: vec![] }
+  }
+
+  /// ルーティングを追加します
+  ///
+  /// # Arguments
+  /// * `method`: HTTPメソッド
+  /// * `path`: axumのパス(例: `/orders/:order_id/ship`)
+  /// * `handler`: ハンドラー
+  ///
+  /// # Return
+  /// * `ApiRoutes<S>`
+  pub fn route<H, T>(mut self, method: Method, path: &'static str, handler: H) -> Self
+  where
+    H: Handler<T, S>,
+    T: 'static,
+  {
+    let filter = MethodFilter::try_from(method.clone()).expect("method must be supported by axum routing");
+    self.routes.push((method, path, on(filter, handler)));
+    self
+  }
+
+  /// HTTPメソッドとOpenAPIの形式のパス(例: `/orders/{order_id}/ship`)の一覧を返します
+  pub fn operations(&self) -> BTreeSet<(String, String)> {
+    self.routes
+      .iter()
+      .map(|(method, path, _)| (method.to_string(), openapi_path(path)))
+      .collect()
+  }
+
+  /// 一覧からルーティングを作成します
+  pub fn into_router(self) -> Router<S> {
+    self.routes
+      .into_iter()
+      .fold(Router::new(), |router, (_, path, method_router)| router.route(path, method_router))
+  }
+}
+
+/// ルーティングの一覧とOpenAPIのドキュメントのパスを照合します
+///
+/// # Arguments
+/// * `openapi`: ルーティングとDTOから生成したドキュメント
+/// * `routes`: ルーティングの一覧
+///
+/// # Return
+/// * 一致しない場合は、ドキュメントにないルーティングとルーティングのないパスを含むエラーメッセージ
+pub fn check_routes<S: Clone + Send + Sync + 'static>(openapi: &OpenApi, routes: &ApiRoutes<S>) -> Result<(), String> {
+  let routed = routes.operations();
+  let documented = openapi.paths.paths
+    .iter()
+    .flat_map(|(path, item)| operation_methods(item).into_iter().map(move |method| (method.to_string(), path.clone())))
+    .collect::<BTreeSet<(String, String)>>();
+  let format = |operations: Vec<&(String, String)>| {
+    operations.iter().map(|(method, path)| format!("{} {}", method, path)).collect::<Vec<_>>().join(", ")
+  };
+  let undocumented = routed.difference(&documented).collect::<Vec<_>>();
+  let unrouted = documented.difference(&routed).collect::<Vec<_>>();
+  if !undocumented.is_empty() || !unrouted.is_empty() {
+    Err(format!(
+      "routes and the OpenAPI document differ. missing from ApiDoc paths: [{}], documented without a route: [{}]",
+      format(undocumented),
+      format(unrouted),
+    ))?
+  }
+  Ok(())
+}
+
+/// axumのパスをOpenAPIの形式のパスにします(`:order_id`、`*rest`を`{order_id}`、`{rest}`にします)
+fn openapi_path(path: &str) -> String {
+  path
+    .split('/')
+    .map(|segment| match segment.strip_prefix(':').or_else(|| segment.strip_prefix('*')) {
+      Some(name) => format!("{{{}}}", name),
+      None => segment.to_string(),
+    })
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+/// パスに定義したオペレーションのHTTPメソッドを返します
+fn operation_methods(item: &PathItem) -> Vec<Method> {
+  [
+    (Method::GET, &item.get),
+    (Method::PUT, &item.put),
+    (Method::POST, &item.post),
+    (Method::DELETE, &item.delete),
+    (Method::OPTIONS, &item.options),
+    (Method::HEAD, &item.head),
+    (Method::PATCH, &item.patch),
+    (Method::TRACE, &item.trace),
+  ]
+    .into_iter()
+    .filter(|(_, operation)| operation.is_some())
+    .map(|(method, _)| method)
+    .collect()
+}
+
+/// マニフェストにlicenseがない場合に、空のライセンスを出力しないためのModifyです
+///
+/// `#[derive(OpenApi)]`はCargoのパッケージ情報からinfoを作成しますが、
+/// licenseがないときも空文字列の`CARGO_PKG_LICENSE`から`{"name": ""}`を出力します
+pub struct OmitEmptyLicense;
+
+impl Modify for OmitEmptyLicense {
+  fn modify(&self, openapi: &mut OpenApi) {
+    if openapi.info.license.as_ref().is_some_and(|license| license.name.is_empty()) {
+      openapi.info.license = None;
+    }
+  }
+}
+
+/// OpenAPIのドキュメントをチェックインしたファイルと比較します
+///
+/// # Arguments
+/// * `openapi`: ルーティングとDTOから生成したドキュメント
+/// * `path`: チェックインしたファイルのパス
+/// * `update`: trueの場合は比較せずにファイルを書き換えます(通常は環境変数`UPDATE_OPENAPI`の有無)
+///
+/// # Return
+/// * 一致しない場合は書き換えの方法を含むエラーメッセージ
+pub fn check_document(openapi: &OpenApi, path: &Path, update: bool) -> Result<(), String> {
+  let generated = to_json(openapi);
+  if update {
+    return std::fs::write(path, generated).map_err(|e| format!("failed to write {}: {}", path.display(), e));
+  }
+  let checked_in = std::fs::read_to_string(path).unwrap_or_default();
+  if checked_in != generated {
+    Err(format!(
+      "{} is out of date with the routes and DTOs. Run the test with {}=1 and commit the result",
+      path.display(),
+      UPDATE_OPENAPI,
+    ))?
+  }
+  Ok(())
+}
+
+/// ドキュメントを末尾に改行のある整形済みのJSONにします
+fn to_json(openapi: &OpenApi) -> String {
+  let json = openapi.to_pretty_json().expect("serialization of an openapi document must not fail");
+  format!("{}\n", json)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use axum_test::TestServer;
+  use serde_json::Value;
+  use utoipa::openapi::path::{HttpMethod, OperationBuilder, PathsBuilder};
+  use utoipa::openapi::{InfoBuilder, LicenseBuilder, OpenApiBuilder};
+
+  fn document(title: &str) -> OpenApi {
+    OpenApiBuilder::new().info(InfoBuilder::new().title(title).version("0.1.0").build()).build()
+  }
+
+  #[tokio::test]
+  async fn test_routes() {
+    let server = TestServer::new(routes(document("test-api"))).unwrap();
+
+    let json = server.get("/openapi.json").await;
+    let docs = server.get("/docs").await;
+
+    // assert
+    json.assert_status_ok();
+    assert_eq!("application/json", json.header(CONTENT_TYPE));
+    assert_eq!("test-api", json.json::<Value>()["info"]["title"]);
+    docs.assert_status_ok();
+    assert!(docs.text().contains(r#""title":"test-api""#));
+  }
+
+  #[test]
+  fn test_omit_empty_license() {
+    let mut empty = document("test-api");
+    empty.info.license = Some(LicenseBuilder::new().name("").build());
+    let mut licensed = document("test-api");
+    licensed.info.license = Some(LicenseBuilder::new().name("MIT").build());
+
+    OmitEmptyLicense.modify(&mut empty);
+    OmitEmptyLicense.modify(&mut licensed);
+
+    // assert
+    assert!(empty.info.license.is_none());
+    assert_eq!("MIT", licensed.info.license.unwrap().name);
+  }
+
+  #[test]
+  fn test_check_document() {
+    let path = std::env::temp_dir().join(format!("openapi-{}.json", std::process::id()));
+    std::fs::write(&path, to_json(&document("test-api"))).unwrap();
+
+    let matched = check_document(&document("test-api"), &path, false);
+    let drifted = check_document(&document("renamed-api"), &path, false);
+    let updated = check_document(&document("renamed-api"), &path, true);
+    let written = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    // assert
+    assert!(matched.is_ok());
+    assert!(drifted.unwrap_err().contains(UPDATE_OPENAPI));
+    assert!(updated.is_ok());
+    assert_eq!(to_json(&document("renamed-api")), written);
+  }
+
+  #[test]
+  fn test_check_routes() {
+    let mut openapi = document("test-api");
+    openapi.paths = PathsBuilder::new()
+      .path("/orders", PathItem::new(HttpMethod::Post, OperationBuilder::new().build()))
+      .path("/orders/{order_id}/ship", PathItem::new(HttpMethod::Post, OperationBuilder::new().build()))
+      .build();
+    let routes = || ApiRoutes::<()>::new()
+      .route(Method::POST, "/orders", || async { "placed" })
+      .route(Method::POST, "/orders/:order_id/ship", || async { "shipped" });
+
+    let matched = check_routes(&openapi, &routes());
+    let undocumented = check_routes(&openapi, &routes().route(Method::GET, "/orders/:order_id", || async { "order" }));
+    let unrouted = check_routes(&openapi, &ApiRoutes::<()>::new().route(Method::POST, "/orders", || async { "placed" }));
+
+    // assert
+    assert_eq!(Ok(()), matched);
+    assert!(undocumented.unwrap_err().contains("missing from ApiDoc paths: [GET /orders/{order_id}], documented without a route: []"));
+    assert!(unrouted.unwrap_err().contains("missing from ApiDoc paths: [], documented without a route: [POST /orders/{order_id}/ship]"));
+  }
+
+  #[tokio::test]
+  async fn test_api_routes_into_router() {
+    let router = ApiRoutes::<()>::new()
+      .route(Method::POST, "/orders/:order_id/ship", || async { "shipped" })
+      .into_router();
+    let server = TestServer::new(router).unwrap();
+
+    let shipped = server.post("/orders/ORDER-1/ship").await;
+    let wrong_method = server.get("/orders/ORDER-1/ship").await;
+
+    // assert
+    assert_eq!("shipped", shipped.text());
+    wrong_method.assert_status(axum::http::StatusCode::METHOD_NOT_ALLOWED);
+  }
+}
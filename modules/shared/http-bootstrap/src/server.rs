@@ -0,0 +1,299 @@
+use crate::settings::ApiSettings;
+use crate::shutdown::{shutdown_signal, Shutdown};
+use crate::health::{Readiness, VersionInfo};
+use crate::metrics::Metrics;
+use crate::{health, middleware, openapi};
+use axum::extract::Request;
+use axum::middleware::Next;
+use axum::Router;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::net::TcpListener;
+use tokio::sync::Notify;
+use tracing::{error, info, warn};
+use utoipa::openapi::OpenApi;
+
+/// アプリケーションのルーティングに、ヘルスチェック・バージョン・メトリクス・APIドキュメントと共通のミドルウェアを追加します
+///
+/// HTTPリクエストの処理時間は、アプリケーションのルーティングのみ記録します
+///
+/// # Arguments
+/// * `router`: アプリケーションのルーティング
+/// * `readiness`: 依存先の確認処理の一覧
+/// * `version`: バージョン情報(`version_info!()`)
+/// * `metrics`: メトリクス
+/// * `openapi`: アプリケーションのルーティングのOpenAPIのドキュメント
+///
+/// # Return
+/// * `Router`
+pub fn app(router: Router, readiness: Readiness, version: VersionInfo, metrics: &Metrics, openapi: OpenApi) -> Router {
+  let router = metrics.layer(router)
+    .merge(health::routes(readiness, version))
+    .merge(metrics.routes())
+    .merge(openapi::routes(openapi));
+  middleware::layer(router)
+}
+
+/// サーバーを起動し、終了シグナル(SIGINT、SIGTERM)を受け取るまでリクエストを処理します
+///
+/// # Arguments
+/// * `name`: ログに出力するサーバー名
+/// * `settings`: API起動時の設定
+/// * `app`: ルーティング
+/// * `shutdown`: 終了の設定
+///
+/// # Return
+/// * `anyhow::Result<()>`
+pub async fn serve(name: &str, settings: &ApiSettings, app: Router, shutdown: Shutdown) -> anyhow::Result<()> {
+  let socket_addr = settings.socket_addr()?;
+  let listener = TcpListener::bind(socket_addr)
+    .await
+    .map_err(|e| anyhow::anyhow!("failed to bind to {}: {}", socket_addr, e))?;
+
+  info!("{} started on http://{}", name, socket_addr);
+  serve_until(name, listener, app, shutdown, shutdown_signal()).await
+}
+
+/// 処理中のリクエスト数を数えます
+///
+/// クライアントの切断などでリクエストの処理が途中で破棄された場合も、Dropで数を戻します
+struct InFlightGuard(Arc<AtomicUsize>);
+
+impl InFlightGuard {
+  fn new(in_flight: Arc<AtomicUsize>) -> Self {
+    in_flight.fetch_add(1, Ordering::SeqCst);
+    Self(in_flight)
+  }
+}
+
+impl Drop for InFlightGuard {
+  fn drop(&mut self) {
+    self.0.fetch_sub(1, Ordering::SeqCst);
+  }
+}
+
+/// `signal`が完了するまでリクエストを処理し、その後サーバーを終了します
+///
+/// 終了は以下の順に行います
+///
+/// 1. 新しい接続の受け付けを止めます
+/// 2. 処理中のリクエストの完了を`drain_timeout`まで待ちます
+/// 3. 終了時の処理を登録した順に呼び出します
+///
+/// # Arguments
+/// * `name`: ログに出力するサーバー名
+/// * `listener`: 接続を受け付けるリスナー
+/// * `app`: ルーティング
+/// * `shutdown`: 終了の設定
+/// * `signal`: 終了のきっかけ(完了時にシグナル名を返します)
+///
+/// # Return
+/// * `anyhow::Result<()>`
+pub async fn serve_until(
+  name: &str,
+  listener: TcpListener,
+  app: Router,
+  shutdown: Shutdown,
+  signal: impl Future<Output = &'static str>,
+) -> anyhow::Result<()> {
+  let in_flight = Arc::new(AtomicUsize::new(0));
+  let app = app.layer(axum::middleware::from_fn({
+    let in_flight = in_flight.clone();
+    move |request: Request, next: Next| {
+      let in_flight = in_flight.clone();
+      async move {
+        let _guard = InFlightGuard::new(in_flight);
+        next.run(request).await
+      }
+    }
+  }));
+
+  let stop = Arc::new(Notify::new());
+  let mut server = tokio::spawn({
+    let stop = stop.clone();
+    async move {
+      axum::serve(listener, app)
+        .with_graceful_shutdown(async move { stop.notified().await })
+        .await
+    }
+  });
+
+  let signal = tokio::select! {
+    result = &mut server => {
+      result??;
+      Err(anyhow::anyhow!("{} stopped without a shutdown signal", name))?
+    }
+    signal = signal => signal,
+  };
+  let started_at = Instant::now();
+  info!(
+    "{} received {}, stopped accepting connections and draining {} in-flight requests",
+    name,
+    signal,
+    in_flight.load(Ordering::SeqCst),
+  );
+  stop.notify_one();
+
+  let drained = match tokio::time::timeout(shutdown.drain_timeout, &mut server).await {
+    Ok(result) => {
+      result??;
+      true
+    }
+    Err(_) => {
+      warn!(
+        "{} gave up waiting for {} in-flight requests after {:?}",
+        name,
+        in_flight.load(Ordering::SeqCst),
+        shutdown.drain_timeout,
+      );
+      server.abort();
+      false
+    }
+  };
+
+  let mut failed_hooks = 0;
+  for (hook_name, hook) in shutdown.hooks {
+    match hook().await {
+      Ok(()) => info!("{} flushed {}", name, hook_name),
+      Err(e) => {
+        failed_hooks += 1;
+        error!("{} failed to flush {}: {:#}", name, hook_name, e);
+      }
+    }
+  }
+
+  let elapsed = started_at.elapsed().as_millis();
+  if drained && failed_hooks == 0 {
+    info!("{} stopped gracefully in {}ms", name, elapsed);
+  } else {
+    warn!("{} stopped in {}ms (drained: {}, failed hooks: {})", name, elapsed, drained, failed_hooks);
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use axum::routing::get;
+  use axum_test::TestServer;
+  use serde_json::{json, Value};
+  use std::sync::atomic::AtomicBool;
+  use std::time::Duration;
+  use tokio::io::{AsyncReadExt, AsyncWriteExt};
+  use tokio::net::TcpStream;
+  use tokio::sync::oneshot;
+
+  #[tokio::test]
+  async fn test_app_adds_health_and_metrics_routes() {
+    let router = Router::new().route("/", get(|| async { "Hello World" }));
+    let server = TestServer::new(app(router, Readiness::new(), crate::version_info!(), &Metrics::new(), OpenApi::default()))
+      .unwrap();
+
+    let health = server.get("/health/live").await;
+    let root = server.get("/").await;
+    let openapi = server.get("/openapi.json").await;
+    let metrics = server.get("/metrics").await;
+
+    // assert
+    health.assert_status_ok();
+    assert_eq!(json!({ "status": "ok" }), health.json::<Value>());
+    assert_eq!("Hello World", root.text());
+    openapi.assert_status_ok();
+    assert!(metrics.text().contains(r#"http_request_duration_seconds_count{method="GET",route="/",status="200"} 1"#));
+    assert!(!metrics.text().contains("/health/live"));
+  }
+
+  #[test]
+  fn test_in_flight_guard_released_on_drop() {
+    let in_flight = Arc::new(AtomicUsize::new(0));
+
+    let guard = InFlightGuard::new(in_flight.clone());
+    let during = in_flight.load(Ordering::SeqCst);
+    let cancelled = async move {
+      let _guard = guard;
+      std::future::pending::<()>().await
+    };
+    drop(cancelled);
+
+    // assert
+    assert_eq!(1, during);
+    assert_eq!(0, in_flight.load(Ordering::SeqCst));
+  }
+
+  /// リクエストを送信し、レスポンスをすべて読み込みます
+  async fn send_request(stream: &mut TcpStream, path: &str) -> String {
+    let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path);
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+  }
+
+  #[tokio::test]
+  async fn test_serve_until_drains_in_flight_requests() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (started_tx, started_rx) = oneshot::channel::<()>();
+    let started_tx = Arc::new(std::sync::Mutex::new(Some(started_tx)));
+    let router = Router::new().route("/slow", get(move || async move {
+      if let Some(started_tx) = started_tx.lock().unwrap().take() {
+        started_tx.send(()).unwrap();
+      }
+      tokio::time::sleep(Duration::from_millis(200)).await;
+      "done"
+    }));
+    let flushed = Arc::new(AtomicBool::new(false));
+    let shutdown = Shutdown::new(Duration::from_secs(5)).with_hook("outbox", {
+      let flushed = flushed.clone();
+      move || async move {
+        flushed.store(true, Ordering::SeqCst);
+        Ok(())
+      }
+    });
+
+    let server = tokio::spawn(serve_until("Test server", listener, router, shutdown, async move {
+      started_rx.await.unwrap();
+      "SIGTERM"
+    }));
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let response = send_request(&mut stream, "/slow").await;
+    server.await.unwrap().unwrap();
+
+    // assert
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.ends_with("done"));
+    assert!(flushed.load(Ordering::SeqCst));
+    assert!(TcpStream::connect(addr).await.is_err());
+  }
+
+  #[tokio::test]
+  async fn test_serve_until_stops_after_drain_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let router = Router::new().route("/hang", get(std::future::pending::<&'static str>));
+    let flushed = Arc::new(AtomicBool::new(false));
+    let shutdown = Shutdown::new(Duration::from_millis(100)).with_hook("projection", {
+      let flushed = flushed.clone();
+      move || async move {
+        flushed.store(true, Ordering::SeqCst);
+        Ok(())
+      }
+    });
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(b"GET /hang HTTP/1.1\r\nHost: localhost\r\n\r\n").await.unwrap();
+
+    let result = tokio::time::timeout(
+      Duration::from_secs(5),
+      serve_until("Test server", listener, router, shutdown, async {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        "SIGINT"
+      }),
+    ).await;
+
+    // assert
+    assert!(result.unwrap().is_ok());
+    assert!(flushed.load(Ordering::SeqCst));
+  }
+}
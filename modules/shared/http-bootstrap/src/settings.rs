@@ -0,0 +1,550 @@
+use anyhow::Context;
+use config::Config;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+pub use shared_telemetry::tracer::TelemetrySettings;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+use tracing_subscriber::EnvFilter;
+
+/// 設定ファイルを配置するディレクトリです
+const CONFIG_DIR: &str = "config";
+
+/// 設定を上書きする環境変数の接頭辞です
+///
+/// `APP_API__PORT`のように、階層は`__`で区切ります
+const ENV_PREFIX: &str = "APP";
+
+/// プロファイルを指定する環境変数です
+const PROFILE_ENV: &str = "APP_PROFILE";
+
+/// `api.host`・`api.port`を指定する、接頭辞のない環境変数です
+///
+/// Lambda Web Adapterなどの実行環境が`PORT`でポートを指定するため、`APP_`の環境変数より低い優先度で読み込みます
+const LEGACY_API_ENVS: [(&str, &str); 2] = [("HOST", "API__HOST"), ("PORT", "API__PORT")];
+
+/// 設定のプロファイルです
+///
+/// Local: 開発環境
+///
+/// Test: テスト環境
+///
+/// Prod: 本番環境
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum Profile {
+  #[default]
+  Local,
+  Test,
+  Prod,
+}
+
+impl Display for Profile {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Profile::Local => write!(f, "local"),
+      Profile::Test => write!(f, "test"),
+      Profile::Prod => write!(f, "prod"),
+    }
+  }
+}
+
+impl FromStr for Profile {
+  type Err = anyhow::Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "local" => Ok(Profile::Local),
+      "test" => Ok(Profile::Test),
+      "prod" => Ok(Profile::Prod),
+      _ => Err(anyhow::anyhow!("unknown profile {:?}, expected one of local, test, prod", s)),
+    }
+  }
+}
+
+/// 設定の読み込み方法です
+///
+/// config: 設定ファイルのパス(未指定の場合は`config/<name>.toml`)
+///
+/// profile: プロファイル
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct ConfigOptions {
+  pub config: Option<PathBuf>,
+  pub profile: Profile,
+}
+
+impl ConfigOptions {
+  /// コマンドライン引数と環境変数から読み込み方法を返します
+  ///
+  /// `--config <path>`、`--profile <profile>`を受け付けます。
+  /// `--profile`を省略した場合は環境変数`APP_PROFILE`、どちらもない場合はlocalになります
+  ///
+  /// # Arguments
+  /// * `args`: プログラム名を除いたコマンドライン引数
+  ///
+  /// # Return
+  /// * `anyhow::Result<ConfigOptions>`
+  pub fn from_args(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+    let mut config = None;
+    let mut profile = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+      let (key, value) = match arg.split_once('=') {
+        Some((key, value)) => (key.to_string(), Some(value.to_string())),
+        None => (arg, None),
+      };
+      let mut value = || value.clone().or_else(|| args.next()).with_context(|| format!("{} requires a value", key));
+      match key.as_str() {
+        "--config" => config = Some(PathBuf::from(value()?)),
+        "--profile" => profile = Some(value()?.parse::<Profile>()?),
+        _ => Err(anyhow::anyhow!("unknown argument {:?}, expected --config <path> or --profile <profile>", key))?,
+      }
+    }
+    let profile = match profile {
+      Some(profile) => profile,
+      None => std::env::var(PROFILE_ENV).ok().map(|value| value.parse::<Profile>()).transpose()?.unwrap_or_default(),
+    };
+    Ok(Self { config, profile })
+  }
+}
+
+/// 設定値の検証を提供するトレイトです
+pub trait ValidateSettings {
+  /// 設定値を検証します
+  ///
+  /// # Return
+  /// * `api.port: must not be 0` のような`<キー>: <理由>`形式の誤りの一覧
+  fn validate(&self) -> Vec<String>;
+}
+
+/// API起動時の設定用の構造体です
+///
+/// host: ホスト
+///
+/// port: ポート番号
+///
+/// shutdown_timeout_secs: 終了時に処理中のリクエストの完了を待つ秒数(未指定の場合は30秒)
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct ApiSettings {
+  pub host: String,
+  pub port: u16,
+  #[serde(default = "default_shutdown_timeout_secs")]
+  pub shutdown_timeout_secs: u64,
+}
+
+/// 終了時に処理中のリクエストの完了を待つ秒数の既定値です
+fn default_shutdown_timeout_secs() -> u64 {
+  30
+}
+
+impl ApiSettings {
+  /// 起動用のアドレスを返します
+  ///
+  /// # Return
+  /// * `anyhow::Result<SocketAddr>`
+  pub fn socket_addr(&self) -> anyhow::Result<SocketAddr> {
+    let ip_addr = IpAddr::from_str(&self.host)
+      .map_err(|e| anyhow::anyhow!("invalid api.host {:?}: {}", self.host, e))?;
+    Ok(SocketAddr::new(ip_addr, self.port))
+  }
+
+  /// 終了時に処理中のリクエストの完了を待つ時間の上限を返します
+  pub fn shutdown_timeout(&self) -> Duration {
+    Duration::from_secs(self.shutdown_timeout_secs)
+  }
+}
+
+impl ValidateSettings for ApiSettings {
+  fn validate(&self) -> Vec<String> {
+    let mut errors = vec![];
+    if IpAddr::from_str(&self.host).is_err() {
+      errors.push(format!("api.host: must be an IP address: {:?}", self.host));
+    }
+    if self.port == 0 {
+      errors.push("api.port: must not be 0".to_string());
+    }
+    if self.shutdown_timeout_secs == 0 {
+      errors.push("api.shutdown_timeout_secs: must not be 0".to_string());
+    }
+    errors
+  }
+}
+
+/// ログ出力の設定用の構造体です
+///
+/// format: 出力形式(未指定の場合はtext)
+///
+/// level: 出力するログの条件(`RUST_LOG`と同じ形式、未指定の場合はdebug)。
+/// 環境変数`RUST_LOG`を指定した場合はそちらを優先します
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct LoggingSettings {
+  #[serde(default)]
+  pub format: LogFormat,
+  #[serde(default = "default_log_level")]
+  pub level: String,
+}
+
+impl Default for LoggingSettings {
+  fn default() -> Self {
+    Self { format: LogFormat::default(), level: default_log_level() }
+  }
+}
+
+/// 出力するログの条件の既定値です
+fn default_log_level() -> String {
+  "debug".to_string()
+}
+
+/// ログの出力形式です
+///
+/// text: 人が読むためのテキスト
+///
+/// json: 1行1件のJSON(ログの収集基盤向け)
+#[derive(Deserialize, Debug, Default, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+  #[default]
+  Text,
+  Json,
+}
+
+impl ValidateSettings for LoggingSettings {
+  fn validate(&self) -> Vec<String> {
+    match EnvFilter::try_new(&self.level) {
+      Ok(_) => vec![],
+      Err(e) => vec![format!("logging.level: invalid filter {:?}: {}", self.level, e)],
+    }
+  }
+}
+
+impl ValidateSettings for TelemetrySettings {
+  fn validate(&self) -> Vec<String> {
+    match &self.otlp_endpoint {
+      Some(endpoint) if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") => {
+        vec![format!("telemetry.otlp_endpoint: must be an http(s) URL: {:?}", endpoint)]
+      }
+      _ => vec![],
+    }
+  }
+}
+
+/// 注文のイベントログの設定用の構造体です
+///
+/// 書き込み用サーバーがイベントを追記し、読み込み用サーバーが読み込んで注文サマリーに投影します
+///
+/// path: イベントログのファイル(JSON Lines)
+///
+/// interval_ms: 書き込み用サーバーはイベントを書き出す間隔、読み込み用サーバーはイベントログを読み込む間隔(ミリ秒、未指定の場合は1000)
+///
+/// max_lag_secs: 読み込み用サーバーの投影の遅れの上限(秒、未指定の場合は30)。超えた場合はreadinessを失敗にします
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct EventLogSettings {
+  pub path: PathBuf,
+  #[serde(default = "default_event_log_interval_ms")]
+  pub interval_ms: u64,
+  #[serde(default = "default_event_log_max_lag_secs")]
+  pub max_lag_secs: u64,
+}
+
+/// イベントログの書き出しと読み込みの間隔の既定値です
+fn default_event_log_interval_ms() -> u64 {
+  1000
+}
+
+/// 投影の遅れの上限の既定値です
+fn default_event_log_max_lag_secs() -> u64 {
+  30
+}
+
+impl EventLogSettings {
+  /// イベントログの書き出しと読み込みの間隔を返します
+  pub fn interval(&self) -> Duration {
+    Duration::from_millis(self.interval_ms)
+  }
+
+  /// 投影の遅れの上限を返します
+  pub fn max_lag(&self) -> Duration {
+    Duration::from_secs(self.max_lag_secs)
+  }
+}
+
+impl ValidateSettings for EventLogSettings {
+  fn validate(&self) -> Vec<String> {
+    let mut errors = vec![];
+    if self.path.as_os_str().is_empty() {
+      errors.push("event_log.path: must not be empty".to_string());
+    }
+    if self.interval_ms == 0 {
+      errors.push("event_log.interval_ms: must not be 0".to_string());
+    }
+    if self.max_lag() <= self.interval() {
+      errors.push("event_log.max_lag_secs: must be longer than event_log.interval_ms".to_string());
+    }
+    errors
+  }
+}
+
+/// 設定ファイルと環境変数から設定を読み込み、検証します
+///
+/// 以下の順に読み込み、後のものが優先されます
+///
+/// 1. 設定ファイル(`--config`、未指定の場合は`config/<name>.toml`)
+/// 2. プロファイルの設定ファイル(`<name>.<profile>.toml`、存在しない場合は読み飛ばします)
+/// 3. 環境変数`HOST`・`PORT`(`api.host`・`api.port`)
+/// 4. `APP_`で始まる環境変数(例: `APP_API__PORT=8080`)
+///
+/// `config`ディレクトリは、カレントディレクトリから親ディレクトリに向かって探します。
+/// `--config`を指定せず設定ファイルも見つからない場合は、環境変数のみから読み込みます
+///
+/// # Arguments
+/// * `name`: 設定ファイル名(例: `write-api-server`)
+/// * `options`: 設定の読み込み方法
+///
+/// # Return
+/// * `anyhow::Result<T>`
+pub fn load_settings<T: DeserializeOwned + ValidateSettings>(name: &str, options: &ConfigOptions) -> anyhow::Result<T> {
+  load_settings_from(name, options, std::env::vars().collect())
+}
+
+/// 環境変数を指定して設定を読み込み、検証します
+fn load_settings_from<T: DeserializeOwned + ValidateSettings>(
+  name: &str,
+  options: &ConfigOptions,
+  env: HashMap<String, String>,
+) -> anyhow::Result<T> {
+  let path = match &options.config {
+    Some(path) if !path.is_file() => Err(anyhow::anyhow!("config file {} not found", path.display()))?,
+    Some(path) => Some(path.clone()),
+    None => find_config_file(name)?,
+  };
+  let source = match &path {
+    Some(path) => path.display().to_string(),
+    None => format!("the environment ({}/{}.toml not found)", CONFIG_DIR, name),
+  };
+  let settings = load_sources::<T>(path.as_deref(), options.profile, env)
+    .with_context(|| format!("failed to load settings from {} (profile: {})", source, options.profile))?;
+  let errors = settings.validate();
+  if !errors.is_empty() {
+    Err(anyhow::anyhow!(
+      "invalid settings in {} (profile: {}):\n  - {}",
+      source,
+      options.profile,
+      errors.join("\n  - "),
+    ))?
+  }
+  Ok(settings)
+}
+
+/// 設定ファイルとプロファイルの設定ファイル、環境変数を重ねて読み込みます
+fn load_sources<T: DeserializeOwned>(path: Option<&Path>, profile: Profile, env: HashMap<String, String>) -> anyhow::Result<T> {
+  let mut builder = Config::builder();
+  if let Some(path) = path {
+    builder = builder
+      .add_source(config::File::from(path))
+      .add_source(config::File::from(profile_path(path, profile)).required(false));
+  }
+  let legacy = LEGACY_API_ENVS
+    .iter()
+    .filter_map(|(name, key)| env.get(*name).map(|value| (key.to_string(), value.clone())))
+    .collect::<HashMap<String, String>>();
+  let settings = builder
+    .add_source(config::Environment::default().separator("__").source(Some(legacy)))
+    .add_source(config::Environment::with_prefix(ENV_PREFIX).prefix_separator("_").separator("__").source(Some(env)))
+    .build()?;
+  let settings = settings.try_deserialize::<T>()?;
+  Ok(settings)
+}
+
+/// プロファイルの設定ファイルのパスを返します
+///
+/// `config/write-api-server.toml`の場合、`config/write-api-server.prod.toml`になります
+fn profile_path(path: &Path, profile: Profile) -> PathBuf {
+  let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+  let file_name = match path.extension() {
+    Some(extension) => format!("{}.{}.{}", stem, profile, extension.to_string_lossy()),
+    None => format!("{}.{}", stem, profile),
+  };
+  path.with_file_name(file_name)
+}
+
+/// カレントディレクトリから親ディレクトリに向かって`config/<name>.toml`を探します
+///
+/// # Return
+/// * 見つからない場合はNone
+fn find_config_file(name: &str) -> anyhow::Result<Option<PathBuf>> {
+  let file_name = format!("{}.toml", name);
+  let current_dir = std::env::current_dir()?;
+  let path = current_dir
+    .ancestors()
+    .map(|dir| dir.join(CONFIG_DIR).join(&file_name))
+    .find(|path| path.is_file());
+  Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rstest::rstest;
+
+  #[derive(Deserialize, Debug)]
+  struct TestSettings {
+    api: ApiSettings,
+  }
+
+  impl ValidateSettings for TestSettings {
+    fn validate(&self) -> Vec<String> {
+      self.api.validate()
+    }
+  }
+
+  /// テストごとに一時ディレクトリに設定ファイルを作成します
+  fn write_config(test_name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("http-bootstrap-{}-{}", test_name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (file_name, content) in files {
+      std::fs::write(dir.join(file_name), content).unwrap();
+    }
+    dir.join(files[0].0)
+  }
+
+  #[test]
+  fn test_api_settings_socket_addr() {
+    let settings = ApiSettings { host: "127.0.0.1".to_string(), port: 18080, shutdown_timeout_secs: 30 };
+    let invalid = ApiSettings { host: "localhost".to_string(), port: 18080, shutdown_timeout_secs: 30 };
+
+    // assert
+    assert_eq!(SocketAddr::from(([127, 0, 0, 1], 18080)), settings.socket_addr().unwrap());
+    assert!(invalid.socket_addr().unwrap_err().to_string().contains("invalid api.host"));
+  }
+
+  #[rstest]
+  #[case("debug", 0)]
+  #[case("info,command_processor=debug", 0)]
+  #[case("info,[", 1)]
+  fn test_logging_settings_validate(#[case] level: &str, #[case] expected: usize) {
+    let settings = LoggingSettings { format: LogFormat::Json, level: level.to_string() };
+
+    // assert
+    assert_eq!(expected, settings.validate().len());
+  }
+
+  #[rstest]
+  #[case(None, 0)]
+  #[case(Some("http://localhost:4318/v1/traces"), 0)]
+  #[case(Some("localhost:4318"), 1)]
+  fn test_telemetry_settings_validate(#[case] otlp_endpoint: Option<&str>, #[case] expected: usize) {
+    let settings = TelemetrySettings { otlp_endpoint: otlp_endpoint.map(str::to_string) };
+
+    // assert
+    assert_eq!(expected, settings.validate().len());
+  }
+
+  #[rstest]
+  #[case(&[], None, Profile::Local)]
+  #[case(&["--config", "server.toml"], Some("server.toml"), Profile::Local)]
+  #[case(&["--config=server.toml", "--profile=prod"], Some("server.toml"), Profile::Prod)]
+  #[case(&["--profile", "test"], None, Profile::Test)]
+  fn test_config_options_from_args(#[case] args: &[&str], #[case] config: Option<&str>, #[case] profile: Profile) {
+    let result = ConfigOptions::from_args(args.iter().map(|arg| arg.to_string())).unwrap();
+
+    // assert
+    assert_eq!(ConfigOptions { config: config.map(PathBuf::from), profile }, result);
+  }
+
+  #[rstest]
+  #[case(&["--config"], "--config requires a value")]
+  #[case(&["--profile", "staging"], "unknown profile \"staging\"")]
+  #[case(&["--port", "8080"], "unknown argument \"--port\"")]
+  fn test_config_options_from_args_failed(#[case] args: &[&str], #[case] expected: &str) {
+    let result = ConfigOptions::from_args(args.iter().map(|arg| arg.to_string()));
+
+    // assert
+    assert!(result.unwrap_err().to_string().contains(expected));
+  }
+
+  #[test]
+  fn test_load_settings_with_profile() {
+    let path = write_config("profile", &[
+      ("server.toml", "[api]\nhost = \"0.0.0.0\"\nport = 18080\n"),
+      ("server.prod.toml", "[api]\nport = 8080\n"),
+    ]);
+
+    let local = load_settings::<TestSettings>("server", &ConfigOptions { config: Some(path.clone()), profile: Profile::Local }).unwrap();
+    let prod = load_settings::<TestSettings>("server", &ConfigOptions { config: Some(path), profile: Profile::Prod }).unwrap();
+
+    // assert
+    assert_eq!(18080, local.api.port);
+    assert_eq!(8080, prod.api.port);
+    assert_eq!(Duration::from_secs(30), prod.api.shutdown_timeout());
+    assert_eq!("0.0.0.0", prod.api.host);
+  }
+
+  #[test]
+  fn test_load_settings_failed() {
+    let path = write_config("invalid", &[("server.toml", "[api]\nhost = \"localhost\"\nport = 0\n")]);
+    let missing = ConfigOptions { config: Some(path.with_file_name("missing.toml")), profile: Profile::Local };
+
+    let invalid = load_settings::<TestSettings>("server", &ConfigOptions { config: Some(path), profile: Profile::Local })
+      .unwrap_err()
+      .to_string();
+    let not_found = format!("{:#}", load_settings::<TestSettings>("server", &missing).unwrap_err());
+
+    // assert
+    assert!(invalid.contains("api.host: must be an IP address: \"localhost\""));
+    assert!(invalid.contains("api.port: must not be 0"));
+    assert!(not_found.contains("missing.toml not found"));
+  }
+
+  #[test]
+  fn test_event_log_settings_validate() {
+    let settings = EventLogSettings { path: PathBuf::from("var/order-events.jsonl"), interval_ms: 500, max_lag_secs: 30 };
+    let invalid = EventLogSettings { path: PathBuf::new(), interval_ms: 0, max_lag_secs: 30 };
+    let too_short = EventLogSettings { path: PathBuf::from("var/order-events.jsonl"), interval_ms: 5000, max_lag_secs: 5 };
+
+    // assert
+    assert!(settings.validate().is_empty());
+    assert_eq!(Duration::from_millis(500), settings.interval());
+    assert_eq!(Duration::from_secs(30), settings.max_lag());
+    assert_eq!(
+      vec!["event_log.path: must not be empty".to_string(), "event_log.interval_ms: must not be 0".to_string()],
+      invalid.validate(),
+    );
+    assert_eq!(vec!["event_log.max_lag_secs: must be longer than event_log.interval_ms".to_string()], too_short.validate());
+  }
+
+  #[test]
+  fn test_load_settings_from_env() {
+    let options = ConfigOptions::default();
+    let env = |vars: &[(&str, &str)]| vars.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect();
+
+    let app = load_settings_from::<TestSettings>(
+      "env-only-server", &options, env(&[("APP_API__HOST", "127.0.0.1"), ("APP_API__PORT", "8080")]),
+    ).unwrap();
+    let legacy = load_settings_from::<TestSettings>(
+      "env-only-server", &options, env(&[("HOST", "0.0.0.0"), ("PORT", "9090"), ("APP_API__PORT", "8080")]),
+    ).unwrap();
+    let missing = format!("{:#}", load_settings_from::<TestSettings>("env-only-server", &options, env(&[])).unwrap_err());
+
+    // assert
+    assert_eq!(ApiSettings { host: "127.0.0.1".to_string(), port: 8080, shutdown_timeout_secs: 30 }, app.api);
+    assert_eq!(ApiSettings { host: "0.0.0.0".to_string(), port: 8080, shutdown_timeout_secs: 30 }, legacy.api);
+    assert!(missing.contains("config/env-only-server.toml not found"));
+  }
+
+  #[test]
+  fn test_load_settings_legacy_env_overrides_file() {
+    let path = write_config("legacy", &[("server.toml", "[api]\nhost = \"0.0.0.0\"\nport = 18080\n")]);
+    let options = ConfigOptions { config: Some(path), profile: Profile::Local };
+
+    let settings = load_settings_from::<TestSettings>(
+      "server", &options, HashMap::from([("PORT".to_string(), "9090".to_string())]),
+    ).unwrap();
+
+    // assert
+    assert_eq!(9090, settings.api.port);
+    assert_eq!("0.0.0.0", settings.api.host);
+  }
+}
@@ -0,0 +1,70 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+/// 終了時の処理です
+///
+/// 処理中のリクエストの完了後に呼び出し、未送信のイベントや投影待ちの処理を書き出します
+pub type ShutdownHook = Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>> + Send>;
+
+/// 終了の設定です
+///
+/// drain_timeout: 処理中のリクエストの完了を待つ時間の上限
+///
+/// hooks: 終了時の処理(登録した順に呼び出します)
+pub struct Shutdown {
+  pub(crate) drain_timeout: Duration,
+  pub(crate) hooks: Vec<(&'static str, ShutdownHook)>,
+}
+
+impl Shutdown {
+  /// コンストラクタです
+  ///
+  /// # Arguments
+  /// * `drain_timeout`: 処理中のリクエストの完了を待つ時間の上限
+  pub fn new(drain_timeout: Duration) -> Self {
+    Self { drain_timeout, hooks: vec![] }
+  }
+
+  /// 終了時の処理を追加します
+  ///
+  /// # Arguments
+  /// * `name`: ログに出力する処理名
+  /// * `hook`: 終了時の処理
+  pub fn with_hook<F, Fut>(mut self, name: &'static str, hook: F) -> Self
+  where
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+  {
+    self.hooks.push((name, Box::new(move || Box::pin(hook()))));
+    self
+  }
+}
+
+/// 終了シグナル(SIGINT、SIGTERM)を受け取るまで待機します
+///
+/// # Return
+/// * 受け取ったシグナル名
+pub async fn shutdown_signal() -> &'static str {
+  let ctrl_c = async {
+    tokio::signal::ctrl_c()
+      .await
+      .expect("failed to install Ctrl+C handler");
+  };
+
+  #[cfg(unix)]
+  let terminate = async {
+    tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+      .expect("failed to install SIGTERM handler")
+      .recv()
+      .await;
+  };
+
+  #[cfg(not(unix))]
+  let terminate = std::future::pending::<()>();
+
+  tokio::select! {
+    _ = ctrl_c => "SIGINT",
+    _ = terminate => "SIGTERM",
+  }
+}
@@ -0,0 +1,87 @@
+use opentelemetry::propagation::{Extractor, Injector, TextMapPropagator};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use std::collections::BTreeMap;
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// 現在のスパンのトレースコンテキストを返します
+///
+/// W3C Trace Context形式(`traceparent`、`tracestate`)で返すため、イベントのメタデータに格納できます。
+/// スパンを記録していない場合は空になります
+///
+/// # Return
+/// * `BTreeMap<String, String>`
+pub fn current_trace_context() -> BTreeMap<String, String> {
+  let mut carrier = Carrier(BTreeMap::new());
+  TraceContextPropagator::new().inject_context(&Span::current().context(), &mut carrier);
+  carrier.0
+}
+
+/// トレースコンテキストのスパンをスパンの親に設定します
+///
+/// トレースコンテキストが空または不正な場合は何もしません
+///
+/// # Arguments
+/// * `span`: 親を設定するスパン
+/// * `trace_context`: `current_trace_context`で取得したトレースコンテキスト
+pub fn set_parent(span: &Span, trace_context: &BTreeMap<String, String>) {
+  let context = TraceContextPropagator::new().extract(&Carrier(trace_context.clone()));
+  // OpenTelemetryのレイヤーがない場合はエラーになりますが、親子関係を記録しないだけのため無視します
+  let _ = span.set_parent(context);
+}
+
+/// トレースコンテキストの格納先です
+struct Carrier(BTreeMap<String, String>);
+
+impl Injector for Carrier {
+  fn set(&mut self, key: &str, value: String) {
+    self.0.insert(key.to_string(), value);
+  }
+}
+
+impl Extractor for Carrier {
+  fn get(&self, key: &str) -> Option<&str> {
+    self.0.get(key).map(String::as_str)
+  }
+
+  fn keys(&self) -> Vec<&str> {
+    self.0.keys().map(String::as_str).collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use opentelemetry::trace::TraceContextExt;
+  use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+  use tracing_subscriber::layer::SubscriberExt;
+
+  #[test]
+  fn test_set_parent_links_to_trace_context() {
+    let exporter = InMemorySpanExporter::default();
+    let tracer_provider = SdkTracerProvider::builder().with_simple_exporter(exporter.clone()).build();
+    let subscriber = tracing_subscriber::registry().with(crate::tracer::layer(&tracer_provider, "test-service"));
+
+    let (trace_context, write_trace_id) = tracing::subscriber::with_default(subscriber, || {
+      let write = tracing::info_span!("place_order");
+      let trace_context = write.in_scope(current_trace_context);
+      let write_trace_id = write.context().span().span_context().trace_id();
+      let projection = tracing::info_span!("projection");
+      set_parent(&projection, &trace_context);
+      projection.in_scope(|| {});
+      (trace_context, write_trace_id)
+    });
+    let spans = exporter.get_finished_spans().unwrap();
+    let projection = spans.iter().find(|span| span.name == "projection").unwrap();
+
+    // assert
+    assert!(trace_context["traceparent"].contains(&write_trace_id.to_string()));
+    assert_eq!(write_trace_id, projection.span_context.trace_id());
+  }
+
+  #[test]
+  fn test_current_trace_context_without_span() {
+    // assert
+    assert!(current_trace_context().is_empty());
+  }
+}
@@ -0,0 +1,108 @@
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use serde::Deserialize;
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// トレースの出力の設定です
+///
+/// otlp_endpoint: スパンを送信するOTLP/HTTPのエンドポイント(例: `http://localhost:4318/v1/traces`)。
+/// 未指定の場合はスパンを送信しません
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct TelemetrySettings {
+  #[serde(default)]
+  pub otlp_endpoint: Option<String>,
+}
+
+/// OTLPでスパンを送信するTracerProviderを作成します
+///
+/// スパンはバックグラウンドのスレッドでまとめて送信します。
+/// 終了時は`SdkTracerProvider::shutdown`で送信していないスパンを書き出します
+///
+/// # Arguments
+/// * `service_name`: スパンに付けるサービス名
+/// * `settings`: TelemetrySettings
+///
+/// # Return
+/// * `anyhow::Result<Option<SdkTracerProvider>>`: エンドポイントが未指定の場合はNone
+pub fn tracer_provider(service_name: &'static str, settings: &TelemetrySettings) -> anyhow::Result<Option<SdkTracerProvider>> {
+  let Some(endpoint) = &settings.otlp_endpoint else {
+    return Ok(None);
+  };
+  let exporter = SpanExporter::builder()
+    .with_http()
+    .with_endpoint(endpoint)
+    .build()
+    .map_err(|e| anyhow::anyhow!("failed to create the OTLP exporter for {}: {}", endpoint, e))?;
+  Ok(Some(
+    SdkTracerProvider::builder()
+      .with_resource(Resource::builder().with_service_name(service_name).build())
+      .with_batch_exporter(exporter)
+      .build()
+  ))
+}
+
+/// tracingのスパンをOpenTelemetryのスパンとして記録するレイヤーを返します
+///
+/// # Arguments
+/// * `tracer_provider`: SdkTracerProvider
+/// * `service_name`: 計装ライブラリの名前
+///
+/// # Return
+/// * `OpenTelemetryLayer<S, SdkTracer>`
+pub fn layer<S>(tracer_provider: &SdkTracerProvider, service_name: &'static str) -> OpenTelemetryLayer<S, SdkTracer>
+where
+  S: Subscriber + for<'span> LookupSpan<'span>,
+{
+  tracing_opentelemetry::layer().with_tracer(tracer_provider.tracer(service_name))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::{Read, Write};
+  use std::net::TcpListener;
+  use std::sync::mpsc;
+  use std::thread;
+  use tracing_subscriber::layer::SubscriberExt;
+
+  #[test]
+  fn test_tracer_provider_exports_to_collector() {
+    // OTLP/HTTPのリクエストを1件だけ受け取るローカルのコレクターです
+    let collector = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!("http://{}/v1/traces", collector.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+      let (mut stream, _) = collector.accept().unwrap();
+      let mut request = vec![0; 64 * 1024];
+      let size = stream.read(&mut request).unwrap();
+      stream.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n").unwrap();
+      sender.send(String::from_utf8_lossy(&request[..size]).to_string()).unwrap();
+    });
+    let settings = TelemetrySettings { otlp_endpoint: Some(endpoint) };
+    let tracer_provider = tracer_provider("test-service", &settings).unwrap().unwrap();
+    let subscriber = tracing_subscriber::registry().with(layer(&tracer_provider, "test-service"));
+
+    tracing::subscriber::with_default(subscriber, || {
+      tracing::info_span!("place_order").in_scope(|| {});
+    });
+    tracer_provider.shutdown().unwrap();
+    let request = receiver.recv().unwrap();
+
+    // assert
+    assert!(request.starts_with("POST /v1/traces"));
+    assert!(request.contains("application/x-protobuf"));
+  }
+
+  #[test]
+  fn test_tracer_provider_without_endpoint() {
+    let result = tracer_provider("test-service", &TelemetrySettings::default()).unwrap();
+
+    // assert
+    assert!(result.is_none());
+  }
+
+}
@@ -0,0 +1,67 @@
+# 注文のイベントログ(書き込み用Lambdaが追記し、読み込み用Lambdaが投影する)を置くEFSの設定
+# Lambdaの/tmpは関数ごとに別のため、両方の関数に同じEFSのアクセスポイントをマウントします
+
+variable "vpc_id" {
+  description = "EFSとLambdaを配置するVPC"
+  type        = string
+}
+
+variable "subnet_ids" {
+  description = "EFSのマウントターゲットとLambdaを配置するサブネット"
+  type        = list(string)
+}
+
+resource "aws_security_group" "lambda_sg" {
+  name   = "terraform_lambda_sg"
+  vpc_id = var.vpc_id
+
+  egress {
+    from_port   = 0
+    to_port     = 0
+    protocol    = "-1"
+    cidr_blocks = ["0.0.0.0/0"]
+  }
+}
+
+resource "aws_security_group" "event_log_efs_sg" {
+  name   = "terraform_event_log_efs_sg"
+  vpc_id = var.vpc_id
+
+  # LambdaからのNFSのみ許可します
+  ingress {
+    from_port       = 2049
+    to_port         = 2049
+    protocol        = "tcp"
+    security_groups = [aws_security_group.lambda_sg.id]
+  }
+}
+
+resource "aws_efs_file_system" "event_log" {
+  creation_token = "order-event-log"
+  encrypted      = true
+}
+
+resource "aws_efs_mount_target" "event_log" {
+  for_each        = toset(var.subnet_ids)
+  file_system_id  = aws_efs_file_system.event_log.id
+  subnet_id       = each.value
+  security_groups = [aws_security_group.event_log_efs_sg.id]
+}
+
+resource "aws_efs_access_point" "event_log" {
+  file_system_id = aws_efs_file_system.event_log.id
+
+  posix_user {
+    uid = 1000
+    gid = 1000
+  }
+
+  root_directory {
+    path = "/event-log"
+    creation_info {
+      owner_uid   = 1000
+      owner_gid   = 1000
+      permissions = "750"
+    }
+  }
+}
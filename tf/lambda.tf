@@ -7,14 +7,29 @@ resource "aws_lambda_function" "write_api_lambda" {
   timeout     = 10
   memory_size = 256
 
+  # イベントログのファイルへ追記するのは1つのインスタンスのみにします(NFS上の同時の追記は行が混ざるため)
+  reserved_concurrent_executions = 1
+
   environment {
     variables = {
       RUST_BACKTRACE = "1"
       RUST_LOG       = "info"
-      HOST           = "0.0.0.0"
-      PORT           = "8080"
+      APP_PROFILE    = "prod"
     }
   }
+
+  # 注文のイベントログを置くEFS(efs.tf)を/mnt/event-logにマウントします
+  vpc_config {
+    subnet_ids         = var.subnet_ids
+    security_group_ids = [aws_security_group.lambda_sg.id]
+  }
+
+  file_system_config {
+    arn              = aws_efs_access_point.event_log.arn
+    local_mount_path = "/mnt/event-log"
+  }
+
+  depends_on = [aws_efs_mount_target.event_log]
 }
 
 resource "aws_lambda_function" "read_api_lambda" {
@@ -30,8 +45,20 @@ resource "aws_lambda_function" "read_api_lambda" {
     variables = {
       RUST_BACKTRACE = "1"
       RUST_LOG       = "info"
-      HOST           = "0.0.0.0"
-      PORT           = "8080"
+      APP_PROFILE    = "prod"
     }
   }
+
+  # 注文のイベントログを置くEFS(efs.tf)を/mnt/event-logにマウントします
+  vpc_config {
+    subnet_ids         = var.subnet_ids
+    security_group_ids = [aws_security_group.lambda_sg.id]
+  }
+
+  file_system_config {
+    arn              = aws_efs_access_point.event_log.arn
+    local_mount_path = "/mnt/event-log"
+  }
+
+  depends_on = [aws_efs_mount_target.event_log]
 }
@@ -59,6 +59,14 @@ resource "aws_iam_role_policy" "lambda_access_policy" {
           aws_ecr_repository.write_api_repo.arn,
           aws_ecr_repository.read_api_repo.arn
         ]
+      },
+      {
+        Effect = "Allow"
+        Action = [
+          "elasticfilesystem:ClientMount",
+          "elasticfilesystem:ClientWrite"
+        ]
+        Resource = aws_efs_file_system.event_log.arn
       }
     ]
   })
@@ -68,4 +76,10 @@ resource "aws_iam_role_policy" "lambda_access_policy" {
 resource "aws_iam_role_policy_attachment" "lambda_basic_execution" {
   policy_arn = "arn:aws:iam::aws:policy/service-role/AWSLambdaBasicExecutionRole"
   role       = aws_iam_role.lambda_iam_role.name
+}
+
+# VPC内のLambdaがネットワークインターフェースを作成するためのポリシーをアタッチ
+resource "aws_iam_role_policy_attachment" "lambda_vpc_access_execution" {
+  policy_arn = "arn:aws:iam::aws:policy/service-role/AWSLambdaVPCAccessExecutionRole"
+  role       = aws_iam_role.lambda_iam_role.name
 }
\ No newline at end of file